@@ -9,11 +9,11 @@ use crate::{
         config_cmd, db, debug_cmd, dump_genesis, import, init_cmd, node, node::NoArgs, p2p,
         recover, stage, test_vectors,
     },
-    core::cli::runner::CliRunner,
+    core::cli::runner::{CliContext, CliRunner},
     version::{LONG_VERSION, SHORT_VERSION},
 };
 use clap::{value_parser, Parser, Subcommand};
-use reth_db::DatabaseEnv;
+use reth_db::{cache::CachedDatabase, DatabaseEnv};
 use reth_node_builder::{InitState, WithLaunchContext};
 use reth_primitives::ChainSpec;
 use reth_tracing::FileWorkerGuard;
@@ -128,10 +128,42 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
     ///     })
     ///     .unwrap();
     /// ````
-    pub fn run<L, Fut>(mut self, launcher: L) -> eyre::Result<()>
+    pub fn run<L, Fut>(self, launcher: L) -> eyre::Result<()>
     where
         L: FnOnce(WithLaunchContext<Arc<DatabaseEnv>, InitState>, Ext) -> Fut,
         Fut: Future<Output = eyre::Result<()>>,
+    {
+        self.run_with(|command, ctx| command.execute(ctx, launcher))
+    }
+
+    /// Execute the configured cli command, launching the node with `cached_launcher` if the read
+    /// cache of the database is enabled with `--db.read-cache-size`, and with `launcher` otherwise.
+    ///
+    /// See [NodeCommand::execute_with_read_cache](node::NodeCommand::execute_with_read_cache).
+    pub fn run_with_read_cache<L, Fut, LC, FutC>(
+        self,
+        launcher: L,
+        cached_launcher: LC,
+    ) -> eyre::Result<()>
+    where
+        L: FnOnce(WithLaunchContext<Arc<DatabaseEnv>, InitState>, Ext) -> Fut,
+        Fut: Future<Output = eyre::Result<()>>,
+        LC: FnOnce(
+            WithLaunchContext<Arc<CachedDatabase<Arc<DatabaseEnv>>>, InitState>,
+            Ext,
+        ) -> FutC,
+        FutC: Future<Output = eyre::Result<()>>,
+    {
+        self.run_with(|command, ctx| {
+            command.execute_with_read_cache(ctx, launcher, cached_launcher)
+        })
+    }
+
+    /// Execute the configured cli command, launching the node with `execute_node`.
+    fn run_with<F, Fut>(mut self, execute_node: F) -> eyre::Result<()>
+    where
+        F: FnOnce(node::NodeCommand<Ext>, CliContext) -> Fut,
+        Fut: Future<Output = eyre::Result<()>>,
     {
         // add network name to logs dir
         self.logs.log_file_directory =
@@ -142,7 +174,7 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
         let runner = CliRunner::default();
         match self.command {
             Commands::Node(command) => {
-                runner.run_command_until_exit(|ctx| command.execute(ctx, launcher))
+                runner.run_command_until_exit(|ctx| execute_node(command, ctx))
             }
             Commands::Init(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Import(command) => runner.run_blocking_until_ctrl_c(command.execute()),
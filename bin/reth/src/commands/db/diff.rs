@@ -1,3 +1,4 @@
+use super::output::{print_json, OutputFormat};
 use crate::{
     args::DatabaseArgs,
     dirs::{DataDirPath, PlatformPath},
@@ -6,13 +7,14 @@ use crate::{
 use clap::Parser;
 use reth_db::{
     cursor::DbCursorRO, database::Database, open_db_read_only, table::Table, transaction::DbTx,
-    AccountChangeSets, AccountsHistory, AccountsTrie, BlockBodyIndices, BlockOmmers,
-    BlockWithdrawals, Bytecodes, CanonicalHeaders, DatabaseEnv, HashedAccounts, HashedStorages,
-    HeaderNumbers, HeaderTerminalDifficulties, Headers, PlainAccountState, PlainStorageState,
-    PruneCheckpoints, Receipts, StageCheckpointProgresses, StageCheckpoints, StorageChangeSets,
-    StoragesHistory, StoragesTrie, Tables, TransactionBlocks, TransactionHashNumbers,
-    TransactionSenders, Transactions, VersionHistory,
+    AccountChangeSetEpochs, AccountChangeSets, AccountsHistory, AccountsTrie, BlockBodyIndices,
+    BlockOmmers, BlockWithdrawals, Bytecodes, CanonicalHeaders, HashedAccounts, HashedStorages,
+    HeaderNumbers, HeaderTerminalDifficulties, Headers, LogIndex, PlainAccountState,
+    PlainStorageState, PruneCheckpoints, Receipts, StageCheckpointProgresses, StageCheckpoints,
+    StorageChangeSetEpochs, StorageChangeSets, StoragesHistory, StoragesTrie, Tables,
+    TransactionBlocks, TransactionHashNumbers, TransactionSenders, Transactions, VersionHistory,
 };
+use serde::Serialize;
 use std::{
     collections::HashMap,
     fmt::Debug,
@@ -57,7 +59,14 @@ impl Command {
     ///
     /// The discrepancies and extra elements, along with a brief summary of the diff results are
     /// then written to a file in the output directory.
-    pub fn execute(self, tool: &DbTool<DatabaseEnv>) -> eyre::Result<()> {
+    ///
+    /// With the JSON output format, the summaries of the diffs of all tables are printed as a
+    /// single document.
+    pub fn execute<DB: Database>(
+        self,
+        tool: &DbTool<DB>,
+        format: OutputFormat,
+    ) -> eyre::Result<()> {
         // open second db
         let second_db_path: PathBuf = self.secondary_datadir.join("db").into();
         let second_db = open_db_read_only(&second_db_path, self.second_db.database_args())?;
@@ -67,12 +76,13 @@ impl Command {
             None => Tables::ALL,
         };
 
+        let mut summaries = Vec::with_capacity(tables.len());
         for table in tables {
             let primary_tx = tool.provider_factory.db_ref().tx()?;
             let secondary_tx = second_db.tx()?;
 
             let output_dir = self.output.clone();
-            match table {
+            let summary = match table {
                 Tables::CanonicalHeaders => {
                     find_diffs::<CanonicalHeaders>(primary_tx, secondary_tx, output_dir)?
                 }
@@ -102,6 +112,7 @@ impl Command {
                     find_diffs::<TransactionHashNumbers>(primary_tx, secondary_tx, output_dir)?
                 }
                 Tables::Receipts => find_diffs::<Receipts>(primary_tx, secondary_tx, output_dir)?,
+                Tables::LogIndex => find_diffs::<LogIndex>(primary_tx, secondary_tx, output_dir)?,
                 Tables::PlainAccountState => {
                     find_diffs::<PlainAccountState>(primary_tx, secondary_tx, output_dir)?
                 }
@@ -121,6 +132,12 @@ impl Command {
                 Tables::StorageChangeSets => {
                     find_diffs::<StorageChangeSets>(primary_tx, secondary_tx, output_dir)?
                 }
+                Tables::AccountChangeSetEpochs => {
+                    find_diffs::<AccountChangeSetEpochs>(primary_tx, secondary_tx, output_dir)?
+                }
+                Tables::StorageChangeSetEpochs => {
+                    find_diffs::<StorageChangeSetEpochs>(primary_tx, secondary_tx, output_dir)?
+                }
                 Tables::HashedAccounts => {
                     find_diffs::<HashedAccounts>(primary_tx, secondary_tx, output_dir)?
                 }
@@ -149,18 +166,36 @@ impl Command {
                     find_diffs::<VersionHistory>(primary_tx, secondary_tx, output_dir)?
                 }
             };
+            summaries.push(summary);
+        }
+
+        if format.is_json() {
+            print_json(&summaries)?;
         }
 
         Ok(())
     }
 }
 
+/// Summary of the diff of a table.
+#[derive(Debug, Serialize)]
+struct TableDiffSummary {
+    /// The name of the table.
+    table: &'static str,
+    /// The number of keys with different values in the two databases.
+    discrepancies: usize,
+    /// The number of keys present in only one of the databases.
+    extra_elements: usize,
+    /// The path of the diff report.
+    report: PathBuf,
+}
+
 /// Find diffs for a table, then analyzing the result
 fn find_diffs<T: Table>(
     primary_tx: impl DbTx,
     secondary_tx: impl DbTx,
     output_dir: impl AsRef<Path>,
-) -> eyre::Result<()>
+) -> eyre::Result<TableDiffSummary>
 where
     T::Key: Hash,
     T::Value: PartialEq,
@@ -234,7 +269,7 @@ where
 
     let full_file_name = output_dir.as_ref().join(file_name);
     info!("Done writing diff results for {table} to {}", full_file_name.display());
-    Ok(())
+    Ok(TableDiffSummary { table: T::NAME, discrepancies, extra_elements, report: full_file_name })
 }
 
 /// This diff algorithm is slightly different, it will walk _each_ table, cross-checking for the
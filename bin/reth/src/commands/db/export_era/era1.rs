@@ -0,0 +1,242 @@
+//! Writer of the [era1](https://github.com/eth-clients/e2store-format-specs/blob/main/formats/era1.md)
+//! files.
+//!
+//! ```text
+//! era1 := Version | block-tuple* | other-entries* | Accumulator | BlockIndex
+//! block-tuple := CompressedHeader | CompressedBody | CompressedReceipts | TotalDifficulty
+//! ```
+
+use reth_primitives::{BlockBody, Receipt, SealedHeader, B256, U256};
+use sha2::{Digest, Sha256};
+use std::io::{self, Write};
+
+/// Maximum number of blocks in a single era1 file.
+pub(crate) const MAX_BLOCKS_PER_ERA1: u64 = 8192;
+
+/// Depth of the accumulator merkle tree, `log2(MAX_BLOCKS_PER_ERA1)`.
+const ACCUMULATOR_DEPTH: usize = 13;
+
+/// Size of the e2store entry header: type (2 bytes), length (4 bytes) and reserved (2 bytes).
+const ENTRY_HEADER_SIZE: u64 = 8;
+
+const TYPE_VERSION: u16 = 0x3265;
+const TYPE_COMPRESSED_HEADER: u16 = 0x03;
+const TYPE_COMPRESSED_BODY: u16 = 0x04;
+const TYPE_COMPRESSED_RECEIPTS: u16 = 0x05;
+const TYPE_TOTAL_DIFFICULTY: u16 = 0x06;
+const TYPE_ACCUMULATOR: u16 = 0x07;
+const TYPE_BLOCK_INDEX: u16 = 0x3266;
+
+/// Streams blocks into a single era1 file.
+#[derive(Debug)]
+pub(crate) struct Era1Writer<W> {
+    writer: W,
+    /// Number of bytes written so far.
+    written: u64,
+    /// Number of the first block in the file.
+    starting_number: u64,
+    /// Offsets of the block tuples from the beginning of the file.
+    offsets: Vec<u64>,
+    /// Block hashes and total difficulties of the written blocks, used for the accumulator.
+    records: Vec<(B256, U256)>,
+}
+
+impl<W: Write> Era1Writer<W> {
+    /// Create a new writer and write the version entry.
+    pub(crate) fn new(writer: W, starting_number: u64) -> io::Result<Self> {
+        let mut this =
+            Self { writer, written: 0, starting_number, offsets: Vec::new(), records: Vec::new() };
+        this.write_entry(TYPE_VERSION, &[])?;
+        Ok(this)
+    }
+
+    /// Returns the number of blocks written so far.
+    pub(crate) fn blocks(&self) -> u64 {
+        self.offsets.len() as u64
+    }
+
+    /// Append the block tuple to the file.
+    ///
+    /// Blocks must be appended in order, starting with the starting number of the writer.
+    pub(crate) fn append_block(
+        &mut self,
+        header: &SealedHeader,
+        body: &BlockBody,
+        receipts: Vec<Receipt>,
+        total_difficulty: U256,
+    ) -> io::Result<()> {
+        let expected = self.starting_number + self.blocks();
+        if header.number != expected || self.blocks() >= MAX_BLOCKS_PER_ERA1 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unexpected block {}, expected {expected}", header.number),
+            ))
+        }
+
+        let receipts = receipts.into_iter().map(Receipt::with_bloom).collect::<Vec<_>>();
+
+        self.offsets.push(self.written);
+        self.write_entry(TYPE_COMPRESSED_HEADER, &snappy(&alloy_rlp::encode(header.header()))?)?;
+        self.write_entry(TYPE_COMPRESSED_BODY, &snappy(&alloy_rlp::encode(body))?)?;
+        self.write_entry(TYPE_COMPRESSED_RECEIPTS, &snappy(&alloy_rlp::encode(receipts))?)?;
+        self.write_entry(TYPE_TOTAL_DIFFICULTY, &total_difficulty.to_le_bytes::<32>())?;
+        self.records.push((header.hash(), total_difficulty));
+
+        Ok(())
+    }
+
+    /// Write the accumulator and block index entries, and flush the writer.
+    ///
+    /// Returns the inner writer and the accumulator root.
+    pub(crate) fn finish(mut self) -> io::Result<(W, B256)> {
+        let accumulator_root = accumulator_root(&self.records);
+        self.write_entry(TYPE_ACCUMULATOR, accumulator_root.as_slice())?;
+
+        // Offsets in the block index are relative to the beginning of the block index entry.
+        let base = self.written as i64;
+        let mut index = Vec::with_capacity(16 + self.offsets.len() * 8);
+        index.extend_from_slice(&self.starting_number.to_le_bytes());
+        for offset in &self.offsets {
+            index.extend_from_slice(&(*offset as i64 - base).to_le_bytes());
+        }
+        index.extend_from_slice(&(self.offsets.len() as u64).to_le_bytes());
+        self.write_entry(TYPE_BLOCK_INDEX, &index)?;
+
+        self.writer.flush()?;
+        Ok((self.writer, accumulator_root))
+    }
+
+    /// Write the e2store entry.
+    fn write_entry(&mut self, entry_type: u16, data: &[u8]) -> io::Result<()> {
+        let length = u32::try_from(data.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "e2store entry too large"))?;
+
+        self.writer.write_all(&entry_type.to_le_bytes())?;
+        self.writer.write_all(&length.to_le_bytes())?;
+        self.writer.write_all(&[0; 2])?;
+        self.writer.write_all(data)?;
+        self.written += ENTRY_HEADER_SIZE + data.len() as u64;
+
+        Ok(())
+    }
+}
+
+/// Compress the data with the snappy framing format.
+fn snappy(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder.write_all(data)?;
+    encoder.into_inner().map_err(|err| io::Error::other(err.to_string()))
+}
+
+/// Calculate the SSZ `hash_tree_root` of the `List[HeaderRecord, MAX_BLOCKS_PER_ERA1]`, where
+/// `HeaderRecord` is a container of the block hash and the total difficulty.
+pub(crate) fn accumulator_root(records: &[(B256, U256)]) -> B256 {
+    let mut layer = records
+        .iter()
+        .map(|(hash, total_difficulty)| {
+            sha256_pair(hash.as_slice(), &total_difficulty.to_le_bytes::<32>())
+        })
+        .collect::<Vec<_>>();
+
+    let mut zero_hash = B256::ZERO;
+    for _ in 0..ACCUMULATOR_DEPTH {
+        if layer.len() % 2 == 1 {
+            layer.push(zero_hash);
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| sha256_pair(pair[0].as_slice(), pair[1].as_slice()))
+            .collect();
+        zero_hash = sha256_pair(zero_hash.as_slice(), zero_hash.as_slice());
+    }
+    let root = layer.first().copied().unwrap_or(zero_hash);
+
+    sha256_pair(root.as_slice(), &U256::from(records.len()).to_le_bytes::<32>())
+}
+
+fn sha256_pair(left: &[u8], right: &[u8]) -> B256 {
+    B256::from_slice(&Sha256::new().chain_update(left).chain_update(right).finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Header;
+
+    /// Read the e2store entries as `(offset, type, data)`.
+    fn read_entries(mut file: &[u8]) -> Vec<(u64, u16, Vec<u8>)> {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        while !file.is_empty() {
+            let entry_type = u16::from_le_bytes([file[0], file[1]]);
+            let length = u32::from_le_bytes([file[2], file[3], file[4], file[5]]) as usize;
+            assert_eq!(&file[6..8], &[0, 0]);
+            entries.push((offset, entry_type, file[8..8 + length].to_vec()));
+            offset += ENTRY_HEADER_SIZE + length as u64;
+            file = &file[8 + length..];
+        }
+        entries
+    }
+
+    #[test]
+    fn era1_layout() {
+        let starting_number = 2 * MAX_BLOCKS_PER_ERA1;
+        let mut writer = Era1Writer::new(Vec::new(), starting_number).unwrap();
+        let mut headers = Vec::new();
+        for number in starting_number..starting_number + 3 {
+            let header = Header { number, difficulty: U256::from(1), ..Default::default() };
+            let header = header.seal_slow();
+            writer
+                .append_block(&header, &BlockBody::default(), vec![], U256::from(number + 1))
+                .unwrap();
+            headers.push(header);
+        }
+
+        // Blocks must be appended in order.
+        let result = writer.append_block(&headers[0], &BlockBody::default(), vec![], U256::ZERO);
+        assert!(result.is_err());
+
+        let (file, root) = writer.finish().unwrap();
+        let entries = read_entries(&file);
+        let types = entries.iter().map(|(_, entry_type, _)| *entry_type).collect::<Vec<_>>();
+        assert_eq!(types.first(), Some(&TYPE_VERSION));
+        assert_eq!(&types[types.len() - 2..], &[TYPE_ACCUMULATOR, TYPE_BLOCK_INDEX]);
+        assert_eq!(types.len(), 1 + 3 * 4 + 2);
+
+        let (_, _, accumulator) = &entries[entries.len() - 2];
+        assert_eq!(accumulator.as_slice(), root.as_slice());
+        let records = headers
+            .iter()
+            .map(|header| (header.hash(), U256::from(header.number + 1)))
+            .collect::<Vec<_>>();
+        assert_eq!(root, accumulator_root(&records));
+
+        let (index_offset, _, index) = &entries[entries.len() - 1];
+        let index = index
+            .chunks(8)
+            .map(|chunk| i64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(index.first(), Some(&(starting_number as i64)));
+        assert_eq!(index.last(), Some(&3));
+        for (i, relative) in index[1..index.len() - 1].iter().enumerate() {
+            let offset = (*index_offset as i64 + relative) as u64;
+            let (_, entry_type, data) =
+                entries.iter().find(|(entry_offset, _, _)| *entry_offset == offset).unwrap();
+            assert_eq!(*entry_type, TYPE_COMPRESSED_HEADER);
+
+            let mut decoder = snap::read::FrameDecoder::new(data.as_slice());
+            let mut rlp = Vec::new();
+            std::io::Read::read_to_end(&mut decoder, &mut rlp).unwrap();
+            assert_eq!(rlp, alloy_rlp::encode(headers[i].header()));
+        }
+    }
+
+    #[test]
+    fn empty_accumulator_root() {
+        let mut zero_hash = B256::ZERO;
+        for _ in 0..ACCUMULATOR_DEPTH {
+            zero_hash = sha256_pair(zero_hash.as_slice(), zero_hash.as_slice());
+        }
+        assert_eq!(accumulator_root(&[]), sha256_pair(zero_hash.as_slice(), &[0; 32]));
+    }
+}
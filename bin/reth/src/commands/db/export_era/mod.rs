@@ -0,0 +1,136 @@
+//! Command exporting the block history into era1 files.
+
+use super::progress::CommandProgress;
+use crate::utils::DbTool;
+use clap::Parser;
+use era1::{Era1Writer, MAX_BLOCKS_PER_ERA1};
+use reth_db::database::Database;
+use reth_primitives::{hex, BlockBody};
+use reth_provider::{BlockNumReader, BlockReader, HeaderProvider, ReceiptProvider};
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+mod era1;
+
+/// The arguments for the `reth db export-era` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The directory to write the era1 files to.
+    #[arg(long, value_name = "DIR")]
+    path: PathBuf,
+
+    /// The first epoch to export. Each epoch contains 8192 blocks.
+    #[arg(long, default_value_t = 0)]
+    from_epoch: u64,
+
+    /// The last epoch to export.
+    ///
+    /// Defaults to the epoch of the last block in the database. The export always stops at the
+    /// merge, as era1 files only contain pre-merge blocks.
+    #[arg(long)]
+    to_epoch: Option<u64>,
+}
+
+impl Command {
+    /// Execute `db export-era` command
+    ///
+    /// The exported epochs are recorded in the progress checkpoint, so an interrupted export skips
+    /// them when it's resumed.
+    pub fn execute<DB: Database>(
+        self,
+        tool: &DbTool<DB>,
+        mut progress: CommandProgress,
+    ) -> eyre::Result<()> {
+        fs::create_dir_all(&self.path)?;
+
+        let provider = tool.provider_factory.provider()?;
+        let last_block = provider.last_block_number()?;
+        let last_epoch = last_block / MAX_BLOCKS_PER_ERA1;
+        let to_epoch = self.to_epoch.map_or(last_epoch, |to_epoch| to_epoch.min(last_epoch));
+        let network = tool.chain.chain.to_string();
+
+        for epoch in self.from_epoch..=to_epoch {
+            let step = format!("epoch {epoch}");
+            if progress.is_completed(&step) {
+                info!(target: "reth::cli", epoch, "Epoch exported by the previous run, skipping");
+                continue
+            }
+
+            let Some(file) = export_epoch(&provider, &self.path, &network, epoch, last_block)?
+            else {
+                info!(target: "reth::cli", epoch, "Reached the merge, stopping the export");
+                break
+            };
+            info!(target: "reth::cli", epoch, file = %file.display(), "Exported epoch");
+            progress.complete(&step)?;
+        }
+
+        progress.finish()
+    }
+}
+
+/// Export the blocks of the epoch into an era1 file in `dir`.
+///
+/// Era1 files only contain pre-merge blocks. Returns [None] if the first block of the epoch is
+/// already post-merge, otherwise returns the path of the written file.
+fn export_epoch<P>(
+    provider: &P,
+    dir: &Path,
+    network: &str,
+    epoch: u64,
+    last_block: u64,
+) -> eyre::Result<Option<PathBuf>>
+where
+    P: HeaderProvider + BlockReader + ReceiptProvider,
+{
+    let first_block = epoch * MAX_BLOCKS_PER_ERA1;
+    let last_block = last_block.min(first_block + MAX_BLOCKS_PER_ERA1 - 1);
+
+    // The file name contains the accumulator root, so the file is renamed after it's written.
+    let tmp_path = dir.join(format!("{network}-{epoch:05}.era1.tmp"));
+    let mut writer = Era1Writer::new(BufWriter::new(File::create(&tmp_path)?), first_block)?;
+
+    for number in first_block..=last_block {
+        let header = provider
+            .sealed_header(number)?
+            .ok_or_else(|| eyre::eyre!("Header not found in database: {number}"))?;
+        // Post-merge blocks have zero difficulty.
+        if header.difficulty.is_zero() {
+            break
+        }
+
+        let block = provider
+            .block(number.into())?
+            .ok_or_else(|| eyre::eyre!("Block not found in database: {number}"))?;
+        let body = BlockBody {
+            transactions: block.body,
+            ommers: block.ommers,
+            withdrawals: block.withdrawals,
+        };
+        let receipts = provider
+            .receipts_by_block(number.into())?
+            .ok_or_else(|| eyre::eyre!("Receipts not found in database: {number}"))?;
+        let total_difficulty = provider
+            .header_td_by_number(number)?
+            .ok_or_else(|| eyre::eyre!("Total difficulty not found in database: {number}"))?;
+
+        writer.append_block(&header, &body, receipts, total_difficulty)?;
+    }
+
+    if writer.blocks() == 0 {
+        drop(writer);
+        fs::remove_file(tmp_path)?;
+        return Ok(None)
+    }
+
+    let (_, accumulator_root) = writer.finish()?;
+    let path =
+        dir.join(format!("{network}-{epoch:05}-{}.era1", hex::encode(&accumulator_root[..4])));
+    fs::rename(tmp_path, &path)?;
+
+    Ok(Some(path))
+}
@@ -0,0 +1,689 @@
+//! Command exporting the execution data of a block range into typed tabular files.
+
+use super::export_snapshot::{csv_field, ExportFormat};
+use crate::utils::DbTool;
+use clap::{Parser, ValueEnum};
+use reth_db::database::Database;
+use reth_primitives::{Address, BlockNumber, TransactionSigned};
+use reth_provider::{
+    BlockHashReader, BlockNumReader, BlockReader, ReceiptProvider, TransactionVariant,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Version of the exported columns. Bumped whenever a column is added, removed or changes meaning.
+const SCHEMA_VERSION: u32 = 2;
+
+/// Name of the file describing the export.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Columns of the blocks dataset.
+const BLOCK_COLUMNS: &[Column] = &[
+    Column::new("number", ColumnType::UInt64),
+    Column::new("hash", ColumnType::String),
+    Column::new("parent_hash", ColumnType::String),
+    Column::new("timestamp", ColumnType::UInt64),
+    Column::new("beneficiary", ColumnType::String),
+    Column::new("gas_used", ColumnType::UInt64),
+    Column::new("gas_limit", ColumnType::UInt64),
+    Column::new("base_fee_per_gas", ColumnType::UInt64),
+    Column::new("transaction_count", ColumnType::UInt64),
+];
+
+/// Columns of the transactions dataset.
+const TRANSACTION_COLUMNS: &[Column] = &[
+    Column::new("block_number", ColumnType::UInt64),
+    Column::new("transaction_index", ColumnType::UInt64),
+    Column::new("hash", ColumnType::String),
+    Column::new("from", ColumnType::String),
+    Column::new("to", ColumnType::String),
+    Column::new("value", ColumnType::String),
+    Column::new("nonce", ColumnType::UInt64),
+    Column::new("gas_limit", ColumnType::UInt64),
+    Column::new("type", ColumnType::UInt64),
+    Column::new("input", ColumnType::String),
+];
+
+/// Columns of the receipts dataset.
+const RECEIPT_COLUMNS: &[Column] = &[
+    Column::new("block_number", ColumnType::UInt64),
+    Column::new("transaction_index", ColumnType::UInt64),
+    Column::new("transaction_hash", ColumnType::String),
+    Column::new("type", ColumnType::UInt64),
+    Column::new("success", ColumnType::Boolean),
+    Column::new("cumulative_gas_used", ColumnType::UInt64),
+    Column::new("log_count", ColumnType::UInt64),
+];
+
+/// Columns of the logs dataset.
+const LOG_COLUMNS: &[Column] = &[
+    Column::new("block_number", ColumnType::UInt64),
+    Column::new("transaction_index", ColumnType::UInt64),
+    Column::new("log_index", ColumnType::UInt64),
+    Column::new("address", ColumnType::String),
+    Column::new("topic0", ColumnType::String),
+    Column::new("topic1", ColumnType::String),
+    Column::new("topic2", ColumnType::String),
+    Column::new("topic3", ColumnType::String),
+    Column::new("data", ColumnType::String),
+];
+
+/// Column of an exported dataset.
+#[derive(Debug, Serialize)]
+struct Column {
+    name: &'static str,
+    #[serde(rename = "type")]
+    ty: ColumnType,
+}
+
+impl Column {
+    const fn new(name: &'static str, ty: ColumnType) -> Self {
+        Self { name, ty }
+    }
+}
+
+/// Type of the values of a column. Any column may have missing values.
+///
+/// Hashes, addresses and byte strings are hex encoded, and amounts that may not fit into 64 bits
+/// are decimal strings.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ColumnType {
+    UInt64,
+    Boolean,
+    String,
+}
+
+/// The arguments for the `reth db export-execution` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The directory to write the `<DATASET>.<FORMAT>` files and the manifest to.
+    #[arg(long, value_name = "DIR")]
+    output_dir: PathBuf,
+
+    /// The first block to export.
+    #[arg(long, default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block to export.
+    ///
+    /// Defaults to the last block in the database.
+    #[arg(long)]
+    to: Option<BlockNumber>,
+
+    /// The datasets to export.
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = Dataset::ALL)]
+    datasets: Vec<Dataset>,
+
+    /// The format of the exported datasets.
+    #[arg(long, value_enum, default_value_t = DatasetFormat::Csv)]
+    export_format: DatasetFormat,
+}
+
+/// File format of the exported datasets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DatasetFormat {
+    /// CSV with a header row.
+    Csv,
+    /// JSON lines with an object per row.
+    Jsonl,
+    /// Parquet with a typed column per dataset column. Requires the `parquet` feature.
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+impl DatasetFormat {
+    /// Returns the extension of the exported files.
+    const fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => ExportFormat::Csv.extension(),
+            Self::Jsonl => ExportFormat::Jsonl.extension(),
+            #[cfg(feature = "parquet")]
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+/// Execution data exported into a separate file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Dataset {
+    /// A row per block header.
+    Blocks,
+    /// A row per transaction.
+    Transactions,
+    /// A row per receipt.
+    Receipts,
+    /// A row per log.
+    Logs,
+}
+
+impl Dataset {
+    /// All datasets.
+    const ALL: [Self; 4] = [Self::Blocks, Self::Transactions, Self::Receipts, Self::Logs];
+
+    /// Returns the name of the dataset.
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Blocks => "blocks",
+            Self::Transactions => "transactions",
+            Self::Receipts => "receipts",
+            Self::Logs => "logs",
+        }
+    }
+
+    /// Returns the columns of the dataset rows.
+    const fn columns(&self) -> &'static [Column] {
+        match self {
+            Self::Blocks => BLOCK_COLUMNS,
+            Self::Transactions => TRANSACTION_COLUMNS,
+            Self::Receipts => RECEIPT_COLUMNS,
+            Self::Logs => LOG_COLUMNS,
+        }
+    }
+}
+
+/// Description of the export, written to [MANIFEST_FILE].
+#[derive(Debug, Serialize)]
+struct Manifest {
+    schema_version: u32,
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+    datasets: Vec<ManifestDataset>,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestDataset {
+    dataset: &'static str,
+    file: String,
+    columns: &'static [Column],
+    rows: usize,
+}
+
+impl Command {
+    /// Execute `db export-execution` command
+    pub fn execute<DB: Database>(self, tool: &DbTool<DB>) -> eyre::Result<()> {
+        fs::create_dir_all(&self.output_dir)?;
+
+        let provider = tool.provider_factory.provider()?;
+        let last_block = provider.last_block_number()?;
+        let to = self.to.map_or(last_block, |to| to.min(last_block));
+        if self.from > to {
+            eyre::bail!("The first block {} is after the last block {to}", self.from)
+        }
+
+        let mut writers = self
+            .datasets
+            .iter()
+            .map(|dataset| {
+                let file = format!("{}.{}", dataset.name(), self.export_format.extension());
+                let writer = RowWriter::create(
+                    &self.output_dir.join(&file),
+                    self.export_format,
+                    dataset.columns(),
+                )?;
+                Ok((*dataset, file, writer))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        let needs_receipts = self
+            .datasets
+            .iter()
+            .any(|dataset| matches!(dataset, Dataset::Receipts | Dataset::Logs));
+        for number in self.from..=to {
+            let block = provider
+                .block_with_senders(number.into(), TransactionVariant::WithHash)?
+                .ok_or_else(|| eyre::eyre!("Block not found in database: {number}"))?;
+            let hash = provider
+                .block_hash(number)?
+                .ok_or_else(|| eyre::eyre!("Block hash not found in database: {number}"))?;
+            let receipts = if needs_receipts {
+                provider
+                    .receipts_by_block(number.into())?
+                    .ok_or_else(|| eyre::eyre!("Receipts not found in database: {number}"))?
+            } else {
+                Vec::new()
+            };
+
+            for (dataset, _, writer) in &mut writers {
+                match dataset {
+                    Dataset::Blocks => {
+                        let header = &block.block.header;
+                        writer.write(&[
+                            json!(number),
+                            json!(hash),
+                            json!(header.parent_hash),
+                            json!(header.timestamp),
+                            json!(header.beneficiary),
+                            json!(header.gas_used),
+                            json!(header.gas_limit),
+                            json!(header.base_fee_per_gas),
+                            json!(block.block.body.len()),
+                        ])?;
+                    }
+                    Dataset::Transactions => {
+                        for (index, (transaction, sender)) in
+                            block.block.body.iter().zip(&block.senders).enumerate()
+                        {
+                            writer.write(&transaction_row(number, index, transaction, sender))?;
+                        }
+                    }
+                    Dataset::Receipts => {
+                        for (index, (receipt, transaction)) in
+                            receipts.iter().zip(&block.block.body).enumerate()
+                        {
+                            writer.write(&[
+                                json!(number),
+                                json!(index),
+                                json!(transaction.hash()),
+                                json!(u8::from(receipt.tx_type)),
+                                json!(receipt.success),
+                                json!(receipt.cumulative_gas_used),
+                                json!(receipt.logs.len()),
+                            ])?;
+                        }
+                    }
+                    Dataset::Logs => {
+                        let logs = receipts.iter().enumerate().flat_map(|(index, receipt)| {
+                            receipt.logs.iter().map(move |log| (index, log))
+                        });
+                        // Log indices are counted from the start of the block, as in RPC.
+                        for (log_index, (index, log)) in logs.enumerate() {
+                            let topic = |i: usize| json!(log.topics.get(i));
+                            writer.write(&[
+                                json!(number),
+                                json!(index),
+                                json!(log_index),
+                                json!(log.address),
+                                topic(0),
+                                topic(1),
+                                topic(2),
+                                topic(3),
+                                json!(log.data),
+                            ])?;
+                        }
+                    }
+                }
+            }
+
+            if number % 10_000 == 0 {
+                info!(target: "reth::cli", block = number, to, "Exporting execution data");
+            }
+        }
+
+        let mut manifest = Manifest {
+            schema_version: SCHEMA_VERSION,
+            from_block: self.from,
+            to_block: to,
+            datasets: Vec::new(),
+        };
+        for (dataset, file, writer) in writers {
+            let rows = writer.finish()?;
+            info!(target: "reth::cli", dataset = dataset.name(), rows, "Exported dataset");
+            manifest.datasets.push(ManifestDataset {
+                dataset: dataset.name(),
+                file,
+                columns: dataset.columns(),
+                rows,
+            });
+        }
+        fs::write(self.output_dir.join(MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?)?;
+
+        Ok(())
+    }
+}
+
+/// Returns the row of the transaction in the transactions dataset.
+fn transaction_row(
+    block_number: BlockNumber,
+    index: usize,
+    transaction: &TransactionSigned,
+    sender: &Address,
+) -> [Value; 10] {
+    [
+        json!(block_number),
+        json!(index),
+        json!(transaction.hash()),
+        json!(sender),
+        json!(transaction.to()),
+        // Decimal, as the hex encoding of large values is awkward to process.
+        json!(transaction.value().to_string()),
+        json!(transaction.nonce()),
+        json!(transaction.gas_limit()),
+        json!(u8::from(transaction.tx_type())),
+        json!(transaction.input()),
+    ]
+}
+
+/// Writes rows with a fixed set of columns to a file of the export format.
+enum RowWriter {
+    Text(TextRowWriter),
+    #[cfg(feature = "parquet")]
+    Parquet(parquet_writer::ParquetRowWriter),
+}
+
+impl RowWriter {
+    /// Creates the file of the dataset.
+    fn create(
+        path: &Path,
+        format: DatasetFormat,
+        columns: &'static [Column],
+    ) -> eyre::Result<Self> {
+        Ok(match format {
+            DatasetFormat::Csv => {
+                Self::Text(TextRowWriter::create(path, ExportFormat::Csv, columns)?)
+            }
+            DatasetFormat::Jsonl => {
+                Self::Text(TextRowWriter::create(path, ExportFormat::Jsonl, columns)?)
+            }
+            #[cfg(feature = "parquet")]
+            DatasetFormat::Parquet => {
+                Self::Parquet(parquet_writer::ParquetRowWriter::create(path, columns)?)
+            }
+        })
+    }
+
+    /// Writes a row with a value for each column.
+    fn write(&mut self, values: &[Value]) -> eyre::Result<()> {
+        match self {
+            Self::Text(writer) => writer.write(values),
+            #[cfg(feature = "parquet")]
+            Self::Parquet(writer) => writer.write(values),
+        }
+    }
+
+    /// Finishes the file and returns the number of written rows.
+    fn finish(self) -> eyre::Result<usize> {
+        match self {
+            Self::Text(writer) => writer.finish(),
+            #[cfg(feature = "parquet")]
+            Self::Parquet(writer) => writer.finish(),
+        }
+    }
+}
+
+/// Writes rows with a fixed set of columns to a CSV or JSON lines file.
+struct TextRowWriter {
+    writer: BufWriter<File>,
+    format: ExportFormat,
+    columns: &'static [Column],
+    rows: usize,
+}
+
+impl TextRowWriter {
+    /// Creates the file, writing the header if it's a CSV file.
+    fn create(path: &Path, format: ExportFormat, columns: &'static [Column]) -> eyre::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        if format == ExportFormat::Csv {
+            let header = columns.iter().map(|column| column.name).collect::<Vec<_>>();
+            writeln!(writer, "{}", header.join(","))?;
+        }
+        Ok(Self { writer, format, columns, rows: 0 })
+    }
+
+    /// Writes a row with a value for each column.
+    fn write(&mut self, values: &[Value]) -> eyre::Result<()> {
+        debug_assert_eq!(values.len(), self.columns.len());
+        match self.format {
+            ExportFormat::Csv => {
+                let cells = values.iter().map(csv_cell).collect::<Vec<_>>();
+                writeln!(self.writer, "{}", cells.join(","))?;
+            }
+            ExportFormat::Jsonl => {
+                let row = self
+                    .columns
+                    .iter()
+                    .map(|column| column.name.to_string())
+                    .zip(values.iter().cloned())
+                    .collect::<serde_json::Map<_, _>>();
+                writeln!(self.writer, "{}", Value::Object(row))?;
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Flushes the file and returns the number of written rows.
+    fn finish(mut self) -> eyre::Result<usize> {
+        self.writer.flush()?;
+        Ok(self.rows)
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_writer {
+    use super::{Column, ColumnType, SCHEMA_VERSION};
+    use arrow::{
+        array::{ArrayRef, BooleanBuilder, StringBuilder, UInt64Builder},
+        datatypes::{DataType, Field, Schema},
+        record_batch::RecordBatch,
+    };
+    use parquet::{
+        arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties,
+        format::KeyValue,
+    };
+    use serde_json::Value;
+    use std::{fs::File, path::Path, sync::Arc};
+
+    /// Number of rows buffered in memory and written as a row group.
+    const ROW_GROUP_SIZE: usize = 64 * 1024;
+
+    /// Key of the Parquet file metadata holding the [SCHEMA_VERSION].
+    const SCHEMA_VERSION_KEY: &str = "reth.schema_version";
+
+    impl ColumnType {
+        const fn data_type(&self) -> DataType {
+            match self {
+                Self::UInt64 => DataType::UInt64,
+                Self::Boolean => DataType::Boolean,
+                Self::String => DataType::Utf8,
+            }
+        }
+    }
+
+    /// Writes rows with a fixed set of typed columns to a Parquet file.
+    pub(super) struct ParquetRowWriter {
+        writer: ArrowWriter<File>,
+        schema: Arc<Schema>,
+        builders: Vec<ColumnBuilder>,
+        buffered: usize,
+        rows: usize,
+    }
+
+    impl ParquetRowWriter {
+        /// Creates the file with the schema of the columns.
+        pub(super) fn create(path: &Path, columns: &'static [Column]) -> eyre::Result<Self> {
+            let schema = Arc::new(Schema::new(
+                columns
+                    .iter()
+                    .map(|column| Field::new(column.name, column.ty.data_type(), true))
+                    .collect::<Vec<_>>(),
+            ));
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .set_key_value_metadata(Some(vec![KeyValue::new(
+                    SCHEMA_VERSION_KEY.to_string(),
+                    SCHEMA_VERSION.to_string(),
+                )]))
+                .build();
+            let writer =
+                ArrowWriter::try_new(File::create(path)?, schema.clone(), Some(properties))?;
+            let builders = columns.iter().map(|column| ColumnBuilder::new(column.ty)).collect();
+            Ok(Self { writer, schema, builders, buffered: 0, rows: 0 })
+        }
+
+        /// Buffers a row with a value for each column, writing a row group once enough rows
+        /// are buffered.
+        pub(super) fn write(&mut self, values: &[Value]) -> eyre::Result<()> {
+            debug_assert_eq!(values.len(), self.builders.len());
+            for (builder, value) in self.builders.iter_mut().zip(values) {
+                builder.append(value)?;
+            }
+            self.buffered += 1;
+            self.rows += 1;
+            if self.buffered == ROW_GROUP_SIZE {
+                self.flush()?;
+            }
+            Ok(())
+        }
+
+        /// Writes the buffered rows as a row group.
+        fn flush(&mut self) -> eyre::Result<()> {
+            let columns = self.builders.iter_mut().map(ColumnBuilder::finish).collect();
+            self.writer.write(&RecordBatch::try_new(self.schema.clone(), columns)?)?;
+            self.buffered = 0;
+            Ok(())
+        }
+
+        /// Writes the remaining rows and the file footer, and returns the number of written rows.
+        pub(super) fn finish(mut self) -> eyre::Result<usize> {
+            if self.buffered > 0 {
+                self.flush()?;
+            }
+            self.writer.close()?;
+            Ok(self.rows)
+        }
+    }
+
+    /// Builder of the array of a column.
+    enum ColumnBuilder {
+        UInt64(UInt64Builder),
+        Boolean(BooleanBuilder),
+        String(StringBuilder),
+    }
+
+    impl ColumnBuilder {
+        fn new(ty: ColumnType) -> Self {
+            match ty {
+                ColumnType::UInt64 => Self::UInt64(UInt64Builder::new()),
+                ColumnType::Boolean => Self::Boolean(BooleanBuilder::new()),
+                ColumnType::String => Self::String(StringBuilder::new()),
+            }
+        }
+
+        /// Appends the value, or a null if the value is missing.
+        fn append(&mut self, value: &Value) -> eyre::Result<()> {
+            match (self, value) {
+                (Self::UInt64(builder), Value::Null) => builder.append_null(),
+                (Self::Boolean(builder), Value::Null) => builder.append_null(),
+                (Self::String(builder), Value::Null) => builder.append_null(),
+                (Self::UInt64(builder), Value::Number(number)) if number.is_u64() => {
+                    builder.append_option(number.as_u64())
+                }
+                (Self::Boolean(builder), Value::Bool(value)) => builder.append_value(*value),
+                (Self::String(builder), Value::String(value)) => builder.append_value(value),
+                (builder, value) => {
+                    eyre::bail!("Unexpected value {value} for a {:?} column", builder.ty())
+                }
+            }
+            Ok(())
+        }
+
+        /// Returns the array of the appended values and resets the builder.
+        fn finish(&mut self) -> ArrayRef {
+            match self {
+                Self::UInt64(builder) => Arc::new(builder.finish()),
+                Self::Boolean(builder) => Arc::new(builder.finish()),
+                Self::String(builder) => Arc::new(builder.finish()),
+            }
+        }
+
+        const fn ty(&self) -> ColumnType {
+            match self {
+                Self::UInt64(_) => ColumnType::UInt64,
+                Self::Boolean(_) => ColumnType::Boolean,
+                Self::String(_) => ColumnType::String,
+            }
+        }
+    }
+}
+
+/// Formats the value as a CSV cell. Missing values are left empty.
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => csv_field(value),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_datasets() {
+        let cmd = Command::try_parse_from(["reth", "--output-dir", "out"]).unwrap();
+        assert_eq!(cmd.datasets, Dataset::ALL);
+
+        let cmd =
+            Command::try_parse_from(["reth", "--output-dir", "out", "--datasets", "blocks,logs"])
+                .unwrap();
+        assert_eq!(cmd.datasets, vec![Dataset::Blocks, Dataset::Logs]);
+    }
+
+    #[test]
+    fn csv_cells() {
+        assert_eq!(csv_cell(&Value::Null), "");
+        assert_eq!(csv_cell(&json!(1)), "1");
+        assert_eq!(csv_cell(&json!(true)), "true");
+        assert_eq!(csv_cell(&json!("0x01")), "\"0x01\"");
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_rows() {
+        use arrow::{
+            array::{Array, AsArray},
+            datatypes::UInt64Type,
+        };
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("receipts.parquet");
+        let mut writer = RowWriter::create(&path, DatasetFormat::Parquet, RECEIPT_COLUMNS).unwrap();
+        writer
+            .write(&[
+                json!(1),
+                json!(0),
+                json!("0x01"),
+                json!(2),
+                json!(true),
+                json!(21000),
+                json!(1),
+            ])
+            .unwrap();
+        writer
+            .write(&[
+                json!(1),
+                json!(1),
+                Value::Null,
+                json!(0),
+                json!(false),
+                json!(42000),
+                json!(0),
+            ])
+            .unwrap();
+        assert!(writer.write(&[json!("1"); 7]).is_err());
+        assert_eq!(writer.finish().unwrap(), 2);
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata().file_metadata().key_value_metadata().unwrap();
+        assert!(metadata.iter().any(|kv| kv.key == "reth.schema_version" &&
+            kv.value.as_deref() == Some(SCHEMA_VERSION.to_string().as_str())));
+        let batches = reader.build().unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(&batch.column(1).as_primitive::<UInt64Type>().values()[..], &[0, 1]);
+        let hashes = batch.column(2).as_string::<i32>();
+        assert_eq!(hashes.value(0), "0x01");
+        assert!(hashes.is_null(1));
+        assert!(batch.column(4).as_boolean().value(0));
+    }
+}
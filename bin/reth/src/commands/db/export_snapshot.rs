@@ -0,0 +1,176 @@
+//! Command exporting tables from a single consistent snapshot of the database.
+
+use crate::utils::DbTool;
+use clap::{Parser, ValueEnum};
+use reth_db::{
+    cursor::DbCursorRO, database::Database, table::Table, tables, transaction::DbTx, TableViewer,
+    Tables,
+};
+use reth_primitives::stage::StageId;
+use serde::Serialize;
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Name of the file describing the exported snapshot.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// The arguments for the `reth db export-snapshot` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The tables to export.
+    ///
+    /// Each entry is exported as a row with the JSON-encoded `key` and `value`.
+    #[arg(long, value_delimiter = ',', required = true)]
+    tables: Vec<Tables>,
+
+    /// The directory to write the `<TABLE>.<FORMAT>` files and the manifest to.
+    #[arg(long, value_name = "DIR")]
+    output_dir: PathBuf,
+
+    /// The format of the exported tables.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    export_format: ExportFormat,
+}
+
+/// File format of the exported tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ExportFormat {
+    /// CSV with a header row.
+    Csv,
+    /// JSON lines with an object per row.
+    Jsonl,
+}
+
+impl ExportFormat {
+    /// Returns the extension of the exported files.
+    pub(crate) const fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// Description of the exported snapshot, written to [MANIFEST_FILE].
+#[derive(Debug, Serialize)]
+struct Manifest {
+    /// Block number of the `Finish` stage checkpoint at the time of the snapshot.
+    finish_checkpoint: Option<u64>,
+    /// The exported tables and their entry count.
+    tables: Vec<ManifestTable>,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestTable {
+    table: &'static str,
+    file: String,
+    entries: usize,
+}
+
+impl Command {
+    /// Execute `db export-snapshot` command
+    ///
+    /// All tables are read from the same read-only transaction, so they come from the same
+    /// committed state of the database even if the node is writing to it during the export.
+    pub fn execute<DB: Database>(self, tool: &DbTool<DB>) -> eyre::Result<()> {
+        fs::create_dir_all(&self.output_dir)?;
+
+        let mut tx = tool.provider_factory.db_ref().tx()?;
+        // Long exports must not be aborted by the read transaction timeout.
+        tx.disable_long_read_transaction_safety();
+
+        let finish_checkpoint = tx
+            .get::<tables::StageCheckpoints>(StageId::Finish.to_string())?
+            .map(|checkpoint| checkpoint.block_number);
+        info!(target: "reth::cli", ?finish_checkpoint, "Opened database snapshot");
+
+        let mut manifest = Manifest { finish_checkpoint, tables: Vec::new() };
+        for table in &self.tables {
+            let file = format!("{}.{}", table.name(), self.export_format.extension());
+            let entries = table.view(&ExportTableViewer {
+                tx: &tx,
+                path: &self.output_dir.join(&file),
+                format: self.export_format,
+            })?;
+            info!(target: "reth::cli", table = table.name(), entries, "Exported table");
+            manifest.tables.push(ManifestTable { table: table.name(), file, entries });
+        }
+        tx.commit()?;
+
+        fs::write(self.output_dir.join(MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?)?;
+        info!(target: "reth::cli", path = %self.output_dir.display(), "Exported snapshot");
+
+        Ok(())
+    }
+}
+
+/// Writes all entries of the table to a file. Returns the number of written entries.
+struct ExportTableViewer<'a, TX> {
+    tx: &'a TX,
+    path: &'a Path,
+    format: ExportFormat,
+}
+
+impl<TX: DbTx> TableViewer<usize> for ExportTableViewer<'_, TX> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<usize, Self::Error> {
+        let mut writer = BufWriter::new(File::create(self.path)?);
+        if self.format == ExportFormat::Csv {
+            writeln!(writer, "key,value")?;
+        }
+
+        let mut entries = 0;
+        for entry in self.tx.cursor_read::<T>()?.walk(None)? {
+            let (key, value) = entry?;
+            match self.format {
+                ExportFormat::Csv => writeln!(
+                    writer,
+                    "{},{}",
+                    csv_field(&serde_json::to_string(&key)?),
+                    csv_field(&serde_json::to_string(&value)?)
+                )?,
+                ExportFormat::Jsonl => {
+                    writeln!(writer, "{}", serde_json::json!({ "key": key, "value": value }))?
+                }
+            }
+            entries += 1;
+        }
+        writer.flush()?;
+
+        Ok(entries)
+    }
+}
+
+/// Quotes the CSV field, escaping the quotes inside it.
+pub(crate) fn csv_field(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tables() {
+        let cmd = Command::try_parse_from([
+            "reth",
+            "--tables",
+            "PlainAccountState,PlainStorageState",
+            "--output-dir",
+            "out",
+        ])
+        .unwrap();
+        assert_eq!(cmd.tables, vec![Tables::PlainAccountState, Tables::PlainStorageState]);
+        assert_eq!(cmd.export_format, ExportFormat::Csv);
+    }
+
+    #[test]
+    fn csv_field_escapes_quotes() {
+        assert_eq!(csv_field(r#"{"nonce":1}"#), r#""{""nonce"":1}""#);
+    }
+}
@@ -1,17 +1,41 @@
-use super::tui::DbListTUI;
+use super::{
+    output::{print_json, OutputFormat},
+    tui::DbListTUI,
+};
 use crate::utils::{DbTool, ListFilter};
 use clap::Parser;
-use eyre::WrapErr;
-use reth_db::{database::Database, table::Table, DatabaseEnv, RawValue, TableViewer, Tables};
+use regex::Regex;
+use reth_db::{database::Database, table::Table, transaction::DbTx, RawValue, TableViewer, Tables};
 use reth_primitives::hex;
-use std::cell::RefCell;
-use tracing::error;
+use serde::{
+    ser::{Error as _, SerializeSeq},
+    Serialize, Serializer,
+};
+use std::{
+    cell::RefCell,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    marker::PhantomData,
+    path::PathBuf,
+};
+use tracing::{error, info};
 
 #[derive(Parser, Debug)]
 /// The arguments for the `reth db list` command
 pub struct Command {
     /// The table name
-    table: Tables,
+    #[arg(required_unless_present = "table_regex")]
+    table: Option<Tables>,
+    /// List all tables with names matching the regular expression instead of a single table.
+    ///
+    /// The entries of each table are dumped as JSON, with the same filters applied to every
+    /// table.
+    #[arg(long, value_name = "REGEX", conflicts_with = "table")]
+    table_regex: Option<Regex>,
+    /// Write the entries of the tables matching `--table-regex` to `<DIR>/<TABLE>.json` files
+    /// instead of stdout.
+    #[arg(long, value_name = "DIR", requires = "table_regex")]
+    output_dir: Option<PathBuf>,
     /// Skip first N entries
     #[arg(long, short, default_value_t = 0)]
     skip: usize,
@@ -40,18 +64,52 @@ pub struct Command {
     /// Returns the number of rows found.
     #[arg(long, short)]
     count: bool,
-    /// Dump as JSON instead of using TUI.
+    /// Dump as JSON instead of using TUI. Implied by `--format json`.
     #[arg(long, short)]
     json: bool,
     /// Output bytes instead of human-readable decoded value
     #[arg(long)]
     raw: bool,
+    /// The output format of the `db` command.
+    #[arg(skip)]
+    format: OutputFormat,
 }
 
 impl Command {
     /// Execute `db list` command
-    pub fn execute(self, tool: &DbTool<DatabaseEnv>) -> eyre::Result<()> {
-        self.table.view(&ListTableViewer { tool, args: &self })
+    pub fn execute<DB: Database>(
+        mut self,
+        tool: &DbTool<DB>,
+        format: OutputFormat,
+    ) -> eyre::Result<()> {
+        self.format = format;
+        self.json |= format.is_json();
+        if let Some(regex) = &self.table_regex {
+            return self.list_matching_tables(tool, regex)
+        }
+        let table = self.table.ok_or_else(|| eyre::eyre!("Table name is required"))?;
+        table.view(&ListTableViewer { tool, args: &self, table })
+    }
+
+    /// List all tables with names matching the regular expression.
+    fn list_matching_tables<DB: Database>(
+        &self,
+        tool: &DbTool<DB>,
+        regex: &Regex,
+    ) -> eyre::Result<()> {
+        let tables =
+            Tables::ALL.iter().filter(|table| regex.is_match(table.name())).collect::<Vec<_>>();
+        if tables.is_empty() {
+            eyre::bail!("No tables match the regular expression {regex}")
+        }
+        if let Some(output_dir) = &self.output_dir {
+            fs::create_dir_all(output_dir)?;
+        }
+
+        for table in tables {
+            table.view(&BulkListTableViewer { tool, args: self, table: *table })?;
+        }
+        Ok(())
     }
 
     /// Generate [`ListFilter`] from command.
@@ -80,19 +138,18 @@ impl Command {
     }
 }
 
-struct ListTableViewer<'a> {
-    tool: &'a DbTool<DatabaseEnv>,
+struct ListTableViewer<'a, DB: Database> {
+    tool: &'a DbTool<DB>,
     args: &'a Command,
+    table: Tables,
 }
 
-impl TableViewer<()> for ListTableViewer<'_> {
+impl<DB: Database> TableViewer<()> for ListTableViewer<'_, DB> {
     type Error = eyre::Report;
 
     fn view<T: Table>(&self) -> Result<(), Self::Error> {
         self.tool.provider_factory.db_ref().view(|tx| {
-            let table_db = tx.inner.open_db(Some(self.args.table.name())).wrap_err("Could not open db.")?;
-            let stats = tx.inner.db_stat(&table_db).wrap_err(format!("Could not find table: {}", stringify!($table)))?;
-            let total_entries = stats.entries();
+            let total_entries = tx.entries::<T>()?;
             let final_entry_idx = total_entries.saturating_sub(1);
             if self.args.skip > final_entry_idx {
                 error!(
@@ -100,7 +157,7 @@ impl TableViewer<()> for ListTableViewer<'_> {
                     "Start index {start} is greater than the final entry index ({final_entry_idx}) in the table {table}",
                     start = self.args.skip,
                     final_entry_idx = final_entry_idx,
-                    table = self.args.table.name()
+                    table = self.table.name()
                 );
                 return Ok(())
             }
@@ -111,7 +168,10 @@ impl TableViewer<()> for ListTableViewer<'_> {
             if self.args.json || self.args.count {
                 let (list, count) = self.tool.list::<T>(&list_filter)?;
 
-                if self.args.count {
+                if self.args.count && self.args.format.is_json() {
+                    let count = serde_json::json!({ "table": self.table.name(), "count": count });
+                    print_json(&count)?;
+                } else if self.args.count {
                     println!("{count} entries found.")
                 } else if self.args.raw {
                     let list = list.into_iter().map(|row| (row.0, RawValue::new(row.1).into_value())).collect::<Vec<_>>();
@@ -132,3 +192,89 @@ impl TableViewer<()> for ListTableViewer<'_> {
         Ok(())
     }
 }
+
+/// Dumps the entries of one of the tables listed with `--table-regex`.
+struct BulkListTableViewer<'a, DB: Database> {
+    tool: &'a DbTool<DB>,
+    args: &'a Command,
+    table: Tables,
+}
+
+impl<DB: Database> TableViewer<()> for BulkListTableViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        let list_filter = self.args.list_filter();
+        if self.args.count {
+            let count = self.tool.for_each::<T>(&list_filter, |_| Ok(()))?;
+            if self.args.format.is_json() {
+                return print_json(
+                    &serde_json::json!({ "table": self.table.name(), "count": count }),
+                )
+            }
+            println!("{}: {count} entries found.", self.table.name());
+            return Ok(())
+        }
+
+        let entries = TableEntries::<DB, T> {
+            tool: self.tool,
+            filter: list_filter,
+            raw: self.args.raw,
+            _table: PhantomData,
+        };
+        match &self.args.output_dir {
+            Some(output_dir) => {
+                let path = output_dir.join(format!("{}.json", self.table.name()));
+                let mut file = BufWriter::new(File::create(&path)?);
+                serde_json::to_writer_pretty(&mut file, &entries)?;
+                file.flush()?;
+                info!(target: "reth::cli", table = self.table.name(), ?path, "Wrote table entries");
+            }
+            None => {
+                let mut stdout = std::io::stdout().lock();
+                serde_json::to_writer_pretty(
+                    &mut stdout,
+                    &TableDump { table: self.table.name(), entries },
+                )?;
+                writeln!(stdout)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Entries of a table dumped with `--table-regex`.
+#[derive(Serialize)]
+struct TableDump<'a, E> {
+    table: &'a str,
+    entries: E,
+}
+
+/// Serializes the entries of the table matching the filter as a sequence, writing every entry
+/// as the table is walked instead of collecting them first.
+struct TableEntries<'a, DB: Database, T: Table> {
+    tool: &'a DbTool<DB>,
+    filter: ListFilter,
+    raw: bool,
+    _table: PhantomData<T>,
+}
+
+impl<DB: Database, T: Table> Serialize for TableEntries<'_, DB, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        let mut error = None;
+        let result = self.tool.for_each::<T>(&self.filter, |(key, value)| {
+            let result = if self.raw {
+                seq.serialize_element(&(key, RawValue::new(value).into_value()))
+            } else {
+                seq.serialize_element(&(key, value))
+            };
+            result.map_err(|err| eyre::eyre!("{}", error.insert(err)))
+        });
+        if let Some(error) = error {
+            return Err(error)
+        }
+        result.map_err(S::Error::custom)?;
+        seq.end()
+    }
+}
@@ -9,9 +9,12 @@ use crate::{
     utils::DbTool,
 };
 use clap::{Parser, Subcommand};
+use output::{print_json, OutputFormat};
+use progress::{CommandProgress, DatabaseState};
+use reth_config::{config::EtlConfig, Config};
 use reth_db::{
-    open_db, open_db_read_only,
-    version::{get_db_version, DatabaseVersionError, DB_VERSION},
+    init_db, open_db, open_db_read_only,
+    version::{check_db_version_file, get_db_version, DatabaseVersionError, DB_VERSION},
 };
 use reth_primitives::ChainSpec;
 use reth_provider::ProviderFactory;
@@ -22,12 +25,23 @@ use std::{
 
 mod clear;
 mod diff;
+mod export_era;
+mod export_execution;
+mod export_snapshot;
 mod get;
 mod list;
+mod output;
+mod progress;
+mod rebuild_index;
+mod replay_log;
+mod seek;
+mod serve;
 mod static_files;
 mod stats;
 /// DB List TUI
 mod tui;
+mod verify;
+mod watch;
 
 /// `reth db` command
 #[derive(Debug, Parser)]
@@ -58,6 +72,11 @@ pub struct Command {
     #[command(flatten)]
     db: DatabaseArgs,
 
+    /// The output format of the `stats`, `list`, `diff`, `verify`, `version` and `path`
+    /// subcommands.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    format: OutputFormat,
+
     #[command(subcommand)]
     command: Subcommands,
 }
@@ -69,10 +88,15 @@ pub enum Subcommands {
     Stats(stats::Command),
     /// Lists the contents of a table
     List(list::Command),
+    /// Periodically prints the entry count and size of each table, with the changes since the
+    /// previous sample
+    Watch(watch::Command),
     /// Create a diff between two database tables or two entire databases.
     Diff(diff::Command),
     /// Gets the content of a table for the given key
     Get(get::Command),
+    /// Seeks to the given raw key in a table and prints the neighboring raw entries
+    Seek(seek::Command),
     /// Deletes all database entries
     Drop {
         /// Bypasses the interactive confirmation and drops the database directly
@@ -83,6 +107,25 @@ pub enum Subcommands {
     Clear(clear::Command),
     /// Creates static files from database tables
     CreateStaticFiles(static_files::Command),
+    /// Exports the pre-merge block history into era1 files
+    ExportEra(export_era::Command),
+    /// Exports tables from a single consistent snapshot of the database into CSV or JSON lines
+    /// files
+    ExportSnapshot(export_snapshot::Command),
+    /// Exports the blocks, transactions, receipts and logs of a block range into CSV, JSON lines
+    /// or Parquet files with typed columns
+    ExportExecution(export_execution::Command),
+    /// Serves read-only access to the database tables over JSON-RPC
+    Serve(serve::Command),
+    /// Replays the committed transactions of an operation log against the database
+    ReplayLog(replay_log::Command),
+    /// Checks the database version, tables and read-write round trip without modifying it
+    Check,
+    /// Verifies the tables derived by the pipeline stages against the data they are derived from
+    Verify(verify::Command),
+    /// Clears a history or transaction lookup index and rebuilds it from the change sets or the
+    /// transactions
+    RebuildIndex(rebuild_index::Command),
     /// Lists current and local database versions
     Version,
     /// Returns the full database path
@@ -113,17 +156,22 @@ impl Command {
             // TODO: We'll need to add this on the DB trait.
             Subcommands::Stats(command) => {
                 db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
-                    command.execute(data_dir, &tool)?;
+                    command.execute(data_dir, &tool, self.format)?;
                 });
             }
             Subcommands::List(command) => {
                 db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
-                    command.execute(&tool)?;
+                    command.execute(&tool, self.format)?;
+                });
+            }
+            Subcommands::Watch(command) => {
+                db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
+                    command.execute(&tool).await?;
                 });
             }
             Subcommands::Diff(command) => {
                 db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
-                    command.execute(&tool)?;
+                    command.execute(&tool, self.format)?;
                 });
             }
             Subcommands::Get(command) => {
@@ -131,6 +179,11 @@ impl Command {
                     command.execute(&tool)?;
                 });
             }
+            Subcommands::Seek(command) => {
+                db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
+                    command.execute(&tool)?;
+                });
+            }
             Subcommands::Drop { force } => {
                 if !force {
                     // Ask for confirmation
@@ -164,6 +217,75 @@ impl Command {
             Subcommands::CreateStaticFiles(command) => {
                 command.execute(data_dir, self.db.database_args(), self.chain.clone())?;
             }
+            Subcommands::ExportEra(command) => {
+                let progress =
+                    CommandProgress::load(&data_dir.db_progress_path(), "export-era", &command)?;
+                db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
+                    command.execute(&tool, progress)?;
+                });
+            }
+            Subcommands::ExportSnapshot(command) => {
+                db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
+                    command.execute(&tool)?;
+                });
+            }
+            Subcommands::ExportExecution(command) => {
+                db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
+                    command.execute(&tool)?;
+                });
+            }
+            Subcommands::Serve(command) => {
+                let db = open_db_read_only(&db_path, db_args)?;
+                command.execute(db).await?;
+            }
+            Subcommands::ReplayLog(command) => {
+                let db = init_db(&db_path, db_args)?;
+                command.execute(&db)?;
+            }
+            Subcommands::Check => {
+                check_db_version_file(&db_path)?;
+                let db = open_db(&db_path, db_args)?;
+                let provider_factory =
+                    ProviderFactory::new(db, self.chain.clone(), static_files_path)?;
+
+                provider_factory.health_check()?;
+                println!("Database health check passed");
+            }
+            Subcommands::Verify(command) => {
+                let db = if command.repair_derived {
+                    open_db(&db_path, db_args)?
+                } else {
+                    open_db_read_only(&db_path, db_args)?
+                };
+                let provider_factory =
+                    ProviderFactory::new(db, self.chain.clone(), static_files_path)?;
+                // The verified tables and the counts of the walk in progress are only valid for
+                // the state of the database they were read from.
+                let progress = CommandProgress::load_for_database(
+                    &data_dir.db_progress_path(),
+                    "verify",
+                    &command,
+                    Some(DatabaseState::read(&provider_factory.provider()?)?),
+                )?;
+
+                command.execute(provider_factory, &self.chain.genesis, progress, self.format)?;
+            }
+            Subcommands::RebuildIndex(command) => {
+                let progress =
+                    CommandProgress::load(&data_dir.db_progress_path(), "rebuild-index", &command)?;
+                let mut config: Config =
+                    confy::load_path(data_dir.config_path()).unwrap_or_default();
+                if config.stages.etl.dir.is_none() {
+                    config.stages.etl.dir =
+                        Some(EtlConfig::from_datadir(&data_dir.data_dir_path()));
+                }
+
+                let db = open_db(&db_path, db_args)?;
+                let provider_factory =
+                    ProviderFactory::new(db, self.chain.clone(), static_files_path)?;
+
+                command.execute(provider_factory, &config.stages, progress)?;
+            }
             Subcommands::Version => {
                 let local_db_version = match get_db_version(&db_path) {
                     Ok(version) => Some(version),
@@ -171,6 +293,14 @@ impl Command {
                     Err(err) => return Err(err.into()),
                 };
 
+                if self.format.is_json() {
+                    print_json(&serde_json::json!({
+                        "current": DB_VERSION,
+                        "local": local_db_version,
+                    }))?;
+                    return Ok(())
+                }
+
                 println!("Current database version: {DB_VERSION}");
 
                 if let Some(version) = local_db_version {
@@ -180,7 +310,11 @@ impl Command {
                 }
             }
             Subcommands::Path => {
-                println!("{}", db_path.display());
+                if self.format.is_json() {
+                    print_json(&serde_json::json!({ "path": db_path }))?;
+                } else {
+                    println!("{}", db_path.display());
+                }
             }
         }
 
@@ -199,4 +333,13 @@ mod tests {
         let cmd = Command::try_parse_from(["reth", "stats", "--datadir", &path]).unwrap();
         assert_eq!(cmd.datadir.as_ref(), Some(Path::new(&path)));
     }
+
+    #[test]
+    fn parse_format_global() {
+        let cmd = Command::try_parse_from(["reth", "stats"]).unwrap();
+        assert_eq!(cmd.format, OutputFormat::Text);
+
+        let cmd = Command::try_parse_from(["reth", "version", "--format", "json"]).unwrap();
+        assert_eq!(cmd.format, OutputFormat::Json);
+    }
 }
@@ -0,0 +1,27 @@
+//! Output formats of the `reth db` subcommands.
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Output format of the `reth db` subcommands.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text and tables.
+    #[default]
+    Text,
+    /// Machine-readable JSON.
+    Json,
+}
+
+impl OutputFormat {
+    /// Returns `true` if the output is JSON.
+    pub const fn is_json(&self) -> bool {
+        matches!(self, Self::Json)
+    }
+}
+
+/// Print the value as pretty JSON to stdout.
+pub(crate) fn print_json(value: &impl Serialize) -> eyre::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
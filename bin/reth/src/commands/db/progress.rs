@@ -0,0 +1,257 @@
+//! Progress checkpoints of long-running `reth db` commands.
+//!
+//! Commands that walk large databases record the steps they completed, and the last key processed
+//! by the step in progress, in a sidecar file in the data directory. An interrupted run is resumed
+//! from the checkpoint by the next run with the same arguments, and the file is removed once the
+//! command completes.
+
+use reth_db::database::Database;
+use reth_primitives::{fs, stage::StageId, BlockNumber, Bytes, B256};
+use reth_provider::{BlockHashReader, BlockNumReader, DatabaseProviderRO, StageCheckpointReader};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// Minimum interval between writes of the progress of the step in progress.
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Persisted progress of a command.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Checkpoint {
+    /// The arguments of the run that wrote the checkpoint.
+    args: String,
+    /// The state of the database the run that wrote the checkpoint was reading, if the progress
+    /// depends on it.
+    #[serde(default)]
+    database: Option<DatabaseState>,
+    /// The completed steps.
+    completed: BTreeSet<String>,
+    /// The step in progress, if any.
+    current: Option<StepCheckpoint>,
+}
+
+/// Persisted progress of a step.
+#[derive(Debug, Serialize, Deserialize)]
+struct StepCheckpoint {
+    /// The name of the step.
+    step: String,
+    /// The last key processed by the step.
+    last_key: Bytes,
+    /// The state accumulated by the step up to and including the last key.
+    state: serde_json::Value,
+}
+
+/// The state of the database a command reads, identified by the canonical tip and the stage
+/// checkpoints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DatabaseState {
+    /// The number and hash of the last block.
+    tip: (BlockNumber, Option<B256>),
+    /// The block numbers of the stage checkpoints.
+    stages: BTreeMap<String, BlockNumber>,
+}
+
+impl DatabaseState {
+    /// Read the state of the database.
+    pub(crate) fn read<DB: Database>(provider: &DatabaseProviderRO<DB>) -> eyre::Result<Self> {
+        let tip = provider.last_block_number()?;
+        let mut stages = BTreeMap::new();
+        for stage in StageId::ALL {
+            if let Some(checkpoint) = provider.get_stage_checkpoint(stage)? {
+                stages.insert(stage.to_string(), checkpoint.block_number);
+            }
+        }
+        Ok(Self { tip: (tip, provider.block_hash(tip)?), stages })
+    }
+}
+
+/// Progress of a long-running command, resumable after an interruption.
+#[derive(Debug)]
+pub(crate) struct CommandProgress {
+    /// The path of the checkpoint file.
+    path: PathBuf,
+    checkpoint: Checkpoint,
+    /// The time the checkpoint was last written.
+    last_save: Instant,
+}
+
+impl CommandProgress {
+    /// Load the progress of the command from the checkpoint in `dir`.
+    ///
+    /// A checkpoint written by a run with different arguments is discarded, and the command starts
+    /// over.
+    pub(crate) fn load(dir: &Path, command: &str, args: &impl Debug) -> eyre::Result<Self> {
+        Self::load_for_database(dir, command, args, None)
+    }
+
+    /// Load the progress of a command whose results depend on the state of the database.
+    ///
+    /// A checkpoint written by a run with different arguments, or while the database was in a
+    /// different state, is discarded, and the command starts over.
+    pub(crate) fn load_for_database(
+        dir: &Path,
+        command: &str,
+        args: &impl Debug,
+        database: Option<DatabaseState>,
+    ) -> eyre::Result<Self> {
+        let path = dir.join(format!("{command}.json"));
+        let args = format!("{args:?}");
+
+        let mut checkpoint = Checkpoint { args: args.clone(), database, ..Default::default() };
+        if path.exists() {
+            let previous: Checkpoint = serde_json::from_str(&fs::read_to_string(&path)?)?;
+            if previous.args == args && previous.database != checkpoint.database {
+                info!(
+                    target: "reth::cli",
+                    ?path,
+                    "Discarding the checkpoint of the previous run, the database has changed"
+                );
+            } else if previous.args == args {
+                info!(
+                    target: "reth::cli",
+                    ?path,
+                    completed = previous.completed.len(),
+                    "Resuming from the checkpoint of the previous run"
+                );
+                checkpoint = previous;
+            }
+        }
+
+        Ok(Self { path, checkpoint, last_save: Instant::now() })
+    }
+
+    /// Returns `true` if the step was completed by a previous run.
+    pub(crate) fn is_completed(&self, step: &str) -> bool {
+        self.checkpoint.completed.contains(step)
+    }
+
+    /// Returns the last key processed by the step and the state accumulated up to it, if the step
+    /// was interrupted in a previous run.
+    pub(crate) fn resume<S: DeserializeOwned>(
+        &self,
+        step: &str,
+    ) -> eyre::Result<Option<(Bytes, S)>> {
+        let Some(current) = self.checkpoint.current.as_ref().filter(|current| current.step == step)
+        else {
+            return Ok(None)
+        };
+        let state = serde_json::from_value(current.state.clone())?;
+        Ok(Some((current.last_key.clone(), state)))
+    }
+
+    /// Record that the step processed all keys up to and including `last_key`.
+    ///
+    /// The checkpoint is written at most once per [SAVE_INTERVAL], so this can be called for every
+    /// processed key.
+    pub(crate) fn record<S: Serialize>(
+        &mut self,
+        step: &str,
+        last_key: &[u8],
+        state: &S,
+    ) -> eyre::Result<()> {
+        if self.last_save.elapsed() < SAVE_INTERVAL {
+            return Ok(())
+        }
+
+        self.checkpoint.current = Some(StepCheckpoint {
+            step: step.to_string(),
+            last_key: Bytes::copy_from_slice(last_key),
+            state: serde_json::to_value(state)?,
+        });
+        self.save()
+    }
+
+    /// Mark the step as completed and write the checkpoint.
+    pub(crate) fn complete(&mut self, step: &str) -> eyre::Result<()> {
+        if self.checkpoint.current.as_ref().is_some_and(|current| current.step == step) {
+            self.checkpoint.current = None;
+        }
+        self.checkpoint.completed.insert(step.to_string());
+        self.save()
+    }
+
+    /// Remove the checkpoint once the command completed, so the next run starts over.
+    pub(crate) fn finish(self) -> eyre::Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    /// Write the checkpoint. It's written to a temporary file first, so an interruption while
+    /// writing leaves the previous checkpoint intact.
+    fn save(&mut self) -> eyre::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string(&self.checkpoint)?)?;
+        fs::rename(tmp_path, &self.path)?;
+        self.last_save = Instant::now();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resume_interrupted_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = ["Headers"];
+
+        let mut progress = CommandProgress::load(dir.path(), "test", &args).unwrap();
+        progress.complete("first").unwrap();
+        progress.last_save = Instant::now() - SAVE_INTERVAL;
+        progress.record("second", &[1, 2], &42usize).unwrap();
+        // Dropped without finishing, as if the command was interrupted.
+        drop(progress);
+
+        let progress = CommandProgress::load(dir.path(), "test", &args).unwrap();
+        assert!(progress.is_completed("first"));
+        assert!(!progress.is_completed("second"));
+        assert_eq!(
+            progress.resume::<usize>("second").unwrap(),
+            Some((Bytes::from_static(&[1, 2]), 42))
+        );
+        assert_eq!(progress.resume::<usize>("first").unwrap(), None);
+
+        // A run with different arguments starts over.
+        let other = CommandProgress::load(dir.path(), "test", &["Bodies"]).unwrap();
+        assert!(!other.is_completed("first"));
+
+        progress.finish().unwrap();
+        let progress = CommandProgress::load(dir.path(), "test", &args).unwrap();
+        assert!(!progress.is_completed("first"));
+    }
+
+    #[test]
+    fn discard_progress_of_other_database_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = ["Headers"];
+        let state = |tip| DatabaseState {
+            tip: (tip, Some(B256::with_last_byte(tip as u8))),
+            stages: BTreeMap::from([(StageId::Execution.to_string(), tip)]),
+        };
+
+        let mut progress =
+            CommandProgress::load_for_database(dir.path(), "test", &args, Some(state(1))).unwrap();
+        progress.complete("first").unwrap();
+        drop(progress);
+
+        let progress =
+            CommandProgress::load_for_database(dir.path(), "test", &args, Some(state(1))).unwrap();
+        assert!(progress.is_completed("first"));
+
+        // The node advanced since the previous run.
+        let progress =
+            CommandProgress::load_for_database(dir.path(), "test", &args, Some(state(2))).unwrap();
+        assert!(!progress.is_completed("first"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Command rebuilding a history or transaction lookup index from scratch.
+
+use super::progress::CommandProgress;
+use clap::{Parser, ValueEnum};
+use reth_config::config::StageConfig;
+use reth_db::{database::Database, tables, transaction::DbTxMut, DatabaseError};
+use reth_primitives::{
+    stage::{StageCheckpoint, StageId},
+    PruneSegment,
+};
+use reth_provider::{
+    ProviderFactory, PruneCheckpointReader, StageCheckpointReader, StageCheckpointWriter,
+};
+use reth_stages::{
+    stages::{IndexAccountHistoryStage, IndexStorageHistoryStage, TransactionLookupStage},
+    ExecInput, ExecOutput, Stage,
+};
+use std::fmt;
+use tracing::info;
+
+/// The progress step clearing the index.
+const CLEAR_STEP: &str = "clear";
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db rebuild-index` command
+pub struct Command {
+    /// The index to rebuild.
+    #[arg(long, value_enum)]
+    segment: IndexSegment,
+
+    /// The number of blocks indexed in one database transaction.
+    #[arg(long, default_value_t = 100_000, value_parser = clap::value_parser!(u64).range(1..))]
+    batch_size: u64,
+}
+
+/// Index derived by a pipeline stage from the change sets or the transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IndexSegment {
+    /// The account history index, derived from the account change sets.
+    AccountHistory,
+    /// The storage history index, derived from the storage change sets.
+    StorageHistory,
+    /// The transaction hash to number index, derived from the transactions.
+    TxLookup,
+}
+
+impl IndexSegment {
+    /// The stage that fills the index.
+    const fn stage_id(&self) -> StageId {
+        match self {
+            Self::AccountHistory => StageId::IndexAccountHistory,
+            Self::StorageHistory => StageId::IndexStorageHistory,
+            Self::TxLookup => StageId::TransactionLookup,
+        }
+    }
+
+    /// The prune segment of the index.
+    const fn prune_segment(&self) -> PruneSegment {
+        match self {
+            Self::AccountHistory => PruneSegment::AccountHistory,
+            Self::StorageHistory => PruneSegment::StorageHistory,
+            Self::TxLookup => PruneSegment::TransactionLookup,
+        }
+    }
+
+    /// Returns `true` if the index is derived from the change sets, whose old blocks may be folded
+    /// into the change set epochs by the pruner.
+    const fn is_history(&self) -> bool {
+        matches!(self, Self::AccountHistory | Self::StorageHistory)
+    }
+
+    /// Clear the index table.
+    fn clear(&self, tx: &impl DbTxMut) -> Result<(), DatabaseError> {
+        match self {
+            Self::AccountHistory => tx.clear::<tables::AccountsHistory>(),
+            Self::StorageHistory => tx.clear::<tables::StoragesHistory>(),
+            Self::TxLookup => tx.clear::<tables::TransactionHashNumbers>(),
+        }
+    }
+
+    /// Returns the stage that fills the index.
+    ///
+    /// The stage is created without a prune mode, as the pruned blocks are skipped by the command.
+    fn stage<DB: Database>(&self, config: &StageConfig) -> Box<dyn Stage<DB>> {
+        match self {
+            Self::AccountHistory => Box::new(IndexAccountHistoryStage::new(
+                config.index_account_history.commit_threshold,
+                None,
+                config.etl.clone(),
+            )),
+            Self::StorageHistory => Box::new(IndexStorageHistoryStage::new(
+                config.index_storage_history.commit_threshold,
+                None,
+                config.etl.clone(),
+            )),
+            Self::TxLookup => Box::new(TransactionLookupStage::new(
+                config.transaction_lookup.chunk_size,
+                config.etl.clone(),
+                None,
+            )),
+        }
+    }
+}
+
+impl fmt::Display for IndexSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AccountHistory => f.write_str("account-history"),
+            Self::StorageHistory => f.write_str("storage-history"),
+            Self::TxLookup => f.write_str("tx-lookup"),
+        }
+    }
+}
+
+impl Command {
+    /// Execute `db rebuild-index` command
+    ///
+    /// The index is cleared, and then refilled by the stage that derives it, a batch of blocks per
+    /// database transaction, up to the block of the execution stage. The stage checkpoint is
+    /// saved with every batch, so an interrupted rebuild is resumed by the next run of the command,
+    /// or completed by the pipeline.
+    ///
+    /// History indices are not rebuilt once change sets were folded into epochs.
+    pub fn execute<DB: Database>(
+        self,
+        provider_factory: ProviderFactory<DB>,
+        config: &StageConfig,
+        mut progress: CommandProgress,
+    ) -> eyre::Result<()> {
+        let stage_id = self.segment.stage_id();
+        let target = provider_factory
+            .provider()?
+            .get_stage_checkpoint(StageId::Execution)?
+            .unwrap_or_default()
+            .block_number;
+
+        // The change sets of the folded blocks are deleted, so their index entries can't be
+        // derived again.
+        if self.segment.is_history() {
+            if let Some(checkpoint) =
+                provider_factory.provider()?.get_prune_checkpoint(PruneSegment::ChangeSetEpochs)?
+            {
+                eyre::bail!(
+                    "The {} index can't be rebuilt, the change sets up to block {} were folded \
+                     into epochs",
+                    self.segment,
+                    checkpoint.block_number.unwrap_or_default()
+                )
+            }
+        }
+
+        if !progress.is_completed(CLEAR_STEP) {
+            let provider_rw = provider_factory.provider_rw()?;
+            self.segment.clear(provider_rw.tx_ref())?;
+            // The blocks up to the prune checkpoint were removed from the index by the pruner, and
+            // the data they are derived from may be gone too.
+            let start = provider_rw
+                .get_prune_checkpoint(self.segment.prune_segment())?
+                .and_then(|checkpoint| checkpoint.block_number)
+                .unwrap_or_default();
+            provider_rw.save_stage_checkpoint(stage_id, StageCheckpoint::new(start))?;
+            provider_rw.commit()?;
+            progress.complete(CLEAR_STEP)?;
+            info!(target: "reth::cli", segment = %self.segment, start, target, "Cleared index");
+        }
+
+        let mut stage = self.segment.stage::<DB>(config);
+        let mut checkpoint =
+            provider_factory.provider()?.get_stage_checkpoint(stage_id)?.unwrap_or_default();
+        while checkpoint.block_number < target {
+            let provider_rw = provider_factory.provider_rw()?;
+            let input = ExecInput {
+                target: Some(target.min(checkpoint.block_number.saturating_add(self.batch_size))),
+                checkpoint: Some(checkpoint),
+            };
+            let ExecOutput { checkpoint: output, .. } = stage.execute(&provider_rw, input)?;
+            checkpoint = output;
+            provider_rw.save_stage_checkpoint(stage_id, checkpoint)?;
+            provider_rw.commit()?;
+            info!(target: "reth::cli", segment = %self.segment, block = checkpoint.block_number, target, "Rebuilt index");
+        }
+        progress.finish()?;
+
+        println!("Rebuilt {} index up to block {target}", self.segment);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{transaction::DbTx, BlockNumberList};
+    use reth_primitives::{PruneCheckpoint, PruneMode};
+    use reth_provider::{test_utils::create_test_provider_factory, PruneCheckpointWriter};
+
+    #[test]
+    fn parse_segment() {
+        let cmd = Command::try_parse_from(["reth", "--segment", "storage-history"]).unwrap();
+        assert_eq!(cmd.segment, IndexSegment::StorageHistory);
+        assert_eq!(cmd.batch_size, 100_000);
+
+        assert!(Command::try_parse_from(["reth", "--segment", "receipts"]).is_err());
+        assert!(Command::try_parse_from(["reth", "--segment", "tx-lookup", "--batch-size", "0"])
+            .is_err());
+    }
+
+    #[test]
+    fn history_folded_into_epochs() {
+        let factory = create_test_provider_factory();
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .tx_ref()
+            .put::<tables::AccountsHistory>(Default::default(), BlockNumberList::new([1]).unwrap())
+            .unwrap();
+        provider_rw
+            .save_prune_checkpoint(
+                PruneSegment::ChangeSetEpochs,
+                PruneCheckpoint {
+                    block_number: Some(10),
+                    tx_number: None,
+                    prune_mode: PruneMode::Before(11),
+                },
+            )
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let command = Command::try_parse_from(["reth", "--segment", "account-history"]).unwrap();
+        let progress = CommandProgress::load(dir.path(), "rebuild-index", &command).unwrap();
+        assert!(command.execute(factory.clone(), &StageConfig::default(), progress).is_err());
+
+        // The index is left intact.
+        assert_eq!(
+            factory.provider().unwrap().tx_ref().entries::<tables::AccountsHistory>(),
+            Ok(1)
+        );
+    }
+}
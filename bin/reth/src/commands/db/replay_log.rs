@@ -0,0 +1,41 @@
+use clap::Parser;
+use reth_db::{
+    database::Database,
+    operation_log::{self, LogReader},
+};
+use std::{fs::File, io::BufReader, ops::Bound, path::PathBuf};
+use tracing::info;
+
+/// The arguments for the `reth db replay-log` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The operation log recorded with `--db.operation-log`.
+    #[arg(value_name = "PATH")]
+    path: PathBuf,
+
+    /// The sequence number of the first committed transaction to replay. Committed transactions
+    /// are numbered from 1 in the order of the log.
+    ///
+    /// Use it to replay the log onto a copy of the database taken after the previous transaction.
+    #[arg(long, value_name = "SEQ")]
+    from_seq: Option<u64>,
+
+    /// The sequence number of the last committed transaction to replay.
+    ///
+    /// Use it to recover the database as of that transaction, e.g. before an operator mistake.
+    #[arg(long, value_name = "SEQ")]
+    to_seq: Option<u64>,
+}
+
+impl Command {
+    /// Execute `db replay-log` command
+    pub fn execute<DB: Database>(self, db: &DB) -> eyre::Result<()> {
+        let reader = LogReader::new(BufReader::new(File::open(&self.path)?));
+        let from = self.from_seq.map_or(Bound::Unbounded, Bound::Included);
+        let to = self.to_seq.map_or(Bound::Unbounded, Bound::Included);
+        let transactions = operation_log::replay(db, reader, (from, to))?;
+        info!(target: "reth::cli", transactions, "Replayed the operation log");
+
+        Ok(())
+    }
+}
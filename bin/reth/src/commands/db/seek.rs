@@ -0,0 +1,102 @@
+use crate::utils::DbTool;
+use clap::Parser;
+use reth_db::{
+    cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx, RawKey, RawTable,
+    TableRawRow, TableViewer, Tables,
+};
+use reth_primitives::{hex, Bytes};
+
+/// The arguments for the `reth db seek` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The table name
+    table: Tables,
+    /// The raw key to seek to, hex-encoded
+    key: Bytes,
+    /// Seek to the last entry with a key less than or equal to the given key, instead of the
+    /// first entry with a key greater than or equal to it.
+    #[arg(long)]
+    prev: bool,
+    /// The number of entries to print before the found entry
+    #[arg(long, default_value_t = 2)]
+    before: usize,
+    /// The number of entries to print after the found entry
+    #[arg(long, default_value_t = 2)]
+    after: usize,
+}
+
+impl Command {
+    /// Execute `db seek` command
+    pub fn execute<DB: Database>(self, tool: &DbTool<DB>) -> eyre::Result<()> {
+        self.table.view(&SeekViewer { tool, args: &self })
+    }
+}
+
+struct SeekViewer<'a, DB: Database> {
+    tool: &'a DbTool<DB>,
+    args: &'a Command,
+}
+
+impl<DB: Database> SeekViewer<'_, DB> {
+    /// Position the cursor at the entry the command is looking for.
+    fn seek<T: Table>(
+        &self,
+        cursor: &mut impl DbCursorRO<RawTable<T>>,
+    ) -> eyre::Result<Option<TableRawRow<T>>> {
+        let key = self.args.key.to_vec();
+        let entry = cursor.seek(RawKey::from_vec(key.clone()))?;
+        if !self.args.prev {
+            return Ok(entry)
+        }
+        Ok(match entry {
+            Some(entry) if *entry.0.raw_key() == key => Some(entry),
+            Some(_) => cursor.prev()?,
+            None => cursor.last()?,
+        })
+    }
+}
+
+impl<DB: Database> TableViewer<()> for SeekViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        let tx = self.tool.provider_factory.db_ref().tx()?;
+
+        // Separate cursors walk backwards and forwards from the found entry.
+        let mut backward = tx.cursor_read::<RawTable<T>>()?;
+        let mut forward = tx.cursor_read::<RawTable<T>>()?;
+        let Some(found) = self.seek::<T>(&mut forward)? else {
+            println!("No entry found in table {}", self.args.table.name());
+            return Ok(())
+        };
+        self.seek::<T>(&mut backward)?;
+
+        let mut before = Vec::with_capacity(self.args.before);
+        while before.len() < self.args.before {
+            let Some(entry) = backward.prev()? else { break };
+            before.push(entry);
+        }
+        let mut after = Vec::with_capacity(self.args.after);
+        while after.len() < self.args.after {
+            let Some(entry) = forward.next()? else { break };
+            after.push(entry);
+        }
+
+        let print = |marker: &str, (key, value): TableRawRow<T>| {
+            println!(
+                "{marker} 0x{} => 0x{}",
+                hex::encode(key.raw_key()),
+                hex::encode(value.raw_value())
+            );
+        };
+        for entry in before.into_iter().rev() {
+            print(" ", entry);
+        }
+        print(">", found);
+        for entry in after {
+            print(" ", entry);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use clap::Parser;
+use jsonrpsee::{
+    core::RpcResult,
+    server::ServerBuilder,
+    types::{
+        error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+        ErrorObject, ErrorObjectOwned,
+    },
+};
+use reth_db::{
+    cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx, DatabaseError, RawKey,
+    RawTable, TableViewer, Tables,
+};
+use reth_primitives::Bytes;
+use reth_rpc_api::DbApiServer;
+use std::{net::SocketAddr, sync::Arc};
+use tracing::info;
+
+/// The arguments for the `reth db serve` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The address to serve the database tables on.
+    #[arg(long, default_value = "127.0.0.1:8549")]
+    addr: SocketAddr,
+
+    /// The maximum number of entries returned by a single range request.
+    #[arg(long, default_value_t = 10_000)]
+    max_range_entries: usize,
+}
+
+impl Command {
+    /// Execute `db serve` command
+    pub async fn execute<DB: Database + 'static>(self, db: DB) -> eyre::Result<()> {
+        let server = ServerBuilder::default().build(self.addr).await?;
+        let addr = server.local_addr()?;
+        let handle = server.start(
+            DbApi { db: Arc::new(db), max_range_entries: self.max_range_entries }.into_rpc(),
+        );
+        info!(target: "reth::cli", %addr, "Serving database tables");
+
+        handle.stopped().await;
+        Ok(())
+    }
+}
+
+/// Implementation of the [DbApiServer] over the database.
+#[derive(Debug)]
+struct DbApi<DB> {
+    db: Arc<DB>,
+    max_range_entries: usize,
+}
+
+impl<DB: Database + 'static> DbApi<DB> {
+    /// Spawn the blocking read of the table on the tokio blocking pool.
+    async fn read<R, V>(&self, table: String, viewer: impl FnOnce(Arc<DB>) -> V) -> RpcResult<R>
+    where
+        R: Send + 'static,
+        V: TableViewer<R, Error = DatabaseError> + Send + 'static,
+    {
+        let table = table
+            .parse::<Tables>()
+            .map_err(|err| ErrorObject::owned(INVALID_PARAMS_CODE, err, None::<()>))?;
+        let viewer = viewer(self.db.clone());
+
+        tokio::task::spawn_blocking(move || table.view(&viewer))
+            .await
+            .map_err(|err| internal_error(err.to_string()))?
+            .map_err(|err| internal_error(err.to_string()))
+    }
+}
+
+#[async_trait]
+impl<DB: Database + 'static> DbApiServer for DbApi<DB> {
+    async fn get(&self, table: String, key: Bytes) -> RpcResult<Option<Bytes>> {
+        let values = self.read(table, |db| GetViewer { db, keys: vec![key] }).await?;
+        Ok(values.into_iter().next().flatten())
+    }
+
+    async fn multi_get(&self, table: String, keys: Vec<Bytes>) -> RpcResult<Vec<Option<Bytes>>> {
+        self.read(table, |db| GetViewer { db, keys }).await
+    }
+
+    async fn range(
+        &self,
+        table: String,
+        start: Option<Bytes>,
+        end: Option<Bytes>,
+        limit: usize,
+    ) -> RpcResult<Vec<(Bytes, Bytes)>> {
+        let limit = limit.min(self.max_range_entries);
+        self.read(table, |db| RangeViewer { db, start, end, limit }).await
+    }
+}
+
+fn internal_error(message: String) -> ErrorObjectOwned {
+    ErrorObject::owned(INTERNAL_ERROR_CODE, message, None::<()>)
+}
+
+/// Reads the raw values of the keys.
+struct GetViewer<DB> {
+    db: Arc<DB>,
+    keys: Vec<Bytes>,
+}
+
+impl<DB: Database> TableViewer<Vec<Option<Bytes>>> for GetViewer<DB> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<Vec<Option<Bytes>>, Self::Error> {
+        let tx = self.db.tx()?;
+        self.keys
+            .iter()
+            .map(|key| {
+                let value = tx.get::<RawTable<T>>(RawKey::from_vec(key.to_vec()))?;
+                Ok(value.map(|value| Bytes::copy_from_slice(value.raw_value())))
+            })
+            .collect()
+    }
+}
+
+/// Reads the raw entries of the `[start, end)` range.
+struct RangeViewer<DB> {
+    db: Arc<DB>,
+    start: Option<Bytes>,
+    end: Option<Bytes>,
+    limit: usize,
+}
+
+impl<DB: Database> TableViewer<Vec<(Bytes, Bytes)>> for RangeViewer<DB> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<Vec<(Bytes, Bytes)>, Self::Error> {
+        let tx = self.db.tx()?;
+        let mut cursor = tx.cursor_read::<RawTable<T>>()?;
+        let start = self.start.as_ref().map(|start| RawKey::from_vec(start.to_vec()));
+
+        let mut entries = Vec::new();
+        for entry in cursor.walk(start)? {
+            let (key, value) = entry?;
+            if entries.len() >= self.limit ||
+                self.end.as_ref().is_some_and(|end| key.raw_key().as_slice() >= end.as_ref())
+            {
+                break
+            }
+            entries.push((
+                Bytes::copy_from_slice(key.raw_key()),
+                Bytes::copy_from_slice(value.raw_value()),
+            ));
+        }
+
+        Ok(entries)
+    }
+}
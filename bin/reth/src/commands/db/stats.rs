@@ -1,13 +1,21 @@
+use super::output::{print_json, OutputFormat};
 use crate::utils::DbTool;
 use clap::Parser;
 use comfy_table::{Cell, Row, Table as ComfyTable};
 use eyre::WrapErr;
 use human_bytes::human_bytes;
 use itertools::Itertools;
-use reth_db::{database::Database, mdbx, static_file::iter_static_files, DatabaseEnv, Tables};
+use reth_db::{
+    database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseTableStats},
+    static_file::iter_static_files,
+    Tables,
+};
 use reth_node_core::dirs::{ChainPath, DataDirPath};
-use reth_primitives::static_file::{find_fixed_range, SegmentRangeInclusive};
+use reth_primitives::static_file::{find_fixed_range, SegmentRangeInclusive, StaticFileSegment};
 use reth_provider::providers::StaticFileProvider;
+use serde::Serialize;
+use std::path::Path;
 
 #[derive(Parser, Debug)]
 /// The arguments for the `reth db stats` command
@@ -20,25 +28,136 @@ pub struct Command {
     summary: bool,
 }
 
+/// Shape and sizes of a static file, or of all static files of a segment with `--summary`.
+#[derive(Debug, Serialize)]
+struct StaticFileStats {
+    segment: StaticFileSegment,
+    block_range: SegmentRangeInclusive,
+    tx_range: Option<SegmentRangeInclusive>,
+    columns: usize,
+    rows: usize,
+    data_size: u64,
+    index_size: u64,
+    offsets_size: u64,
+    config_size: u64,
+}
+
+impl StaticFileStats {
+    /// Combine the stats of all static files of a segment, ordered by block range.
+    fn summarize(files: &[Self]) -> Self {
+        let first = files.first().expect("not empty list of ranges");
+        let last = files.last().expect("not empty list of ranges");
+        for file in files {
+            assert_eq!(file.columns, first.columns);
+        }
+
+        Self {
+            segment: first.segment,
+            block_range: SegmentRangeInclusive::new(
+                first.block_range.start(),
+                last.block_range.end(),
+            ),
+            tx_range: first
+                .tx_range
+                .zip(last.tx_range)
+                .map(|(first, last)| SegmentRangeInclusive::new(first.start(), last.end())),
+            columns: first.columns,
+            rows: files.iter().map(|file| file.rows).sum(),
+            data_size: files.iter().map(|file| file.data_size).sum(),
+            index_size: files.iter().map(|file| file.index_size).sum(),
+            offsets_size: files.iter().map(|file| file.offsets_size).sum(),
+            config_size: files.iter().map(|file| file.config_size).sum(),
+        }
+    }
+
+    fn total_size(&self) -> u64 {
+        self.data_size + self.index_size + self.offsets_size + self.config_size
+    }
+}
+
+/// Entry count and sizes of a database table. Page counts are only reported by backends that
+/// store tables in pages.
+#[derive(Debug, Serialize)]
+struct DbTableStats {
+    name: &'static str,
+    entries: usize,
+    branch_pages: Option<usize>,
+    leaf_pages: Option<usize>,
+    overflow_pages: Option<usize>,
+    size: usize,
+}
+
+/// Free pages of the database.
+#[derive(Debug, Serialize)]
+struct FreelistStats {
+    pages: usize,
+    size: usize,
+}
+
 impl Command {
     /// Execute `db stats` command
-    pub fn execute(
+    pub fn execute<DB: Database + DatabaseTableStats + DatabaseMetadata>(
         self,
         data_dir: ChainPath<DataDirPath>,
-        tool: &DbTool<DatabaseEnv>,
+        tool: &DbTool<DB>,
+        format: OutputFormat,
     ) -> eyre::Result<()> {
-        let static_files_stats_table = self.static_files_stats_table(data_dir)?;
+        let static_files = self.static_files_stats(data_dir)?;
+        let (db_tables, freelist) = Self::db_stats(tool)?;
+
+        if format.is_json() {
+            return print_json(&serde_json::json!({
+                "static_files": static_files,
+                "tables": db_tables,
+                "freelist": freelist,
+            }))
+        }
+
+        let static_files_stats_table = self.static_files_stats_table(&static_files);
         println!("{static_files_stats_table}");
 
         println!("\n");
 
-        let db_stats_table = self.db_stats_table(tool)?;
+        let db_stats_table = Self::db_stats_table(&db_tables, freelist.as_ref());
         println!("{db_stats_table}");
 
         Ok(())
     }
 
-    fn db_stats_table(&self, tool: &DbTool<DatabaseEnv>) -> eyre::Result<ComfyTable> {
+    fn db_stats<DB: Database + DatabaseTableStats + DatabaseMetadata>(
+        tool: &DbTool<DB>,
+    ) -> eyre::Result<(Vec<DbTableStats>, Option<FreelistStats>)> {
+        let mut db_tables = Tables::ALL.iter().collect::<Vec<_>>();
+        db_tables.sort_by_key(|table| table.name());
+
+        let mut tables = Vec::with_capacity(db_tables.len());
+        let mut page_size = None;
+        for db_table in db_tables {
+            let stats = tool
+                .table_stats(*db_table)
+                .wrap_err(format!("Could not find table: {}", db_table.name()))?;
+
+            let pages = stats.pages();
+            page_size = page_size.or(pages.map(|pages| pages.page_size));
+            tables.push(DbTableStats {
+                name: db_table.name(),
+                entries: stats.entries(),
+                branch_pages: pages.map(|pages| pages.branch),
+                leaf_pages: pages.map(|pages| pages.leaf),
+                overflow_pages: pages.map(|pages| pages.overflow),
+                size: stats.size(),
+            });
+        }
+
+        let freelist = tool.provider_factory.db_ref().metadata().freelist_size();
+        let freelist = freelist
+            .zip(page_size)
+            .map(|(pages, page_size)| FreelistStats { pages, size: pages * page_size });
+
+        Ok((tables, freelist))
+    }
+
+    fn db_stats_table(db_tables: &[DbTableStats], freelist: Option<&FreelistStats>) -> ComfyTable {
         let mut table = ComfyTable::new();
         table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
         table.set_header([
@@ -50,78 +169,100 @@ impl Command {
             "Total Size",
         ]);
 
-        tool.provider_factory.db_ref().view(|tx| {
-            let mut db_tables = Tables::ALL.iter().map(|table| table.name()).collect::<Vec<_>>();
-            db_tables.sort();
-            let mut total_size = 0;
-            for db_table in db_tables {
-                let table_db = tx.inner.open_db(Some(db_table)).wrap_err("Could not open db.")?;
-
-                let stats = tx
-                    .inner
-                    .db_stat(&table_db)
-                    .wrap_err(format!("Could not find table: {db_table}"))?;
-
-                // Defaults to 16KB right now but we should
-                // re-evaluate depending on the DB we end up using
-                // (e.g. REDB does not have these options as configurable intentionally)
-                let page_size = stats.page_size() as usize;
-                let leaf_pages = stats.leaf_pages();
-                let branch_pages = stats.branch_pages();
-                let overflow_pages = stats.overflow_pages();
-                let num_pages = leaf_pages + branch_pages + overflow_pages;
-                let table_size = page_size * num_pages;
-
-                total_size += table_size;
-                let mut row = Row::new();
-                row.add_cell(Cell::new(db_table))
-                    .add_cell(Cell::new(stats.entries()))
-                    .add_cell(Cell::new(branch_pages))
-                    .add_cell(Cell::new(leaf_pages))
-                    .add_cell(Cell::new(overflow_pages))
-                    .add_cell(Cell::new(human_bytes(table_size as f64)));
-                table.add_row(row);
-            }
+        let mut total_size = 0;
+        for db_table in db_tables {
+            total_size += db_table.size;
 
-            let max_widths = table.column_max_content_widths();
-            let mut separator = Row::new();
-            for width in max_widths {
-                separator.add_cell(Cell::new("-".repeat(width as usize)));
-            }
-            table.add_row(separator);
+            // Page cells are left empty if the backend doesn't report them.
+            let page_cell = |pages: Option<usize>| pages.map_or_else(|| Cell::new(""), Cell::new);
 
             let mut row = Row::new();
-            row.add_cell(Cell::new("Tables"))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(human_bytes(total_size as f64)));
+            row.add_cell(Cell::new(db_table.name))
+                .add_cell(Cell::new(db_table.entries))
+                .add_cell(page_cell(db_table.branch_pages))
+                .add_cell(page_cell(db_table.leaf_pages))
+                .add_cell(page_cell(db_table.overflow_pages))
+                .add_cell(Cell::new(human_bytes(db_table.size as f64)));
             table.add_row(row);
+        }
+
+        let max_widths = table.column_max_content_widths();
+        let mut separator = Row::new();
+        for width in max_widths {
+            separator.add_cell(Cell::new("-".repeat(width as usize)));
+        }
+        table.add_row(separator);
 
-            let freelist = tx.inner.env().freelist()?;
-            let pagesize = tx.inner.db_stat(&mdbx::Database::freelist_db())?.page_size() as usize;
-            let freelist_size = freelist * pagesize;
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Tables"))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(human_bytes(total_size as f64)));
+        table.add_row(row);
 
+        if let Some(freelist) = freelist {
             let mut row = Row::new();
             row.add_cell(Cell::new("Freelist"))
-                .add_cell(Cell::new(freelist))
+                .add_cell(Cell::new(freelist.pages))
                 .add_cell(Cell::new(""))
                 .add_cell(Cell::new(""))
                 .add_cell(Cell::new(""))
-                .add_cell(Cell::new(human_bytes(freelist_size as f64)));
+                .add_cell(Cell::new(human_bytes(freelist.size as f64)));
             table.add_row(row);
+        }
 
-            Ok::<(), eyre::Report>(())
-        })??;
-
-        Ok(table)
+        table
     }
 
-    fn static_files_stats_table(
+    fn static_files_stats(
         &self,
         data_dir: ChainPath<DataDirPath>,
-    ) -> eyre::Result<ComfyTable> {
+    ) -> eyre::Result<Vec<StaticFileStats>> {
+        let static_files = iter_static_files(data_dir.static_files_path())?;
+        let static_file_provider = StaticFileProvider::new(data_dir.static_files_path())?;
+
+        let mut stats = Vec::new();
+        for (segment, ranges) in static_files.into_iter().sorted_by_key(|(segment, _)| *segment) {
+            let mut files = Vec::with_capacity(ranges.len());
+            for (block_range, tx_range) in &ranges {
+                let fixed_block_range = find_fixed_range(block_range.start());
+                let jar_provider = static_file_provider
+                    .get_segment_provider(segment, || Some(fixed_block_range), None)?
+                    .ok_or_else(|| {
+                        eyre::eyre!("Failed to get segment provider for segment: {}", segment)
+                    })?;
+
+                let file_size = |path: &Path| {
+                    reth_primitives::fs::metadata(path)
+                        .map(|metadata| metadata.len())
+                        .unwrap_or_default()
+                };
+                files.push(StaticFileStats {
+                    segment,
+                    block_range: *block_range,
+                    tx_range: *tx_range,
+                    columns: jar_provider.columns(),
+                    rows: jar_provider.rows(),
+                    data_size: file_size(jar_provider.data_path()),
+                    index_size: file_size(&jar_provider.index_path()),
+                    offsets_size: file_size(&jar_provider.offsets_path()),
+                    config_size: file_size(&jar_provider.config_path()),
+                });
+            }
+
+            if self.summary {
+                stats.push(StaticFileStats::summarize(&files));
+            } else {
+                stats.extend(files);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    fn static_files_stats_table(&self, static_files: &[StaticFileStats]) -> ComfyTable {
         let mut table = ComfyTable::new();
         table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
 
@@ -147,117 +288,32 @@ impl Command {
             ]);
         }
 
-        let static_files = iter_static_files(data_dir.static_files_path())?;
-        let static_file_provider = StaticFileProvider::new(data_dir.static_files_path())?;
-
         let mut total_data_size = 0;
         let mut total_index_size = 0;
         let mut total_offsets_size = 0;
         let mut total_config_size = 0;
 
-        for (segment, ranges) in static_files.into_iter().sorted_by_key(|(segment, _)| *segment) {
-            let (
-                mut segment_columns,
-                mut segment_rows,
-                mut segment_data_size,
-                mut segment_index_size,
-                mut segment_offsets_size,
-                mut segment_config_size,
-            ) = (0, 0, 0, 0, 0, 0);
-
-            for (block_range, tx_range) in &ranges {
-                let fixed_block_range = find_fixed_range(block_range.start());
-                let jar_provider = static_file_provider
-                    .get_segment_provider(segment, || Some(fixed_block_range), None)?
-                    .ok_or_else(|| {
-                        eyre::eyre!("Failed to get segment provider for segment: {}", segment)
-                    })?;
-
-                let columns = jar_provider.columns();
-                let rows = jar_provider.rows();
-
-                let data_size = reth_primitives::fs::metadata(jar_provider.data_path())
-                    .map(|metadata| metadata.len())
-                    .unwrap_or_default();
-                let index_size = reth_primitives::fs::metadata(jar_provider.index_path())
-                    .map(|metadata| metadata.len())
-                    .unwrap_or_default();
-                let offsets_size = reth_primitives::fs::metadata(jar_provider.offsets_path())
-                    .map(|metadata| metadata.len())
-                    .unwrap_or_default();
-                let config_size = reth_primitives::fs::metadata(jar_provider.config_path())
-                    .map(|metadata| metadata.len())
-                    .unwrap_or_default();
-
-                if self.summary {
-                    if segment_columns > 0 {
-                        assert_eq!(segment_columns, columns);
-                    } else {
-                        segment_columns = columns;
-                    }
-                    segment_rows += rows;
-                    segment_data_size += data_size;
-                    segment_index_size += index_size;
-                    segment_offsets_size += offsets_size;
-                    segment_config_size += config_size;
-                } else {
-                    let mut row = Row::new();
-                    row.add_cell(Cell::new(segment))
-                        .add_cell(Cell::new(format!("{block_range}")))
-                        .add_cell(Cell::new(
-                            tx_range.map_or("N/A".to_string(), |tx_range| format!("{tx_range}")),
-                        ))
-                        .add_cell(Cell::new(format!("{columns} x {rows}")));
-                    if !self.only_total_size {
-                        row.add_cell(Cell::new(human_bytes(data_size as f64)))
-                            .add_cell(Cell::new(human_bytes(index_size as f64)))
-                            .add_cell(Cell::new(human_bytes(offsets_size as f64)))
-                            .add_cell(Cell::new(human_bytes(config_size as f64)));
-                    }
-                    row.add_cell(Cell::new(human_bytes(
-                        (data_size + index_size + offsets_size + config_size) as f64,
-                    )));
-                    table.add_row(row);
-                }
-
-                total_data_size += data_size;
-                total_index_size += index_size;
-                total_offsets_size += offsets_size;
-                total_config_size += config_size;
+        for file in static_files {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(file.segment))
+                .add_cell(Cell::new(format!("{}", file.block_range)))
+                .add_cell(Cell::new(
+                    file.tx_range.map_or("N/A".to_string(), |tx_range| format!("{tx_range}")),
+                ))
+                .add_cell(Cell::new(format!("{} x {}", file.columns, file.rows)));
+            if !self.only_total_size {
+                row.add_cell(Cell::new(human_bytes(file.data_size as f64)))
+                    .add_cell(Cell::new(human_bytes(file.index_size as f64)))
+                    .add_cell(Cell::new(human_bytes(file.offsets_size as f64)))
+                    .add_cell(Cell::new(human_bytes(file.config_size as f64)));
             }
+            row.add_cell(Cell::new(human_bytes(file.total_size() as f64)));
+            table.add_row(row);
 
-            if self.summary {
-                let first_ranges = ranges.first().expect("not empty list of ranges");
-                let last_ranges = ranges.last().expect("not empty list of ranges");
-
-                let block_range =
-                    SegmentRangeInclusive::new(first_ranges.0.start(), last_ranges.0.end());
-                let tx_range = first_ranges
-                    .1
-                    .zip(last_ranges.1)
-                    .map(|(first, last)| SegmentRangeInclusive::new(first.start(), last.end()));
-
-                let mut row = Row::new();
-                row.add_cell(Cell::new(segment))
-                    .add_cell(Cell::new(format!("{block_range}")))
-                    .add_cell(Cell::new(
-                        tx_range.map_or("N/A".to_string(), |tx_range| format!("{tx_range}")),
-                    ))
-                    .add_cell(Cell::new(format!("{segment_columns} x {segment_rows}")));
-                if !self.only_total_size {
-                    row.add_cell(Cell::new(human_bytes(segment_data_size as f64)))
-                        .add_cell(Cell::new(human_bytes(segment_index_size as f64)))
-                        .add_cell(Cell::new(human_bytes(segment_offsets_size as f64)))
-                        .add_cell(Cell::new(human_bytes(segment_config_size as f64)));
-                }
-                row.add_cell(Cell::new(human_bytes(
-                    (segment_data_size +
-                        segment_index_size +
-                        segment_offsets_size +
-                        segment_config_size) as f64,
-                )));
-                table.add_row(row);
-            }
+            total_data_size += file.data_size;
+            total_index_size += file.index_size;
+            total_offsets_size += file.offsets_size;
+            total_config_size += file.config_size;
         }
 
         let max_widths = table.column_max_content_widths();
@@ -283,6 +339,6 @@ impl Command {
         )));
         table.add_row(row);
 
-        Ok(table)
+        table
     }
 }
@@ -0,0 +1,822 @@
+use super::{
+    output::{print_json, OutputFormat},
+    progress::CommandProgress,
+};
+use clap::Parser;
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    table::Table,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    BlockNumberList, RawKey, RawTable,
+};
+use reth_node_core::init::insert_genesis_history;
+use reth_primitives::{
+    keccak256, stage::StageId, BlockNumber, Genesis, PruneSegment, StaticFileSegment, TxNumber,
+    B256,
+};
+use reth_provider::{
+    providers::StaticFileProvider, DatabaseProviderRO, DatabaseProviderRW, HeaderProvider,
+    ProviderError, ProviderFactory, PruneCheckpointReader, StageCheckpointReader,
+};
+use reth_trie::{
+    trie_cursor::noop::NoopTrieCursorFactory,
+    updates::{TrieKey, TrieOp},
+    StateRoot, StateRootProgress,
+};
+use std::fmt;
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db verify` command
+pub struct Command {
+    /// Clear the derived tables that failed the verification and reset the checkpoints of the
+    /// stages that fill them, so they are re-derived from the plain state and the change sets on
+    /// the next run of the pipeline.
+    #[arg(long)]
+    pub repair_derived: bool,
+
+    /// Also cross-check the block ranges, the transaction numbering and the block hashes of the
+    /// canonical chain between the database tables and the static files.
+    ///
+    /// Inconsistencies found by this check are reported, but not repaired.
+    #[arg(long)]
+    pub static_files: bool,
+}
+
+impl Command {
+    /// Execute `db verify` command
+    ///
+    /// The tables verified as valid, and the progress of the history index and static file header
+    /// walks, are recorded in the progress checkpoint, so an interrupted verification doesn't
+    /// start over.
+    pub fn execute<DB: Database>(
+        self,
+        provider_factory: ProviderFactory<DB>,
+        genesis: &Genesis,
+        mut progress: CommandProgress,
+        format: OutputFormat,
+    ) -> eyre::Result<()> {
+        // With the JSON output format, the outcomes are printed as a single document at the end.
+        let mut reports = Vec::new();
+        let mut report = |data: &dyn fmt::Display, status: &str, reason: Option<String>| {
+            if format.is_json() {
+                reports.push(serde_json::json!({
+                    "data": data.to_string(),
+                    "status": status,
+                    "reason": reason,
+                }));
+            } else if let Some(reason) = reason {
+                println!("{data}: {status}, {reason}");
+            } else {
+                println!("{data}: {status}");
+            }
+        };
+
+        let provider = provider_factory.provider()?;
+        let mut corrupted = Vec::new();
+        for data in DerivedData::ALL {
+            let step = data.to_string();
+            if progress.is_completed(&step) {
+                report(&data, "valid", Some("verified by the previous run".to_string()));
+                continue
+            }
+
+            // The trie is recomputed from the hashed state, so it can't be verified against a
+            // corrupted one.
+            if data == DerivedData::Trie && corrupted.contains(&DerivedData::HashedState) {
+                report(&data, "skipped", Some("the hashed state is corrupted".to_string()));
+                continue
+            }
+
+            match data.verify(&provider, &mut progress)? {
+                Verification::Valid => {
+                    report(&data, "valid", None);
+                    progress.complete(&step)?;
+                }
+                Verification::Skipped(reason) => report(&data, "skipped", Some(reason)),
+                Verification::Corrupted(reason) => {
+                    report(&data, "corrupted", Some(reason));
+                    corrupted.push(data);
+                }
+            }
+        }
+
+        let mut inconsistent_static_files = false;
+        if self.static_files {
+            for data in StaticFileData::ALL {
+                let step = data.to_string();
+                if progress.is_completed(&step) {
+                    report(&data, "valid", Some("verified by the previous run".to_string()));
+                    continue
+                }
+
+                match data.verify(&provider, &mut progress)? {
+                    Verification::Valid => {
+                        report(&data, "valid", None);
+                        progress.complete(&step)?;
+                    }
+                    Verification::Skipped(reason) => report(&data, "skipped", Some(reason)),
+                    Verification::Corrupted(reason) => {
+                        report(&data, "inconsistent", Some(reason));
+                        inconsistent_static_files = true;
+                    }
+                }
+            }
+        }
+        drop(provider);
+        progress.finish()?;
+
+        let repair = !corrupted.is_empty() && self.repair_derived;
+        if repair {
+            let provider_rw = provider_factory.provider_rw()?;
+            for data in &corrupted {
+                data.clear(&provider_rw, genesis)?;
+                report(
+                    data,
+                    "cleared",
+                    Some("will be re-derived on the next run of the pipeline".to_string()),
+                );
+            }
+            provider_rw.commit()?;
+        }
+
+        if format.is_json() {
+            print_json(&reports)?;
+        }
+        if !corrupted.is_empty() && !repair {
+            eyre::bail!(
+                "Derived tables are corrupted, run with `--repair-derived` to clear them and \
+                 re-derive them on the next run of the pipeline"
+            )
+        }
+        if inconsistent_static_files {
+            eyre::bail!("The database tables and the static files are inconsistent")
+        }
+
+        Ok(())
+    }
+}
+
+/// Tables derived by the pipeline stages from the plain state and the change sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DerivedData {
+    /// Hashed accounts and storages.
+    HashedState,
+    /// Account and storage tries.
+    Trie,
+    /// Account and storage history indices.
+    HistoryIndices,
+}
+
+/// The outcome of the verification of derived tables.
+#[derive(Debug)]
+enum Verification {
+    /// The tables are consistent with the data they are derived from.
+    Valid,
+    /// The tables can't be verified.
+    Skipped(String),
+    /// The tables are not consistent with the data they are derived from.
+    Corrupted(String),
+}
+
+impl DerivedData {
+    const ALL: [Self; 3] = [Self::HashedState, Self::Trie, Self::HistoryIndices];
+
+    /// The stages that fill the tables.
+    const fn stages(&self) -> &'static [StageId] {
+        match self {
+            Self::HashedState => &[StageId::AccountHashing, StageId::StorageHashing],
+            Self::Trie => &[StageId::MerkleExecute],
+            Self::HistoryIndices => &[StageId::IndexAccountHistory, StageId::IndexStorageHistory],
+        }
+    }
+
+    /// Verify the tables against the data they are derived from.
+    ///
+    /// The tables are only verified if the stages that fill them are synced with the execution
+    /// stage.
+    fn verify<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        progress: &mut CommandProgress,
+    ) -> eyre::Result<Verification> {
+        let checkpoint = |id| {
+            provider.get_stage_checkpoint(id).map(|c| c.map(|c| c.block_number).unwrap_or_default())
+        };
+        let execution = checkpoint(StageId::Execution)?;
+        for stage in self.stages() {
+            let block = checkpoint(*stage)?;
+            if block != execution {
+                return Ok(Verification::Skipped(format!(
+                    "stage {stage} is at block {block}, while execution is at block {execution}"
+                )))
+            }
+        }
+
+        let tx = provider.tx_ref();
+        match self {
+            Self::HashedState => {
+                let accounts = tx.entries::<tables::PlainAccountState>()?;
+                let hashed_accounts = tx.entries::<tables::HashedAccounts>()?;
+                if accounts != hashed_accounts {
+                    return Ok(Verification::Corrupted(format!(
+                        "{hashed_accounts} hashed accounts for {accounts} accounts"
+                    )))
+                }
+                let slots = tx.entries::<tables::PlainStorageState>()?;
+                let hashed_slots = tx.entries::<tables::HashedStorages>()?;
+                if slots != hashed_slots {
+                    return Ok(Verification::Corrupted(format!(
+                        "{hashed_slots} hashed storage slots for {slots} storage slots"
+                    )))
+                }
+                // With equal counts, every plain entry having its hashed counterpart means the
+                // tables hold the same state.
+                if let Some(reason) = verify_hashed_state(tx)? {
+                    return Ok(Verification::Corrupted(reason))
+                }
+            }
+            Self::Trie => {
+                let header = provider
+                    .sealed_header(execution)?
+                    .ok_or_else(|| eyre::eyre!("Header of block {execution} not found"))?;
+                if let Some(reason) = verify_trie(tx, header.state_root, execution)? {
+                    return Ok(Verification::Corrupted(reason))
+                }
+            }
+            Self::HistoryIndices => {
+                for segment in [
+                    PruneSegment::AccountHistory,
+                    PruneSegment::StorageHistory,
+                    PruneSegment::ChangeSetEpochs,
+                ] {
+                    if provider.get_prune_checkpoint(segment)?.is_some() {
+                        return Ok(Verification::Skipped(format!("segment {segment} is pruned")))
+                    }
+                }
+
+                let account_changes = tx.entries::<tables::AccountChangeSets>()?;
+                let indexed_account_changes =
+                    indexed_changes::<tables::AccountsHistory>(tx, progress)?;
+                if account_changes != indexed_account_changes {
+                    return Ok(Verification::Corrupted(format!(
+                        "{indexed_account_changes} indexed account changes for {account_changes} \
+                         account changes"
+                    )))
+                }
+                let storage_changes = tx.entries::<tables::StorageChangeSets>()?;
+                let indexed_storage_changes =
+                    indexed_changes::<tables::StoragesHistory>(tx, progress)?;
+                if storage_changes != indexed_storage_changes {
+                    return Ok(Verification::Corrupted(format!(
+                        "{indexed_storage_changes} indexed storage changes for {storage_changes} \
+                         storage changes"
+                    )))
+                }
+            }
+        }
+
+        Ok(Verification::Valid)
+    }
+
+    /// Clear the tables and reset the checkpoints of the stages that fill them.
+    fn clear<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        genesis: &Genesis,
+    ) -> eyre::Result<()> {
+        let tx = provider.tx_ref();
+        match self {
+            Self::HashedState => {
+                tx.clear::<tables::HashedAccounts>()?;
+                tx.clear::<tables::HashedStorages>()?;
+            }
+            Self::Trie => {
+                tx.clear::<tables::AccountsTrie>()?;
+                tx.clear::<tables::StoragesTrie>()?;
+                tx.put::<tables::StageCheckpoints>(
+                    StageId::MerkleUnwind.to_string(),
+                    Default::default(),
+                )?;
+                tx.delete::<tables::StageCheckpointProgresses>(
+                    StageId::MerkleExecute.to_string(),
+                    None,
+                )?;
+            }
+            Self::HistoryIndices => {
+                tx.clear::<tables::AccountsHistory>()?;
+                tx.clear::<tables::StoragesHistory>()?;
+            }
+        }
+        for stage in self.stages() {
+            tx.put::<tables::StageCheckpoints>(stage.to_string(), Default::default())?;
+        }
+        if *self == Self::HistoryIndices {
+            insert_genesis_history(provider, genesis)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DerivedData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HashedState => f.write_str("Hashed state"),
+            Self::Trie => f.write_str("Trie"),
+            Self::HistoryIndices => f.write_str("History indices"),
+        }
+    }
+}
+
+/// Checks that every account and storage slot of the plain state is in the hashed state with the
+/// same value. Returns the first mismatch, if any.
+fn verify_hashed_state(tx: &impl DbTx) -> eyre::Result<Option<String>> {
+    let mut hashed_accounts = tx.cursor_read::<tables::HashedAccounts>()?;
+    for entry in tx.cursor_read::<tables::PlainAccountState>()?.walk(None)? {
+        let (address, account) = entry?;
+        if hashed_accounts.seek_exact(keccak256(address))?.map(|(_, hashed)| hashed) !=
+            Some(account)
+        {
+            return Ok(Some(format!("hashed account of {address} doesn't match the account")))
+        }
+    }
+
+    let mut hashed_storages = tx.cursor_dup_read::<tables::HashedStorages>()?;
+    for entry in tx.cursor_read::<tables::PlainStorageState>()?.walk(None)? {
+        let (address, slot) = entry?;
+        let hashed_slot = keccak256(slot.key);
+        let hashed = hashed_storages
+            .seek_by_key_subkey(keccak256(address), hashed_slot)?
+            .filter(|hashed| hashed.key == hashed_slot);
+        if hashed.map(|hashed| hashed.value) != Some(slot.value) {
+            return Ok(Some(format!(
+                "hashed storage slot {} of {address} doesn't match the storage slot",
+                slot.key
+            )))
+        }
+    }
+    Ok(None)
+}
+
+/// Recomputes the state root and the trie nodes from the hashed state, ignoring the stored nodes,
+/// and checks the root against the state root of `block` and the nodes against the account and
+/// storage tries. Returns the first mismatch, if any.
+///
+/// The nodes are compared in batches as the computation progresses, so they are never all held
+/// in memory.
+fn verify_trie(
+    tx: &impl DbTx,
+    state_root: B256,
+    block: BlockNumber,
+) -> eyre::Result<Option<String>> {
+    let mut account_trie = tx.cursor_read::<tables::AccountsTrie>()?;
+    let mut storage_tries = tx.cursor_dup_read::<tables::StoragesTrie>()?;
+    let (mut account_nodes, mut storage_nodes) = (0, 0);
+
+    let mut intermediate_state = None;
+    loop {
+        let progress = StateRoot::from_tx(tx)
+            .with_trie_cursor_factory(NoopTrieCursorFactory)
+            .with_intermediate_state(intermediate_state.take())
+            .root_with_progress()?;
+        let (root, updates) = match progress {
+            StateRootProgress::Progress(state, _, updates) => {
+                intermediate_state = Some(*state);
+                (None, updates)
+            }
+            StateRootProgress::Complete(root, _, updates) => (Some(root), updates),
+        };
+
+        for (key, op) in updates {
+            // The tries are rebuilt from scratch, so there are no nodes to delete.
+            let TrieOp::Update(node) = op else { continue };
+            let stored = match &key {
+                TrieKey::AccountNode(nibbles) => {
+                    account_nodes += 1;
+                    account_trie.seek_exact(nibbles.clone())?.map(|(_, stored)| stored.0)
+                }
+                TrieKey::StorageNode(hashed_address, nibbles) => {
+                    storage_nodes += 1;
+                    storage_tries
+                        .seek_by_key_subkey(*hashed_address, nibbles.clone())?
+                        .filter(|stored| &stored.nibbles == nibbles)
+                        .map(|stored| stored.node)
+                }
+                TrieKey::StorageTrie(_) => continue,
+            };
+            if stored.as_ref() != Some(&node) {
+                return Ok(Some(format!("trie node {key:?} doesn't match the hashed state")))
+            }
+        }
+
+        if let Some(root) = root {
+            if root != state_root {
+                return Ok(Some(format!(
+                    "state root {root} of the hashed state does not match the state root \
+                     {state_root} of block {block}"
+                )))
+            }
+            break
+        }
+    }
+
+    // Every computed node is stored, so the tries hold extra nodes if the counts differ.
+    let stored_account_nodes = tx.entries::<tables::AccountsTrie>()?;
+    if stored_account_nodes != account_nodes {
+        return Ok(Some(format!(
+            "{stored_account_nodes} account trie nodes for {account_nodes} computed nodes"
+        )))
+    }
+    let stored_storage_nodes = tx.entries::<tables::StoragesTrie>()?;
+    if stored_storage_nodes != storage_nodes {
+        return Ok(Some(format!(
+            "{stored_storage_nodes} storage trie nodes for {storage_nodes} computed nodes"
+        )))
+    }
+    Ok(None)
+}
+
+/// Canonical chain data split between the database tables and the static files.
+///
+/// The static files hold the oldest part of the data, and the database tables continue it from
+/// the block or transaction after the last one in the static files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StaticFileData {
+    /// Headers and canonical block hashes.
+    Headers,
+    /// Block body indices and transactions.
+    Transactions,
+    /// Receipts.
+    Receipts,
+}
+
+impl StaticFileData {
+    const ALL: [Self; 3] = [Self::Headers, Self::Transactions, Self::Receipts];
+
+    /// Cross-check the data in the static files against the database tables.
+    fn verify<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        progress: &mut CommandProgress,
+    ) -> eyre::Result<Verification> {
+        let static_file_provider = provider.static_file_provider();
+        let tx = provider.tx_ref();
+        match self {
+            Self::Headers => {
+                let highest =
+                    static_file_provider.get_highest_static_file_block(StaticFileSegment::Headers);
+                if let Some(reason) =
+                    continues_static_files::<tables::Headers>(tx, highest.map_or(0, |b| b + 1))?
+                {
+                    return Ok(Verification::Corrupted(reason))
+                }
+                if let Some(highest) = highest {
+                    return verify_static_file_headers(
+                        static_file_provider,
+                        tx,
+                        highest,
+                        &self.to_string(),
+                        progress,
+                    )
+                }
+            }
+            Self::Transactions => {
+                // Transaction numbers of consecutive blocks must be contiguous.
+                let (mut last_block, mut next_tx) = (0, 0);
+                for entry in tx.cursor_read::<tables::BlockBodyIndices>()?.walk(None)? {
+                    let (number, body) = entry?;
+                    if body.first_tx_num != next_tx {
+                        return Ok(Verification::Corrupted(format!(
+                            "block {number} starts at transaction {}, while the previous block \
+                             ends before transaction {next_tx}",
+                            body.first_tx_num
+                        )))
+                    }
+                    (last_block, next_tx) = (number, body.next_tx_num());
+                }
+
+                if let Some(reason) = verify_static_file_transactions::<tables::Transactions>(
+                    provider,
+                    StaticFileSegment::Transactions,
+                    last_block,
+                )? {
+                    return Ok(Verification::Corrupted(reason))
+                }
+            }
+            Self::Receipts => {
+                // Receipts are only written to the static files if they are not pruned.
+                for segment in [PruneSegment::Receipts, PruneSegment::ContractLogs] {
+                    if provider.get_prune_checkpoint(segment)?.is_some() {
+                        return Ok(Verification::Skipped(format!("segment {segment} is pruned")))
+                    }
+                }
+
+                // Receipts are written up to the last executed block.
+                let execution = provider
+                    .get_stage_checkpoint(StageId::Execution)?
+                    .unwrap_or_default()
+                    .block_number;
+                if let Some(reason) = verify_static_file_transactions::<tables::Receipts>(
+                    provider,
+                    StaticFileSegment::Receipts,
+                    execution,
+                )? {
+                    return Ok(Verification::Corrupted(reason))
+                }
+            }
+        }
+
+        Ok(Verification::Valid)
+    }
+}
+
+impl fmt::Display for StaticFileData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Headers => f.write_str("Static file headers"),
+            Self::Transactions => f.write_str("Static file transactions"),
+            Self::Receipts => f.write_str("Static file receipts"),
+        }
+    }
+}
+
+/// Checks that the entries of the table in the database start right after the static files,
+/// which end before `next`. Returns the inconsistency, if any.
+fn continues_static_files<T: Table<Key = u64>>(
+    tx: &impl DbTx,
+    next: u64,
+) -> eyre::Result<Option<String>> {
+    let Some((first, _)) = tx.cursor_read::<T>()?.first()? else { return Ok(None) };
+    Ok(if first < next {
+        Some(format!(
+            "{} {first}..{next} are both in the database and in the static files",
+            T::NAME
+        ))
+    } else if first > next {
+        Some(format!(
+            "{} {next}..{first} are neither in the database nor in the static files",
+            T::NAME
+        ))
+    } else {
+        None
+    })
+}
+
+/// Checks that the transactions or receipts in the static files end with the last transaction of
+/// a block, and that the database table holds all the transactions after them, up to the last
+/// transaction of `last_block`. Returns the inconsistency, if any.
+fn verify_static_file_transactions<T: Table<Key = TxNumber>>(
+    provider: &DatabaseProviderRO<impl Database>,
+    segment: StaticFileSegment,
+    last_block: BlockNumber,
+) -> eyre::Result<Option<String>> {
+    let next_tx = provider
+        .tx_ref()
+        .get::<tables::BlockBodyIndices>(last_block)?
+        .map(|body| body.next_tx_num())
+        .unwrap_or_default();
+    let static_file_provider = provider.static_file_provider();
+    let static_file_next_tx =
+        static_file_provider.get_highest_static_file_tx(segment).map_or(0, |tx| tx + 1);
+    if let Some(block) = static_file_provider.get_highest_static_file_block(segment) {
+        let Some(body) = provider.tx_ref().get::<tables::BlockBodyIndices>(block)? else {
+            return Ok(Some(format!(
+                "{segment} static files end at block {block}, which has no body indices"
+            )))
+        };
+        if body.next_tx_num() != static_file_next_tx {
+            return Ok(Some(format!(
+                "{segment} static files end before transaction {static_file_next_tx}, while block \
+                 {block} ends before transaction {}",
+                body.next_tx_num()
+            )))
+        }
+    }
+
+    if let Some(reason) = continues_static_files::<T>(provider.tx_ref(), static_file_next_tx)? {
+        return Ok(Some(reason))
+    }
+    let db_next_tx =
+        provider.tx_ref().cursor_read::<T>()?.last()?.map_or(static_file_next_tx, |(tx, _)| tx + 1);
+    if db_next_tx != next_tx {
+        return Ok(Some(format!(
+            "{} end before transaction {db_next_tx}, while block {last_block} ends before \
+             transaction {next_tx}",
+            T::NAME
+        )))
+    }
+    Ok(None)
+}
+
+/// Checks that the headers in the static files up to `highest` are present, hashed correctly,
+/// linked to their parents and canonical. Returns the outcome of the check.
+///
+/// The walk is resumed from the last block recorded in the progress checkpoint, if any.
+fn verify_static_file_headers(
+    static_file_provider: &StaticFileProvider,
+    tx: &impl DbTx,
+    highest: BlockNumber,
+    step: &str,
+    progress: &mut CommandProgress,
+) -> eyre::Result<Verification> {
+    /// Number of headers read from the static files at once.
+    const BATCH_SIZE: u64 = 10_000;
+
+    let (mut start, mut parent_hash) = match progress.resume::<B256>(step)? {
+        Some((last_block, hash)) => {
+            (u64::from_be_bytes(last_block[..].try_into()?) + 1, Some(hash))
+        }
+        None => (0, None),
+    };
+
+    let mut canonical = tx.cursor_read::<tables::CanonicalHeaders>()?;
+    while start <= highest {
+        let end = highest.min(start + BATCH_SIZE - 1);
+        let headers = match static_file_provider.sealed_headers_while(start..=end, |_| true) {
+            Ok(headers) => headers,
+            Err(ProviderError::MissingStaticFileBlock(_, number)) => {
+                return Ok(Verification::Corrupted(format!(
+                    "header of block {number} is missing from the static files"
+                )))
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        for header in headers {
+            let number = header.number;
+            if header.hash() != header.header().hash_slow() {
+                return Ok(Verification::Corrupted(format!(
+                    "hash {} of block {number} in the static files doesn't match its header",
+                    header.hash()
+                )))
+            }
+            if parent_hash.is_some_and(|parent_hash| parent_hash != header.parent_hash) {
+                return Ok(Verification::Corrupted(format!(
+                    "block {number} in the static files isn't a child of the previous block"
+                )))
+            }
+            match canonical.seek_exact(number)? {
+                Some((_, hash)) if hash == header.hash() => {}
+                Some((_, hash)) => {
+                    return Ok(Verification::Corrupted(format!(
+                        "canonical hash {hash} of block {number} doesn't match the hash {} in the \
+                         static files",
+                        header.hash()
+                    )))
+                }
+                None => {
+                    return Ok(Verification::Corrupted(format!(
+                        "block {number} is in the static files, but not in the canonical chain"
+                    )))
+                }
+            }
+
+            parent_hash = Some(header.hash());
+            progress.record(step, &number.to_be_bytes(), &header.hash())?;
+        }
+        start = end + 1;
+    }
+
+    Ok(Verification::Valid)
+}
+
+/// Returns the total number of block numbers in the shards of the history index.
+///
+/// The walk is resumed from the last shard recorded in the progress checkpoint, if any.
+fn indexed_changes<T: Table<Value = BlockNumberList>>(
+    tx: &impl DbTx,
+    progress: &mut CommandProgress,
+) -> eyre::Result<usize> {
+    let (start, mut changes) = match progress.resume::<usize>(T::NAME)? {
+        Some((last_key, changes)) => (Some(RawKey::<T::Key>::from_vec(last_key.to_vec())), changes),
+        None => (None, 0),
+    };
+    let last_key = start.as_ref().map(|key| key.raw_key().clone());
+
+    let mut cursor = tx.cursor_read::<RawTable<T>>()?;
+    for entry in cursor.walk(start)? {
+        let (key, blocks) = entry?;
+        // The walk starts at the last shard counted by the previous run.
+        if last_key.as_ref() == Some(key.raw_key()) {
+            continue
+        }
+        changes += blocks.value()?.0.len() as usize;
+        progress.record(T::NAME, key.raw_key(), &changes)?;
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+    use reth_primitives::{Account, Address, Header, StorageEntry, U256};
+    use reth_provider::test_utils::create_test_provider_factory;
+    use std::sync::Arc;
+
+    /// Create a database with a state of 256 accounts with storage, its hashed state and tries,
+    /// and a block 0 with its state root.
+    fn create_state() -> ProviderFactory<Arc<TempDatabase<DatabaseEnv>>> {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        for i in 0..=u8::MAX {
+            let address = Address::with_last_byte(i);
+            let account = Account { nonce: i as u64, balance: U256::from(i), bytecode_hash: None };
+            tx.put::<tables::PlainAccountState>(address, account).unwrap();
+            tx.put::<tables::HashedAccounts>(keccak256(address), account).unwrap();
+            for slot in 0..32 {
+                let (key, value) = (B256::with_last_byte(slot), U256::from(i as u64 + 1));
+                tx.put::<tables::PlainStorageState>(address, StorageEntry { key, value }).unwrap();
+                tx.put::<tables::HashedStorages>(
+                    keccak256(address),
+                    StorageEntry { key: keccak256(key), value },
+                )
+                .unwrap();
+            }
+        }
+
+        let (state_root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.flush(tx).unwrap();
+        let header = Header { state_root, ..Default::default() }.seal_slow();
+        tx.put::<tables::CanonicalHeaders>(0, header.hash()).unwrap();
+        tx.put::<tables::Headers>(0, header.unseal()).unwrap();
+        provider.commit().unwrap();
+        factory
+    }
+
+    fn verify(
+        factory: &ProviderFactory<Arc<TempDatabase<DatabaseEnv>>>,
+        data: DerivedData,
+    ) -> Verification {
+        let dir = tempfile::tempdir().unwrap();
+        let mut progress = CommandProgress::load(dir.path(), "verify", &()).unwrap();
+        data.verify(&factory.provider().unwrap(), &mut progress).unwrap()
+    }
+
+    #[test]
+    fn valid_state() {
+        let factory = create_state();
+        assert!(matches!(verify(&factory, DerivedData::HashedState), Verification::Valid));
+        assert!(matches!(verify(&factory, DerivedData::Trie), Verification::Valid));
+    }
+
+    #[test]
+    fn corrupted_hashed_state() {
+        let factory = create_state();
+        let provider = factory.provider_rw().unwrap();
+        let hashed_address = keccak256(Address::with_last_byte(7));
+        provider
+            .tx_ref()
+            .put::<tables::HashedAccounts>(
+                hashed_address,
+                Account { nonce: 8, ..Default::default() },
+            )
+            .unwrap();
+        provider.commit().unwrap();
+
+        assert!(matches!(verify(&factory, DerivedData::HashedState), Verification::Corrupted(_)));
+        assert!(matches!(verify(&factory, DerivedData::Trie), Verification::Corrupted(_)));
+    }
+
+    #[test]
+    fn corrupted_trie_nodes() {
+        // A node below the root of the account trie, which isn't read by the incremental root.
+        let factory = create_state();
+        let provider = factory.provider_rw().unwrap();
+        let (nibbles, mut node) = provider
+            .tx_ref()
+            .cursor_read::<tables::AccountsTrie>()
+            .unwrap()
+            .last()
+            .unwrap()
+            .unwrap();
+        assert!(!nibbles.0.is_empty());
+        node.0.root_hash = Some(B256::repeat_byte(0xff));
+        provider.tx_ref().put::<tables::AccountsTrie>(nibbles, node).unwrap();
+        provider.commit().unwrap();
+        assert!(matches!(verify(&factory, DerivedData::Trie), Verification::Corrupted(_)));
+
+        // A node of a storage trie.
+        let factory = create_state();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        let (hashed_address, mut entry) =
+            tx.cursor_read::<tables::StoragesTrie>().unwrap().first().unwrap().unwrap();
+        tx.delete::<tables::StoragesTrie>(hashed_address, Some(entry.clone())).unwrap();
+        entry.node.root_hash = Some(B256::repeat_byte(0xff));
+        tx.put::<tables::StoragesTrie>(hashed_address, entry).unwrap();
+        provider.commit().unwrap();
+        assert!(matches!(verify(&factory, DerivedData::Trie), Verification::Corrupted(_)));
+
+        // An extra node, not derived from the hashed state.
+        let factory = create_state();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        let (_, node) = tx.cursor_read::<tables::AccountsTrie>().unwrap().first().unwrap().unwrap();
+        tx.put::<tables::AccountsTrie>(vec![0xf; 40].into(), node).unwrap();
+        provider.commit().unwrap();
+        assert!(matches!(verify(&factory, DerivedData::Trie), Verification::Corrupted(_)));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::utils::DbTool;
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::WrapErr;
+use human_bytes::human_bytes;
+use reth_db::{database::Database, database_metrics::DatabaseTableStats, Tables};
+use std::{collections::BTreeMap, time::Duration};
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db watch` command
+///
+/// The database is opened read-only, so it can be watched while the node is running.
+pub struct Command {
+    /// The number of seconds between samples.
+    #[arg(long, value_name = "SECONDS", default_value_t = 10)]
+    interval: u64,
+    /// The number of samples to take. If not set, samples are taken until interrupted.
+    #[arg(long)]
+    samples: Option<usize>,
+    /// Show only the tables whose entry count or size changed since the previous sample.
+    #[arg(long, default_value_t = false)]
+    changed_only: bool,
+}
+
+/// Entry count and size of a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TableSample {
+    entries: usize,
+    size: usize,
+}
+
+impl Command {
+    /// Execute `db watch` command
+    pub async fn execute<DB: Database + DatabaseTableStats>(
+        self,
+        tool: &DbTool<DB>,
+    ) -> eyre::Result<()> {
+        let interval = Duration::from_secs(self.interval);
+        let mut previous = Self::sample(tool)?;
+        println!("{}", self.table(&previous, None));
+
+        let mut taken = 1;
+        while self.samples.map_or(true, |samples| taken < samples) {
+            tokio::time::sleep(interval).await;
+            let current = Self::sample(tool)?;
+            taken += 1;
+
+            println!("\nChanges in the last {interval:?}:");
+            println!("{}", self.table(&current, Some(&previous)));
+            previous = current;
+        }
+
+        Ok(())
+    }
+
+    /// Read the entry counts and sizes of all tables. Every sample reads the tables in new
+    /// read-only transactions, so that the writes committed by the node since the previous sample
+    /// are visible.
+    fn sample<DB: Database + DatabaseTableStats>(
+        tool: &DbTool<DB>,
+    ) -> eyre::Result<BTreeMap<&'static str, TableSample>> {
+        let mut samples = BTreeMap::new();
+        for table in Tables::ALL {
+            let stats = tool
+                .table_stats(*table)
+                .wrap_err(format!("Could not find table: {}", table.name()))?;
+            samples
+                .insert(table.name(), TableSample { entries: stats.entries(), size: stats.size() });
+        }
+        Ok(samples)
+    }
+
+    /// Build the table of the sample, with the changes since the previous sample if any.
+    fn table(
+        &self,
+        current: &BTreeMap<&'static str, TableSample>,
+        previous: Option<&BTreeMap<&'static str, TableSample>>,
+    ) -> ComfyTable {
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header([
+            "Table Name",
+            "# Entries",
+            "Entries Change",
+            "Total Size",
+            "Size Change",
+        ]);
+
+        for (name, sample) in current {
+            let previous = previous.map(|previous| previous.get(name).copied().unwrap_or(*sample));
+            if self.changed_only && previous.is_some_and(|previous| previous == *sample) {
+                continue
+            }
+
+            let (entries_change, size_change) = match previous {
+                Some(previous) => (
+                    format_change(sample.entries, previous.entries, |change| change.to_string()),
+                    format_change(sample.size, previous.size, |change| human_bytes(change as f64)),
+                ),
+                None => (String::new(), String::new()),
+            };
+            let mut row = Row::new();
+            row.add_cell(Cell::new(name))
+                .add_cell(Cell::new(sample.entries))
+                .add_cell(Cell::new(entries_change))
+                .add_cell(Cell::new(human_bytes(sample.size as f64)))
+                .add_cell(Cell::new(size_change));
+            table.add_row(row);
+        }
+
+        table
+    }
+}
+
+/// Format the signed difference between the current and previous values.
+fn format_change(current: usize, previous: usize, format: impl Fn(usize) -> String) -> String {
+    if current >= previous {
+        format!("+{}", format(current - previous))
+    } else {
+        format!("-{}", format(previous - current))
+    }
+}
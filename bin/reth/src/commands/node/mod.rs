@@ -7,10 +7,10 @@ use crate::{
         RpcServerArgs, TxPoolArgs,
     },
     core::cli::runner::CliContext,
-    dirs::{DataDirPath, MaybePlatformPath},
+    dirs::{ChainPath, DataDirPath, MaybePlatformPath},
 };
 use clap::{value_parser, Args, Parser};
-use reth_db::{init_db, DatabaseEnv};
+use reth_db::{cache::CachedDatabase, init_db, DatabaseEnv};
 use reth_node_builder::{InitState, NodeBuilder, WithLaunchContext};
 use reth_node_core::{node_config::NodeConfig, version};
 use reth_primitives::ChainSpec;
@@ -100,6 +100,11 @@ pub struct NodeCommand<Ext: clap::Args + fmt::Debug = NoArgs> {
     #[command(flatten)]
     pub db: DatabaseArgs,
 
+    /// Cache the reads of the accounts, storage slots and bytecodes in memory, holding at most the
+    /// given number of keys. The cached keys are invalidated when they're written.
+    #[arg(long = "db.read-cache-size", value_name = "KEYS", help_heading = "Database")]
+    pub read_cache_size: Option<u32>,
+
     /// All dev related arguments with --dev prefix
     #[command(flatten)]
     pub dev: DevArgs,
@@ -134,11 +139,74 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
     ///
     /// This transforms the node command into a node config and launches the node using the given
     /// closure.
+    ///
+    /// The read cache of the database is not supported by this launcher, see
+    /// [NodeCommand::execute_with_read_cache].
     pub async fn execute<L, Fut>(self, ctx: CliContext, launcher: L) -> eyre::Result<()>
     where
         L: FnOnce(WithLaunchContext<Arc<DatabaseEnv>, InitState>, Ext) -> Fut,
         Fut: Future<Output = eyre::Result<()>>,
     {
+        if self.read_cache_size.is_some_and(|size| size > 0) {
+            eyre::bail!("--db.read-cache-size is not supported by the launcher of this node")
+        }
+
+        let (node_config, data_dir, database, ext) = self.open_database()?;
+        let database = Arc::new(database);
+        sync_database_on_shutdown(&ctx, database.clone());
+
+        let builder = NodeBuilder::new(node_config)
+            .with_database(database)
+            .with_launch_context(ctx.task_executor, data_dir);
+
+        launcher(builder, ext).await
+    }
+
+    /// Launches the node, wrapping the database in a [CachedDatabase] if a read cache size is
+    /// configured with `--db.read-cache-size`.
+    ///
+    /// The node is launched using `launcher` if the read cache is disabled, and `cached_launcher`
+    /// otherwise.
+    pub async fn execute_with_read_cache<L, Fut, LC, FutC>(
+        self,
+        ctx: CliContext,
+        launcher: L,
+        cached_launcher: LC,
+    ) -> eyre::Result<()>
+    where
+        L: FnOnce(WithLaunchContext<Arc<DatabaseEnv>, InitState>, Ext) -> Fut,
+        Fut: Future<Output = eyre::Result<()>>,
+        LC: FnOnce(
+            WithLaunchContext<Arc<CachedDatabase<Arc<DatabaseEnv>>>, InitState>,
+            Ext,
+        ) -> FutC,
+        FutC: Future<Output = eyre::Result<()>>,
+    {
+        let read_cache_size = self.read_cache_size.unwrap_or_default();
+        let (node_config, data_dir, database, ext) = self.open_database()?;
+        let database = Arc::new(database);
+        sync_database_on_shutdown(&ctx, database.clone());
+
+        if read_cache_size == 0 {
+            let builder = NodeBuilder::new(node_config)
+                .with_database(database)
+                .with_launch_context(ctx.task_executor, data_dir);
+            launcher(builder, ext).await
+        } else {
+            tracing::info!(
+                target: "reth::cli",
+                keys = read_cache_size,
+                "Enabling database read cache"
+            );
+            let builder = NodeBuilder::new(node_config)
+                .with_database(Arc::new(CachedDatabase::new(database, read_cache_size)))
+                .with_launch_context(ctx.task_executor, data_dir);
+            cached_launcher(builder, ext).await
+        }
+    }
+
+    /// Transforms the node command into a node config and opens the database.
+    fn open_database(self) -> eyre::Result<(NodeConfig, ChainPath<DataDirPath>, DatabaseEnv, Ext)> {
         tracing::info!(target: "reth::cli", version = ?version::SHORT_VERSION, "Starting reth");
 
         let Self {
@@ -154,6 +222,7 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             builder,
             debug,
             db,
+            read_cache_size: _,
             dev,
             pruning,
             ext,
@@ -183,20 +252,35 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
         let db_path = data_dir.db_path();
 
         tracing::info!(target: "reth::cli", path = ?db_path, "Opening database");
-        let database = Arc::new(init_db(db_path.clone(), self.db.database_args())?.with_metrics());
+        let database = init_db(db_path, node_config.db.database_args())?.with_metrics();
 
         if with_unused_ports {
             node_config = node_config.with_unused_ports();
         }
 
-        let builder = NodeBuilder::new(node_config)
-            .with_database(database)
-            .with_launch_context(ctx.task_executor, data_dir);
-
-        launcher(builder, ext).await
+        Ok((node_config, data_dir, database, ext))
     }
 }
 
+/// Flushes the commits that aren't durable yet when the node is shut down, so that they aren't
+/// lost to a system crash after exit.
+///
+/// The pipeline and the engine may still commit after the shutdown signal, so the commits are made
+/// durable from then on, instead of being flushed only once.
+fn sync_database_on_shutdown(ctx: &CliContext, database: Arc<DatabaseEnv>) {
+    ctx.task_executor.spawn_with_graceful_shutdown_signal(|shutdown| async move {
+        let _guard = shutdown.await;
+        // Waits for the current write transaction to finish.
+        match tokio::task::spawn_blocking(move || database.disable_sync_period()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => {
+                tracing::error!(target: "reth::cli", %error, "Failed to sync database")
+            }
+            Err(error) => tracing::error!(target: "reth::cli", %error, "Failed to sync database"),
+        }
+    });
+}
+
 /// No Additional arguments
 #[derive(Debug, Clone, Copy, Default, Args)]
 #[non_exhaustive]
@@ -225,6 +309,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_read_cache_size() {
+        let cmd =
+            NodeCommand::try_parse_args_from(["reth", "--db.read-cache-size", "100000"]).unwrap();
+        assert_eq!(cmd.read_cache_size, Some(100000));
+    }
+
     #[test]
     fn parse_discovery_addr() {
         let cmd =
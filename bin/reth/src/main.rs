@@ -20,10 +20,16 @@ fn main() {
         std::env::set_var("RUST_BACKTRACE", "1");
     }
 
-    if let Err(err) = Cli::parse_args().run(|builder, _| async {
-        let handle = builder.launch_node(EthereumNode::default()).await?;
-        handle.node_exit_future.await
-    }) {
+    if let Err(err) = Cli::parse_args().run_with_read_cache(
+        |builder, _| async {
+            let handle = builder.launch_node(EthereumNode::default()).await?;
+            handle.node_exit_future.await
+        },
+        |builder, _| async {
+            let handle = builder.launch_node(EthereumNode::default()).await?;
+            handle.node_exit_future.await
+        },
+    ) {
         eprintln!("Error: {err:?}");
         std::process::exit(1);
     }
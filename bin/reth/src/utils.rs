@@ -5,9 +5,10 @@ use eyre::Result;
 use reth_db::{
     cursor::{DbCursorRO, DbDupCursorRO},
     database::Database,
+    database_metrics::{DatabaseTableStats, TableStats},
     table::{Decode, Decompress, DupSort, Table, TableRow},
     transaction::{DbTx, DbTxMut},
-    DatabaseError, RawTable, TableRawRow,
+    DatabaseError, RawTable, TableRawRow, Tables,
 };
 use reth_primitives::{fs, ChainSpec};
 use reth_provider::ProviderFactory;
@@ -44,6 +45,24 @@ impl<DB: Database> DbTool<DB> {
     /// [`ListFilter`] can be used to further
     /// filter down the desired results. (eg. List only rows which include `0xd3adbeef`)
     pub fn list<T: Table>(&self, filter: &ListFilter) -> Result<(Vec<TableRow<T>>, usize)> {
+        let mut list = Vec::new();
+        let hits = self.for_each::<T>(filter, |row| {
+            list.push(row);
+            Ok(())
+        })?;
+        Ok((list, hits))
+    }
+
+    /// Walks the entries of the table that match the [`ListFilter`], calling `f` with each of
+    /// them, and returns the number of matching entries.
+    ///
+    /// Unlike [`DbTool::list`], the entries are not collected, so the table is read in a single
+    /// pass without holding it in memory.
+    pub fn for_each<T: Table>(
+        &self,
+        filter: &ListFilter,
+        mut f: impl FnMut(TableRow<T>) -> Result<()>,
+    ) -> Result<usize> {
         let bmb = Rc::new(BMByte::from(&filter.search));
         if bmb.is_none() && filter.has_search() {
             eyre::bail!("Invalid search.")
@@ -51,9 +70,8 @@ impl<DB: Database> DbTool<DB> {
 
         let mut hits = 0;
 
-        let data = self.provider_factory.db_ref().view(|tx| {
-            let mut cursor =
-                tx.cursor_read::<RawTable<T>>().expect("Was not able to obtain a cursor.");
+        self.provider_factory.db_ref().view(|tx| {
+            let mut cursor = tx.cursor_read::<RawTable<T>>()?;
 
             let map_filter = |row: Result<TableRawRow<T>, _>| {
                 if let Ok((k, v)) = row {
@@ -97,24 +115,16 @@ impl<DB: Database> DbTool<DB> {
                 None
             };
 
-            if filter.reverse {
-                Ok(cursor
-                    .walk_back(None)?
-                    .skip(filter.skip)
-                    .filter_map(map_filter)
-                    .take(filter.len)
-                    .collect::<Vec<(_, _)>>())
-            } else {
-                Ok(cursor
-                    .walk(None)?
-                    .skip(filter.skip)
-                    .filter_map(map_filter)
-                    .take(filter.len)
-                    .collect::<Vec<(_, _)>>())
-            }
-        })?;
+            let rows: Box<dyn Iterator<Item = Result<TableRawRow<T>, DatabaseError>>> =
+                if filter.reverse {
+                    Box::new(cursor.walk_back(None)?)
+                } else {
+                    Box::new(cursor.walk(None)?)
+                };
+            rows.skip(filter.skip).filter_map(map_filter).take(filter.len).try_for_each(&mut f)
+        })??;
 
-        Ok((data.map_err(|e: DatabaseError| eyre::eyre!(e))?, hits))
+        Ok(hits)
     }
 
     /// Grabs the content of the table for the given key
@@ -155,6 +165,13 @@ impl<DB: Database> DbTool<DB> {
     }
 }
 
+impl<DB: Database + DatabaseTableStats> DbTool<DB> {
+    /// Returns the [TableStats] of the table, as reported by the database backend.
+    pub fn table_stats(&self, table: Tables) -> Result<TableStats> {
+        Ok(self.provider_factory.db_ref().table_stats(table)?)
+    }
+}
+
 /// Filters the results coming from the database.
 #[derive(Debug)]
 pub struct ListFilter {
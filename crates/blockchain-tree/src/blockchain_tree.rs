@@ -78,7 +78,7 @@ pub struct BlockchainTree<DB: Database, EVM: ExecutorFactory> {
 
 impl<DB, EVM> BlockchainTree<DB, EVM>
 where
-    DB: Database + Clone,
+    DB: Database + Clone + 'static,
     EVM: ExecutorFactory,
 {
     /// Create a new blockchain tree.
@@ -1274,6 +1274,7 @@ mod tests {
         ProviderFactory,
     };
     use reth_revm::EvmProcessorFactory;
+    use reth_tasks::pool::BlockingTaskPool;
     use reth_trie::StateRoot;
     use std::collections::HashMap;
 
@@ -1678,6 +1679,40 @@ mod tests {
         assert_eq!(state_root, block5.state_root);
     }
 
+    #[test]
+    fn async_state_root() {
+        let data = BlockChainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+        let genesis = data.genesis;
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(vec![exec2, exec1])
+            .with_state_root_pool(BlockingTaskPool::build().unwrap());
+
+        // last finalized block would be number 9.
+        setup_genesis(&externals.provider_factory, genesis);
+
+        // make tree
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2);
+        let mut tree = BlockchainTree::new(externals, config, None).expect("failed to create tree");
+        // genesis block 10 is already canonical
+        tree.make_canonical(&B256::ZERO).unwrap();
+
+        for block in [block1, block2] {
+            assert_eq!(
+                tree.insert_block(block.clone(), BlockValidationKind::Exhaustive).unwrap(),
+                InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+            );
+            let chain_id = tree.state.block_indices.get_blocks_chain_id(&block.hash()).unwrap();
+            assert!(tree.state.chains.get(&chain_id).unwrap().trie_updates().is_some());
+            assert_eq!(
+                tree.make_canonical(&block.hash()).unwrap(),
+                CanonicalOutcome::Committed { head: block.header.clone() }
+            );
+        }
+    }
+
     #[test]
     fn test_side_chain_fork() {
         let data = BlockChainTestData::default_from_number(11);
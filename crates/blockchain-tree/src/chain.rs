@@ -15,7 +15,8 @@ use reth_interfaces::{
     RethResult,
 };
 use reth_primitives::{
-    BlockHash, BlockNumber, ForkBlock, GotExpected, SealedBlockWithSenders, SealedHeader, U256,
+    BlockHash, BlockNumber, ForkBlock, GotExpected, SealedBlockWithSenders, SealedHeader, B256,
+    U256,
 };
 use reth_provider::{
     providers::{BundleStateProvider, ConsistentDbView},
@@ -23,7 +24,7 @@ use reth_provider::{
     StateRootProvider,
 };
 use reth_trie::updates::TrieUpdates;
-use reth_trie_parallel::parallel_root::ParallelStateRoot;
+use reth_trie_parallel::{async_root::AsyncStateRoot, parallel_root::ParallelStateRoot};
 use std::{
     collections::BTreeMap,
     ops::{Deref, DerefMut},
@@ -76,7 +77,7 @@ impl AppendableChain {
         block_validation_kind: BlockValidationKind,
     ) -> Result<Self, InsertBlockErrorKind>
     where
-        DB: Database + Clone,
+        DB: Database + Clone + 'static,
         EF: ExecutorFactory,
     {
         let state = BundleStateWithReceipts::default();
@@ -114,7 +115,7 @@ impl AppendableChain {
         block_validation_kind: BlockValidationKind,
     ) -> Result<Self, InsertBlockErrorKind>
     where
-        DB: Database + Clone,
+        DB: Database + Clone + 'static,
         EF: ExecutorFactory,
     {
         let parent_number = block.number - 1;
@@ -176,7 +177,7 @@ impl AppendableChain {
     ) -> RethResult<(BundleStateWithReceipts, Option<TrieUpdates>)>
     where
         BSDP: BundleStateDataProvider,
-        DB: Database + Clone,
+        DB: Database + Clone + 'static,
         EVM: ExecutorFactory,
     {
         // some checks are done before blocks comes here.
@@ -214,10 +215,53 @@ impl AppendableChain {
                 let mut state = provider.bundle_state_data_provider.state().clone();
                 state.extend(bundle_state.clone());
                 let hashed_state = state.hash_state_slow();
-                ParallelStateRoot::new(consistent_view, hashed_state)
-                    .incremental_root_with_updates()
-                    .map(|(root, updates)| (root, Some(updates)))
-                    .map_err(ProviderError::from)?
+                let (root, updates, is_sequential) = match &externals.state_root_pool {
+                    Some(pool) => {
+                        externals.state_root_metrics.async_roots.increment(1);
+                        let mut state_root =
+                            AsyncStateRoot::new(consistent_view, pool.clone(), hashed_state);
+                        if let Some(guard) = &externals.state_root_task_guard {
+                            state_root = state_root.with_task_guard(guard.clone());
+                        }
+                        if let Some(trie_cache) = &externals.trie_cache {
+                            state_root = state_root.with_trie_cache(trie_cache.clone());
+                        }
+                        // The tree validates blocks one at a time, so the calling thread waits
+                        // for the result. On failure, the remaining storage root tasks are
+                        // cancelled before falling back to the sequential computation.
+                        let result =
+                            futures::executor::block_on(state_root.incremental_root_with_updates());
+                        match result {
+                            Ok((root, updates)) => (root, updates, false),
+                            Err(error) => {
+                                tracing::warn!(
+                                    target: "blockchain_tree::chain",
+                                    number = block.number,
+                                    %error,
+                                    "Async state root failed, falling back to sequential"
+                                );
+                                externals.state_root_metrics.sequential_fallbacks.increment(1);
+                                let (root, updates) =
+                                    provider.state_root_with_updates(bundle_state.state())?;
+                                (root, updates, true)
+                            }
+                        }
+                    }
+                    None => {
+                        externals.state_root_metrics.parallel_roots.increment(1);
+                        let (root, updates) = ParallelStateRoot::new(consistent_view, hashed_state)
+                            .incremental_root_with_updates()
+                            .map_err(ProviderError::from)?;
+                        (root, updates, false)
+                    }
+                };
+                let (root, updates) = if externals.state_root_cross_check && !is_sequential {
+                    let sequential = provider.state_root_with_updates(bundle_state.state())?;
+                    cross_check_state_root(block.number, (root, updates), sequential)
+                } else {
+                    (root, updates)
+                };
+                (root, Some(updates))
             } else {
                 (provider.state_root(bundle_state.state())?, None)
             };
@@ -266,7 +310,7 @@ impl AppendableChain {
         block_validation_kind: BlockValidationKind,
     ) -> Result<(), InsertBlockErrorKind>
     where
-        DB: Database + Clone,
+        DB: Database + Clone + 'static,
         EF: ExecutorFactory,
     {
         let parent_block = self.chain.tip();
@@ -292,3 +336,35 @@ impl AppendableChain {
         Ok(())
     }
 }
+
+/// Compare the state root and trie updates computed in parallel with the ones computed
+/// sequentially, logging the first divergence. The sequential result is returned.
+fn cross_check_state_root(
+    number: BlockNumber,
+    parallel: (B256, TrieUpdates),
+    sequential: (B256, TrieUpdates),
+) -> (B256, TrieUpdates) {
+    let Some((key, parallel_op, sequential_op)) = parallel.1.first_difference(&sequential.1) else {
+        if parallel.0 != sequential.0 {
+            tracing::error!(
+                target: "blockchain_tree::chain",
+                number,
+                parallel_root = %parallel.0,
+                sequential_root = %sequential.0,
+                "Parallel state root diverged with the same trie updates"
+            );
+        }
+        return sequential
+    };
+    tracing::error!(
+        target: "blockchain_tree::chain",
+        number,
+        parallel_root = %parallel.0,
+        sequential_root = %sequential.0,
+        ?key,
+        ?parallel_op,
+        ?sequential_op,
+        "Parallel trie updates diverged"
+    );
+    sequential
+}
@@ -1,11 +1,14 @@
 //! Blockchain tree externals.
 
+use crate::metrics::StateRootMetrics;
 use reth_db::{
     cursor::DbCursorRO, database::Database, static_file::HeaderMask, tables, transaction::DbTx,
 };
 use reth_interfaces::{consensus::Consensus, RethResult};
 use reth_primitives::{BlockHash, BlockNumber, StaticFileSegment};
 use reth_provider::{ProviderFactory, StatsReader};
+use reth_tasks::pool::{BlockingTaskGuard, BlockingTaskPool};
+use reth_trie::trie_cursor::TrieNodeCache;
 use std::{collections::BTreeMap, sync::Arc};
 
 /// A container for external components.
@@ -25,6 +28,18 @@ pub struct TreeExternals<DB, EVM> {
     pub(crate) consensus: Arc<dyn Consensus>,
     /// The executor factory to execute blocks with.
     pub(crate) executor_factory: EVM,
+    /// Whether the state root computed in parallel is compared with the one computed
+    /// sequentially.
+    pub(crate) state_root_cross_check: bool,
+    /// The pool to compute the state root asynchronously on. If `None`, the state root is
+    /// computed in parallel on the calling thread.
+    pub(crate) state_root_pool: Option<BlockingTaskPool>,
+    /// The guard limiting the number of storage root tasks on the state root pool.
+    pub(crate) state_root_task_guard: Option<BlockingTaskGuard>,
+    /// The cache of the trie nodes the asynchronous state root computations read through.
+    pub(crate) trie_cache: Option<TrieNodeCache>,
+    /// Metrics of the state root computations.
+    pub(crate) state_root_metrics: StateRootMetrics,
 }
 
 impl<DB, EVM> TreeExternals<DB, EVM> {
@@ -34,7 +49,45 @@ impl<DB, EVM> TreeExternals<DB, EVM> {
         consensus: Arc<dyn Consensus>,
         executor_factory: EVM,
     ) -> Self {
-        Self { provider_factory, consensus, executor_factory }
+        Self {
+            provider_factory,
+            consensus,
+            executor_factory,
+            state_root_cross_check: false,
+            state_root_pool: None,
+            state_root_task_guard: None,
+            trie_cache: None,
+            state_root_metrics: StateRootMetrics::default(),
+        }
+    }
+
+    /// Compare the state root computed in parallel with the one computed sequentially, falling
+    /// back to the sequential result on divergence.
+    pub fn with_state_root_cross_check(mut self, state_root_cross_check: bool) -> Self {
+        self.state_root_cross_check = state_root_cross_check;
+        self
+    }
+
+    /// Compute the state root asynchronously on the given pool, falling back to the sequential
+    /// computation if it fails.
+    pub fn with_state_root_pool(mut self, state_root_pool: BlockingTaskPool) -> Self {
+        self.state_root_pool = Some(state_root_pool);
+        self
+    }
+
+    /// Limit the number of storage root tasks queued or running on the state root pool at once.
+    pub fn with_state_root_task_guard(mut self, guard: BlockingTaskGuard) -> Self {
+        self.state_root_task_guard = Some(guard);
+        self
+    }
+
+    /// Read the tries through the given cache when computing the state root asynchronously.
+    ///
+    /// The cache must be kept consistent with the canonical chain by the caller, e.g. with
+    /// [invalidate_trie_cache_task](reth_trie_parallel::trie_cache::invalidate_trie_cache_task).
+    pub fn with_trie_cache(mut self, trie_cache: TrieNodeCache) -> Self {
+        self.trie_cache = Some(trie_cache);
+        self
     }
 }
 
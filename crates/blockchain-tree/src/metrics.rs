@@ -25,6 +25,19 @@ pub struct TreeMetrics {
     pub trie_updates_insert_recomputed: Counter,
 }
 
+/// Metrics for the state root computations of the blocks that extend the canonical chain.
+#[derive(Metrics)]
+#[metrics(scope = "blockchain_tree.state_root")]
+pub struct StateRootMetrics {
+    /// The number of state roots computed asynchronously on the state root pool.
+    pub async_roots: Counter,
+    /// The number of state roots computed in parallel on the calling thread.
+    pub parallel_roots: Counter,
+    /// The number of times the asynchronous computation failed and the state root was computed
+    /// sequentially instead.
+    pub sequential_fallbacks: Counter,
+}
+
 /// Metrics for the blockchain tree block buffer
 #[derive(Metrics)]
 #[metrics(scope = "blockchain_tree.block_buffer")]
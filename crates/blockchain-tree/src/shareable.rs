@@ -34,7 +34,7 @@ pub struct ShareableBlockchainTree<DB: Database + Clone, EF: ExecutorFactory> {
 
 impl<DB, EF> ShareableBlockchainTree<DB, EF>
 where
-    DB: Database + Clone,
+    DB: Database + Clone + 'static,
     EF: ExecutorFactory,
 {
     /// Create a new shareable database.
@@ -45,7 +45,7 @@ where
 
 impl<DB, EF> BlockchainTreeEngine for ShareableBlockchainTree<DB, EF>
 where
-    DB: Database + Clone,
+    DB: Database + Clone + 'static,
     EF: ExecutorFactory,
 {
     fn buffer_block(&self, block: SealedBlockWithSenders) -> Result<(), InsertBlockError> {
@@ -113,7 +113,7 @@ where
 
 impl<DB, EF> BlockchainTreeViewer for ShareableBlockchainTree<DB, EF>
 where
-    DB: Database + Clone,
+    DB: Database + Clone + 'static,
     EF: ExecutorFactory,
 {
     fn blocks(&self) -> BTreeMap<BlockNumber, HashSet<BlockHash>> {
@@ -210,7 +210,7 @@ where
 
 impl<DB, EF> BlockchainTreePendingStateProvider for ShareableBlockchainTree<DB, EF>
 where
-    DB: Database + Clone,
+    DB: Database + Clone + 'static,
     EF: ExecutorFactory,
 {
     fn find_pending_state_provider(
@@ -225,7 +225,7 @@ where
 
 impl<DB, EF> CanonStateSubscriptions for ShareableBlockchainTree<DB, EF>
 where
-    DB: Database + Clone,
+    DB: Database + Clone + 'static,
     EF: ExecutorFactory,
 {
     fn subscribe_to_canonical_state(&self) -> reth_provider::CanonStateNotifications {
@@ -31,6 +31,9 @@ pub enum DatabaseError {
     /// Failed to decode a key from a table.
     #[error("failed to decode a key from a table")]
     Decode,
+    /// Failed to flush the database data to disk.
+    #[error("failed to sync the database: {0}")]
+    Sync(DatabaseErrorInfo),
     /// Failed to get database stats.
     #[error("failed to get stats: {0}")]
     Stats(DatabaseErrorInfo),
@@ -134,6 +134,9 @@ pub enum ProviderError {
     /// Consistent view error.
     #[error("failed to initialize consistent view: {0}")]
     ConsistentView(Box<ConsistentViewError>),
+    /// The database failed the health check.
+    #[error("database health check failed: {0}")]
+    HealthCheck(String),
 }
 
 impl From<reth_primitives::fs::FsPathError> for ProviderError {
@@ -51,6 +51,8 @@ use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, error, info};
 use reth_transaction_pool::{PoolConfig, TransactionPool};
+use reth_trie::trie_cursor::TrieNodeCache;
+use reth_trie_parallel::trie_cache::invalidate_trie_cache_task;
 use std::{cmp::max, str::FromStr, sync::Arc, thread::available_parallelism};
 use tokio::sync::{mpsc::unbounded_channel, oneshot};
 
@@ -499,6 +501,9 @@ where
         .with_static_files_metrics();
         info!(target: "reth::cli", "Database opened");
 
+        provider_factory.health_check()?;
+        debug!(target: "reth::cli", "Database health check passed");
+
         let prometheus_handle = config.install_prometheus_recorder()?;
         config
             .start_metrics_endpoint(
@@ -512,6 +517,13 @@ where
 
         let genesis_hash = init_genesis(provider_factory.clone())?;
 
+        let provider_rw = provider_factory.provider_rw()?;
+        let log_index_start = provider_rw.set_log_index_enabled(config.db.log_index)?;
+        provider_rw.commit()?;
+        if let Some(start) = log_index_start {
+            info!(target: "reth::cli", start, "Log index enabled");
+        }
+
         info!(target: "reth::cli", "\n{}", config.chain.display_hardforks());
 
         let consensus = config.consensus();
@@ -521,10 +533,13 @@ where
         let sync_metrics_listener = reth_stages::MetricsListener::new(sync_metrics_rx);
         executor.spawn_critical("stages metrics listener task", sync_metrics_listener);
 
-        let prune_config = config.prune_config()?.or_else(|| reth_config.prune.clone());
+        let prune_config = config.pruning.with_transaction_lookup_distance(
+            config.prune_config()?.or_else(|| reth_config.prune.clone()),
+        );
 
         let evm_config = types.evm_config();
         let tree_config = BlockchainTreeConfig::default();
+        let trie_cache = TrieNodeCache::default();
         let tree = config.build_blockchain_tree(
             provider_factory.clone(),
             consensus.clone(),
@@ -532,9 +547,18 @@ where
             sync_metrics_tx.clone(),
             tree_config,
             evm_config.clone(),
+            trie_cache.clone(),
         )?;
 
         let canon_state_notification_sender = tree.canon_state_notification_sender();
+        debug!(target: "reth::cli", "Spawning trie cache invalidation task");
+        executor.spawn_critical(
+            "trie cache invalidation task",
+            invalidate_trie_cache_task(
+                trie_cache.clone(),
+                canon_state_notification_sender.subscribe(),
+            ),
+        );
         let blockchain_tree = ShareableBlockchainTree::new(tree);
         debug!(target: "reth::cli", "configured blockchain tree");
 
@@ -669,6 +693,11 @@ where
             (pipeline, EitherDownloader::Right(network_client))
         };
 
+        if let Some(write_profiler) = database.metadata().write_profiler() {
+            debug!(target: "reth::cli", "Profiling pipeline database writes");
+            pipeline.set_write_profiler(Arc::clone(write_profiler));
+        }
+
         let pipeline_events = pipeline.events();
 
         let initial_target = config.initial_pipeline_target(genesis_hash);
@@ -750,6 +779,7 @@ where
             engine_api,
             &config,
             jwt_secret,
+            trie_cache,
             rpc,
         )
         .await?;
@@ -7,17 +7,19 @@ use reth_node_core::{
     cli::config::RethRpcConfig,
     node_config::NodeConfig,
     rpc::{
-        api::EngineApiServer,
+        api::{DebugStorageRootApiServer, EngineApiServer},
         builder::{
             auth::{AuthRpcModule, AuthServerHandle},
-            RethModuleRegistry, RpcModuleBuilder, RpcServerHandle, TransportRpcModules,
+            RethModuleRegistry, RethRpcModule, RpcModuleBuilder, RpcServerHandle,
+            TransportRpcModules,
         },
     },
 };
 use reth_payload_builder::PayloadBuilderHandle;
-use reth_rpc::JwtSecret;
+use reth_rpc::{DebugStorageRootApi, JwtSecret};
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, info};
+use reth_trie::trie_cursor::TrieNodeCache;
 use std::{
     fmt,
     ops::{Deref, DerefMut},
@@ -252,6 +254,7 @@ pub(crate) async fn launch_rpc_servers<Node, Engine>(
     engine_api: Engine,
     config: &NodeConfig,
     jwt_secret: JwtSecret,
+    trie_cache: TrieNodeCache,
     hooks: RpcHooks<Node>,
 ) -> eyre::Result<(RethRpcServerHandles, RpcRegistry<Node>)>
 where
@@ -264,7 +267,7 @@ where
     let module_config = config.rpc.transport_rpc_module_config();
     debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
-    let (mut modules, mut auth_module, registry) = RpcModuleBuilder::default()
+    let (mut modules, mut auth_module, mut registry) = RpcModuleBuilder::default()
         .with_provider(node.provider().clone())
         .with_pool(node.pool().clone())
         .with_network(node.network().clone())
@@ -273,6 +276,15 @@ where
         .with_evm_config(node.evm_config())
         .build_with_auth_server(module_config, engine_api);
 
+    // The storage root handler reads the database directly, which the module builder isn't
+    // aware of, so it's merged into the transports with the `debug` namespace enabled here.
+    let storage_root_api = DebugStorageRootApi::new(
+        node.provider().clone(),
+        registry.eth_handlers().blocking_task_pool,
+        Some(trie_cache),
+    );
+    modules.merge_if_module_configured(RethRpcModule::Debug, storage_root_api.into_rpc())?;
+
     let mut registry = RpcRegistry { registry };
     let ctx = RpcContext {
         node: node.clone(),
@@ -2,11 +2,12 @@
 
 use clap::Args;
 use reth_interfaces::db::LogLevel;
+use std::{path::PathBuf, time::Duration};
 
 use crate::version::default_client_version;
 
 /// Parameters for database configuration
-#[derive(Debug, Args, PartialEq, Eq, Default, Clone, Copy)]
+#[derive(Debug, Args, PartialEq, Eq, Default, Clone)]
 #[command(next_help_heading = "Database")]
 pub struct DatabaseArgs {
     /// Database logging level. Levels higher than "notice" require a debug build.
@@ -16,6 +17,32 @@ pub struct DatabaseArgs {
     /// NFS volume.
     #[arg(long = "db.exclusive")]
     pub exclusive: Option<bool>,
+    /// Log database operations that take longer than the given number of milliseconds. Logs are
+    /// sampled to at most one per second.
+    #[arg(long = "db.slow-query-ms")]
+    pub slow_query_ms: Option<u64>,
+    /// Wrap database operations in tracing spans tagged with the table and operation name.
+    /// The spans are emitted with the `storage::db::operation` target at `debug` level.
+    #[arg(long = "db.operation-spans", default_value_t = false)]
+    pub operation_spans: bool,
+    /// Record the write operations of the database to the file, so they can be replayed against
+    /// another database with `reth db replay-log`.
+    #[arg(long = "db.operation-log", value_name = "PATH")]
+    pub operation_log: Option<PathBuf>,
+    /// Don't wait for the data of every commit to be flushed to disk, and flush it on the first
+    /// commit after the given number of milliseconds instead. A system crash can lose the most
+    /// recent commits, but never corrupts the database.
+    #[arg(long = "db.sync-period-ms")]
+    pub sync_period_ms: Option<u64>,
+    /// Index the logs of the executed blocks by address and first topic, so `eth_getLogs` only
+    /// reads the receipts of the matching blocks. The index covers the blocks executed after it's
+    /// enabled, and is removed when the node is started without this flag.
+    #[arg(long = "db.log-index", default_value_t = false)]
+    pub log_index: bool,
+    /// Count the puts, deletes and commits of each table, and log a summary of the writes of each
+    /// stage when a pipeline run finishes. The writes are also reported to the metrics.
+    #[arg(long = "db.write-profile", default_value_t = false)]
+    pub write_profile: bool,
 }
 
 impl DatabaseArgs {
@@ -24,6 +51,11 @@ impl DatabaseArgs {
         reth_db::mdbx::DatabaseArguments::new(default_client_version())
             .with_log_level(self.log_level)
             .with_exclusive(self.exclusive)
+            .with_slow_query_threshold(self.slow_query_ms.map(Duration::from_millis))
+            .with_operation_spans(self.operation_spans)
+            .with_operation_log(self.operation_log.clone())
+            .with_sync_period(self.sync_period_ms.map(Duration::from_millis))
+            .with_write_profile(self.write_profile)
     }
 }
 
@@ -45,4 +77,30 @@ mod tests {
         let args = CommandParser::<DatabaseArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn test_parse_slow_query_threshold() {
+        let args =
+            CommandParser::<DatabaseArgs>::parse_from(["reth", "--db.slow-query-ms", "50"]).args;
+        assert_eq!(args.slow_query_ms, Some(50));
+    }
+
+    #[test]
+    fn test_parse_sync_period() {
+        let args =
+            CommandParser::<DatabaseArgs>::parse_from(["reth", "--db.sync-period-ms", "1000"]).args;
+        assert_eq!(args.sync_period_ms, Some(1000));
+    }
+
+    #[test]
+    fn test_parse_log_index() {
+        let args = CommandParser::<DatabaseArgs>::parse_from(["reth", "--db.log-index"]).args;
+        assert!(args.log_index);
+    }
+
+    #[test]
+    fn test_parse_write_profile() {
+        let args = CommandParser::<DatabaseArgs>::parse_from(["reth", "--db.write-profile"]).args;
+        assert!(args.write_profile);
+    }
 }
@@ -64,6 +64,32 @@ pub struct DebugArgs {
     /// will be written to specified location.
     #[arg(long = "debug.engine-api-store", help_heading = "Debug", value_name = "PATH")]
     pub engine_api_store: Option<PathBuf>,
+
+    /// Compute the state root of new blocks both in parallel and sequentially, and compare the
+    /// results.
+    ///
+    /// The first divergence of the trie updates is logged and the sequential result is used.
+    #[arg(long = "debug.state-root-cross-check", help_heading = "Debug")]
+    pub state_root_cross_check: bool,
+
+    /// Compute the state root of new blocks in parallel on the calling thread, instead of
+    /// asynchronously on a dedicated pool.
+    #[arg(long = "debug.parallel-state-root", help_heading = "Debug")]
+    pub parallel_state_root: bool,
+
+    /// The number of threads of the pool the state root of new blocks is computed on
+    /// asynchronously. If the computation fails, the state root is computed sequentially.
+    ///
+    /// Defaults to one thread per CPU.
+    #[arg(long = "debug.async-state-root-threads", help_heading = "Debug", value_name = "THREADS")]
+    pub async_state_root_threads: Option<usize>,
+
+    /// The maximum number of storage root tasks queued or running on the asynchronous state root
+    /// pool at once.
+    ///
+    /// Defaults to twice the number of threads of the pool.
+    #[arg(long = "debug.async-state-root-max-tasks", help_heading = "Debug", value_name = "TASKS")]
+    pub async_state_root_max_tasks: Option<usize>,
 }
 
 #[cfg(test)]
@@ -15,6 +15,17 @@ pub struct PruningArgs {
     /// This flag takes priority over pruning configuration in reth.toml.
     #[arg(long, default_value_t = false)]
     pub full: bool,
+
+    /// Prune the transaction hash to number lookup entries of the transactions older than the
+    /// given number of blocks. Older transactions are looked up using the transaction hash
+    /// filters of the static files. This flag takes priority over the `transaction_lookup`
+    /// pruning configuration in reth.toml.
+    #[arg(
+        long = "prune.transaction-lookup.distance",
+        value_name = "BLOCKS",
+        value_parser = clap::value_parser!(u64).range(MINIMUM_PRUNING_DISTANCE..)
+    )]
+    pub transaction_lookup_distance: Option<u64>,
 }
 
 impl PruningArgs {
@@ -40,12 +51,24 @@ impl PruningArgs {
                             .into_iter()
                             .collect(),
                     ),
+                    changeset_epochs: None,
                 },
             })
         } else {
             None
         })
     }
+
+    /// Applies the transaction lookup pruning distance, if any, to the pruning configuration.
+    pub fn with_transaction_lookup_distance(
+        &self,
+        config: Option<PruneConfig>,
+    ) -> Option<PruneConfig> {
+        let Some(distance) = self.transaction_lookup_distance else { return config };
+        let mut config = config.unwrap_or_default();
+        config.segments.transaction_lookup = Some(PruneMode::Distance(distance));
+        Some(config)
+    }
 }
 
 #[cfg(test)]
@@ -66,4 +89,32 @@ mod tests {
         let args = CommandParser::<PruningArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn parse_transaction_lookup_distance() {
+        let args = CommandParser::<PruningArgs>::parse_from([
+            "reth",
+            "--prune.transaction-lookup.distance",
+            "100000",
+        ])
+        .args;
+        assert_eq!(args.transaction_lookup_distance, Some(100_000));
+        assert_eq!(
+            args.with_transaction_lookup_distance(None),
+            Some(PruneConfig {
+                segments: PruneModes {
+                    transaction_lookup: Some(PruneMode::Distance(100_000)),
+                    ..PruneModes::none()
+                },
+                ..Default::default()
+            })
+        );
+
+        let result = CommandParser::<PruningArgs>::try_parse_from([
+            "reth",
+            "--prune.transaction-lookup.distance",
+            "1",
+        ]);
+        assert!(result.is_err());
+    }
 }
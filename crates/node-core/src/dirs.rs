@@ -287,6 +287,14 @@ impl<D> ChainPath<D> {
         self.0.join("static_files").into()
     }
 
+    /// Returns the path to the directory with the progress checkpoints of interrupted `reth db`
+    /// commands for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/db-progress`
+    pub fn db_progress_path(&self) -> PathBuf {
+        self.0.join("db-progress").into()
+    }
+
     /// Returns the path to the reth p2p secret key for this chain.
     ///
     /// `<DIR>/<CHAIN_ID>/discovery-secret`
@@ -65,11 +65,15 @@ use reth_stages::{
     MetricEvent,
 };
 use reth_static_file::StaticFileProducer;
-use reth_tasks::TaskExecutor;
+use reth_tasks::{
+    pool::{BlockingTaskGuard, BlockingTaskPool},
+    TaskExecutor,
+};
 use reth_transaction_pool::{
     blobstore::{DiskFileBlobStore, DiskFileBlobStoreConfig},
     EthTransactionPool, TransactionPool, TransactionValidationTaskExecutor,
 };
+use reth_trie::trie_cursor::TrieNodeCache;
 use secp256k1::SecretKey;
 use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 use tokio::sync::{
@@ -404,6 +408,8 @@ impl NodeConfig {
     /// - `evm_config`: The EVM (Ethereum Virtual Machine) configuration, which affects how smart
     ///   contracts and transactions are executed. Proper validation of this configuration is
     ///   crucial for the correct execution of transactions.
+    /// - `trie_cache`: The cache of the trie nodes the asynchronous state root computations read
+    ///   through. It must be invalidated on canonical chain updates by the caller.
     ///
     /// # Returns
     /// A `ShareableBlockchainTree` instance, which provides access to the blockchain state and
@@ -418,6 +424,7 @@ impl NodeConfig {
     ///     sync_metrics_tx,
     ///     BlockchainTreeConfig::default(),
     ///     evm_config,
+    ///     trie_cache,
     /// )?;
     /// ```
     ///
@@ -433,17 +440,30 @@ impl NodeConfig {
         sync_metrics_tx: UnboundedSender<MetricEvent>,
         tree_config: BlockchainTreeConfig,
         evm_config: EvmConfig,
+        trie_cache: TrieNodeCache,
     ) -> eyre::Result<BlockchainTree<DB, EvmProcessorFactory<EvmConfig>>>
     where
         DB: Database + Unpin + Clone + 'static,
         EvmConfig: ConfigureEvm + Clone + 'static,
     {
         // configure blockchain tree
-        let tree_externals = TreeExternals::new(
+        let mut tree_externals = TreeExternals::new(
             provider_factory,
             consensus.clone(),
             EvmProcessorFactory::new(self.chain.clone(), evm_config),
-        );
+        )
+        .with_state_root_cross_check(self.debug.state_root_cross_check);
+        if !self.debug.parallel_state_root {
+            let pool = BlockingTaskPool::build_with_num_threads(
+                self.debug.async_state_root_threads.unwrap_or_default(),
+            )?;
+            let max_tasks =
+                self.debug.async_state_root_max_tasks.unwrap_or(pool.num_threads() * 2).max(1);
+            tree_externals = tree_externals
+                .with_state_root_pool(pool)
+                .with_state_root_task_guard(BlockingTaskGuard::new(max_tasks))
+                .with_trie_cache(trie_cache);
+        }
         let tree = BlockchainTree::new(
             tree_externals,
             tree_config,
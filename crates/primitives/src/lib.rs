@@ -77,9 +77,9 @@ pub use net::{
 };
 pub use peer::{id2pk, pk2id, AnyNode, PeerId, WithPeerId};
 pub use prune::{
-    PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode, PruneModes, PruneProgress,
-    PrunePurpose, PruneSegment, PruneSegmentError, ReceiptsLogPruneConfig,
-    MINIMUM_PRUNING_DISTANCE,
+    changeset_epoch_start, PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode,
+    PruneModes, PruneProgress, PrunePurpose, PruneSegment, PruneSegmentError,
+    ReceiptsLogPruneConfig, CHANGESET_EPOCH_LENGTH, MINIMUM_PRUNING_DISTANCE,
 };
 pub use receipt::{Receipt, ReceiptWithBloom, ReceiptWithBloomRef, Receipts};
 pub use static_file::StaticFileSegment;
@@ -11,7 +11,9 @@ pub use mode::PruneMode;
 pub use segment::{PrunePurpose, PruneSegment, PruneSegmentError};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-pub use target::{PruneModes, MINIMUM_PRUNING_DISTANCE};
+pub use target::{
+    changeset_epoch_start, PruneModes, CHANGESET_EPOCH_LENGTH, MINIMUM_PRUNING_DISTANCE,
+};
 
 /// Configuration for pruning receipts not associated with logs emitted by the specified contracts.
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
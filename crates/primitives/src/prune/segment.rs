@@ -24,6 +24,9 @@ pub enum PruneSegment {
     Headers,
     /// Prune segment responsible for the `Transactions` table.
     Transactions,
+    /// Prune segment responsible for folding the `AccountChangeSets` and `StorageChangeSets`
+    /// tables into the `AccountChangeSetEpochs` and `StorageChangeSetEpochs` tables.
+    ChangeSetEpochs,
 }
 
 impl PruneSegment {
@@ -34,9 +37,10 @@ impl PruneSegment {
                 0
             }
             Self::Receipts if purpose.is_static_file() => 0,
-            Self::ContractLogs | Self::AccountHistory | Self::StorageHistory => {
-                MINIMUM_PRUNING_DISTANCE
-            }
+            Self::ContractLogs |
+            Self::AccountHistory |
+            Self::StorageHistory |
+            Self::ChangeSetEpochs => MINIMUM_PRUNING_DISTANCE,
             Self::Receipts => MINIMUM_PRUNING_DISTANCE,
         }
     }
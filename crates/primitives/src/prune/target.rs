@@ -1,4 +1,4 @@
-use crate::{PruneMode, ReceiptsLogPruneConfig};
+use crate::{BlockNumber, PruneMode, ReceiptsLogPruneConfig};
 use serde::{Deserialize, Deserializer, Serialize};
 
 /// Minimum distance from the tip necessary for the node to work correctly:
@@ -8,6 +8,17 @@ use serde::{Deserialize, Deserializer, Serialize};
 ///    unwind is required.
 pub const MINIMUM_PRUNING_DISTANCE: u64 = 32 * 2 + 10_000;
 
+/// Number of blocks whose changesets are folded into a single state diff by
+/// [PruneSegment::ChangeSetEpochs](crate::PruneSegment::ChangeSetEpochs). Epochs start at the
+/// multiples of this number.
+pub const CHANGESET_EPOCH_LENGTH: u64 = 10_000;
+
+/// Returns the first block of the changeset epoch that contains the block, see
+/// [CHANGESET_EPOCH_LENGTH].
+pub const fn changeset_epoch_start(block: BlockNumber) -> BlockNumber {
+    block - block % CHANGESET_EPOCH_LENGTH
+}
+
 /// Pruning configuration for every segment of the data that can be pruned.
 #[derive(Debug, Clone, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(default)]
@@ -43,6 +54,14 @@ pub struct PruneModes {
     /// The [BlockNumber](`crate::BlockNumber`) represents the starting block from which point
     /// onwards the receipts are preserved.
     pub receipts_log_filter: ReceiptsLogPruneConfig,
+    /// Changesets compaction configuration. The account and storage changesets of the blocks
+    /// targeted by the mode are folded into a state diff per [CHANGESET_EPOCH_LENGTH] blocks, so
+    /// the historical state of these blocks is only available at the first block of each epoch.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
+    )]
+    pub changeset_epochs: Option<PruneMode>,
 }
 
 impl PruneModes {
@@ -60,6 +79,8 @@ impl PruneModes {
             account_history: Some(PruneMode::Full),
             storage_history: Some(PruneMode::Full),
             receipts_log_filter: Default::default(),
+            // There are no changesets left to compact.
+            changeset_epochs: None,
         }
     }
 }
@@ -0,0 +1,280 @@
+use crate::{
+    segments::{PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
+    PrunerError,
+};
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+    database::Database,
+    models::BlockNumberAddress,
+    tables,
+    transaction::DbTxMut,
+};
+use reth_primitives::{
+    changeset_epoch_start, PruneInterruptReason, PruneMode, PruneProgress, PruneSegment,
+};
+use reth_provider::DatabaseProviderRW;
+use tracing::{instrument, trace};
+
+/// Folds the changesets of the blocks into the changesets of their epochs.
+///
+/// For every account and storage slot changed within an epoch, only the value before its first
+/// change in the epoch is kept in [tables::AccountChangeSetEpochs] and
+/// [tables::StorageChangeSetEpochs]. The history indices are left intact, so the historical
+/// state remains available at the first block of every epoch.
+#[derive(Debug)]
+pub struct ChangeSetEpochs {
+    mode: PruneMode,
+}
+
+impl ChangeSetEpochs {
+    pub fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<DB: Database> Segment<DB> for ChangeSetEpochs {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::ChangeSetEpochs
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<PruneOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No changesets to fold");
+                return Ok(PruneOutput::done())
+            }
+        };
+
+        let mut limiter = input.limiter;
+        if limiter.is_limit_reached() {
+            return Ok(PruneOutput::not_done(
+                PruneInterruptReason::new(&limiter),
+                input.previous_checkpoint.map(|checkpoint| checkpoint.into()),
+            ))
+        }
+
+        let tx = provider.tx_ref();
+        let mut account_changesets = tx.cursor_write::<tables::AccountChangeSets>()?;
+        let mut account_epochs = tx.cursor_dup_write::<tables::AccountChangeSetEpochs>()?;
+        let mut storage_changesets = tx.cursor_write::<tables::StorageChangeSets>()?;
+        let mut storage_epochs = tx.cursor_dup_write::<tables::StorageChangeSetEpochs>()?;
+
+        let mut pruned = 0;
+        let mut last_folded_block = None;
+        let mut done = true;
+        for block_number in range {
+            // Blocks are folded as a whole, otherwise the state at the block following the
+            // checkpoint would be read from the epoch while its changeset is partially there.
+            if limiter.is_limit_reached() {
+                done = false;
+                break
+            }
+
+            let epoch = changeset_epoch_start(block_number);
+
+            let mut walker = account_changesets.walk_range(block_number..=block_number)?;
+            while let Some((_, account_before)) = walker.next().transpose()? {
+                let address = account_before.address;
+                if account_epochs
+                    .seek_by_key_subkey(epoch, address)?
+                    .filter(|entry| entry.address == address)
+                    .is_none()
+                {
+                    account_epochs.upsert(epoch, account_before)?;
+                }
+                walker.delete_current()?;
+                limiter.increment_deleted_entries_count();
+                pruned += 1;
+            }
+
+            let mut walker = storage_changesets
+                .walk_range(BlockNumberAddress::range(block_number..=block_number))?;
+            while let Some((BlockNumberAddress((_, address)), storage_before)) =
+                walker.next().transpose()?
+            {
+                let epoch_address = BlockNumberAddress((epoch, address));
+                if storage_epochs
+                    .seek_by_key_subkey(epoch_address, storage_before.key)?
+                    .filter(|entry| entry.key == storage_before.key)
+                    .is_none()
+                {
+                    storage_epochs.upsert(epoch_address, storage_before)?;
+                }
+                walker.delete_current()?;
+                limiter.increment_deleted_entries_count();
+                pruned += 1;
+            }
+
+            last_folded_block = Some(block_number);
+        }
+        trace!(target: "pruner", %pruned, %done, ?last_folded_block, "Folded changesets into epochs");
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(PruneOutput {
+            progress,
+            pruned,
+            checkpoint: Some(PruneOutputCheckpoint {
+                block_number: last_folded_block,
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{ChangeSetEpochs, PruneInput, PruneOutput, Segment};
+    use assert_matches::assert_matches;
+    use reth_db::{models::AccountBeforeTx, tables};
+    use reth_interfaces::test_utils::{
+        generators,
+        generators::{random_block_range, random_changeset_range, random_eoa_accounts},
+    };
+    use reth_primitives::{
+        changeset_epoch_start, BlockNumber, PruneCheckpoint, PruneInterruptReason, PruneLimiter,
+        PruneMode, PruneProgress, PruneSegment, B256, CHANGESET_EPOCH_LENGTH,
+    };
+    use reth_provider::PruneCheckpointReader;
+    use reth_stages::test_utils::TestStageDB;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        // Changesets of 100 blocks, spanning the boundary of the first two epochs.
+        let first_block = CHANGESET_EPOCH_LENGTH - 50;
+        let blocks = random_block_range(&mut rng, 0..=99, B256::ZERO, 0..1);
+        let accounts = random_eoa_accounts(&mut rng, 2).into_iter().collect::<BTreeMap<_, _>>();
+        let (changesets, _) = random_changeset_range(
+            &mut rng,
+            blocks.iter(),
+            accounts.into_iter().map(|(addr, acc)| (addr, (acc, Vec::new()))),
+            1..2,
+            1..2,
+        );
+        db.insert_changesets(changesets.clone(), Some(first_block)).expect("insert changesets");
+
+        let test_prune = |to_block: BlockNumber,
+                          deleted_entries_limit: usize,
+                          expected_progress: PruneProgress| {
+            let prune_mode = PruneMode::Before(to_block);
+            let input = PruneInput {
+                previous_checkpoint: db
+                    .factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::ChangeSetEpochs)
+                    .unwrap(),
+                to_block,
+                limiter: PruneLimiter::default().set_deleted_entries_limit(deleted_entries_limit),
+            };
+            let segment = ChangeSetEpochs::new(prune_mode);
+
+            let provider = db.factory.provider_rw().unwrap();
+            let result = segment.prune(&provider, input).unwrap();
+            assert_matches!(
+                result,
+                PruneOutput { progress, checkpoint: Some(_), .. } if progress == expected_progress
+            );
+            let checkpoint = result.checkpoint.unwrap();
+            segment.save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode)).unwrap();
+            provider.commit().expect("commit");
+
+            let last_folded_block = checkpoint.block_number.unwrap();
+
+            // The first change of every account and storage slot in each epoch is kept.
+            let mut expected_accounts = BTreeMap::new();
+            let mut expected_storages = BTreeMap::new();
+            for (block_number, changeset) in (first_block..).zip(&changesets) {
+                let epoch = changeset_epoch_start(block_number);
+                for (address, account, storage) in changeset {
+                    if block_number <= last_folded_block {
+                        expected_accounts.entry((epoch, *address)).or_insert(*account);
+                        for entry in storage {
+                            expected_storages
+                                .entry((epoch, *address, entry.key))
+                                .or_insert(entry.value);
+                        }
+                    }
+                }
+            }
+            let actual_accounts = db
+                .table::<tables::AccountChangeSetEpochs>()
+                .unwrap()
+                .into_iter()
+                .map(|(epoch, AccountBeforeTx { address, info })| ((epoch, address), info.unwrap()))
+                .collect::<BTreeMap<_, _>>();
+            assert_eq!(actual_accounts, expected_accounts);
+            let actual_storages = db
+                .table::<tables::StorageChangeSetEpochs>()
+                .unwrap()
+                .into_iter()
+                .map(|(key, entry)| ((key.block_number(), key.address(), entry.key), entry.value))
+                .collect::<BTreeMap<_, _>>();
+            assert_eq!(actual_storages, expected_storages);
+
+            // Folded changesets are removed, the others are left intact.
+            assert!(db
+                .table::<tables::AccountChangeSets>()
+                .unwrap()
+                .into_iter()
+                .all(|(block_number, _)| block_number > last_folded_block));
+            assert!(db
+                .table::<tables::StorageChangeSets>()
+                .unwrap()
+                .into_iter()
+                .all(|(key, _)| key.block_number() > last_folded_block));
+            assert_eq!(
+                db.table::<tables::AccountChangeSets>().unwrap().len(),
+                (first_block..)
+                    .zip(&changesets)
+                    .filter(|(block_number, _)| *block_number > last_folded_block)
+                    .map(|(_, changeset)| changeset.len())
+                    .sum::<usize>()
+            );
+
+            assert_eq!(
+                db.factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::ChangeSetEpochs)
+                    .unwrap(),
+                Some(PruneCheckpoint {
+                    block_number: Some(last_folded_block),
+                    tx_number: None,
+                    prune_mode
+                })
+            );
+
+            last_folded_block
+        };
+
+        let last_folded_block = test_prune(
+            first_block + 80,
+            10,
+            PruneProgress::HasMoreData(PruneInterruptReason::DeletedEntriesLimitReached),
+        );
+        assert!(last_folded_block < first_block + 80);
+        assert_eq!(
+            test_prune(first_block + 80, usize::MAX, PruneProgress::Finished),
+            first_block + 80
+        );
+        assert_eq!(
+            test_prune(first_block + 99, usize::MAX, PruneProgress::Finished),
+            first_block + 99
+        );
+    }
+}
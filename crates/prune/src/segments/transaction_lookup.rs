@@ -42,6 +42,20 @@ impl<DB: Database> Segment<DB> for TransactionLookup {
             }
         }
         .into_inner();
+        // Entries are not pruned past the highest transaction hash filter, so that the lookups of
+        // the pruned transactions are served by the filters. Without filters, the pruned
+        // transactions can't be looked up.
+        let end = match provider.static_file_provider().highest_transaction_hash_filter()? {
+            Some(filter) if filter.tx_range.end <= start => {
+                trace!(
+                    target: "pruner",
+                    "No transaction lookup entries covered by transaction hash filters to prune"
+                );
+                return Ok(PruneOutput::done())
+            }
+            Some(filter) => end.min(filter.tx_range.end - 1),
+            None => end,
+        };
         let tx_range = start..=
             Some(end)
                 .min(input.limiter.deleted_entries_limit_left().map(|left| start + left as u64 - 1))
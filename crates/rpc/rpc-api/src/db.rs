@@ -0,0 +1,34 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_primitives::Bytes;
+
+/// Read-only access to the database tables.
+///
+/// Tables are identified by their names, and keys and values are encoded the same way they're
+/// stored in the database.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "db"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "db"))]
+pub trait DbApi {
+    /// Returns the value of the key in the table.
+    ///
+    /// For dupsort tables, returns the first value of the key.
+    #[method(name = "get")]
+    async fn get(&self, table: String, key: Bytes) -> RpcResult<Option<Bytes>>;
+
+    /// Returns the values of the keys in the table, in the same order as the keys.
+    #[method(name = "multiGet")]
+    async fn multi_get(&self, table: String, keys: Vec<Bytes>) -> RpcResult<Vec<Option<Bytes>>>;
+
+    /// Returns up to `limit` entries of the table with keys in the `[start, end)` range, in
+    /// ascending order.
+    ///
+    /// If `start` is not provided, the range starts from the first entry of the table. If `end` is
+    /// not provided, the range ends with the last entry of the table.
+    #[method(name = "range")]
+    async fn range(
+        &self,
+        table: String,
+        start: Option<Bytes>,
+        end: Option<Bytes>,
+        limit: usize,
+    ) -> RpcResult<Vec<(Bytes, Bytes)>>;
+}
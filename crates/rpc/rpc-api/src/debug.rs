@@ -376,3 +376,18 @@ pub trait DebugApi {
     #[method(name = "writeMutexProfile")]
     async fn debug_write_mutex_profile(&self, file: String) -> RpcResult<()>;
 }
+
+/// Debug rpc interface for the methods that compute state roots.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "debug"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "debug"))]
+pub trait DebugStorageRootApi {
+    /// Returns the storage root of the account at the given block.
+    ///
+    /// If the block is not provided, the storage root at the latest block is returned.
+    #[method(name = "storageRoot")]
+    async fn debug_storage_root(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> RpcResult<B256>;
+}
@@ -16,6 +16,7 @@
 
 mod admin;
 mod bundle;
+mod db;
 mod debug;
 mod engine;
 mod eth;
@@ -39,7 +40,8 @@ pub mod servers {
     pub use crate::{
         admin::AdminApiServer,
         bundle::{EthBundleApiServer, EthCallBundleApiServer},
-        debug::DebugApiServer,
+        db::DbApiServer,
+        debug::{DebugApiServer, DebugStorageRootApiServer},
         engine::{EngineApiServer, EngineEthApiServer},
         eth::EthApiServer,
         eth_filter::EthFilterApiServer,
@@ -66,7 +68,8 @@ pub mod clients {
     pub use crate::{
         admin::AdminApiClient,
         bundle::{EthBundleApiClient, EthCallBundleApiClient},
-        debug::DebugApiClient,
+        db::DbApiClient,
+        debug::{DebugApiClient, DebugStorageRootApiClient},
         engine::{EngineApiClient, EngineEthApiClient},
         eth::EthApiClient,
         eth_filter::EthFilterApiClient,
@@ -754,6 +754,11 @@ impl RpcModuleSelection {
         }
     }
 
+    /// Returns true if the given [RethRpcModule] is configured.
+    pub fn contains(&self, module: &RethRpcModule) -> bool {
+        self.iter_selection().any(|configured| configured == *module)
+    }
+
     /// Returns the list of configured [RethRpcModule]
     pub fn into_selection(self) -> Vec<RethRpcModule> {
         match self {
@@ -1928,6 +1933,28 @@ impl TransportRpcModules {
         Ok(())
     }
 
+    /// Merge the given [Methods] in the methods of the transports that have the given
+    /// [RethRpcModule] configured.
+    ///
+    /// Fails if any of the methods in other is present already.
+    pub fn merge_if_module_configured(
+        &mut self,
+        module: RethRpcModule,
+        other: impl Into<Methods>,
+    ) -> Result<(), jsonrpsee::core::error::Error> {
+        let other = other.into();
+        if self.config.http().is_some_and(|http| http.contains(&module)) {
+            self.merge_http(other.clone())?;
+        }
+        if self.config.ws().is_some_and(|ws| ws.contains(&module)) {
+            self.merge_ws(other.clone())?;
+        }
+        if self.config.ipc().is_some_and(|ipc| ipc.contains(&module)) {
+            self.merge_ipc(other)?;
+        }
+        Ok(())
+    }
+
     /// Convenience function for starting a server
     pub async fn start_server(self, builder: RpcServerConfig) -> Result<RpcServerHandle, RpcError> {
         builder.start(self).await
@@ -2392,6 +2419,15 @@ mod tests {
         assert_eq!(selection, vec![RethRpcModule::Eth, RethRpcModule::Net, RethRpcModule::Web3,])
     }
 
+    #[test]
+    fn test_selection_contains() {
+        assert!(RpcModuleSelection::All.contains(&RethRpcModule::Debug));
+        assert!(!RpcModuleSelection::Standard.contains(&RethRpcModule::Debug));
+        let selection = RpcModuleSelection::Selection(vec![RethRpcModule::Debug]);
+        assert!(selection.contains(&RethRpcModule::Debug));
+        assert!(!selection.contains(&RethRpcModule::Eth));
+    }
+
     #[test]
     fn test_create_rpc_module_config() {
         let selection = vec!["eth", "admin"];
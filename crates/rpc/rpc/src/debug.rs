@@ -13,15 +13,17 @@ use crate::{
 use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
+use reth_db::database::Database;
 use reth_primitives::{
-    revm::env::tx_env_with_recovered, Address, Block, BlockId, BlockNumberOrTag, Bytes,
+    keccak256, revm::env::tx_env_with_recovered, Address, Block, BlockId, BlockNumberOrTag, Bytes,
     TransactionSignedEcRecovered, Withdrawals, B256, U256,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, HeaderProvider, StateProviderBox, TransactionVariant,
+    providers::ConsistentDbView, BlockNumReader, BlockReaderIdExt, ChainSpecProvider,
+    DatabaseProviderFactory, HeaderProvider, ProviderError, StateProviderBox, TransactionVariant,
 };
 use reth_revm::database::{StateProviderDatabase, SubState};
-use reth_rpc_api::DebugApiServer;
+use reth_rpc_api::{DebugApiServer, DebugStorageRootApiServer};
 use reth_rpc_types::{
     trace::geth::{
         BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
@@ -29,7 +31,9 @@ use reth_rpc_types::{
     },
     BlockError, Bundle, RichBlock, StateContext, TransactionRequest,
 };
-use reth_tasks::pool::BlockingTaskGuard;
+use reth_tasks::pool::{BlockingTaskGuard, BlockingTaskPool};
+use reth_trie::{trie_cursor::TrieNodeCache, HashedPostState};
+use reth_trie_parallel::async_root::AsyncStateRoot;
 use revm::{
     db::CacheDB,
     primitives::{db::DatabaseCommit, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg},
@@ -38,7 +42,7 @@ use revm_inspectors::tracing::{
     js::{JsInspector, TransactionContext},
     FourByteInspector, MuxInspector, TracingInspector, TracingInspectorConfig,
 };
-use std::sync::Arc;
+use std::{marker::PhantomData, sync::Arc};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
 /// `debug` API implementation.
@@ -1002,3 +1006,125 @@ struct DebugApiInner<Provider, Eth> {
     // restrict the number of concurrent calls to blocking calls
     blocking_task_guard: BlockingTaskGuard,
 }
+
+/// `debug_storageRoot` implementation.
+///
+/// Storage roots are computed with [AsyncStateRoot] over a [ConsistentDbView], so unlike the
+/// [DebugApi] this handler needs direct access to the database.
+pub struct DebugStorageRootApi<DB, Provider> {
+    inner: Arc<DebugStorageRootApiInner<DB, Provider>>,
+}
+
+// === impl DebugStorageRootApi ===
+
+impl<DB, Provider> DebugStorageRootApi<DB, Provider> {
+    /// Create a new instance of the [DebugStorageRootApi]
+    ///
+    /// If the trie node cache is provided, the storage tries are read through it.
+    pub fn new(
+        provider: Provider,
+        blocking_task_pool: BlockingTaskPool,
+        trie_cache: Option<TrieNodeCache>,
+    ) -> Self {
+        let inner = Arc::new(DebugStorageRootApiInner {
+            provider,
+            blocking_task_pool,
+            trie_cache,
+            _database: PhantomData,
+        });
+        Self { inner }
+    }
+}
+
+impl<DB, Provider> DebugStorageRootApi<DB, Provider>
+where
+    DB: Database + Clone + 'static,
+    Provider: DatabaseProviderFactory<DB> + BlockReaderIdExt + Clone + Send + Sync + 'static,
+{
+    /// Computes the storage root of the account at the given block.
+    pub async fn storage_root(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> EthResult<B256> {
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let this = self.clone();
+        let (view, hashed_state) = self
+            .inner
+            .blocking_task_pool
+            .spawn(move || this.try_view_at_block(block_id))
+            .await
+            .map_err(|_| EthApiError::InternalBlockingTaskError)??;
+
+        let mut state_root =
+            AsyncStateRoot::new(view, self.inner.blocking_task_pool.clone(), hashed_state);
+        if let Some(trie_cache) = &self.inner.trie_cache {
+            state_root = state_root.with_trie_cache(trie_cache.clone());
+        }
+        Ok(state_root.storage_root(keccak256(address)).await?)
+    }
+
+    /// Returns the consistent view of the database at the latest block, along with the hashed
+    /// state that reverts it to the given block.
+    fn try_view_at_block(
+        &self,
+        block_id: BlockId,
+    ) -> EthResult<(ConsistentDbView<DB, Provider>, HashedPostState)> {
+        let Some(block_number) = self.inner.provider.block_number_for_id(block_id)? else {
+            return Err(EthApiError::UnknownBlockNumber)
+        };
+
+        let view = ConsistentDbView::new_with_latest_tip(self.inner.provider.clone())?;
+        let provider = view.provider_ro()?;
+        let tip = provider.best_block_number()?;
+        if block_number > tip {
+            return Err(EthApiError::UnknownBlockNumber)
+        }
+
+        let hashed_state = if block_number < tip {
+            HashedPostState::from_revert_range(provider.tx_ref(), block_number + 1..=tip)
+                .map_err(ProviderError::Database)?
+        } else {
+            HashedPostState::default()
+        };
+        Ok((view, hashed_state))
+    }
+}
+
+#[async_trait]
+impl<DB, Provider> DebugStorageRootApiServer for DebugStorageRootApi<DB, Provider>
+where
+    DB: Database + Clone + 'static,
+    Provider: DatabaseProviderFactory<DB> + BlockReaderIdExt + Clone + Send + Sync + 'static,
+{
+    /// Handler for `debug_storageRoot`
+    async fn debug_storage_root(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> RpcResult<B256> {
+        Ok(DebugStorageRootApi::storage_root(self, address, block_id).await?)
+    }
+}
+
+impl<DB, Provider> std::fmt::Debug for DebugStorageRootApi<DB, Provider> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DebugStorageRootApi").finish_non_exhaustive()
+    }
+}
+
+impl<DB, Provider> Clone for DebugStorageRootApi<DB, Provider> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+struct DebugStorageRootApiInner<DB, Provider> {
+    /// The provider that can interact with the chain.
+    provider: Provider,
+    /// The pool the storage roots are computed on.
+    blocking_task_pool: BlockingTaskPool,
+    /// Cache of the trie nodes shared between computations.
+    trie_cache: Option<TrieNodeCache>,
+    _database: PhantomData<DB>,
+}
@@ -4,8 +4,8 @@ use crate::{
         api::pending_block::PendingBlockEnv,
         error::{EthApiError, EthResult, RpcInvalidTransactionError, SignError},
         revm_utils::{
-            inspect, inspect_and_return_db, prepare_call_env, replay_transactions_until, transact,
-            EvmOverrides,
+            inspect, inspect_and_return_db, prefetch_access_list, prepare_call_env,
+            replay_transactions_until, transact, EvmOverrides,
         },
         utils::recover_raw_transaction,
     },
@@ -938,6 +938,7 @@ where
                     &mut db,
                     overrides,
                 )?;
+                prefetch_access_list(&mut db, &env.tx.access_list)?;
                 f(db, env)
             })
             .await
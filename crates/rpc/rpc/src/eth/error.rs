@@ -6,7 +6,7 @@ use jsonrpsee::{
     core::Error as RpcError,
     types::{error::CALL_EXECUTION_FAILED_CODE, ErrorObject},
 };
-use reth_interfaces::RethError;
+use reth_interfaces::{trie::StorageRootError, RethError};
 use reth_primitives::{revm_primitives::InvalidHeader, Address, Bytes, U256};
 use reth_revm::tracing::{js::JsInspectorError, MuxError};
 use reth_rpc_types::{error::EthRpcErrorCode, request::TransactionInputError, BlockError};
@@ -14,6 +14,7 @@ use reth_transaction_pool::error::{
     Eip4844PoolTransactionError, InvalidPoolTransactionError, PoolError, PoolErrorKind,
     PoolTransactionError,
 };
+use reth_trie_parallel::async_root::AsyncStateRootError;
 use revm::primitives::{EVMError, ExecutionResult, HaltReason, OutOfGasError};
 use std::time::Duration;
 
@@ -218,6 +219,20 @@ impl From<reth_interfaces::provider::ProviderError> for EthApiError {
     }
 }
 
+impl From<AsyncStateRootError> for EthApiError {
+    fn from(error: AsyncStateRootError) -> Self {
+        match error {
+            AsyncStateRootError::Provider(err) => err.into(),
+            AsyncStateRootError::StorageRoot(StorageRootError::DB(err)) => {
+                EthApiError::Internal(err.into())
+            }
+            AsyncStateRootError::StorageRootChannelClosed { .. } => {
+                EthApiError::InternalBlockingTaskError
+            }
+        }
+    }
+}
+
 impl<T> From<EVMError<T>> for EthApiError
 where
     T: Into<EthApiError>,
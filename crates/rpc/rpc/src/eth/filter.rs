@@ -15,14 +15,15 @@ use reth_primitives::{ChainInfo, IntoRecoveredTransaction, TxHash};
 use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider, ProviderError};
 use reth_rpc_api::EthFilterApiServer;
 use reth_rpc_types::{
-    BlockNumHash, Filter, FilterBlockOption, FilterChanges, FilterId, FilteredParams, Log,
-    PendingTransactionFilterKind,
+    BlockNumHash, Filter, FilterBlockOption, FilterChanges, FilterId, FilterSet, FilteredParams,
+    Log, PendingTransactionFilterKind, ValueOrArray,
 };
 
 use reth_tasks::TaskSpawner;
 use reth_transaction_pool::{NewSubpoolTransactionStream, PoolTransaction, TransactionPool};
 use std::{
     collections::HashMap,
+    hash::Hash,
     iter::StepBy,
     ops::RangeInclusive,
     sync::Arc,
@@ -453,6 +454,33 @@ where
             return Ok(all_logs)
         }
 
+        let is_multi_block_range = from_block != to_block;
+
+        // if the filter is restricted to addresses and first topics, the log index yields the
+        // blocks that may contain matching logs
+        if let Some((addresses, topics0)) =
+            filter_set_values(&filter.address).zip(filter_set_values(&filter.topics[0]))
+        {
+            if let Some(blocks) =
+                self.provider.log_index_blocks(&addresses, &topics0, from_block..=to_block)?
+            {
+                for block_number in blocks.into_keys() {
+                    let block_hash = self
+                        .provider
+                        .block_hash(block_number)?
+                        .ok_or(ProviderError::BlockNotFound(block_number.into()))?;
+                    self.append_block_logs(
+                        &mut all_logs,
+                        &filter_params,
+                        BlockNumHash::new(block_number, block_hash),
+                        is_multi_block_range,
+                    )
+                    .await?;
+                }
+                return Ok(all_logs)
+            }
+        }
+
         // derive bloom filters from filter input, so we can check headers for matching logs
         let address_filter = FilteredParams::address_filter(&filter.address);
         let topics_filter = FilteredParams::topics_filter(&filter.topics);
@@ -479,31 +507,54 @@ where
                             .ok_or(ProviderError::BlockNotFound(header.number.into()))?,
                     };
 
-                    if let Some(receipts) = self.eth_cache.get_receipts(block_hash).await? {
-                        append_matching_block_logs(
-                            &mut all_logs,
-                            &self.provider,
-                            &filter_params,
-                            BlockNumHash::new(header.number, block_hash),
-                            &receipts,
-                            false,
-                        )?;
-
-                        // size check but only if range is multiple blocks, so we always return all
-                        // logs of a single block
-                        let is_multi_block_range = from_block != to_block;
-                        if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
-                            return Err(FilterError::QueryExceedsMaxResults(
-                                self.max_logs_per_response,
-                            ))
-                        }
-                    }
+                    self.append_block_logs(
+                        &mut all_logs,
+                        &filter_params,
+                        BlockNumHash::new(header.number, block_hash),
+                        is_multi_block_range,
+                    )
+                    .await?;
                 }
             }
         }
 
         Ok(all_logs)
     }
+
+    /// Appends the logs of the block matching the filter.
+    async fn append_block_logs(
+        &self,
+        all_logs: &mut Vec<Log>,
+        filter_params: &FilteredParams,
+        block: BlockNumHash,
+        is_multi_block_range: bool,
+    ) -> Result<(), FilterError> {
+        if let Some(receipts) = self.eth_cache.get_receipts(block.hash).await? {
+            append_matching_block_logs(
+                all_logs,
+                &self.provider,
+                filter_params,
+                block,
+                &receipts,
+                false,
+            )?;
+
+            // size check but only if range is multiple blocks, so we always return all logs of a
+            // single block
+            if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
+                return Err(FilterError::QueryExceedsMaxResults(self.max_logs_per_response))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the values of the filter set, or `None` if the set matches any value.
+fn filter_set_values<T: Clone + Eq + Hash>(set: &FilterSet<T>) -> Option<Vec<T>> {
+    match set.to_value_or_array()? {
+        ValueOrArray::Value(value) => Some(vec![value]),
+        ValueOrArray::Array(values) => Some(values),
+    }
 }
 
 /// Config for the filter
@@ -11,6 +11,8 @@ use reth_primitives::{
     revm::env::fill_tx_env_with_recovered, Address, TransactionSigned,
     TransactionSignedEcRecovered, TxHash, B256, U256,
 };
+use reth_provider::StateProvider;
+use reth_revm::database::StateProviderDatabase;
 use reth_rpc_types::{
     state::{AccountOverride, StateOverride},
     BlockOverrides, TransactionRequest,
@@ -301,6 +303,33 @@ where
     Ok(env)
 }
 
+/// Loads the storage slots of the access list into the [CacheDB] with a single batched read of
+/// the state, instead of reading them one by one during the execution.
+///
+/// Accounts that are already cached, e.g. because of state overrides, are skipped.
+pub(crate) fn prefetch_access_list<S>(
+    db: &mut CacheDB<StateProviderDatabase<S>>,
+    access_list: &[(Address, Vec<U256>)],
+) -> EthResult<()>
+where
+    S: StateProvider,
+{
+    let slots = access_list
+        .iter()
+        .filter(|(address, _)| !db.accounts.contains_key(address))
+        .flat_map(|(address, keys)| keys.iter().map(|key| (*address, B256::new(key.to_be_bytes()))))
+        .collect::<Vec<_>>();
+    if slots.is_empty() {
+        return Ok(())
+    }
+
+    let values = db.db.storages(&slots)?;
+    for ((address, key), value) in slots.into_iter().zip(values) {
+        db.insert_account_storage(address, U256::from_be_bytes(key.0), value.unwrap_or_default())?;
+    }
+    Ok(())
+}
+
 /// Creates a new [EnvWithHandlerCfg] to be used for executing the [TransactionRequest] in
 /// `eth_call`.
 ///
@@ -37,7 +37,7 @@ mod trace;
 mod txpool;
 mod web3;
 pub use admin::AdminApi;
-pub use debug::DebugApi;
+pub use debug::{DebugApi, DebugStorageRootApi};
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthApiSpec, EthFilter, EthPubSub, EthSubscriptionIdProvider};
 pub use layers::{AuthLayer, AuthValidator, Claims, JwtAuthValidator, JwtError, JwtSecret};
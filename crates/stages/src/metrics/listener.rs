@@ -1,4 +1,5 @@
 use crate::metrics::SyncMetrics;
+use reth_db::{write_profile::TableWriteProfile, Tables};
 use reth_primitives::{
     constants::MGAS_TO_GAS,
     stage::{StageCheckpoint, StageId},
@@ -38,6 +39,22 @@ pub enum MetricEvent {
         /// Gas processed.
         gas: u64,
     },
+    /// Stage wrote to a database table.
+    StageTableWrites {
+        /// Stage ID.
+        stage_id: StageId,
+        /// The table that was written to.
+        table: Tables,
+        /// Writes to the table since the previous event.
+        writes: TableWriteProfile,
+    },
+    /// Stage committed database transactions.
+    StageCommits {
+        /// Stage ID.
+        stage_id: StageId,
+        /// The number of commits since the previous event.
+        commits: u64,
+    },
 }
 
 /// Metrics routine that listens to new metric events on the `events_rx` receiver.
@@ -88,6 +105,17 @@ impl MetricsListener {
             MetricEvent::ExecutionStageGas { gas } => {
                 self.sync_metrics.execution_stage.mgas_processed_total.increment(gas / MGAS_TO_GAS)
             }
+            MetricEvent::StageTableWrites { stage_id, table, writes } => {
+                let table_metrics =
+                    self.sync_metrics.get_stage_table_write_metrics(stage_id, table);
+
+                table_metrics.puts.increment(writes.puts);
+                table_metrics.put_bytes.increment(writes.put_bytes);
+                table_metrics.deletes.increment(writes.deletes);
+            }
+            MetricEvent::StageCommits { stage_id, commits } => {
+                self.sync_metrics.get_stage_metrics(stage_id).db_commits.increment(commits)
+            }
         }
     }
 }
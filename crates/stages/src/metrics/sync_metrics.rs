@@ -1,3 +1,4 @@
+use reth_db::Tables;
 use reth_metrics::{
     metrics::{Counter, Gauge},
     Metrics,
@@ -8,6 +9,7 @@ use std::collections::HashMap;
 #[derive(Debug, Default)]
 pub(crate) struct SyncMetrics {
     pub(crate) stages: HashMap<StageId, StageMetrics>,
+    pub(crate) stage_table_writes: HashMap<(StageId, Tables), StageTableWriteMetrics>,
     pub(crate) execution_stage: ExecutionStageMetrics,
 }
 
@@ -18,6 +20,21 @@ impl SyncMetrics {
             .entry(stage_id)
             .or_insert_with(|| StageMetrics::new_with_labels(&[("stage", stage_id.to_string())]))
     }
+
+    /// Returns existing or initializes a new instance of [StageTableWriteMetrics] for the provided
+    /// [StageId] and [Tables].
+    pub(crate) fn get_stage_table_write_metrics(
+        &mut self,
+        stage_id: StageId,
+        table: Tables,
+    ) -> &mut StageTableWriteMetrics {
+        self.stage_table_writes.entry((stage_id, table)).or_insert_with(|| {
+            StageTableWriteMetrics::new_with_labels(&[
+                ("stage", stage_id.to_string()),
+                ("table", table.name().to_string()),
+            ])
+        })
+    }
 }
 
 #[derive(Metrics)]
@@ -29,6 +46,20 @@ pub(crate) struct StageMetrics {
     pub(crate) entities_processed: Gauge,
     /// The number of total entities of the last commit for a stage, if applicable.
     pub(crate) entities_total: Gauge,
+    /// The number of database transactions committed by a stage.
+    pub(crate) db_commits: Counter,
+}
+
+/// Database writes of a stage to a table.
+#[derive(Metrics)]
+#[metrics(scope = "sync.db_writes")]
+pub(crate) struct StageTableWriteMetrics {
+    /// The number of puts to the table.
+    pub(crate) puts: Counter,
+    /// The total encoded size of the keys and values put to the table.
+    pub(crate) put_bytes: Counter,
+    /// The number of deletes from the table.
+    pub(crate) deletes: Counter,
 }
 
 /// Execution stage metrics.
@@ -83,6 +83,7 @@ where
             listeners: Default::default(),
             progress: Default::default(),
             metrics_tx,
+            write_profile: None,
         }
     }
 }
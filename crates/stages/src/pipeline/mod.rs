@@ -2,7 +2,10 @@ use crate::{
     error::*, ExecInput, ExecOutput, MetricEvent, MetricEventsSender, Stage, StageExt, UnwindInput,
 };
 use futures_util::Future;
-use reth_db::database::Database;
+use reth_db::{
+    database::Database,
+    write_profile::{WriteProfile, WriteProfiler},
+};
 use reth_interfaces::RethResult;
 use reth_primitives::{
     constants::BEACON_CONSENSUS_REORG_UNWIND_DEPTH,
@@ -15,7 +18,7 @@ use reth_provider::{
 };
 use reth_static_file::StaticFileProducer;
 use reth_tokio_util::EventListeners;
-use std::pin::Pin;
+use std::{pin::Pin, sync::Arc};
 use tokio::sync::watch;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::*;
@@ -25,12 +28,14 @@ mod ctrl;
 mod event;
 mod progress;
 mod set;
+mod write_profile;
 
 pub use crate::pipeline::ctrl::ControlFlow;
 pub use builder::*;
 pub use event::*;
 use progress::*;
 pub use set::*;
+use write_profile::*;
 
 /// A container for a queued stage.
 pub(crate) type BoxedStage<DB> = Box<dyn Stage<DB>>;
@@ -79,6 +84,8 @@ pub struct Pipeline<DB: Database> {
     /// A receiver for the current chain tip to sync to.
     tip_tx: Option<watch::Sender<B256>>,
     metrics_tx: Option<MetricEventsSender>,
+    /// Database writes of the stages. If [Some], the writes are reported when a run finishes.
+    write_profile: Option<StageWriteProfiles>,
 }
 
 impl<DB> Pipeline<DB>
@@ -104,6 +111,23 @@ where
         });
     }
 
+    /// Attribute the database writes counted by the profiler to the stages that made them.
+    ///
+    /// When a run of the pipeline finishes, a summary of the writes of each stage is logged, see
+    /// [Pipeline::write_profile]. The writes are also reported to the metrics, if the metric events
+    /// sender is set.
+    ///
+    /// Writes made by other components while a stage is running are attributed to that stage.
+    pub fn set_write_profiler(&mut self, profiler: Arc<WriteProfiler>) {
+        self.write_profile = Some(StageWriteProfiles::new(profiler));
+    }
+
+    /// Returns the database writes of each stage in the current or the last finished run, if the
+    /// write profiler is set.
+    pub fn write_profile(&self) -> Option<&[(StageId, WriteProfile)]> {
+        self.write_profile.as_ref().map(StageWriteProfiles::stages)
+    }
+
     /// Listen for events on the pipeline.
     pub fn events(&mut self) -> UnboundedReceiverStream<PipelineEvent> {
         self.listeners.new_listener()
@@ -185,6 +209,10 @@ where
     pub async fn run_loop(&mut self) -> Result<ControlFlow, PipelineError> {
         self.produce_static_files()?;
 
+        if let Some(write_profile) = &mut self.write_profile {
+            write_profile.start();
+        }
+
         let mut previous_stage = None;
         for stage_index in 0..self.stages.len() {
             let stage = &self.stages[stage_index];
@@ -204,6 +232,7 @@ where
                 ControlFlow::Continue { block_number } => self.progress.update(block_number),
                 ControlFlow::Unwind { target, bad_block } => {
                     self.unwind(target, Some(bad_block.number))?;
+                    self.finish_write_profile();
                     return Ok(ControlFlow::Unwind { target, bad_block })
                 }
             }
@@ -217,9 +246,17 @@ where
             );
         }
 
+        self.finish_write_profile();
         Ok(self.progress.next_ctrl())
     }
 
+    /// Logs the database writes of each stage in the run, if the write profiler is set.
+    fn finish_write_profile(&self) {
+        if let Some(write_profile) = &self.write_profile {
+            write_profile.finish();
+        }
+    }
+
     /// Run [static file producer](StaticFileProducer) and move all data from the database to static
     /// files for corresponding [segments](reth_primitives::static_file::StaticFileSegment),
     /// according to their [stage checkpoints](StageCheckpoint):
@@ -322,6 +359,10 @@ where
                         self.provider_factory.static_file_provider().commit()?;
                         provider_rw.commit()?;
 
+                        if let Some(write_profile) = &mut self.write_profile {
+                            write_profile.record(stage_id, self.metrics_tx.as_ref());
+                        }
+
                         provider_rw = self.provider_factory.provider_rw()?;
                     }
                     Err(err) => {
@@ -426,6 +467,10 @@ where
                     self.provider_factory.static_file_provider().commit()?;
                     provider_rw.commit()?;
 
+                    if let Some(write_profile) = &mut self.write_profile {
+                        write_profile.record(stage_id, self.metrics_tx.as_ref());
+                    }
+
                     if done {
                         let block_number = checkpoint.block_number;
                         return Ok(if made_progress {
@@ -551,12 +596,18 @@ mod tests {
     use super::*;
     use crate::{test_utils::TestStage, UnwindOutput};
     use assert_matches::assert_matches;
+    use reth_db::{
+        mdbx::DatabaseArguments,
+        models::client_version::ClientVersion,
+        test_utils::{create_test_static_files_dir, tempdir_path},
+        DatabaseEnv, DatabaseEnvKind, Tables,
+    };
     use reth_interfaces::{
         consensus,
         provider::ProviderError,
         test_utils::{generators, generators::random_header},
     };
-    use reth_primitives::PruneModes;
+    use reth_primitives::{PruneModes, MAINNET};
     use reth_provider::test_utils::create_test_provider_factory;
     use tokio_stream::StreamExt;
 
@@ -659,6 +710,59 @@ mod tests {
         );
     }
 
+    /// Attributes the database writes of a pipeline run to the stages.
+    #[tokio::test]
+    async fn run_pipeline_with_write_profile() {
+        let (static_dir, _) = create_test_static_files_dir();
+        let db = DatabaseEnv::open(
+            &tempdir_path(),
+            DatabaseEnvKind::RW,
+            DatabaseArguments::new(ClientVersion::default()).with_write_profile(true),
+        )
+        .unwrap();
+        db.create_tables().unwrap();
+        let profiler = db.write_profiler().unwrap();
+        let provider_factory =
+            ProviderFactory::new(Arc::new(db), MAINNET.clone(), static_dir.into_path()).unwrap();
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(5), done: false }))
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(10), done: true })),
+            )
+            .add_stage(
+                TestStage::new(StageId::Other("B"))
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(10), done: true })),
+            )
+            .with_max_block(10)
+            .build(
+                provider_factory.clone(),
+                StaticFileProducer::new(
+                    provider_factory.clone(),
+                    provider_factory.static_file_provider(),
+                    PruneModes::default(),
+                ),
+            );
+        pipeline.set_write_profiler(profiler);
+        pipeline.run().await.unwrap();
+
+        // Each commit of a stage saves its checkpoint.
+        let profile = pipeline.write_profile().unwrap();
+        let commits = profile.iter().map(|(stage_id, profile)| (*stage_id, profile.commits));
+        assert_eq!(
+            commits.collect::<Vec<_>>(),
+            vec![(StageId::Other("A"), 2), (StageId::Other("B"), 1)]
+        );
+        for (_, profile) in profile {
+            assert_eq!(
+                profile.tables.iter().map(|(table, _)| *table).collect::<Vec<_>>(),
+                vec![Tables::StageCheckpoints]
+            );
+            assert_eq!(profile.total().puts, profile.commits);
+        }
+    }
+
     /// Unwinds a simple pipeline.
     #[tokio::test]
     async fn unwind_pipeline() {
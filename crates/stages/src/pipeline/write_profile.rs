@@ -0,0 +1,90 @@
+use crate::{MetricEvent, MetricEventsSender};
+use reth_db::write_profile::{WriteProfile, WriteProfiler};
+use reth_primitives::stage::StageId;
+use std::{fmt::Write, sync::Arc};
+use tracing::*;
+
+/// Database writes of the stages in a single pipeline run.
+#[derive(Debug)]
+pub(crate) struct StageWriteProfiles {
+    /// Counters of the database writes.
+    profiler: Arc<WriteProfiler>,
+    /// Writes of each stage that committed in the current run, in the order of the first commit.
+    stages: Vec<(StageId, WriteProfile)>,
+}
+
+impl StageWriteProfiles {
+    pub(crate) fn new(profiler: Arc<WriteProfiler>) -> Self {
+        Self { profiler, stages: Vec::new() }
+    }
+
+    /// Returns the writes of each stage in the current or the last finished run.
+    pub(crate) fn stages(&self) -> &[(StageId, WriteProfile)] {
+        &self.stages
+    }
+
+    /// Starts a new run, discarding the writes made since the previous one.
+    pub(crate) fn start(&mut self) {
+        self.profiler.take();
+        self.stages.clear();
+    }
+
+    /// Attributes the writes made since the previous call to the stage, and reports them to the
+    /// metrics.
+    pub(crate) fn record(&mut self, stage_id: StageId, metrics_tx: Option<&MetricEventsSender>) {
+        let profile = self.profiler.take();
+        if profile.is_empty() {
+            return
+        }
+
+        if let Some(metrics_tx) = metrics_tx {
+            for (table, writes) in &profile.tables {
+                let _ = metrics_tx.send(MetricEvent::StageTableWrites {
+                    stage_id,
+                    table: *table,
+                    writes: *writes,
+                });
+            }
+            let _ =
+                metrics_tx.send(MetricEvent::StageCommits { stage_id, commits: profile.commits });
+        }
+
+        match self.stages.iter_mut().find(|(id, _)| *id == stage_id) {
+            Some((_, existing)) => existing.merge(&profile),
+            None => self.stages.push((stage_id, profile)),
+        }
+    }
+
+    /// Logs the summary of the writes of each stage in the run.
+    pub(crate) fn finish(&self) {
+        if self.stages.is_empty() {
+            return
+        }
+
+        let mut summary = format!(
+            "{:<24} {:>10} {:>14} {:>16} {:>14}  {}",
+            "Stage", "Commits", "Puts", "Put bytes", "Deletes", "Top table"
+        );
+        for (stage_id, profile) in &self.stages {
+            let total = profile.total();
+            let top_table = profile
+                .tables
+                .iter()
+                .max_by_key(|(_, writes)| writes.put_bytes)
+                .map(|(table, _)| table.name())
+                .unwrap_or_default();
+            let _ = write!(
+                summary,
+                "\n{:<24} {:>10} {:>14} {:>16} {:>14}  {}",
+                stage_id.to_string(),
+                profile.commits,
+                total.puts,
+                total.put_bytes,
+                total.deletes,
+                top_table
+            );
+            debug!(target: "sync::pipeline", stage = %stage_id, "Stage database writes\n{profile}");
+        }
+        info!(target: "sync::pipeline", "Pipeline database writes\n{summary}");
+    }
+}
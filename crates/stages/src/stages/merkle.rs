@@ -16,6 +16,7 @@ use reth_provider::{
     StageCheckpointWriter, StatsReader,
 };
 use reth_trie::{IntermediateStateRootState, StateRoot, StateRootProgress};
+use reth_trie_parallel::parallel_root::ParallelFullStateRoot;
 use std::fmt::Debug;
 use tracing::*;
 
@@ -191,38 +192,55 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             });
 
             let tx = provider.tx_ref();
-            let progress = StateRoot::from_tx(tx)
-                .with_intermediate_state(checkpoint.map(IntermediateStateRootState::from))
-                .root_with_progress()
-                .map_err(|e| StageError::Fatal(Box::new(e)))?;
-            match progress {
-                StateRootProgress::Progress(state, hashed_entries_walked, updates) => {
-                    updates.flush(tx)?;
-
-                    let checkpoint = MerkleCheckpoint::new(
-                        to_block,
-                        state.last_account_key,
-                        state.walker_stack.into_iter().map(StoredSubNode::from).collect(),
-                        state.hash_builder.into(),
-                    );
-                    self.save_execution_checkpoint(provider, Some(checkpoint))?;
-
-                    entities_checkpoint.processed += hashed_entries_walked as u64;
-
-                    return Ok(ExecOutput {
-                        checkpoint: input
-                            .checkpoint()
-                            .with_entities_stage_checkpoint(entities_checkpoint),
-                        done: false,
-                    })
-                }
-                StateRootProgress::Complete(root, hashed_entries_walked, updates) => {
-                    updates.flush(tx)?;
+            if let Some(checkpoint) = checkpoint {
+                let progress = StateRoot::from_tx(tx)
+                    .with_intermediate_state(Some(IntermediateStateRootState::from(checkpoint)))
+                    .root_with_progress()
+                    .map_err(|e| StageError::Fatal(Box::new(e)))?;
+                match progress {
+                    StateRootProgress::Progress(state, hashed_entries_walked, updates) => {
+                        updates.flush(tx)?;
+
+                        let checkpoint = MerkleCheckpoint::new(
+                            to_block,
+                            state.last_account_key,
+                            state.walker_stack.into_iter().map(StoredSubNode::from).collect(),
+                            state.hash_builder.into(),
+                        );
+                        self.save_execution_checkpoint(provider, Some(checkpoint))?;
+
+                        entities_checkpoint.processed += hashed_entries_walked as u64;
+
+                        return Ok(ExecOutput {
+                            checkpoint: input
+                                .checkpoint()
+                                .with_entities_stage_checkpoint(entities_checkpoint),
+                            done: false,
+                        })
+                    }
+                    StateRootProgress::Complete(root, hashed_entries_walked, updates) => {
+                        updates.flush(tx)?;
 
-                    entities_checkpoint.processed += hashed_entries_walked as u64;
+                        entities_checkpoint.processed += hashed_entries_walked as u64;
 
-                    (root, entities_checkpoint)
+                        (root, entities_checkpoint)
+                    }
                 }
+            } else {
+                // The trie is rebuilt from scratch, so the ranges of hashed accounts are
+                // calculated in parallel. The storage tries are written after every batch of
+                // ranges, and the account trie once the root is calculated.
+                let (root, updates) = ParallelFullStateRoot::new(tx)
+                    .root_with_updates(|updates| {
+                        updates.flush(tx).map_err(ProviderError::Database)?;
+                        Ok(())
+                    })
+                    .map_err(ProviderError::from)?;
+                updates.flush(tx)?;
+
+                entities_checkpoint.processed = entities_checkpoint.total;
+
+                (root, entities_checkpoint)
             }
         } else {
             debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, "Updating trie");
@@ -4,11 +4,17 @@ use crate::{segments, segments::Segment, StaticFileProducerEvent};
 use parking_lot::Mutex;
 use rayon::prelude::*;
 use reth_db::database::Database;
-use reth_interfaces::RethResult;
-use reth_primitives::{static_file::HighestStaticFiles, BlockNumber, PruneModes};
+use reth_interfaces::{provider::ProviderError, RethResult};
+use reth_primitives::{
+    static_file::{find_fixed_range, HighestStaticFiles, SegmentRangeInclusive},
+    BlockNumber, PruneModes, StaticFileSegment,
+};
 use reth_provider::{
-    providers::{StaticFileProvider, StaticFileWriter},
-    ProviderFactory,
+    providers::{
+        StaticFileProvider, StaticFileWriter, TransactionHashFilter,
+        BLOCKS_PER_TRANSACTION_HASH_FILTER,
+    },
+    BlockReader, ProviderFactory, TransactionsProviderExt,
 };
 use reth_tokio_util::EventListeners;
 use std::{
@@ -170,6 +176,12 @@ impl<DB: Database> StaticFileProducerInner<DB> {
             self.static_file_provider.update_index(segment.segment(), Some(*block_range.end()))?;
         }
 
+        // Transaction lookups beyond the pruned `TransactionHashNumbers` entries are served by the
+        // transaction hash filters.
+        if targets.transactions.is_some() && self.prune_modes.transaction_lookup.is_some() {
+            self.write_transaction_hash_filters()?;
+        }
+
         let elapsed = start.elapsed(); // TODO(alexey): track in metrics
         debug!(target: "static_file", ?targets, ?elapsed, "StaticFileProducer finished");
 
@@ -179,6 +191,63 @@ impl<DB: Database> StaticFileProducerInner<DB> {
         Ok(targets)
     }
 
+    /// Builds the transaction hash filters of the transactions static files up to the highest
+    /// static file block.
+    ///
+    /// The highest filter is built again if its block range is partial.
+    fn write_transaction_hash_filters(&self) -> RethResult<()> {
+        let Some(highest_block) = self
+            .static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::Transactions)
+        else {
+            return Ok(())
+        };
+        let start = match self.static_file_provider.highest_transaction_hash_filter()? {
+            Some(filter)
+                if filter.block_range.end() - filter.block_range.start() + 1 ==
+                    BLOCKS_PER_TRANSACTION_HASH_FILTER =>
+            {
+                filter.block_range.end() + 1
+            }
+            Some(filter) => filter.block_range.start(),
+            None => 0,
+        };
+
+        let provider = self.provider_factory.provider()?.disable_long_read_transaction_safety();
+        let mut filters = Vec::new();
+        for block_start in
+            (start..=highest_block).step_by(BLOCKS_PER_TRANSACTION_HASH_FILTER as usize)
+        {
+            let block_end =
+                (block_start + BLOCKS_PER_TRANSACTION_HASH_FILTER - 1).min(highest_block);
+            let tx_start = provider
+                .block_body_indices(block_start)?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(block_start))?
+                .first_tx_num();
+            let tx_end = provider
+                .block_body_indices(block_end)?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(block_end))?
+                .next_tx_num();
+
+            let hashes = provider.transaction_hashes_by_range(tx_start..tx_end)?;
+            filters.push(TransactionHashFilter::new(
+                SegmentRangeInclusive::new(block_start, block_end),
+                tx_start..tx_end,
+                hashes.iter().map(|(hash, _)| hash),
+            ));
+
+            // Write the filters of each static file at once, to bound the memory usage.
+            if block_end == highest_block || block_end == find_fixed_range(block_end).end() {
+                self.static_file_provider
+                    .write_transaction_hash_filters(std::mem::take(&mut filters))?;
+            }
+        }
+
+        debug!(target: "static_file", start, highest_block, "Wrote transaction hash filters");
+
+        Ok(())
+    }
+
     /// Returns a static file targets at the provided finalized block numbers per segment.
     /// The target is determined by the check against highest static_files using
     /// [StaticFileProvider::get_highest_static_files].
@@ -251,11 +320,12 @@ mod tests {
         RethError,
     };
     use reth_primitives::{
-        static_file::HighestStaticFiles, PruneModes, StaticFileSegment, B256, U256,
+        static_file::{HighestStaticFiles, SegmentRangeInclusive},
+        PruneMode, PruneModes, StaticFileSegment, B256, U256,
     };
     use reth_provider::{
         providers::{StaticFileProvider, StaticFileWriter},
-        ProviderFactory,
+        ProviderFactory, TransactionsProviderExt,
     };
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use std::{
@@ -376,6 +446,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn transaction_hash_filters() {
+        let (provider_factory, static_file_provider, _temp_static_files_dir) = setup();
+
+        let mut static_file_producer = StaticFileProducerInner::new(
+            provider_factory,
+            static_file_provider.clone(),
+            PruneModes { transaction_lookup: Some(PruneMode::Full), ..Default::default() },
+        );
+
+        for finalized_block_number in [1, 3] {
+            let targets = static_file_producer
+                .get_static_file_targets(HighestStaticFiles {
+                    headers: Some(finalized_block_number),
+                    receipts: Some(finalized_block_number),
+                    transactions: Some(finalized_block_number),
+                })
+                .expect("get static file targets");
+            assert_matches!(static_file_producer.run(targets), Ok(_));
+        }
+
+        // The filter of the partial block range is built again with the new blocks.
+        let highest_tx = static_file_provider
+            .get_highest_static_file_tx(StaticFileSegment::Transactions)
+            .expect("highest static file transaction");
+        let filter = static_file_provider
+            .highest_transaction_hash_filter()
+            .expect("get highest transaction hash filter")
+            .expect("transaction hash filter");
+        assert_eq!(filter.block_range, SegmentRangeInclusive::new(0, 3));
+        assert_eq!(filter.tx_range, 0..highest_tx + 1);
+
+        let hashes = static_file_provider
+            .transaction_hashes_by_range(0..highest_tx + 1)
+            .expect("get transaction hashes");
+        for (hash, tx_number) in hashes {
+            assert_eq!(
+                static_file_provider.transaction_id_by_hash_filters(hash, highest_tx),
+                Ok(Some(tx_number))
+            );
+        }
+        assert_eq!(
+            static_file_provider.transaction_id_by_hash_filters(B256::random(), highest_tx),
+            Ok(None)
+        );
+    }
+
     /// Tests that a cloneable [`StaticFileProducer`] type is not susceptible to any race condition.
     #[test]
     fn only_one() {
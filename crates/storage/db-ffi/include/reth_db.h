@@ -0,0 +1,65 @@
+/*
+ * C interface for read-only access to the tables of a reth database.
+ *
+ * See the documentation of the `reth-db-ffi` crate for the details.
+ */
+
+#ifndef RETH_DB_H
+#define RETH_DB_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Handle of a database opened with `reth_db_open_ro`. */
+typedef struct RethDb RethDb;
+
+/* Bytes allocated by the library, released with `reth_db_bytes_free`. */
+typedef struct RethDbBytes {
+    uint8_t *data;
+    size_t len;
+} RethDbBytes;
+
+/*
+ * Called by `reth_db_scan` for each scanned entry. The key and value are only valid for the
+ * duration of the call. The scan stops when the function returns false.
+ */
+typedef bool (*RethDbScanCallback)(void *ctx, const uint8_t *key, size_t key_len,
+                                   const uint8_t *value, size_t value_len);
+
+/* Opens the database at `path` in read-only mode. Returns NULL on error. */
+RethDb *reth_db_open_ro(const char *path);
+
+/* Closes the database. */
+void reth_db_close(RethDb *db);
+
+/*
+ * Gets the value of the encoded `key` from the table named `table`. Returns 1 and writes the
+ * value if the key exists, 0 if it doesn't, and -1 on error.
+ */
+int reth_db_get(const RethDb *db, const char *table, const uint8_t *key, size_t key_len,
+                RethDbBytes *value);
+
+/* Releases the bytes returned by the library. */
+void reth_db_bytes_free(RethDbBytes bytes);
+
+/*
+ * Scans the entries of the table named `table` with encoded keys in the [start, end) range. A
+ * NULL `start` or `end` leaves the range unbounded on that side. Returns the number of entries
+ * passed to `callback`, or -1 on error.
+ */
+int64_t reth_db_scan(const RethDb *db, const char *table, const uint8_t *start, size_t start_len,
+                     const uint8_t *end, size_t end_len, RethDbScanCallback callback, void *ctx);
+
+/* Returns the message of the last error on the calling thread, or NULL if there was none. */
+const char *reth_db_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RETH_DB_H */
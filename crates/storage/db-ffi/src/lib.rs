@@ -0,0 +1,351 @@
+//! C interface for read-only access to the tables of a reth database.
+//!
+//! This crate is built as a `cdylib`, so software written in other languages can read the node's
+//! database directly, e.g. to index it, instead of querying it over JSON-RPC. The C declarations
+//! of the exported functions are in `include/reth_db.h`.
+//!
+//! The database is opened in read-only mode, which is safe to do while the node is running: every
+//! call opens its own read-only transaction and observes the latest committed state of the
+//! database.
+//!
+//! Tables are selected by name (see [`Tables`]), and keys and values are passed as raw bytes in
+//! their database encoding, see [`DbTx::get_by_encoded_key`].
+//!
+//! # Errors
+//!
+//! Functions returning a pointer return null on error, and functions returning an integer return
+//! `-1`. The message of the last error on the calling thread is returned by
+//! [`reth_db_last_error`].
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use reth_db::{
+    database::Database, mdbx::DatabaseArguments, models::client_version::ClientVersion,
+    open_db_read_only, transaction::DbTx, DatabaseEnv, Tables,
+};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_int, c_void, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
+    ptr, slice,
+};
+
+thread_local! {
+    /// The message of the last error on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Handle of a database opened with [`reth_db_open_ro`].
+#[derive(Debug)]
+pub struct RethDb {
+    env: DatabaseEnv,
+}
+
+/// Bytes allocated by this library, released with [`reth_db_bytes_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct RethDbBytes {
+    /// Pointer to the first byte, null if there are no bytes.
+    pub data: *mut u8,
+    /// The number of bytes.
+    pub len: usize,
+}
+
+impl RethDbBytes {
+    const fn empty() -> Self {
+        Self { data: ptr::null_mut(), len: 0 }
+    }
+
+    fn new(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        Self { data, len }
+    }
+}
+
+/// Function called by [`reth_db_scan`] for each scanned entry, with the `ctx` passed to the scan.
+///
+/// The key and value are only valid for the duration of the call. The scan stops when the
+/// function returns `false`.
+pub type RethDbScanCallback = unsafe extern "C" fn(
+    ctx: *mut c_void,
+    key: *const u8,
+    key_len: usize,
+    value: *const u8,
+    value_len: usize,
+) -> bool;
+
+/// Opens the database at `path` in read-only mode.
+///
+/// Returns null on error. The handle must be closed with [`reth_db_close`].
+///
+/// # Safety
+///
+/// `path` must be a valid null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn reth_db_open_ro(path: *const c_char) -> *mut RethDb {
+    ffi_call(ptr::null_mut(), || {
+        let path = c_str(path)?;
+        let env =
+            open_db_read_only(Path::new(path), DatabaseArguments::new(ClientVersion::default()))
+                .map_err(|err| format!("{err:#}"))?;
+        Ok(Box::into_raw(Box::new(RethDb { env })))
+    })
+}
+
+/// Closes the database opened with [`reth_db_open_ro`].
+///
+/// # Safety
+///
+/// `db` must be a handle returned by [`reth_db_open_ro`] that wasn't closed yet, or null.
+#[no_mangle]
+pub unsafe extern "C" fn reth_db_close(db: *mut RethDb) {
+    if !db.is_null() {
+        drop(Box::from_raw(db));
+    }
+}
+
+/// Gets the value of the encoded `key` from the table named `table`.
+///
+/// Returns `1` and writes the value to `value` if the key exists, `0` if it doesn't, and `-1` on
+/// error. For dupsort tables, the first value of the key is returned. The value must be released
+/// with [`reth_db_bytes_free`].
+///
+/// # Safety
+///
+/// `db` must be an open handle, `table` a valid null-terminated string, `key` must point to
+/// `key_len` readable bytes and `value` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn reth_db_get(
+    db: *const RethDb,
+    table: *const c_char,
+    key: *const u8,
+    key_len: usize,
+    value: *mut RethDbBytes,
+) -> c_int {
+    ffi_call(-1, || {
+        let db = db.as_ref().ok_or("database handle is null")?;
+        let table = table_by_name(table)?;
+        let key = bytes(key, key_len)?;
+        if value.is_null() {
+            return Err("value pointer is null".to_string())
+        }
+
+        let tx = db.env.tx().map_err(|err| err.to_string())?;
+        let result = tx.get_by_encoded_key(table, key).map_err(|err| err.to_string())?;
+        let _ = tx.commit();
+
+        match result {
+            Some(bytes) => {
+                value.write(RethDbBytes::new(bytes));
+                Ok(1)
+            }
+            None => {
+                value.write(RethDbBytes::empty());
+                Ok(0)
+            }
+        }
+    })
+}
+
+/// Releases the bytes returned by this library.
+///
+/// # Safety
+///
+/// `bytes` must be returned by this library and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn reth_db_bytes_free(bytes: RethDbBytes) {
+    if !bytes.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(bytes.data, bytes.len)));
+    }
+}
+
+/// Scans the entries of the table named `table` with encoded keys in the `[start, end)` range,
+/// in the order of the database, calling `callback` for each entry.
+///
+/// A null `start` scans from the first entry, and a null `end` scans to the last entry. For
+/// dupsort tables, each value of a key is a separate entry. All entries are read from the same
+/// read-only transaction.
+///
+/// Returns the number of entries passed to `callback`, or `-1` on error.
+///
+/// # Safety
+///
+/// `db` must be an open handle, `table` a valid null-terminated string, and `start` and `end`
+/// must point to `start_len` and `end_len` readable bytes respectively, or be null.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn reth_db_scan(
+    db: *const RethDb,
+    table: *const c_char,
+    start: *const u8,
+    start_len: usize,
+    end: *const u8,
+    end_len: usize,
+    callback: RethDbScanCallback,
+    ctx: *mut c_void,
+) -> i64 {
+    ffi_call(-1, || {
+        let db = db.as_ref().ok_or("database handle is null")?;
+        let table = table_by_name(table)?;
+        let start = if start.is_null() { None } else { Some(bytes(start, start_len)?) };
+        let end = if end.is_null() { None } else { Some(bytes(end, end_len)?) };
+
+        let mut tx = db.env.tx().map_err(|err| err.to_string())?;
+        // Scans are driven by the caller and may take arbitrarily long.
+        tx.disable_long_read_transaction_safety();
+        let scanned = tx
+            .walk_by_encoded_key(table, start, end, |key, value| {
+                // SAFETY: the callback and its context are provided by the caller of the scan.
+                unsafe { callback(ctx, key.as_ptr(), key.len(), value.as_ptr(), value.len()) }
+            })
+            .map_err(|err| err.to_string())?;
+        let _ = tx.commit();
+
+        Ok(scanned as i64)
+    })
+}
+
+/// Returns the message of the last error on the calling thread, or null if there was none.
+///
+/// The message is valid until the next call to this library on the same thread.
+#[no_mangle]
+pub extern "C" fn reth_db_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |err| err.as_ptr()))
+}
+
+/// Calls the exported function body `f`, recording its error or panic as the last error and
+/// returning `on_error` instead.
+fn ffi_call<R>(on_error: R, f: impl FnOnce() -> Result<R, String>) -> R {
+    let err = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(result)) => return result,
+        Ok(Err(err)) => err,
+        Err(_) => "panicked".to_string(),
+    };
+    LAST_ERROR.with(|last| {
+        *last.borrow_mut() = Some(CString::new(err.replace('\0', "")).unwrap_or_default())
+    });
+    on_error
+}
+
+/// Converts the null-terminated string to a `str`.
+unsafe fn c_str<'a>(ptr: *const c_char) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err("string pointer is null".to_string())
+    }
+    CStr::from_ptr(ptr).to_str().map_err(|err| err.to_string())
+}
+
+/// Returns the table with the name in the null-terminated string.
+unsafe fn table_by_name(ptr: *const c_char) -> Result<Tables, String> {
+    c_str(ptr)?.parse()
+}
+
+/// Converts the pointer and length to a slice. A null pointer is only allowed for empty slices.
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], String> {
+    match (ptr.is_null(), len) {
+        (true, 0) => Ok(&[]),
+        (true, _) => Err("bytes pointer is null".to_string()),
+        (false, _) => Ok(slice::from_raw_parts(ptr, len)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{init_db, tables, transaction::DbTxMut};
+    use reth_primitives::{Address, StorageEntry, B256, U256};
+
+    fn open(path: &Path) -> *mut RethDb {
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+        let db = unsafe { reth_db_open_ro(path.as_ptr()) };
+        assert!(!db.is_null());
+        db
+    }
+
+    unsafe extern "C" fn collect(
+        ctx: *mut c_void,
+        key: *const u8,
+        key_len: usize,
+        value: *const u8,
+        value_len: usize,
+    ) -> bool {
+        let entries = &mut *(ctx as *mut Vec<(Vec<u8>, Vec<u8>)>);
+        entries.push((
+            slice::from_raw_parts(key, key_len).to_vec(),
+            slice::from_raw_parts(value, value_len).to_vec(),
+        ));
+        entries.len() < 3
+    }
+
+    #[test]
+    fn get_and_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let address = Address::with_last_byte(1);
+        {
+            let db = init_db(dir.path(), DatabaseArguments::new(ClientVersion::default())).unwrap();
+            let tx = db.tx_mut().unwrap();
+            for number in 0..5u64 {
+                tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8))
+                    .unwrap();
+            }
+            for slot in 0..2 {
+                let entry = StorageEntry { key: B256::with_last_byte(slot), value: U256::from(1) };
+                tx.put::<tables::PlainStorageState>(address, entry).unwrap();
+            }
+            tx.commit().unwrap();
+        }
+
+        let db = open(dir.path());
+        let table = CString::new("CanonicalHeaders").unwrap();
+        unsafe {
+            let mut value = RethDbBytes::empty();
+            let key = 3u64.to_be_bytes();
+            assert_eq!(reth_db_get(db, table.as_ptr(), key.as_ptr(), key.len(), &mut value), 1);
+            assert_eq!(
+                slice::from_raw_parts(value.data, value.len),
+                B256::with_last_byte(3).as_slice()
+            );
+            reth_db_bytes_free(value);
+
+            let key = 7u64.to_be_bytes();
+            assert_eq!(reth_db_get(db, table.as_ptr(), key.as_ptr(), key.len(), &mut value), 0);
+            assert!(value.data.is_null());
+
+            // `[1, 4)`, stopped by the callback after three entries.
+            let mut entries = Vec::new();
+            let (start, end) = (1u64.to_be_bytes(), 4u64.to_be_bytes());
+            let ctx = &mut entries as *mut _ as *mut c_void;
+            let scanned =
+                reth_db_scan(db, table.as_ptr(), start.as_ptr(), 8, end.as_ptr(), 8, collect, ctx);
+            assert_eq!(scanned, 3);
+            assert_eq!(
+                entries.iter().map(|(key, _)| key.clone()).collect::<Vec<_>>(),
+                [1u64, 2, 3].map(|number| number.to_be_bytes().to_vec())
+            );
+
+            // Each value of a dupsort key is a separate entry.
+            let table = CString::new("PlainStorageState").unwrap();
+            let mut entries = Vec::new();
+            let ctx = &mut entries as *mut _ as *mut c_void;
+            let scanned =
+                reth_db_scan(db, table.as_ptr(), ptr::null(), 0, ptr::null(), 0, collect, ctx);
+            assert_eq!(scanned, 2);
+            assert!(entries.iter().all(|(key, _)| key == address.as_slice()));
+
+            let table = CString::new("Unknown").unwrap();
+            assert_eq!(reth_db_get(db, table.as_ptr(), ptr::null(), 0, &mut value), -1);
+            let err = CStr::from_ptr(reth_db_last_error()).to_str().unwrap();
+            assert_eq!(err, "unknown table: \"Unknown\"");
+
+            reth_db_close(db);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Python bindings for read-only access to the tables of a reth database.
+//!
+//! The `reth_db` Python module is built with [maturin](https://www.maturin.rs) from this crate,
+//! e.g. `maturin develop --release` in the crate directory. It exposes a [`Database`] class:
+//!
+//! ```python
+//! import reth_db
+//!
+//! db = reth_db.Database("/path/to/datadir/db")
+//! print(db.tables())
+//! print(db.stats("PlainAccountState"))
+//! for key, value in db.scan("CanonicalHeaders", limit=10):
+//!     print(key.hex(), value.hex())
+//! ```
+//!
+//! Tables are selected by name, and keys and values are passed as `bytes` in their database
+//! encoding, see [`DbTx::get_by_encoded_key`]. The database is opened in read-only mode, which is
+//! safe to do while the node is running: every call opens its own read-only transaction and
+//! observes the latest committed state of the database.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+};
+use reth_db::{
+    database::Database as _, database_metrics::DatabaseTableStats, mdbx::DatabaseArguments,
+    models::client_version::ClientVersion, open_db_read_only, transaction::DbTx, DatabaseEnv,
+    Tables,
+};
+use std::{borrow::Cow, collections::BTreeMap, fmt::Display, path::PathBuf};
+
+/// Encoded key and value of a table entry.
+type Entry = (Cow<'static, [u8]>, Cow<'static, [u8]>);
+
+/// Database opened in read-only mode.
+#[pyclass(frozen)]
+#[derive(Debug)]
+pub struct Database {
+    env: DatabaseEnv,
+}
+
+#[pymethods]
+impl Database {
+    /// Opens the database at `path` in read-only mode.
+    #[new]
+    fn open(path: PathBuf) -> PyResult<Self> {
+        let env = open_db_read_only(&path, DatabaseArguments::new(ClientVersion::default()))
+            .map_err(|err| PyRuntimeError::new_err(format!("{err:#}")))?;
+        Ok(Self { env })
+    }
+
+    /// Returns the names of all tables.
+    #[staticmethod]
+    fn tables() -> Vec<&'static str> {
+        Tables::ALL.iter().map(|table| table.name()).collect()
+    }
+
+    /// Returns the value of the encoded `key` in the table, or `None` if the key doesn't exist.
+    ///
+    /// For dupsort tables, the first value of the key is returned.
+    fn get(&self, table: &str, key: &[u8]) -> PyResult<Option<Cow<'static, [u8]>>> {
+        let table = table_by_name(table)?;
+        let tx = self.env.tx().map_err(database_error)?;
+        let value = tx.get_by_encoded_key(table, key).map_err(database_error)?;
+        let _ = tx.commit();
+        Ok(value.map(Cow::Owned))
+    }
+
+    /// Returns the entries of the table with encoded keys in the `[start, end)` range, up to
+    /// `limit` entries.
+    ///
+    /// An unset bound leaves the range unbounded on that side. For dupsort tables, each value of
+    /// a key is a separate entry.
+    #[pyo3(signature = (table, start = None, end = None, limit = None))]
+    fn scan(
+        &self,
+        table: &str,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        limit: Option<usize>,
+    ) -> PyResult<Vec<Entry>> {
+        let table = table_by_name(table)?;
+        let limit = limit.unwrap_or(usize::MAX);
+        if limit == 0 {
+            return Ok(Vec::new())
+        }
+
+        let mut tx = self.env.tx().map_err(database_error)?;
+        // Scans of whole tables may take arbitrarily long.
+        tx.disable_long_read_transaction_safety();
+        let mut entries = Vec::new();
+        tx.walk_by_encoded_key(table, start, end, |key, value| {
+            entries.push((Cow::Owned(key.to_vec()), Cow::Owned(value.to_vec())));
+            entries.len() < limit
+        })
+        .map_err(database_error)?;
+        let _ = tx.commit();
+
+        Ok(entries)
+    }
+
+    /// Returns the statistics of the table: the number of `entries`, the `size` in bytes and,
+    /// if the database stores tables in pages, the `page_size` and the number of `branch_pages`,
+    /// `leaf_pages` and `overflow_pages`.
+    fn stats(&self, table: &str) -> PyResult<BTreeMap<&'static str, usize>> {
+        let stats = self.env.table_stats(table_by_name(table)?).map_err(database_error)?;
+
+        let mut result = BTreeMap::from([("entries", stats.entries()), ("size", stats.size())]);
+        if let Some(pages) = stats.pages() {
+            result.extend([
+                ("page_size", pages.page_size),
+                ("branch_pages", pages.branch),
+                ("leaf_pages", pages.leaf),
+                ("overflow_pages", pages.overflow),
+            ]);
+        }
+        Ok(result)
+    }
+}
+
+/// Returns the table with the name, raising a `ValueError` if there's none.
+fn table_by_name(name: &str) -> PyResult<Tables> {
+    name.parse().map_err(PyValueError::new_err)
+}
+
+/// Converts the database error into a `RuntimeError`.
+fn database_error(err: impl Display) -> PyErr {
+    PyRuntimeError::new_err(err.to_string())
+}
+
+/// The `reth_db` Python module.
+#[pymodule]
+#[pyo3(name = "reth_db")]
+fn module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Database>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{init_db, tables, transaction::DbTxMut};
+    use reth_primitives::B256;
+
+    #[test]
+    fn get_scan_and_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let db = init_db(dir.path(), DatabaseArguments::new(ClientVersion::default())).unwrap();
+            let tx = db.tx_mut().unwrap();
+            for number in 0..5u64 {
+                tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8))
+                    .unwrap();
+            }
+            tx.commit().unwrap();
+        }
+
+        let db = Database::open(dir.path().to_path_buf()).unwrap();
+        assert!(Database::tables().contains(&"CanonicalHeaders"));
+
+        let value = db.get("CanonicalHeaders", &2u64.to_be_bytes()).unwrap();
+        assert_eq!(value.as_deref(), Some(B256::with_last_byte(2).as_slice()));
+        assert_eq!(db.get("CanonicalHeaders", &7u64.to_be_bytes()).unwrap(), None);
+        assert!(db.get("Unknown", &[]).is_err());
+
+        let keys =
+            |entries: Vec<Entry>| entries.into_iter().map(|(key, _)| key[7]).collect::<Vec<_>>();
+        let entries = db.scan("CanonicalHeaders", None, None, None).unwrap();
+        assert_eq!(keys(entries), vec![0, 1, 2, 3, 4]);
+        let (start, end) = (1u64.to_be_bytes(), 4u64.to_be_bytes());
+        let entries = db.scan("CanonicalHeaders", Some(&start), Some(&end), Some(2)).unwrap();
+        assert_eq!(keys(entries), vec![1, 2]);
+
+        let stats = db.stats("CanonicalHeaders").unwrap();
+        assert_eq!(stats["entries"], 5);
+        assert!(stats.contains_key("leaf_pages"));
+    }
+}
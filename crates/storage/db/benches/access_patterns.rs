@@ -0,0 +1,138 @@
+#![allow(missing_docs)]
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+};
+use pprof::criterion::{Output, PProfProfiler};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRW},
+    tables::{CanonicalHeaders, PlainStorageState},
+    transaction::DbTx,
+};
+use reth_primitives::{Address, StorageEntry, B256, U256};
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = access_patterns
+}
+criterion_main!(benches);
+
+/// Number of rows in the [CanonicalHeaders] dataset.
+const ROWS: u64 = 100_000;
+/// Number of addresses in the [PlainStorageState] dataset.
+const DUP_KEYS: u8 = 100;
+/// Number of storage slots of every address in the [PlainStorageState] dataset.
+const DUP_VALUES: u64 = 1_000;
+/// Number of rows read by every range walk and reverse scan.
+const WALK_LENGTH: u64 = 1_000;
+
+/// It benchmarks the table access patterns of the node on the same datasets for every database
+/// backend:
+/// * `sequential_append`: appends [ROWS] sorted rows to an empty table, like the sync stages.
+/// * `random_point_reads`: reads all [ROWS] rows in a random order.
+/// * `dup_insertion`: upserts storage slots in a random order into an empty dupsort table.
+/// * `range_walks`: walks [WALK_LENGTH] rows from random start keys.
+/// * `reverse_scans`: walks back [WALK_LENGTH] rows from the tip, like the unwinds.
+pub fn access_patterns(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Access Patterns");
+    group.sample_size(10);
+
+    measure_backend(&mut group, "mdbx", || {
+        let bench_db_path = Path::new(BENCH_DB_PATH);
+        let _ = fs::remove_dir_all(bench_db_path);
+        Arc::try_unwrap(create_test_rw_db_with_path(bench_db_path)).unwrap().into_inner_db()
+    });
+}
+
+fn measure_backend<DB: Database>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    backend: &str,
+    open: impl Fn() -> DB,
+) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let rows = (0..ROWS).map(|key| (key, B256::from(rng.gen::<[u8; 32]>()))).collect::<Vec<_>>();
+    let mut shuffled_keys = rows.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+    shuffled_keys.shuffle(&mut rng);
+    let mut storage = (0..DUP_KEYS)
+        .flat_map(|address| (0..DUP_VALUES).map(move |slot| (address, slot)))
+        .map(|(address, slot)| {
+            let key = B256::from(U256::from(slot).to_be_bytes::<32>());
+            let entry = StorageEntry { key, value: U256::from(rng.gen::<u64>()) };
+            (Address::with_last_byte(address), entry)
+        })
+        .collect::<Vec<_>>();
+    storage.shuffle(&mut rng);
+    let walk_starts = (0..100).map(|_| rng.gen_range(0..ROWS - WALK_LENGTH)).collect::<Vec<_>>();
+
+    group.bench_function(format!("{backend} | sequential_append | rows: {ROWS}"), |b| {
+        b.iter_with_setup(&open, |db| {
+            let tx = db.tx_mut().expect("tx");
+            let mut cursor = tx.cursor_write::<CanonicalHeaders>().expect("cursor");
+            for (key, value) in &rows {
+                cursor.append(*key, *value).expect("append");
+            }
+            black_box(tx.commit().expect("commit"));
+        })
+    });
+
+    group.bench_function(
+        format!("{backend} | dup_insertion | rows: {}", DUP_KEYS as u64 * DUP_VALUES),
+        |b| {
+            b.iter_with_setup(&open, |db| {
+                let tx = db.tx_mut().expect("tx");
+                let mut cursor = tx.cursor_dup_write::<PlainStorageState>().expect("cursor");
+                for (address, entry) in &storage {
+                    cursor.upsert(*address, *entry).expect("upsert");
+                }
+                black_box(tx.commit().expect("commit"));
+            })
+        },
+    );
+
+    // The read benchmarks share the same preloaded database.
+    let db = open();
+    db.update(|tx| {
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().expect("cursor");
+        for (key, value) in &rows {
+            cursor.append(*key, *value).expect("append");
+        }
+    })
+    .expect("preload");
+
+    group.bench_function(format!("{backend} | random_point_reads | rows: {ROWS}"), |b| {
+        b.iter(|| {
+            let tx = db.tx().expect("tx");
+            for key in &shuffled_keys {
+                black_box(tx.get::<CanonicalHeaders>(*key).expect("get"));
+            }
+        })
+    });
+
+    group.bench_function(
+        format!("{backend} | range_walks | walks: {} | rows: {WALK_LENGTH}", walk_starts.len()),
+        |b| {
+            b.iter(|| {
+                let tx = db.tx().expect("tx");
+                let mut cursor = tx.cursor_read::<CanonicalHeaders>().expect("cursor");
+                for start in &walk_starts {
+                    for row in cursor.walk_range(*start..*start + WALK_LENGTH).expect("walk") {
+                        black_box(row.expect("row"));
+                    }
+                }
+            })
+        },
+    );
+
+    group.bench_function(format!("{backend} | reverse_scans | rows: {WALK_LENGTH}"), |b| {
+        b.iter(|| {
+            let tx = db.tx().expect("tx");
+            let mut cursor = tx.cursor_read::<CanonicalHeaders>().expect("cursor");
+            for row in cursor.walk_back(None).expect("walk").take(WALK_LENGTH as usize) {
+                black_box(row.expect("row"));
+            }
+        })
+    });
+}
+
+include!("./utils.rs");
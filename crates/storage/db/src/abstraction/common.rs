@@ -28,8 +28,12 @@ mod sealed {
 
     impl<DB: Database> Sealed for &DB {}
     impl<DB: Database> Sealed for Arc<DB> {}
+    impl<DB: Database> Sealed for crate::cache::CachedDatabase<DB> {}
     impl Sealed for DatabaseEnv {}
     impl Sealed for DatabaseMock {}
+    impl Sealed for crate::memory::MemoryDatabase {}
+    impl<DB: Database> Sealed for crate::overlay::OverlayDatabase<DB> {}
+    impl<P: Database, S: Database> Sealed for crate::shadow::ShadowDatabase<P, S> {}
 
     #[cfg(any(test, feature = "test-utils"))]
     impl<DB: Database> Sealed for crate::test_utils::TempDatabase<DB> {}
@@ -10,6 +10,12 @@ use crate::{
 };
 
 /// A read-only cursor over table `T`.
+///
+/// # Empty tables
+///
+/// On an empty table, every method that positions the cursor or reads the current entry returns
+/// `Ok(None)`, and every walker yields no entries. The same holds for a cursor that was never
+/// positioned, or whose position was lost.
 pub trait DbCursorRO<T: Table> {
     /// Positions the cursor at the first entry in the table, returning it.
     fn first(&mut self) -> PairResult<T>;
@@ -62,6 +68,9 @@ pub trait DbCursorRO<T: Table> {
 }
 
 /// A read-only cursor over the dup table `T`.
+///
+/// On an empty table, the methods return `Ok(None)` and the walkers yield no entries, as for
+/// [`DbCursorRO`].
 pub trait DbDupCursorRO<T: DupSort> {
     /// Positions the cursor at the next KV pair of the table, returning it.
     fn next_dup(&mut self) -> PairResult<T>;
@@ -98,6 +107,31 @@ pub trait DbDupCursorRO<T: DupSort> {
     ) -> Result<DupWalker<'_, T, Self>, DatabaseError>
     where
         Self: Sized;
+
+    /// Get an iterator that walks through the values of `key` whose subkeys are in the range.
+    fn walk_dup_range(
+        &mut self,
+        key: T::Key,
+        range: impl RangeBounds<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError>
+    where
+        Self: Sized,
+    {
+        let mut walker = match range.start_bound().cloned() {
+            Bound::Included(subkey) => self.walk_dup(Some(key), Some(subkey))?,
+            Bound::Excluded(subkey) => {
+                // Seek to the subkey and skip the values with an equal one.
+                let mut walker = self.walk_dup(Some(key), Some(subkey.clone()))?;
+                while matches!(&walker.start, Some(Ok((_, value))) if T::subkey(value) == subkey) {
+                    walker.start = walker.cursor.next_dup().transpose();
+                }
+                walker
+            }
+            Bound::Unbounded => self.walk_dup(Some(key), None)?,
+        };
+        walker.end_subkey = range.end_bound().cloned();
+        Ok(walker)
+    }
 }
 
 /// Read write cursor over table.
@@ -118,6 +152,46 @@ pub trait DbCursorRW<T: Table> {
 
     /// Delete current value that cursor points to
     fn delete_current(&mut self) -> Result<(), DatabaseError>;
+
+    /// Delete the entry the cursor points to and the `n - 1` entries following it, returning the
+    /// number of deleted entries. Fewer entries are deleted if the table ends first.
+    ///
+    /// The cursor is left at the first entry that's not deleted.
+    fn delete_current_n(&mut self, n: usize) -> Result<usize, DatabaseError>
+    where
+        Self: DbCursorRO<T> + Sized,
+    {
+        let mut remaining = n;
+        self.delete_while(|_, _| {
+            let delete = remaining > 0;
+            remaining = remaining.saturating_sub(1);
+            delete
+        })
+    }
+
+    /// Delete the entry the cursor points to and the entries following it while `predicate`
+    /// returns `true`, returning the number of deleted entries.
+    ///
+    /// The cursor is left at the first entry that's not deleted.
+    fn delete_while(
+        &mut self,
+        mut predicate: impl FnMut(&T::Key, &T::Value) -> bool,
+    ) -> Result<usize, DatabaseError>
+    where
+        Self: DbCursorRO<T> + Sized,
+    {
+        let mut deleted = 0;
+        let mut entry = self.current()?;
+        while let Some((key, value)) = entry {
+            if !predicate(&key, &value) {
+                break
+            }
+            self.delete_current()?;
+            deleted += 1;
+            entry = self.next()?;
+        }
+        Ok(deleted)
+    }
 }
 
 /// Read Write Cursor over DupSorted table.
@@ -335,6 +409,8 @@ pub struct DupWalker<'cursor, T: DupSort, CURSOR: DbDupCursorRO<T>> {
     pub cursor: &'cursor mut CURSOR,
     /// Value where to start the walk.
     pub start: IterPairResult<T>,
+    /// Subkey where to stop the walk.
+    pub end_subkey: Bound<T::SubKey>,
 }
 
 impl<T, CURSOR> fmt::Debug for DupWalker<'_, T, CURSOR>
@@ -346,6 +422,7 @@ where
         f.debug_struct("DupWalker")
             .field("cursor", &self.cursor)
             .field("start", &self.start)
+            .field("end_subkey", &self.end_subkey)
             .finish()
     }
 }
@@ -360,10 +437,19 @@ impl<'cursor, T: DupSort, CURSOR: DbCursorRW<T> + DbDupCursorRO<T>> DupWalker<'c
 impl<'cursor, T: DupSort, CURSOR: DbDupCursorRO<T>> Iterator for DupWalker<'cursor, T, CURSOR> {
     type Item = Result<TableRow<T>, DatabaseError>;
     fn next(&mut self) -> Option<Self::Item> {
-        let start = self.start.take();
-        if start.is_some() {
-            return start
+        let next_item = self.start.take().or_else(|| self.cursor.next_dup().transpose());
+
+        match next_item {
+            Some(Ok((key, value))) => {
+                let subkey = || T::subkey(&value);
+                let in_range = match &self.end_subkey {
+                    Bound::Included(end_subkey) => &subkey() <= end_subkey,
+                    Bound::Excluded(end_subkey) => &subkey() < end_subkey,
+                    Bound::Unbounded => true,
+                };
+                in_range.then_some(Ok((key, value)))
+            }
+            item => item,
         }
-        self.cursor.next_dup().transpose()
     }
 }
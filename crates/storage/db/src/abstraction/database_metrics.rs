@@ -1,3 +1,4 @@
+use crate::{write_profile::WriteProfiler, DatabaseError, Tables};
 use metrics::{counter, gauge, histogram, Label};
 use std::sync::Arc;
 
@@ -46,18 +47,31 @@ impl<DB: DatabaseMetrics> DatabaseMetrics for Arc<DB> {
 pub struct DatabaseMetadataValue {
     /// The freelist size
     freelist_size: Option<usize>,
+    /// The counters of the writes per table, if enabled.
+    write_profiler: Option<Arc<WriteProfiler>>,
 }
 
 impl DatabaseMetadataValue {
     /// Creates a new [DatabaseMetadataValue] with the given freelist size.
     pub fn new(freelist_size: Option<usize>) -> Self {
-        Self { freelist_size }
+        Self { freelist_size, write_profiler: None }
+    }
+
+    /// Sets the counters of the writes per table.
+    pub fn with_write_profiler(mut self, write_profiler: Option<Arc<WriteProfiler>>) -> Self {
+        self.write_profiler = write_profiler;
+        self
     }
 
     /// Returns the freelist size, if available.
     pub fn freelist_size(&self) -> Option<usize> {
         self.freelist_size
     }
+
+    /// Returns the counters of the writes per table, if enabled.
+    pub fn write_profiler(&self) -> Option<&Arc<WriteProfiler>> {
+        self.write_profiler.as_ref()
+    }
 }
 
 /// Includes a method to return a [DatabaseMetadataValue] type, which can be used to dynamically
@@ -72,3 +86,68 @@ impl<DB: DatabaseMetadata> DatabaseMetadata for Arc<DB> {
         <DB as DatabaseMetadata>::metadata(self)
     }
 }
+
+/// Statistics of a database table.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TableStats {
+    /// The number of entries.
+    entries: usize,
+    /// The size in bytes.
+    size: usize,
+    /// The pages, if the database stores tables in pages.
+    pages: Option<TablePages>,
+}
+
+impl TableStats {
+    /// Creates a new [TableStats] with the given number of entries and size in bytes.
+    pub fn new(entries: usize, size: usize) -> Self {
+        Self { entries, size, pages: None }
+    }
+
+    /// Sets the pages of the table.
+    pub fn with_pages(mut self, pages: TablePages) -> Self {
+        self.pages = Some(pages);
+        self
+    }
+
+    /// Returns the number of entries.
+    pub fn entries(&self) -> usize {
+        self.entries
+    }
+
+    /// Returns the size in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the pages, if available.
+    pub fn pages(&self) -> Option<TablePages> {
+        self.pages
+    }
+}
+
+/// The pages of a database table.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TablePages {
+    /// The page size in bytes.
+    pub page_size: usize,
+    /// The number of branch pages.
+    pub branch: usize,
+    /// The number of leaf pages.
+    pub leaf: usize,
+    /// The number of overflow pages.
+    pub overflow: usize,
+}
+
+/// Includes a method to return the [TableStats] of a table, which can be used to inspect the
+/// contents of the database regardless of the backend.
+pub trait DatabaseTableStats {
+    /// Returns the [TableStats] of the table.
+    fn table_stats(&self, table: Tables) -> Result<TableStats, DatabaseError>;
+}
+
+impl<DB: DatabaseTableStats> DatabaseTableStats for Arc<DB> {
+    fn table_stats(&self, table: Tables) -> Result<TableStats, DatabaseError> {
+        <DB as DatabaseTableStats>::table_stats(self, table)
+    }
+}
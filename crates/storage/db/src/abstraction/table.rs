@@ -108,6 +108,37 @@ pub trait DupSort: Table {
     ///
     /// Upstream docs: <https://libmdbx.dqdkfa.ru/usage.html#autotoc_md48>
     type SubKey: Key;
+
+    /// How the table treats a value equal to one already stored for the key.
+    const DUPLICATE_VALUES: DuplicateValues = DuplicateValues::Ignore;
+
+    /// Returns the subkey of the value.
+    ///
+    /// This method is required, so it's a breaking change for `DupSort` implementations outside
+    /// of this crate. They usually implement [`DupSortValue`] for the value and forward to it, as
+    /// the tables defined with the `tables!` macro do.
+    fn subkey(value: &Self::Value) -> Self::SubKey;
+}
+
+/// Value of a [`DupSort`] table. The encoded subkey is the prefix of the compressed value, so the
+/// values of a key are sorted by their subkey.
+pub trait DupSortValue<SubKey> {
+    /// Returns the subkey of the value.
+    fn subkey(&self) -> SubKey;
+}
+
+/// How a `DUPSORT` table treats a value equal to one already stored for the key.
+///
+/// The values of a key are a set, so an equal value is never stored twice. The policy is enforced
+/// by `put`, `upsert` and `append_dup` on all database backends.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateValues {
+    /// Writing an equal value succeeds without changing the table, as in mdbx.
+    #[default]
+    Ignore,
+    /// Writing an equal value fails with a `KEYEXIST` error, for tables where it can only be
+    /// the result of a bug.
+    Reject,
 }
 
 /// Allows duplicating tables across databases
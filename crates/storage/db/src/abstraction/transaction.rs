@@ -1,8 +1,9 @@
 use crate::{
     cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
     table::{DupSort, Table},
-    DatabaseError,
+    DatabaseError, RawKey, RawTable, RawValue, TableViewer, Tables,
 };
+use std::{cell::RefCell, ops::Bound};
 
 /// Read only transaction
 pub trait DbTx: Send + Sync {
@@ -26,9 +27,52 @@ pub trait DbTx: Send + Sync {
     fn entries<T: Table>(&self) -> Result<usize, DatabaseError>;
     /// Disables long-lived read transaction safety guarantees.
     fn disable_long_read_transaction_safety(&mut self);
+
+    /// Get the encoded value by the encoded key, without decoding either of them.
+    ///
+    /// For dupsort tables, this returns the first value of the key, same as [DbTx::get].
+    fn get_by_encoded_key(
+        &self,
+        table: Tables,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, DatabaseError> {
+        table.view(&GetByEncodedKey { tx: self, key })
+    }
+
+    /// Walk the entries with encoded keys in the `[start, end)` range in the order of the table,
+    /// passing the encoded keys and values to `f` until it returns `false`. An unset bound leaves
+    /// the range unbounded on that side.
+    ///
+    /// For dupsort tables, each value of a key is a separate entry. Returns the number of entries
+    /// passed to `f`.
+    fn walk_by_encoded_key<F>(
+        &self,
+        table: Tables,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        f: F,
+    ) -> Result<usize, DatabaseError>
+    where
+        F: FnMut(&[u8], &[u8]) -> bool,
+    {
+        table.view(&WalkByEncodedKey { tx: self, start, end, f: RefCell::new(f) })
+    }
 }
 
 /// Read write transaction that allows writing to database
+///
+/// # Read-your-writes
+///
+/// Reads in a read-write transaction observe every write made earlier in the same transaction,
+/// whichever method made it: [DbTx::get] and the cursors of the transaction see the entries
+/// written through [DbTxMut::put], [DbTxMut::delete], their encoded key variants and the write
+/// cursors of the transaction.
+///
+/// This also holds for cursors and walkers that are positioned when the write is made. A cursor
+/// stays on its current entry, and the next step moves to the entry that follows it in the table
+/// at the time of the step: entries put after the position are yielded, and entries deleted after
+/// it are skipped. If the current entry itself is deleted through another cursor, the next step
+/// moves to the entry that followed it.
 pub trait DbTxMut: Send + Sync {
     /// Read-Write Cursor type
     type CursorMut<T: Table>: DbCursorRW<T> + DbCursorRO<T> + Send + Sync;
@@ -45,6 +89,29 @@ pub trait DbTxMut: Send + Sync {
     /// Delete value from database
     fn delete<T: Table>(&self, key: T::Key, value: Option<T::Value>)
         -> Result<bool, DatabaseError>;
+    /// Put the encoded value by the encoded key, without decoding either of them.
+    ///
+    /// For dupsort tables, the value is added to the values of the key, same as [DbTxMut::put].
+    /// To replace a value, delete it with [DbTxMut::delete_by_encoded_key] first.
+    fn put_by_encoded_key(
+        &self,
+        table: Tables,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), DatabaseError> {
+        table.view(&PutByEncodedKey { tx: self, key, value })
+    }
+    /// Delete the encoded value by the encoded key, without decoding either of them.
+    ///
+    /// For dupsort tables, `value` selects the value to delete, same as [DbTxMut::delete].
+    fn delete_by_encoded_key(
+        &self,
+        table: Tables,
+        key: &[u8],
+        value: Option<&[u8]>,
+    ) -> Result<bool, DatabaseError> {
+        table.view(&DeleteByEncodedKey { tx: self, key, value })
+    }
     /// Clears database.
     fn clear<T: Table>(&self) -> Result<(), DatabaseError>;
     /// Cursor mut
@@ -52,3 +119,89 @@ pub trait DbTxMut: Send + Sync {
     /// DupCursor mut.
     fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError>;
 }
+
+/// Gets the value by the encoded key from the [RawTable] of the viewed table.
+struct GetByEncodedKey<'a, TX: ?Sized> {
+    tx: &'a TX,
+    key: &'a [u8],
+}
+
+impl<TX: DbTx + ?Sized> TableViewer<Option<Vec<u8>>> for GetByEncodedKey<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<Option<Vec<u8>>, Self::Error> {
+        let value = self.tx.get::<RawTable<T>>(RawKey::from_vec(self.key.to_vec()))?;
+        Ok(value.map(RawValue::into_value))
+    }
+}
+
+/// Walks the entries of the [RawTable] of the viewed table in the range of encoded keys.
+struct WalkByEncodedKey<'a, TX: ?Sized, F> {
+    tx: &'a TX,
+    start: Option<&'a [u8]>,
+    end: Option<&'a [u8]>,
+    f: RefCell<F>,
+}
+
+impl<TX, F> TableViewer<usize> for WalkByEncodedKey<'_, TX, F>
+where
+    TX: DbTx + ?Sized,
+    F: FnMut(&[u8], &[u8]) -> bool,
+{
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<usize, Self::Error> {
+        let key = |key: &[u8]| RawKey::<T::Key>::from_vec(key.to_vec());
+        let range = (
+            self.start.map_or(Bound::Unbounded, |start| Bound::Included(key(start))),
+            self.end.map_or(Bound::Unbounded, |end| Bound::Excluded(key(end))),
+        );
+
+        let mut f = self.f.borrow_mut();
+        let mut walked = 0;
+        for entry in self.tx.cursor_read::<RawTable<T>>()?.walk_range(range)? {
+            let (key, value) = entry?;
+            walked += 1;
+            if !f(key.raw_key(), value.raw_value()) {
+                break
+            }
+        }
+        Ok(walked)
+    }
+}
+
+/// Puts the encoded value by the encoded key to the [RawTable] of the viewed table.
+struct PutByEncodedKey<'a, TX: ?Sized> {
+    tx: &'a TX,
+    key: &'a [u8],
+    value: &'a [u8],
+}
+
+impl<TX: DbTxMut + ?Sized> TableViewer<()> for PutByEncodedKey<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        self.tx.put::<RawTable<T>>(
+            RawKey::from_vec(self.key.to_vec()),
+            RawValue::from_vec(self.value.to_vec()),
+        )
+    }
+}
+
+/// Deletes the encoded value by the encoded key from the [RawTable] of the viewed table.
+struct DeleteByEncodedKey<'a, TX: ?Sized> {
+    tx: &'a TX,
+    key: &'a [u8],
+    value: Option<&'a [u8]>,
+}
+
+impl<TX: DbTxMut + ?Sized> TableViewer<bool> for DeleteByEncodedKey<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<bool, Self::Error> {
+        self.tx.delete::<RawTable<T>>(
+            RawKey::from_vec(self.key.to_vec()),
+            self.value.map(|value| RawValue::from_vec(value.to_vec())),
+        )
+    }
+}
@@ -0,0 +1,299 @@
+use super::{is_cached, is_cached_duplicate, StateCache, WrittenKeys};
+use crate::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    table::{Compress, Decode, Decompress, DupSort, Encode, Table},
+    DatabaseError,
+};
+use parking_lot::Mutex;
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+
+/// Cursor of the [CachedDatabase](super::CachedDatabase).
+///
+/// A seek by key and subkey that is served from the cache doesn't move the inner cursor. The seek
+/// is executed against the inner cursor only before the next operation that depends on the
+/// cursor position.
+pub struct CachedCursor<T, C> {
+    /// The inner database cursor.
+    inner: C,
+    /// The cache shared by all transactions.
+    cache: Arc<StateCache>,
+    /// The generation of the cache the read-only transaction was opened at.
+    /// If [None], the reads aren't served from the cache.
+    generation: Option<u64>,
+    /// The keys written by the read-write transaction.
+    /// If [None], the writes aren't recorded.
+    written: Option<Arc<Mutex<WrittenKeys>>>,
+    /// The seek served from the cache that wasn't executed against the inner cursor yet.
+    pending_seek: Option<PendingSeek<C>>,
+    _table: PhantomData<T>,
+}
+
+/// Seek by the encoded key and subkey that is pending on the inner cursor.
+struct PendingSeek<C> {
+    key: Vec<u8>,
+    subkey: Vec<u8>,
+    seek: fn(&mut C, &[u8], &[u8]) -> Result<(), DatabaseError>,
+}
+
+impl<T, C: fmt::Debug> fmt::Debug for CachedCursor<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedCursor")
+            .field("inner", &self.inner)
+            .field("generation", &self.generation)
+            .field("pending_seek", &self.pending_seek.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Table, C> CachedCursor<T, C> {
+    pub(crate) fn new(
+        inner: C,
+        cache: Arc<StateCache>,
+        generation: Option<u64>,
+        written: Option<Arc<Mutex<WrittenKeys>>>,
+    ) -> Self {
+        Self { inner, cache, generation, written, pending_seek: None, _table: PhantomData }
+    }
+
+    /// Execute the pending seek against the inner cursor, if any.
+    fn restore_position(&mut self) -> Result<(), DatabaseError> {
+        if let Some(PendingSeek { key, subkey, seek }) = self.pending_seek.take() {
+            seek(&mut self.inner, &key, &subkey)?;
+        }
+        Ok(())
+    }
+
+    /// Record the write of the key if the table is cached.
+    fn record_write(&self, key: &T::Key) {
+        if let Some(written) = self.written.as_ref().filter(|_| is_cached::<T>()) {
+            written.lock().insert::<T>(key.clone());
+        }
+    }
+}
+
+/// Seek the cursor by the encoded key and subkey.
+fn seek_encoded<T, C>(cursor: &mut C, key: &[u8], subkey: &[u8]) -> Result<(), DatabaseError>
+where
+    T: DupSort,
+    C: DbDupCursorRO<T>,
+{
+    cursor.seek_by_key_subkey(T::Key::decode(key)?, T::SubKey::decode(subkey)?)?;
+    Ok(())
+}
+
+impl<T, C> DbCursorRO<T> for CachedCursor<T, C>
+where
+    T: Table,
+    C: DbCursorRO<T>,
+{
+    fn first(&mut self) -> PairResult<T> {
+        self.pending_seek = None;
+        self.inner.first()
+    }
+
+    fn seek_exact(&mut self, key: T::Key) -> PairResult<T> {
+        self.pending_seek = None;
+        self.inner.seek_exact(key)
+    }
+
+    fn seek(&mut self, key: T::Key) -> PairResult<T> {
+        self.pending_seek = None;
+        self.inner.seek(key)
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        self.restore_position()?;
+        self.inner.next()
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        self.restore_position()?;
+        self.inner.prev()
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        self.pending_seek = None;
+        self.inner.last()
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        self.restore_position()?;
+        self.inner.current()
+    }
+
+    fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
+        let start = match start_key {
+            Some(start_key) => self.seek(start_key),
+            None => self.first(),
+        }
+        .transpose();
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range(
+        &mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start = match start_key {
+            Some(start_key) => self.seek(start_key),
+            None => self.last(),
+        }
+        .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<T, C> DbDupCursorRO<T> for CachedCursor<T, C>
+where
+    T: DupSort,
+    C: DbDupCursorRO<T> + DbCursorRO<T>,
+{
+    fn next_dup(&mut self) -> PairResult<T> {
+        self.restore_position()?;
+        self.inner.next_dup()
+    }
+
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        self.restore_position()?;
+        self.inner.next_no_dup()
+    }
+
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        self.restore_position()?;
+        self.inner.next_dup_val()
+    }
+
+    fn seek_by_key_subkey(&mut self, key: T::Key, subkey: T::SubKey) -> ValueOnlyResult<T> {
+        self.pending_seek = None;
+        let Some(generation) = self.generation.filter(|_| is_cached_duplicate::<T>()) else {
+            return self.inner.seek_by_key_subkey(key, subkey)
+        };
+
+        let cache_key = (T::NAME, key.clone().encode().into());
+        let encoded_subkey: Vec<u8> = subkey.clone().encode().into();
+        if let Some(value) = self.cache.get_duplicate(generation, &cache_key, &encoded_subkey) {
+            self.pending_seek = Some(PendingSeek {
+                key: cache_key.1,
+                subkey: encoded_subkey,
+                seek: seek_encoded::<T, C>,
+            });
+            return Ok(Some(T::Value::decompress_owned(value)?))
+        }
+
+        // The seek returns the first value with the subkey equal or greater than the requested
+        // one, only the exact matches are cached.
+        let Some(value) = self.inner.seek_by_key_subkey(key, subkey.clone())? else {
+            return Ok(None)
+        };
+        if T::subkey(&value) != subkey {
+            return Ok(Some(value))
+        }
+        let value: Vec<u8> = value.compress().into();
+        let result = T::Value::decompress(&value)?;
+        self.cache.insert_duplicate(generation, cache_key, encoded_subkey, value);
+        Ok(Some(result))
+    }
+
+    fn walk_dup(
+        &mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        let start = match (key, subkey) {
+            (Some(key), Some(subkey)) => self
+                .seek_by_key_subkey(key.clone(), subkey)
+                .map(|value| value.map(|value| (key, value)))
+                .transpose(),
+            (Some(key), None) => self.seek_exact(key).transpose(),
+            (None, Some(subkey)) => match self.first()? {
+                Some((key, _)) => self
+                    .seek_by_key_subkey(key.clone(), subkey)
+                    .map(|value| value.map(|value| (key, value)))
+                    .transpose(),
+                None => None,
+            },
+            (None, None) => self.first().transpose(),
+        };
+
+        Ok(DupWalker { cursor: self, start, end_subkey: Bound::Unbounded })
+    }
+}
+
+impl<T, C> DbCursorRW<T> for CachedCursor<T, C>
+where
+    T: Table,
+    C: DbCursorRW<T> + DbCursorRO<T>,
+{
+    fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.record_write(&key);
+        self.inner.upsert(key, value)
+    }
+
+    fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.record_write(&key);
+        self.inner.insert(key, value)
+    }
+
+    fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.record_write(&key);
+        self.inner.append(key, value)
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        self.restore_position()?;
+        if self.written.is_some() && is_cached::<T>() {
+            if let Some((key, _)) = self.inner.current()? {
+                self.record_write(&key);
+            }
+        }
+        self.inner.delete_current()
+    }
+}
+
+impl<T, C> DbDupCursorRW<T> for CachedCursor<T, C>
+where
+    T: DupSort,
+    C: DbDupCursorRW<T> + DbCursorRO<T>,
+{
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        self.restore_position()?;
+        if self.written.is_some() && is_cached::<T>() {
+            if let Some((key, _)) = self.inner.current()? {
+                self.record_write(&key);
+            }
+        }
+        self.inner.delete_current_duplicates()
+    }
+
+    fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.record_write(&key);
+        self.inner.append_dup(key, value)
+    }
+}
@@ -0,0 +1,396 @@
+//! Database that caches the point lookups of the state tables in memory.
+//!
+//! Block execution reads the same accounts, storage slots and bytecodes over and over across
+//! consecutive blocks. The cached database serves these reads from a sharded in-memory LRU cache,
+//! and invalidates the entries of the keys written by a read-write transaction when it's
+//! committed.
+
+use crate::{
+    database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
+    table::{Encode, Table},
+    DatabaseError, Tables,
+};
+use metrics::Label;
+use parking_lot::Mutex;
+use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
+use schnellru::{ByLength, LruMap};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+mod cursor;
+pub use cursor::CachedCursor;
+
+mod tx;
+pub use tx::CachedTx;
+
+/// The number of shards of the cache. Every shard is guarded by its own lock.
+const SHARDS: usize = 16;
+
+/// The maximum number of cached duplicate values of a single key.
+const MAX_CACHED_DUPLICATES: usize = 1024;
+
+/// Database that caches the point lookups of [PlainAccountState](crate::tables::PlainAccountState),
+/// [PlainStorageState](crate::tables::PlainStorageState) and [Bytecodes](crate::tables::Bytecodes)
+/// in memory.
+///
+/// Only read-only transactions are served from the cache, read-write transactions always read
+/// from the inner database to observe their own writes. The keys written by a read-write
+/// transaction are removed from the cache when the transaction is committed, and read-only
+/// transactions opened before the commit stop using the cache.
+#[derive(Debug)]
+pub struct CachedDatabase<DB> {
+    /// The inner database.
+    inner: DB,
+    /// The cache shared by all transactions.
+    cache: Arc<StateCache>,
+}
+
+impl<DB> CachedDatabase<DB> {
+    /// Create new cached database holding at most `capacity` keys.
+    /// If zero, the cache is disabled.
+    pub fn new(inner: DB, capacity: u32) -> Self {
+        Self { inner, cache: Arc::new(StateCache::new(capacity)) }
+    }
+
+    /// Returns the inner database.
+    pub fn inner(&self) -> &DB {
+        &self.inner
+    }
+
+    /// Returns the number of reads served from the cache so far.
+    pub fn hits(&self) -> u64 {
+        self.cache.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of cacheable reads served from the inner database so far.
+    pub fn misses(&self) -> u64 {
+        self.cache.misses.load(Ordering::Relaxed)
+    }
+}
+
+impl<DB: Database> Database for CachedDatabase<DB> {
+    type TX = CachedTx<DB::TX>;
+    type TXMut = CachedTx<DB::TXMut>;
+
+    fn tx(&self) -> Result<Self::TX, DatabaseError> {
+        // The generation must be read before the transaction is opened, so that the commits
+        // which aren't visible to the transaction are guaranteed to change it.
+        let generation = self.cache.begin_read();
+        Ok(CachedTx::new(self.inner.tx()?, self.cache.clone(), generation, None))
+    }
+
+    fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
+        let written = self.cache.is_enabled().then(Default::default);
+        Ok(CachedTx::new(self.inner.tx_mut()?, self.cache.clone(), None, written))
+    }
+}
+
+impl<DB: DatabaseMetrics> DatabaseMetrics for CachedDatabase<DB> {
+    fn report_metrics(&self) {
+        self.inner.report_metrics();
+        metrics::gauge!("db.cache.hits", self.hits() as f64);
+        metrics::gauge!("db.cache.misses", self.misses() as f64);
+    }
+
+    fn gauge_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
+        let mut metrics = self.inner.gauge_metrics();
+        metrics.push(("db.cache.hits", self.hits() as f64, vec![]));
+        metrics.push(("db.cache.misses", self.misses() as f64, vec![]));
+        metrics
+    }
+}
+
+impl<DB: DatabaseMetadata> DatabaseMetadata for CachedDatabase<DB> {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        self.inner.metadata()
+    }
+}
+
+/// Returns `true` if the values of the table are cached by key.
+fn is_cached_value<T: Table>() -> bool {
+    matches!(T::TABLE, Tables::PlainAccountState | Tables::Bytecodes)
+}
+
+/// Returns `true` if the duplicate values of the table are cached by key and subkey.
+fn is_cached_duplicate<T: Table>() -> bool {
+    matches!(T::TABLE, Tables::PlainStorageState)
+}
+
+/// Returns `true` if the writes to the table must invalidate the cache.
+fn is_cached<T: Table>() -> bool {
+    is_cached_value::<T>() || is_cached_duplicate::<T>()
+}
+
+/// Key of the cache entry: the table name and the encoded key.
+type CacheKey = (&'static str, Vec<u8>);
+
+/// Cache entry holding the compressed values of a key.
+#[derive(Debug)]
+enum CacheEntry {
+    /// The value of the key, or [None] if the key doesn't exist.
+    Value(Option<Vec<u8>>),
+    /// The duplicate values of the key by encoded subkey.
+    Duplicates(FxHashMap<Vec<u8>, Vec<u8>>),
+}
+
+/// Keys written by a read-write transaction.
+#[derive(Debug, Default)]
+pub(crate) struct WrittenKeys {
+    /// The written keys of the cached tables.
+    keys: FxHashSet<CacheKey>,
+    /// Whether one of the cached tables was cleared.
+    cleared: bool,
+}
+
+impl WrittenKeys {
+    /// Record the write of the key to the table.
+    pub(crate) fn insert<T: Table>(&mut self, key: T::Key) {
+        self.keys.insert((T::NAME, key.encode().into()));
+    }
+
+    /// Record the clear of a cached table.
+    pub(crate) fn clear(&mut self) {
+        self.cleared = true;
+    }
+
+    /// Returns `true` if nothing was written to the cached tables.
+    fn is_empty(&self) -> bool {
+        self.keys.is_empty() && !self.cleared
+    }
+}
+
+/// Sharded LRU cache of the state tables.
+///
+/// The generation is incremented before and after every commit that invalidates the cache, so
+/// it's odd while such a commit is in progress. Read-only transactions remember the generation
+/// they were opened at, and read from or insert into the cache only as long as it's unchanged.
+pub(crate) struct StateCache {
+    /// The shards of the cache. Empty if the cache is disabled.
+    shards: Box<[Mutex<LruMap<CacheKey, CacheEntry, ByLength>>]>,
+    /// The generation of the cache.
+    generation: AtomicU64,
+    /// The number of reads served from the cache.
+    hits: AtomicU64,
+    /// The number of cacheable reads served from the inner database.
+    misses: AtomicU64,
+}
+
+impl fmt::Debug for StateCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateCache")
+            .field("shards", &self.shards.len())
+            .field("generation", &self.generation)
+            .field("hits", &self.hits)
+            .field("misses", &self.misses)
+            .finish()
+    }
+}
+
+impl StateCache {
+    fn new(capacity: u32) -> Self {
+        let shards = if capacity == 0 {
+            Vec::new()
+        } else {
+            let shard_capacity = (capacity / SHARDS as u32).max(1);
+            (0..SHARDS).map(|_| Mutex::new(LruMap::new(ByLength::new(shard_capacity)))).collect()
+        };
+        Self {
+            shards: shards.into_boxed_slice(),
+            generation: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns `true` if the cache is enabled.
+    fn is_enabled(&self) -> bool {
+        !self.shards.is_empty()
+    }
+
+    /// Returns the generation for a new read-only transaction, or [None] if the transaction
+    /// can't use the cache.
+    fn begin_read(&self) -> Option<u64> {
+        let generation = self.generation.load(Ordering::SeqCst);
+        (self.is_enabled() && generation % 2 == 0).then_some(generation)
+    }
+
+    fn shard(&self, key: &CacheKey) -> &Mutex<LruMap<CacheKey, CacheEntry, ByLength>> {
+        let mut hasher = FxHasher::default();
+        key.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+
+    fn record_lookup(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the cached value of the key, or [None] if it's not cached.
+    pub(crate) fn get_value(&self, generation: u64, key: &CacheKey) -> Option<Option<Vec<u8>>> {
+        let mut shard = self.shard(key).lock();
+        if self.generation.load(Ordering::SeqCst) != generation {
+            return None
+        }
+        let value = match shard.get(key) {
+            Some(CacheEntry::Value(value)) => Some(value.clone()),
+            _ => None,
+        };
+        self.record_lookup(value.is_some());
+        value
+    }
+
+    /// Returns the cached duplicate value of the key and subkey, or [None] if it's not cached.
+    pub(crate) fn get_duplicate(
+        &self,
+        generation: u64,
+        key: &CacheKey,
+        subkey: &[u8],
+    ) -> Option<Vec<u8>> {
+        let mut shard = self.shard(key).lock();
+        if self.generation.load(Ordering::SeqCst) != generation {
+            return None
+        }
+        let value = match shard.get(key) {
+            Some(CacheEntry::Duplicates(values)) => values.get(subkey).cloned(),
+            _ => None,
+        };
+        self.record_lookup(value.is_some());
+        value
+    }
+
+    /// Cache the value of the key read at the generation.
+    pub(crate) fn insert_value(&self, generation: u64, key: CacheKey, value: Option<Vec<u8>>) {
+        let mut shard = self.shard(&key).lock();
+        if self.generation.load(Ordering::SeqCst) == generation {
+            shard.insert(key, CacheEntry::Value(value));
+        }
+    }
+
+    /// Cache the duplicate value of the key and subkey read at the generation.
+    pub(crate) fn insert_duplicate(
+        &self,
+        generation: u64,
+        key: CacheKey,
+        subkey: Vec<u8>,
+        value: Vec<u8>,
+    ) {
+        let mut shard = self.shard(&key).lock();
+        if self.generation.load(Ordering::SeqCst) != generation {
+            return
+        }
+        match shard.get(&key) {
+            Some(CacheEntry::Duplicates(values)) => {
+                if values.len() < MAX_CACHED_DUPLICATES {
+                    values.insert(subkey, value);
+                }
+            }
+            _ => {
+                shard.insert(key, CacheEntry::Duplicates(FxHashMap::from_iter([(subkey, value)])));
+            }
+        }
+    }
+
+    /// Execute the commit and remove the written keys from the cache.
+    pub(crate) fn commit<R>(&self, written: WrittenKeys, commit: impl FnOnce() -> R) -> R {
+        if written.is_empty() {
+            return commit()
+        }
+
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        let result = commit();
+        if written.cleared {
+            for shard in self.shards.iter() {
+                shard.lock().clear();
+            }
+        } else {
+            for key in written.keys {
+                self.shard(&key).lock().remove(&key);
+            }
+        }
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cursor::{DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+        tables,
+        test_utils::create_test_rw_db,
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{Account, Address, StorageEntry, B256, U256};
+
+    #[test]
+    fn reads_are_cached_and_invalidated_on_commit() {
+        let db = CachedDatabase::new(create_test_rw_db(), 1024);
+
+        let address = Address::with_last_byte(1);
+        let account = Account { nonce: 1, ..Default::default() };
+        let entry = StorageEntry { key: B256::with_last_byte(2), value: U256::from(3) };
+        let next_entry = StorageEntry { key: B256::with_last_byte(3), value: U256::from(4) };
+        {
+            let tx = db.tx_mut().unwrap();
+            tx.put::<tables::PlainAccountState>(address, account).unwrap();
+            let mut cursor = tx.cursor_dup_write::<tables::PlainStorageState>().unwrap();
+            cursor.upsert(address, entry).unwrap();
+            cursor.upsert(address, next_entry).unwrap();
+            tx.commit().unwrap();
+        }
+
+        for _ in 0..2 {
+            let tx = db.tx().unwrap();
+            assert_eq!(tx.get::<tables::PlainAccountState>(address).unwrap(), Some(account));
+            let mut cursor = tx.cursor_dup_read::<tables::PlainStorageState>().unwrap();
+            assert_eq!(cursor.seek_by_key_subkey(address, entry.key).unwrap(), Some(entry));
+            // The cursor is positioned at the seeked entry even if it was served from the cache.
+            assert_eq!(cursor.next_dup().unwrap(), Some((address, next_entry)));
+        }
+        assert_eq!((db.hits(), db.misses()), (2, 2));
+
+        let updated_account = Account { nonce: 2, ..account };
+        let updated_entry = StorageEntry { value: U256::from(5), ..entry };
+        {
+            let tx = db.tx_mut().unwrap();
+            tx.put::<tables::PlainAccountState>(address, updated_account).unwrap();
+            let mut cursor = tx.cursor_dup_write::<tables::PlainStorageState>().unwrap();
+            cursor.seek_by_key_subkey(address, entry.key).unwrap();
+            cursor.delete_current().unwrap();
+            cursor.upsert(address, updated_entry).unwrap();
+            tx.commit().unwrap();
+        }
+
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.get::<tables::PlainAccountState>(address).unwrap(), Some(updated_account));
+        let mut cursor = tx.cursor_dup_read::<tables::PlainStorageState>().unwrap();
+        assert_eq!(cursor.seek_by_key_subkey(address, entry.key).unwrap(), Some(updated_entry));
+    }
+
+    #[test]
+    fn transaction_opened_before_commit_bypasses_cache() {
+        let db = CachedDatabase::new(create_test_rw_db(), 1024);
+
+        let address = Address::with_last_byte(1);
+        let account = Account { nonce: 1, ..Default::default() };
+        let stale_tx = db.tx().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::PlainAccountState>(address, account).unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.get::<tables::PlainAccountState>(address).unwrap(), Some(account));
+        assert_eq!(stale_tx.get::<tables::PlainAccountState>(address).unwrap(), None);
+        assert_eq!(tx.get::<tables::PlainAccountState>(address).unwrap(), Some(account));
+        assert_eq!((db.hits(), db.misses()), (1, 1));
+    }
+}
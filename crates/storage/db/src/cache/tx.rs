@@ -0,0 +1,134 @@
+use super::{is_cached, is_cached_value, CachedCursor, StateCache, WrittenKeys};
+use crate::{
+    table::{Compress, Decompress, DupSort, Encode, Table, TableImporter},
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use parking_lot::Mutex;
+use std::{mem, sync::Arc};
+
+/// Transaction of the [CachedDatabase](super::CachedDatabase).
+#[derive(Debug)]
+pub struct CachedTx<TX> {
+    /// The inner database transaction.
+    inner: TX,
+    /// The cache shared by all transactions.
+    cache: Arc<StateCache>,
+    /// The generation of the cache the read-only transaction was opened at.
+    /// If [None], the reads aren't served from the cache.
+    generation: Option<u64>,
+    /// The keys written by the read-write transaction, shared with its cursors.
+    /// If [None], the writes aren't recorded.
+    written: Option<Arc<Mutex<WrittenKeys>>>,
+}
+
+impl<TX> CachedTx<TX> {
+    pub(crate) fn new(
+        inner: TX,
+        cache: Arc<StateCache>,
+        generation: Option<u64>,
+        written: Option<Arc<Mutex<WrittenKeys>>>,
+    ) -> Self {
+        Self { inner, cache, generation, written }
+    }
+
+    /// Returns the inner database transaction.
+    pub fn inner(&self) -> &TX {
+        &self.inner
+    }
+
+    fn cursor<T: Table, C>(&self, cursor: C) -> CachedCursor<T, C> {
+        CachedCursor::new(cursor, self.cache.clone(), self.generation, self.written.clone())
+    }
+
+    /// Record the write of the key if the table is cached.
+    fn record_write<T: Table>(&self, key: &T::Key) {
+        if let Some(written) = self.written.as_ref().filter(|_| is_cached::<T>()) {
+            written.lock().insert::<T>(key.clone());
+        }
+    }
+}
+
+impl<TX: DbTx> DbTx for CachedTx<TX> {
+    type Cursor<T: Table> = CachedCursor<T, TX::Cursor<T>>;
+    type DupCursor<T: DupSort> = CachedCursor<T, TX::DupCursor<T>>;
+
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        let Some(generation) = self.generation.filter(|_| is_cached_value::<T>()) else {
+            return self.inner.get::<T>(key)
+        };
+
+        let cache_key = (T::NAME, key.clone().encode().into());
+        if let Some(value) = self.cache.get_value(generation, &cache_key) {
+            return value.map(T::Value::decompress_owned).transpose()
+        }
+
+        let value: Option<Vec<u8>> = self.inner.get::<T>(key)?.map(|value| value.compress().into());
+        let result = value.as_deref().map(T::Value::decompress).transpose()?;
+        self.cache.insert_value(generation, cache_key, value);
+        Ok(result)
+    }
+
+    fn commit(self) -> Result<bool, DatabaseError> {
+        let Some(written) = self.written else { return self.inner.commit() };
+        let written = mem::take(&mut *written.lock());
+        let inner = self.inner;
+        self.cache.commit(written, || inner.commit())
+    }
+
+    fn abort(self) {
+        self.inner.abort()
+    }
+
+    fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        Ok(self.cursor(self.inner.cursor_read()?))
+    }
+
+    fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        Ok(self.cursor(self.inner.cursor_dup_read()?))
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        self.inner.entries::<T>()
+    }
+
+    fn disable_long_read_transaction_safety(&mut self) {
+        self.inner.disable_long_read_transaction_safety()
+    }
+}
+
+impl<TX: DbTxMut + DbTx> DbTxMut for CachedTx<TX> {
+    type CursorMut<T: Table> = CachedCursor<T, TX::CursorMut<T>>;
+    type DupCursorMut<T: DupSort> = CachedCursor<T, TX::DupCursorMut<T>>;
+
+    fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.record_write::<T>(&key);
+        self.inner.put::<T>(key, value)
+    }
+
+    fn delete<T: Table>(
+        &self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        self.record_write::<T>(&key);
+        self.inner.delete::<T>(key, value)
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        if let Some(written) = self.written.as_ref().filter(|_| is_cached::<T>()) {
+            written.lock().clear();
+        }
+        self.inner.clear::<T>()
+    }
+
+    fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
+        Ok(self.cursor(self.inner.cursor_write()?))
+    }
+
+    fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
+        Ok(self.cursor(self.inner.cursor_dup_write()?))
+    }
+}
+
+impl<TX: DbTxMut + DbTx> TableImporter for CachedTx<TX> {}
@@ -0,0 +1,278 @@
+//! Writer splitting a large write into chunks committed in separate transactions.
+//!
+//! A read-write transaction with millions of writes holds all of its dirty pages until it's
+//! committed, and may hit the transaction size limit of the database. The [ChunkedTxWriter] commits
+//! the transaction and opens a new one whenever the written operations or bytes reach the
+//! configured thresholds. Every chunk is committed atomically, but the write as a whole is not: if
+//! it's interrupted, the chunks committed before the interruption stay in the database.
+
+use crate::{
+    database::Database,
+    table::Table,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError, RawKey, RawTable, RawValue,
+};
+use reth_tracing::tracing::debug;
+
+/// Progress of a [ChunkedTxWriter], counting the committed chunks and the writes in them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkedTxProgress {
+    /// The number of committed chunks.
+    pub chunks: u64,
+    /// The number of puts and deletes in the committed chunks.
+    pub operations: u64,
+    /// The total encoded size of the keys and values put in the committed chunks.
+    pub bytes: u64,
+}
+
+/// Writes to the database in chunks, committing the transaction and opening a new one every
+/// `max_operations` puts and deletes or `max_bytes` put bytes, whichever is reached first.
+///
+/// Writes that must be committed together are grouped with [ChunkedTxWriter::atomic]. Reads
+/// through [ChunkedTxWriter::tx] observe the writes of the current chunk and of the committed ones.
+///
+/// The last chunk is committed by [ChunkedTxWriter::commit]. If the writer is dropped instead, the
+/// last chunk is aborted.
+pub struct ChunkedTxWriter<'a, DB: Database> {
+    db: &'a DB,
+    /// The transaction of the current chunk, opened on the first write after a commit.
+    tx: Option<DB::TXMut>,
+    max_operations: Option<u64>,
+    max_bytes: Option<u64>,
+    /// Writes of the current chunk.
+    chunk_operations: u64,
+    chunk_bytes: u64,
+    /// Depth of the nested [ChunkedTxWriter::atomic] groups, chunks are not committed inside them.
+    atomic_depth: usize,
+    progress: ChunkedTxProgress,
+    on_commit: Option<Box<dyn FnMut(&ChunkedTxProgress) + 'a>>,
+}
+
+impl<DB: Database> std::fmt::Debug for ChunkedTxWriter<'_, DB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkedTxWriter")
+            .field("tx", &self.tx)
+            .field("max_operations", &self.max_operations)
+            .field("max_bytes", &self.max_bytes)
+            .field("chunk_operations", &self.chunk_operations)
+            .field("chunk_bytes", &self.chunk_bytes)
+            .field("atomic_depth", &self.atomic_depth)
+            .field("progress", &self.progress)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, DB: Database> ChunkedTxWriter<'a, DB> {
+    /// Opens the transaction of the first chunk.
+    ///
+    /// Without thresholds, all writes are committed in a single chunk.
+    pub fn new(db: &'a DB) -> Result<Self, DatabaseError> {
+        Ok(Self {
+            db,
+            tx: Some(db.tx_mut()?),
+            max_operations: None,
+            max_bytes: None,
+            chunk_operations: 0,
+            chunk_bytes: 0,
+            atomic_depth: 0,
+            progress: ChunkedTxProgress::default(),
+            on_commit: None,
+        })
+    }
+
+    /// Commits a chunk once it has `max_operations` puts and deletes.
+    pub fn with_max_operations(mut self, max_operations: u64) -> Self {
+        self.max_operations = Some(max_operations);
+        self
+    }
+
+    /// Commits a chunk once the keys and values put in it have `max_bytes` total encoded size.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Calls `on_commit` with the progress of the writer after every committed chunk.
+    pub fn with_on_commit(mut self, on_commit: impl FnMut(&ChunkedTxProgress) + 'a) -> Self {
+        self.on_commit = Some(Box::new(on_commit));
+        self
+    }
+
+    /// Returns the progress of the writer.
+    pub fn progress(&self) -> ChunkedTxProgress {
+        self.progress
+    }
+
+    /// Returns the transaction of the current chunk.
+    ///
+    /// Writes made directly to the transaction are not counted towards the thresholds, see
+    /// [ChunkedTxWriter::record].
+    pub fn tx(&mut self) -> Result<&DB::TXMut, DatabaseError> {
+        match self.tx {
+            Some(ref tx) => Ok(tx),
+            None => Ok(self.tx.insert(self.db.tx_mut()?)),
+        }
+    }
+
+    /// Puts the value for the key, committing the chunk if it's full.
+    pub fn put<T: Table>(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let (key, value) = (RawKey::<T::Key>::new(key), RawValue::<T::Value>::new(value));
+        let bytes = key.raw_key().len() + value.raw_value().len();
+        self.tx()?.put::<RawTable<T>>(key, value)?;
+        self.record(1, bytes as u64)
+    }
+
+    /// Deletes the key, or the value of the key in dupsort tables, committing the chunk if it's
+    /// full. Returns `true` if the entry existed.
+    pub fn delete<T: Table>(
+        &mut self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        let deleted = self.tx()?.delete::<T>(key, value)?;
+        self.record(1, 0)?;
+        Ok(deleted)
+    }
+
+    /// Runs `f`, committing the writes it makes through the writer in the same chunk.
+    ///
+    /// The chunk is committed after `f` returns if it's full, so it may exceed the thresholds by
+    /// the writes of `f`. If `f` fails, its writes stay in the current chunk, so the writer should
+    /// be dropped to abort them.
+    pub fn atomic<R>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<R, DatabaseError>,
+    ) -> Result<R, DatabaseError> {
+        self.atomic_depth += 1;
+        let result = f(self);
+        self.atomic_depth -= 1;
+        let result = result?;
+        self.record(0, 0)?;
+        Ok(result)
+    }
+
+    /// Counts the writes made directly to the transaction of the current chunk, committing the
+    /// chunk if it's full.
+    pub fn record(&mut self, operations: u64, bytes: u64) -> Result<(), DatabaseError> {
+        self.chunk_operations += operations;
+        self.chunk_bytes += bytes;
+
+        let full = self.max_operations.is_some_and(|max| self.chunk_operations >= max) ||
+            self.max_bytes.is_some_and(|max| self.chunk_bytes >= max);
+        if full && self.atomic_depth == 0 {
+            self.commit_chunk()?;
+        }
+        Ok(())
+    }
+
+    /// Commits the last chunk, returning the progress of the writer.
+    ///
+    /// The last chunk is counted only if it has recorded writes.
+    pub fn commit(mut self) -> Result<ChunkedTxProgress, DatabaseError> {
+        if self.chunk_operations > 0 || self.chunk_bytes > 0 {
+            self.commit_chunk()?;
+        } else if let Some(tx) = self.tx.take() {
+            // Writes made directly to the transaction without recording them are still committed.
+            tx.commit()?;
+        }
+        Ok(self.progress)
+    }
+
+    /// Commits the current chunk. The transaction of the next one is opened on the next write.
+    fn commit_chunk(&mut self) -> Result<(), DatabaseError> {
+        if let Some(tx) = self.tx.take() {
+            tx.commit()?;
+        }
+
+        self.progress.chunks += 1;
+        self.progress.operations += std::mem::take(&mut self.chunk_operations);
+        self.progress.bytes += std::mem::take(&mut self.chunk_bytes);
+        debug!(
+            target: "storage::db::chunked_tx",
+            chunks = self.progress.chunks,
+            operations = self.progress.operations,
+            bytes = self.progress.bytes,
+            "Committed chunk"
+        );
+        if let Some(on_commit) = &mut self.on_commit {
+            on_commit(&self.progress);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tables::CanonicalHeaders, test_utils::create_test_rw_db};
+    use reth_primitives::B256;
+
+    #[test]
+    fn commits_chunks() {
+        let db = create_test_rw_db();
+        let mut chunks = Vec::new();
+
+        // Nothing was written, so no chunk is counted.
+        assert_eq!(
+            ChunkedTxWriter::new(&db).unwrap().commit().unwrap(),
+            ChunkedTxProgress::default()
+        );
+
+        let mut writer =
+            ChunkedTxWriter::new(&db).unwrap().with_max_operations(3).with_on_commit(|progress| {
+                chunks.push(*progress);
+            });
+        for number in 0..7 {
+            writer.put::<CanonicalHeaders>(number, B256::with_last_byte(number as u8)).unwrap();
+        }
+        // Reads observe the current chunk too.
+        assert_eq!(writer.tx().unwrap().entries::<CanonicalHeaders>().unwrap(), 7);
+        assert_eq!(writer.progress().chunks, 2);
+
+        // The group is committed in a single chunk of 4 operations.
+        writer
+            .atomic(|writer| {
+                writer.delete::<CanonicalHeaders>(0, None)?;
+                writer.put::<CanonicalHeaders>(7, B256::with_last_byte(7))?;
+                writer.put::<CanonicalHeaders>(8, B256::with_last_byte(8))?;
+                Ok(())
+            })
+            .unwrap();
+        writer.put::<CanonicalHeaders>(9, B256::with_last_byte(9)).unwrap();
+        writer.put::<CanonicalHeaders>(10, B256::with_last_byte(10)).unwrap();
+
+        // The last chunk is aborted on drop.
+        drop(writer);
+        assert_eq!(
+            chunks.iter().map(|progress| progress.operations).collect::<Vec<_>>(),
+            [3, 6, 10]
+        );
+        assert_eq!(chunks[2].bytes, 9 * (8 + 32));
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.get::<CanonicalHeaders>(0).unwrap(), None);
+        assert_eq!(tx.get::<CanonicalHeaders>(9).unwrap(), None);
+        assert_eq!(tx.entries::<CanonicalHeaders>().unwrap(), 8);
+        drop(tx);
+
+        let mut writer = ChunkedTxWriter::new(&db).unwrap().with_max_bytes(100);
+        writer.put::<CanonicalHeaders>(9, B256::with_last_byte(9)).unwrap();
+        writer.put::<CanonicalHeaders>(10, B256::with_last_byte(10)).unwrap();
+        writer.put::<CanonicalHeaders>(11, B256::with_last_byte(11)).unwrap();
+        assert_eq!(writer.progress().chunks, 1);
+        writer.put::<CanonicalHeaders>(12, B256::with_last_byte(12)).unwrap();
+        assert_eq!(
+            writer.commit().unwrap(),
+            ChunkedTxProgress { chunks: 2, operations: 4, bytes: 4 * (8 + 32) }
+        );
+        assert_eq!(db.tx().unwrap().entries::<CanonicalHeaders>().unwrap(), 12);
+
+        // The chunk committed by the last put is not followed by an empty one.
+        let mut writer = ChunkedTxWriter::new(&db).unwrap().with_max_operations(2);
+        writer.put::<CanonicalHeaders>(13, B256::with_last_byte(13)).unwrap();
+        writer.put::<CanonicalHeaders>(14, B256::with_last_byte(14)).unwrap();
+        assert_eq!(
+            writer.commit().unwrap(),
+            ChunkedTxProgress { chunks: 1, operations: 2, bytes: 2 * (8 + 32) }
+        );
+    }
+}
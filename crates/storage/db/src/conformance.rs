@@ -0,0 +1,199 @@
+//! Conformance tests shared by the database backends.
+//!
+//! Each assertion runs against a fresh database of every backend listed in
+//! [`conformance_tests`], so a backend diverging from mdbx fails the test named after it.
+
+use crate::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    database::Database,
+    memory::MemoryDatabase,
+    models::BlockNumberAddress,
+    overlay::OverlayDatabase,
+    table::DuplicateValues,
+    tables::{CanonicalHeaders, PlainStorageState, StorageChangeSetEpochs},
+    test_utils::create_test_rw_db,
+    transaction::{DbTx, DbTxMut},
+    Tables,
+};
+use reth_primitives::{Address, StorageEntry, B256, U256};
+use std::ops::Bound;
+
+/// Generates a module per backend, with a test per assertion run against a fresh database of the
+/// backend.
+macro_rules! conformance_tests {
+    ($($backend:ident => $db:expr),* $(,)?) => {
+        $(
+            mod $backend {
+                use super::*;
+
+                #[test]
+                fn duplicate_values() {
+                    assert_duplicate_values(&$db);
+                }
+
+                #[test]
+                fn seek_exact_or_previous_key() {
+                    assert_seek_exact_or_previous_key(&$db);
+                }
+
+                #[test]
+                fn walk_dup_range() {
+                    assert_walk_dup_range(&$db);
+                }
+
+                #[test]
+                fn batched_delete() {
+                    assert_batched_delete(&$db);
+                }
+
+                #[test]
+                fn empty_table() {
+                    assert_empty_table(&$db);
+                }
+            }
+        )*
+    };
+}
+
+conformance_tests! {
+    memory => MemoryDatabase::new(),
+    mdbx => create_test_rw_db(),
+    overlay => OverlayDatabase::new(create_test_rw_db()),
+}
+
+/// Assert that writing equal values follows the policy of the table.
+fn assert_duplicate_values<DB: Database>(db: &DB) {
+    let entry = |value: u8| StorageEntry { key: B256::ZERO, value: U256::from(value) };
+
+    let tx = db.tx_mut().unwrap();
+    let address = Address::ZERO;
+    tx.put::<PlainStorageState>(address, entry(1)).unwrap();
+    tx.put::<PlainStorageState>(address, entry(1)).unwrap();
+    let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+    cursor.upsert(address, entry(1)).unwrap();
+    cursor.append_dup(address, entry(1)).unwrap();
+    assert_eq!(tx.entries::<PlainStorageState>().unwrap(), 1);
+
+    let key = BlockNumberAddress((1, address));
+    tx.put::<StorageChangeSetEpochs>(key, entry(1)).unwrap();
+    assert!(tx.put::<StorageChangeSetEpochs>(key, entry(1)).is_err());
+    let mut cursor = tx.cursor_dup_write::<StorageChangeSetEpochs>().unwrap();
+    assert!(cursor.upsert(key, entry(1)).is_err());
+    assert!(cursor.append_dup(key, entry(1)).is_err());
+    cursor.append_dup(key, entry(2)).unwrap();
+    assert_eq!(tx.entries::<StorageChangeSetEpochs>().unwrap(), 2);
+}
+
+/// Assert that seeking a missing key positions the cursor at the following key, as in mdbx.
+fn assert_seek_exact_or_previous_key<DB: Database>(db: &DB) {
+    let tx = db.tx_mut().unwrap();
+    for key in [0, 1, 3] {
+        tx.put::<CanonicalHeaders>(key, B256::ZERO).unwrap();
+    }
+    tx.commit().unwrap();
+
+    let tx = db.tx().unwrap();
+    let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+    assert_eq!(cursor.current(), Ok(None));
+    assert_eq!(cursor.seek_exact(2), Ok(None));
+    assert_eq!(cursor.current(), Ok(Some((3, B256::ZERO))));
+    assert_eq!(cursor.prev(), Ok(Some((1, B256::ZERO))));
+    assert_eq!(cursor.prev(), Ok(Some((0, B256::ZERO))));
+
+    assert_eq!(cursor.seek_exact(1), Ok(Some((1, B256::ZERO))));
+    assert_eq!(cursor.next(), Ok(Some((3, B256::ZERO))));
+}
+
+/// Assert that walking the values of a key starts and stops at the bounds of the subkey range.
+fn assert_walk_dup_range<DB: Database>(db: &DB) {
+    let entry = |key: u8| StorageEntry { key: B256::with_last_byte(key), value: U256::ZERO };
+
+    let tx = db.tx_mut().unwrap();
+    for (address, key) in [(1, 1), (1, 2), (1, 3), (1, 4), (2, 1)] {
+        tx.put::<PlainStorageState>(Address::with_last_byte(address), entry(key)).unwrap();
+    }
+    tx.commit().unwrap();
+
+    let tx = db.tx().unwrap();
+    let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+    let mut walk = |range: (Bound<B256>, Bound<B256>)| {
+        cursor
+            .walk_dup_range(Address::with_last_byte(1), range)
+            .unwrap()
+            .map(|row| row.map(|(_, entry)| entry.key[31]))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+
+    let key = B256::with_last_byte;
+    assert_eq!(walk((Bound::Included(key(2)), Bound::Excluded(key(4)))), [2, 3]);
+    assert_eq!(walk((Bound::Included(key(2)), Bound::Included(key(4)))), [2, 3, 4]);
+    assert_eq!(walk((Bound::Unbounded, Bound::Excluded(key(3)))), [1, 2]);
+    assert_eq!(walk((Bound::Included(key(3)), Bound::Unbounded)), [3, 4]);
+    assert!(walk((Bound::Included(key(3)), Bound::Excluded(key(3)))).is_empty());
+    assert_eq!(walk((Bound::Excluded(key(2)), Bound::Unbounded)), [3, 4]);
+    assert_eq!(walk((Bound::Excluded(key(0)), Bound::Included(key(2)))), [1, 2]);
+    assert!(walk((Bound::Excluded(key(4)), Bound::Unbounded)).is_empty());
+}
+
+/// Assert that batched deletes remove contiguous runs and leave the cursor after them.
+fn assert_batched_delete<DB: Database>(db: &DB) {
+    let tx = db.tx_mut().unwrap();
+    for number in 0..10 {
+        tx.put::<CanonicalHeaders>(number, B256::ZERO).unwrap();
+    }
+
+    let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+    cursor.seek_exact(2).unwrap();
+    assert_eq!(cursor.delete_current_n(3), Ok(3));
+    assert_eq!(cursor.current(), Ok(Some((5, B256::ZERO))));
+    assert_eq!(cursor.delete_while(|number, _| *number < 7), Ok(2));
+    assert_eq!(cursor.current(), Ok(Some((7, B256::ZERO))));
+    assert_eq!(cursor.delete_current_n(5), Ok(3));
+
+    let rows = cursor.walk(None).unwrap().map(|row| row.map(|(number, _)| number));
+    assert_eq!(rows.collect::<Result<Vec<_>, _>>(), Ok(vec![0, 1]));
+}
+
+/// Assert that cursors over an empty table return nothing.
+fn assert_empty_table<DB: Database>(db: &DB) {
+    let tx = db.tx().unwrap();
+
+    let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+    assert_eq!(cursor.current(), Ok(None));
+    assert_eq!(cursor.next(), Ok(None));
+    assert_eq!(cursor.prev(), Ok(None));
+    assert_eq!(cursor.first(), Ok(None));
+    assert_eq!(cursor.last(), Ok(None));
+    assert_eq!(cursor.next(), Ok(None));
+    assert_eq!(cursor.prev(), Ok(None));
+    assert_eq!(cursor.current(), Ok(None));
+    assert_eq!(cursor.seek(1), Ok(None));
+    assert_eq!(cursor.seek_exact(1), Ok(None));
+    assert_eq!(cursor.current(), Ok(None));
+    assert_eq!(cursor.walk(None).unwrap().count(), 0);
+    assert_eq!(cursor.walk(Some(1)).unwrap().count(), 0);
+    assert_eq!(cursor.walk_range(..).unwrap().count(), 0);
+    assert_eq!(cursor.walk_range(1..3).unwrap().count(), 0);
+    assert_eq!(cursor.walk_back(None).unwrap().count(), 0);
+    assert_eq!(cursor.walk_back(Some(1)).unwrap().count(), 0);
+
+    let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+    assert_eq!(cursor.next_dup(), Ok(None));
+    assert_eq!(cursor.next_no_dup(), Ok(None));
+    assert_eq!(cursor.next_dup_val(), Ok(None));
+    assert_eq!(cursor.seek_by_key_subkey(Address::ZERO, B256::ZERO), Ok(None));
+    assert_eq!(cursor.walk_dup(None, None).unwrap().count(), 0);
+    assert_eq!(cursor.walk_dup(Some(Address::ZERO), None).unwrap().count(), 0);
+    assert_eq!(cursor.walk_dup(None, Some(B256::ZERO)).unwrap().count(), 0);
+    assert_eq!(cursor.walk_dup(Some(Address::ZERO), Some(B256::ZERO)).unwrap().count(), 0);
+    assert_eq!(cursor.walk_dup_range(Address::ZERO, ..).unwrap().count(), 0);
+}
+
+#[test]
+fn duplicate_value_policies() {
+    assert_eq!(Tables::CanonicalHeaders.duplicate_values(), None);
+    assert_eq!(Tables::PlainStorageState.duplicate_values(), Some(DuplicateValues::Ignore));
+    assert_eq!(Tables::StorageChangeSets.duplicate_values(), Some(DuplicateValues::Ignore));
+    assert_eq!(Tables::StorageChangeSetEpochs.duplicate_values(), Some(DuplicateValues::Reject));
+}
@@ -7,14 +7,28 @@ use crate::{
         ReverseWalker, Walker,
     },
     metrics::{DatabaseEnvMetrics, Operation},
+    operation_log::{LogEntry, OperationLog},
+    slow_query::SlowQueryLogger,
     table::{Compress, Decode, Decompress, DupSort, Encode, Table},
     tables::utils::*,
+    write_profile::WriteProfiler,
     DatabaseError,
 };
 use reth_interfaces::db::{DatabaseErrorInfo, DatabaseWriteError, DatabaseWriteOperation};
-use reth_libmdbx::{Error as MDBXError, TransactionKind, WriteFlags, RO, RW};
+use reth_libmdbx::{TransactionKind, WriteFlags, RO, RW};
+use reth_tracing::tracing::{debug_span, span::EnteredSpan};
 use std::{borrow::Cow, collections::Bound, marker::PhantomData, ops::RangeBounds, sync::Arc};
 
+/// Returns the flags of a write to the table. Writing a value equal to one already stored for the
+/// key fails with `KEYEXIST` if the table rejects duplicate values.
+pub(crate) fn write_flags<T: Table>(flags: WriteFlags) -> WriteFlags {
+    if T::TABLE.rejects_duplicate_values() {
+        flags | WriteFlags::NO_DUP_DATA
+    } else {
+        flags
+    }
+}
+
 /// Read only Cursor.
 pub type CursorRO<T> = Cursor<RO, T>;
 /// Read write cursor.
@@ -29,6 +43,14 @@ pub struct Cursor<K: TransactionKind, T: Table> {
     buf: Vec<u8>,
     /// Reference to metric handles in the DB environment. If `None`, metrics are not recorded.
     metrics: Option<Arc<DatabaseEnvMetrics>>,
+    /// Logger for slow database operations. If `None`, slow operations are not logged.
+    slow_query_logger: Option<Arc<SlowQueryLogger>>,
+    /// If `true`, cursor operations are wrapped in tracing spans.
+    operation_spans: bool,
+    /// Log of the write operations. If `Some`, successful writes are recorded.
+    operation_log: Option<Arc<OperationLog>>,
+    /// Counters of the writes per table. If `Some`, successful writes are counted.
+    write_profiler: Option<Arc<WriteProfiler>>,
     /// Phantom data to enforce encoding/decoding.
     _dbi: PhantomData<T>,
 }
@@ -38,19 +60,127 @@ impl<K: TransactionKind, T: Table> Cursor<K, T> {
         inner: reth_libmdbx::Cursor<K>,
         metrics: Option<Arc<DatabaseEnvMetrics>>,
     ) -> Self {
-        Self { inner, buf: Vec::new(), metrics, _dbi: PhantomData }
+        Self {
+            inner,
+            buf: Vec::new(),
+            metrics,
+            slow_query_logger: None,
+            operation_spans: false,
+            operation_log: None,
+            write_profiler: None,
+            _dbi: PhantomData,
+        }
+    }
+
+    /// Enables logging of slow cursor operations.
+    pub(crate) fn with_slow_query_logger(
+        mut self,
+        slow_query_logger: Option<Arc<SlowQueryLogger>>,
+    ) -> Self {
+        self.slow_query_logger = slow_query_logger;
+        self
+    }
+
+    /// Enables wrapping of cursor operations in tracing spans.
+    pub(crate) fn with_operation_spans(mut self, operation_spans: bool) -> Self {
+        self.operation_spans = operation_spans;
+        self
+    }
+
+    /// Enables recording of the successful cursor writes.
+    pub(crate) fn with_operation_log(mut self, operation_log: Option<Arc<OperationLog>>) -> Self {
+        self.operation_log = operation_log;
+        self
+    }
+
+    /// Enables counting of the successful cursor writes.
+    pub(crate) fn with_write_profiler(
+        mut self,
+        write_profiler: Option<Arc<WriteProfiler>>,
+    ) -> Self {
+        self.write_profiler = write_profiler;
+        self
+    }
+
+    /// Records a put of `bytes` encoded key and value size to the write profile, if enabled.
+    fn profile_put(&self, bytes: usize) {
+        if let Some(write_profiler) = &self.write_profiler {
+            write_profiler.record_put(T::TABLE, bytes);
+        }
+    }
+
+    /// Records a delete to the write profile, if enabled.
+    fn profile_delete(&self) {
+        if let Some(write_profiler) = &self.write_profiler {
+            write_profiler.record_delete(T::TABLE);
+        }
+    }
+
+    /// Records the put of the encoded key to the operation log, if enabled. The value is either
+    /// the uncompressable reference or the compressed value in the cache buffer.
+    fn record_put(&self, key: Option<Vec<u8>>, value: Option<&[u8]>) {
+        if let (Some(operation_log), Some(key)) = (&self.operation_log, key) {
+            let value = value.unwrap_or(&self.buf).to_vec();
+            operation_log.record(LogEntry::Put { table: T::TABLE, key, value });
+        }
+    }
+
+    /// Records the delete of the raw entry to the operation log, if enabled.
+    fn record_delete(&self, key: Vec<u8>, value: Option<Vec<u8>>) {
+        if let Some(operation_log) = &self.operation_log {
+            operation_log.record(LogEntry::Delete { table: T::TABLE, key, value });
+        }
+    }
+
+    /// Returns the raw entry at the cursor position, if the operation log is enabled.
+    fn current_for_operation_log(&mut self) -> Result<Option<(Vec<u8>, Vec<u8>)>, DatabaseError> {
+        if self.operation_log.is_none() {
+            return Ok(None)
+        }
+        self.inner.get_current().map_err(|e| DatabaseError::Read(e.into()))
+    }
+
+    /// If `self.operation_spans` is `true`, enter a tracing span tagged with the table and the
+    /// operation name.
+    fn enter_operation_span(&self, operation: &'static str) -> Option<EnteredSpan> {
+        self.operation_spans.then(|| {
+            debug_span!(target: "storage::db::operation", "db_operation", table = T::NAME, operation)
+                .entered()
+        })
+    }
+
+    /// If `self.slow_query_logger` is `Some(...)`, measure the time it takes to execute the closure
+    /// and log the operation if it's slow. The encoded key is retrieved with `key` only if the
+    /// operation needs to be logged.
+    ///
+    /// Otherwise, just execute the closure. In both cases, the operation span is entered if
+    /// enabled.
+    fn execute_with_slow_query_log<R>(
+        &mut self,
+        operation: &'static str,
+        f: impl FnOnce(&mut Self) -> R,
+        key: impl FnOnce(&R) -> Option<Vec<u8>>,
+    ) -> R {
+        let _span = self.enter_operation_span(operation);
+        if let Some(logger) = self.slow_query_logger.as_ref().cloned() {
+            logger.record(T::NAME, operation, || f(self), key)
+        } else {
+            f(self)
+        }
     }
 
     /// If `self.metrics` is `Some(...)`, record a metric with the provided operation and value
     /// size.
     ///
-    /// Otherwise, just execute the closure.
+    /// Otherwise, just execute the closure. In both cases, the operation span is entered if
+    /// enabled.
     fn execute_with_operation_metric<R>(
         &mut self,
         operation: Operation,
         value_size: Option<usize>,
         f: impl FnOnce(&mut Self) -> R,
     ) -> R {
+        let _span = self.enter_operation_span(operation.as_str());
         if let Some(metrics) = self.metrics.as_ref().cloned() {
             metrics.record_operation(T::TABLE, operation, value_size, || f(self))
         } else {
@@ -86,25 +216,48 @@ macro_rules! compress_to_buf_or_ref {
     };
 }
 
+/// Returns the encoded key of the entry the cursor operation resulted in.
+fn encoded_key<T: Table>(result: &PairResult<T>) -> Option<Vec<u8>> {
+    result.as_ref().ok()?.as_ref().map(|(key, _)| key.clone().encode().as_ref().to_vec())
+}
+
 impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
     fn first(&mut self) -> PairResult<T> {
         decode::<T>(self.inner.first())
     }
 
     fn seek_exact(&mut self, key: <T as Table>::Key) -> PairResult<T> {
-        decode::<T>(self.inner.set_key(key.encode().as_ref()))
+        let key = key.encode();
+        self.execute_with_slow_query_log(
+            "seek_exact",
+            |this| decode::<T>(this.inner.set_key(key.as_ref())),
+            |_| Some(key.as_ref().to_vec()),
+        )
     }
 
     fn seek(&mut self, key: <T as Table>::Key) -> PairResult<T> {
-        decode::<T>(self.inner.set_range(key.encode().as_ref()))
+        let key = key.encode();
+        self.execute_with_slow_query_log(
+            "seek",
+            |this| decode::<T>(this.inner.set_range(key.as_ref())),
+            |_| Some(key.as_ref().to_vec()),
+        )
     }
 
     fn next(&mut self) -> PairResult<T> {
-        decode::<T>(self.inner.next())
+        self.execute_with_slow_query_log(
+            "next",
+            |this| decode::<T>(this.inner.next()),
+            encoded_key::<T>,
+        )
     }
 
     fn prev(&mut self) -> PairResult<T> {
-        decode::<T>(self.inner.prev())
+        self.execute_with_slow_query_log(
+            "prev",
+            |this| decode::<T>(this.inner.prev()),
+            encoded_key::<T>,
+        )
     }
 
     fn last(&mut self) -> PairResult<T> {
@@ -158,12 +311,20 @@ impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
 impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
     /// Returns the next `(key, value)` pair of a DUPSORT table.
     fn next_dup(&mut self) -> PairResult<T> {
-        decode::<T>(self.inner.next_dup())
+        self.execute_with_slow_query_log(
+            "next_dup",
+            |this| decode::<T>(this.inner.next_dup()),
+            encoded_key::<T>,
+        )
     }
 
     /// Returns the next `(key, value)` pair skipping the duplicates.
     fn next_no_dup(&mut self) -> PairResult<T> {
-        decode::<T>(self.inner.next_nodup())
+        self.execute_with_slow_query_log(
+            "next_no_dup",
+            |this| decode::<T>(this.inner.next_nodup()),
+            encoded_key::<T>,
+        )
     }
 
     /// Returns the next `value` of a duplicate `key`.
@@ -180,11 +341,18 @@ impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
         key: <T as Table>::Key,
         subkey: <T as DupSort>::SubKey,
     ) -> ValueOnlyResult<T> {
-        self.inner
-            .get_both_range(key.encode().as_ref(), subkey.encode().as_ref())
-            .map_err(|e| DatabaseError::Read(e.into()))?
-            .map(decode_one::<T>)
-            .transpose()
+        let key = key.encode();
+        self.execute_with_slow_query_log(
+            "seek_by_key_subkey",
+            |this| {
+                this.inner
+                    .get_both_range(key.as_ref(), subkey.encode().as_ref())
+                    .map_err(|e| DatabaseError::Read(e.into()))?
+                    .map(decode_one::<T>)
+                    .transpose()
+            },
+            |_| Some(key.as_ref().to_vec()),
+        )
     }
 
     /// Depending on its arguments, returns an iterator starting at:
@@ -214,21 +382,51 @@ impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
                     .map_err(|e| DatabaseError::Read(e.into()))?
                     .map(|val| decoder::<T>((Cow::Owned(key), val)))
             }
-            (None, Some(subkey)) => {
-                if let Some((key, _)) = self.first()? {
+            (None, Some(subkey)) => match self.first()? {
+                Some((key, _)) => {
                     let key: Vec<u8> = key.encode().into();
                     self.inner
                         .get_both_range(key.as_ref(), subkey.encode().as_ref())
                         .map_err(|e| DatabaseError::Read(e.into()))?
                         .map(|val| decoder::<T>((Cow::Owned(key), val)))
-                } else {
-                    Some(Err(DatabaseError::Read(MDBXError::NotFound.into())))
                 }
-            }
+                // The table is empty.
+                None => None,
+            },
             (None, None) => self.first().transpose(),
         };
 
-        Ok(DupWalker::<'_, T, Self> { cursor: self, start })
+        Ok(DupWalker::<'_, T, Self> { cursor: self, start, end_subkey: Bound::Unbounded })
+    }
+}
+
+impl<T: Table> Cursor<RW, T> {
+    /// Deletes the raw entries from the cursor position while `predicate` returns `true`, under a
+    /// single transaction lock and a single operation metric.
+    fn delete_raw_while(
+        &mut self,
+        mut predicate: impl FnMut(&[u8], &[u8]) -> bool,
+    ) -> Result<usize, DatabaseError> {
+        let mut logged = self.operation_log.is_some().then(Vec::new);
+        let deleted =
+            self.execute_with_operation_metric(Operation::CursorDeleteWhile, None, |this| {
+                this.inner
+                    .del_while(|key, value| {
+                        let delete = predicate(key, value);
+                        if let (true, Some(logged)) = (delete, logged.as_mut()) {
+                            logged.push((key.to_vec(), value.to_vec()));
+                        }
+                        delete
+                    })
+                    .map_err(|e| DatabaseError::Delete(e.into()))
+            })?;
+        for (key, value) in logged.into_iter().flatten() {
+            self.record_delete(key, Some(value));
+        }
+        for _ in 0..deleted {
+            self.profile_delete();
+        }
+        Ok(deleted)
     }
 }
 
@@ -243,29 +441,37 @@ impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
     fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
         let key = key.encode();
         let value = compress_to_buf_or_ref!(self, value);
-        self.execute_with_operation_metric(
+        let logged_key = self.operation_log.is_some().then(|| key.as_ref().to_vec());
+        let bytes = key.as_ref().len() + value.unwrap_or(&self.buf).len();
+        let flags = write_flags::<T>(WriteFlags::UPSERT);
+        let result = self.execute_with_operation_metric(
             Operation::CursorUpsert,
             Some(value.unwrap_or(&self.buf).len()),
             |this| {
-                this.inner
-                    .put(key.as_ref(), value.unwrap_or(&this.buf), WriteFlags::UPSERT)
-                    .map_err(|e| {
-                        DatabaseWriteError {
-                            info: e.into(),
-                            operation: DatabaseWriteOperation::CursorUpsert,
-                            table_name: T::NAME,
-                            key: key.into(),
-                        }
-                        .into()
-                    })
+                this.inner.put(key.as_ref(), value.unwrap_or(&this.buf), flags).map_err(|e| {
+                    DatabaseWriteError {
+                        info: e.into(),
+                        operation: DatabaseWriteOperation::CursorUpsert,
+                        table_name: T::NAME,
+                        key: key.into(),
+                    }
+                    .into()
+                })
             },
-        )
+        );
+        if result.is_ok() {
+            self.record_put(logged_key, value);
+            self.profile_put(bytes);
+        }
+        result
     }
 
     fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
         let key = key.encode();
         let value = compress_to_buf_or_ref!(self, value);
-        self.execute_with_operation_metric(
+        let logged_key = self.operation_log.is_some().then(|| key.as_ref().to_vec());
+        let bytes = key.as_ref().len() + value.unwrap_or(&self.buf).len();
+        let result = self.execute_with_operation_metric(
             Operation::CursorInsert,
             Some(value.unwrap_or(&self.buf).len()),
             |this| {
@@ -281,7 +487,12 @@ impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
                         .into()
                     })
             },
-        )
+        );
+        if result.is_ok() {
+            self.record_put(logged_key, value);
+            self.profile_put(bytes);
+        }
+        result
     }
 
     /// Appends the data to the end of the table. Consequently, the append operation
@@ -289,7 +500,9 @@ impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
     fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
         let key = key.encode();
         let value = compress_to_buf_or_ref!(self, value);
-        self.execute_with_operation_metric(
+        let logged_key = self.operation_log.is_some().then(|| key.as_ref().to_vec());
+        let bytes = key.as_ref().len() + value.unwrap_or(&self.buf).len();
+        let result = self.execute_with_operation_metric(
             Operation::CursorAppend,
             Some(value.unwrap_or(&self.buf).len()),
             |this| {
@@ -305,42 +518,395 @@ impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
                         .into()
                     })
             },
-        )
+        );
+        if result.is_ok() {
+            self.record_put(logged_key, value);
+            self.profile_put(bytes);
+        }
+        result
     }
 
     fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        let current = self.current_for_operation_log()?;
         self.execute_with_operation_metric(Operation::CursorDeleteCurrent, None, |this| {
             this.inner.del(WriteFlags::CURRENT).map_err(|e| DatabaseError::Delete(e.into()))
+        })?;
+        if let Some((key, value)) = current {
+            self.record_delete(key, Some(value));
+        }
+        self.profile_delete();
+        Ok(())
+    }
+
+    /// Deletes the entries without decoding them.
+    fn delete_current_n(&mut self, n: usize) -> Result<usize, DatabaseError>
+    where
+        Self: DbCursorRO<T> + Sized,
+    {
+        let mut remaining = n;
+        self.delete_raw_while(|_, _| {
+            let delete = remaining > 0;
+            remaining = remaining.saturating_sub(1);
+            delete
         })
     }
+
+    fn delete_while(
+        &mut self,
+        mut predicate: impl FnMut(&T::Key, &T::Value) -> bool,
+    ) -> Result<usize, DatabaseError>
+    where
+        Self: DbCursorRO<T> + Sized,
+    {
+        let mut decode_error = None;
+        let deleted = self.delete_raw_while(|key, value| {
+            match decoder::<T>((Cow::Borrowed(key), Cow::Borrowed(value))) {
+                Ok((key, value)) => predicate(&key, &value),
+                Err(err) => {
+                    decode_error = Some(err);
+                    false
+                }
+            }
+        })?;
+        match decode_error {
+            Some(err) => Err(err),
+            None => Ok(deleted),
+        }
+    }
 }
 
 impl<T: DupSort> DbDupCursorRW<T> for Cursor<RW, T> {
     fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
-        self.execute_with_operation_metric(Operation::CursorDeleteCurrentDuplicates, None, |this| {
-            this.inner.del(WriteFlags::NO_DUP_DATA).map_err(|e| DatabaseError::Delete(e.into()))
-        })
+        let current = self.current_for_operation_log()?;
+        self.execute_with_operation_metric(
+            Operation::CursorDeleteCurrentDuplicates,
+            None,
+            |this| {
+                this.inner.del(WriteFlags::NO_DUP_DATA).map_err(|e| DatabaseError::Delete(e.into()))
+            },
+        )?;
+        if let Some((key, _)) = current {
+            self.record_delete(key, None);
+        }
+        self.profile_delete();
+        Ok(())
     }
 
     fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
         let key = key.encode();
         let value = compress_to_buf_or_ref!(self, value);
-        self.execute_with_operation_metric(
+        let logged_key = self.operation_log.is_some().then(|| key.as_ref().to_vec());
+        let bytes = key.as_ref().len() + value.unwrap_or(&self.buf).len();
+        let flags = write_flags::<T>(WriteFlags::APPEND_DUP);
+        let result = self.execute_with_operation_metric(
             Operation::CursorAppendDup,
             Some(value.unwrap_or(&self.buf).len()),
             |this| {
-                this.inner
-                    .put(key.as_ref(), value.unwrap_or(&this.buf), WriteFlags::APPEND_DUP)
-                    .map_err(|e| {
-                        DatabaseWriteError {
-                            info: e.into(),
-                            operation: DatabaseWriteOperation::CursorAppendDup,
-                            table_name: T::NAME,
-                            key: key.into(),
-                        }
-                        .into()
-                    })
+                this.inner.put(key.as_ref(), value.unwrap_or(&this.buf), flags).map_err(|e| {
+                    DatabaseWriteError {
+                        info: e.into(),
+                        operation: DatabaseWriteOperation::CursorAppendDup,
+                        table_name: T::NAME,
+                        key: key.into(),
+                    }
+                    .into()
+                })
             },
+        );
+        if result.is_ok() {
+            self.record_put(logged_key, value);
+            self.profile_put(bytes);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+        database::Database,
+        tables::{CanonicalHeaders, PlainStorageState},
+        test_utils::create_test_rw_db,
+        transaction::{DbTx, DbTxMut},
+    };
+    use proptest::{prelude::*, test_runner::TestRunner};
+    use reth_primitives::{Address, StorageEntry, B256, U256};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    /// Operation on the [CanonicalHeaders] table.
+    #[derive(Debug, Clone)]
+    enum Op {
+        Upsert(u64, u8),
+        Insert(u64, u8),
+        Append(u64, u8),
+        Delete(u64),
+        SeekExact(u64),
+        Seek { key: u64, steps: usize },
+        WalkBack { steps: usize },
+        WalkRange(u64, u64),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        let key = 0..32u64;
+        prop_oneof![
+            (key.clone(), any::<u8>()).prop_map(|(key, value)| Op::Upsert(key, value)),
+            (key.clone(), any::<u8>()).prop_map(|(key, value)| Op::Insert(key, value)),
+            (key.clone(), any::<u8>()).prop_map(|(key, value)| Op::Append(key, value)),
+            key.clone().prop_map(Op::Delete),
+            key.clone().prop_map(Op::SeekExact),
+            (key.clone(), 0..8usize).prop_map(|(key, steps)| Op::Seek { key, steps }),
+            (0..8usize).prop_map(|steps| Op::WalkBack { steps }),
+            (key.clone(), key).prop_map(|(start, end)| Op::WalkRange(start, end)),
+        ]
+    }
+
+    /// Operation on the [PlainStorageState] table. Entries are `(address, subkey, value)`.
+    #[derive(Debug, Clone)]
+    enum DupOp {
+        Upsert(u8, u8, u8),
+        AppendDup(u8, u8, u8),
+        DeleteKey(u8),
+        DeleteEntry(u8, u8, u8),
+        SeekBySubkey { address: u8, subkey: u8, steps: usize },
+        WalkDup(u8),
+        NextNoDup { steps: usize },
+    }
+
+    fn dup_op() -> impl Strategy<Value = DupOp> {
+        let entry = (0..4u8, 0..8u8, 0..4u8);
+        prop_oneof![
+            entry.clone().prop_map(|(a, s, v)| DupOp::Upsert(a, s, v)),
+            entry.clone().prop_map(|(a, s, v)| DupOp::AppendDup(a, s, v)),
+            (0..4u8).prop_map(DupOp::DeleteKey),
+            entry.prop_map(|(a, s, v)| DupOp::DeleteEntry(a, s, v)),
+            (0..4u8, 0..8u8, 0..8usize).prop_map(|(address, subkey, steps)| {
+                DupOp::SeekBySubkey { address, subkey, steps }
+            }),
+            (0..4u8).prop_map(DupOp::WalkDup),
+            (0..8usize).prop_map(|steps| DupOp::NextNoDup { steps }),
+        ]
+    }
+
+    fn dup_entry((address, subkey, value): (u8, u8, u8)) -> (Address, StorageEntry) {
+        (
+            Address::with_last_byte(address),
+            StorageEntry { key: B256::with_last_byte(subkey), value: U256::from(value) },
         )
     }
+
+    /// Applies random operations to the mdbx cursor and a [BTreeMap] reference model, and checks
+    /// that the results match.
+    #[test]
+    fn cursor_matches_reference_model() {
+        let db = create_test_rw_db();
+        let mut runner = TestRunner::new(ProptestConfig { cases: 128, ..Default::default() });
+        runner
+            .run(&proptest::collection::vec(op(), 1..64), |ops| {
+                let tx = db.tx_mut().unwrap();
+                tx.clear::<CanonicalHeaders>().unwrap();
+                let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+                let mut model = BTreeMap::new();
+
+                for op in ops {
+                    match op {
+                        Op::Upsert(key, value) => {
+                            cursor.upsert(key, B256::with_last_byte(value)).unwrap();
+                            model.insert(key, B256::with_last_byte(value));
+                        }
+                        Op::Insert(key, value) => {
+                            let result = cursor.insert(key, B256::with_last_byte(value));
+                            prop_assert_eq!(result.is_ok(), !model.contains_key(&key));
+                            model.entry(key).or_insert(B256::with_last_byte(value));
+                        }
+                        Op::Append(key, value) => {
+                            let last = model.keys().next_back().copied();
+                            // Appending the last key again is backend-specific.
+                            if last == Some(key) {
+                                continue
+                            }
+                            let result = cursor.append(key, B256::with_last_byte(value));
+                            prop_assert_eq!(result.is_ok(), last.map_or(true, |last| key > last));
+                            if result.is_ok() {
+                                model.insert(key, B256::with_last_byte(value));
+                            }
+                        }
+                        Op::Delete(key) => {
+                            let deleted = tx.delete::<CanonicalHeaders>(key, None).unwrap();
+                            prop_assert_eq!(deleted, model.remove(&key).is_some());
+                        }
+                        Op::SeekExact(key) => {
+                            let expected = model.get(&key).map(|value| (key, *value));
+                            prop_assert_eq!(cursor.seek_exact(key).unwrap(), expected);
+                        }
+                        Op::Seek { key, steps } => {
+                            let mut rows = Vec::new();
+                            let mut row = cursor.seek(key).unwrap();
+                            while let Some(current) = row {
+                                rows.push(current);
+                                if rows.len() > steps {
+                                    break
+                                }
+                                row = cursor.next().unwrap();
+                            }
+                            let expected = model
+                                .range(key..)
+                                .take(steps + 1)
+                                .map(|(key, value)| (*key, *value))
+                                .collect::<Vec<_>>();
+                            prop_assert_eq!(rows, expected);
+                        }
+                        Op::WalkBack { steps } => {
+                            let rows = cursor
+                                .walk_back(None)
+                                .unwrap()
+                                .take(steps)
+                                .collect::<Result<Vec<_>, _>>()
+                                .unwrap();
+                            let expected = model
+                                .iter()
+                                .rev()
+                                .take(steps)
+                                .map(|(key, value)| (*key, *value))
+                                .collect::<Vec<_>>();
+                            prop_assert_eq!(rows, expected);
+                        }
+                        Op::WalkRange(start, end) => {
+                            let range = start.min(end)..start.max(end);
+                            let rows = cursor
+                                .walk_range(range.clone())
+                                .unwrap()
+                                .collect::<Result<Vec<_>, _>>()
+                                .unwrap();
+                            let expected = model
+                                .range(range)
+                                .map(|(key, value)| (*key, *value))
+                                .collect::<Vec<_>>();
+                            prop_assert_eq!(rows, expected);
+                        }
+                    }
+                }
+
+                let rows = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+                prop_assert_eq!(rows, model.into_iter().collect::<Vec<_>>());
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    /// Applies random operations to the mdbx dup cursor and a [BTreeSet] reference model, and
+    /// checks that the results match.
+    #[test]
+    fn dup_cursor_matches_reference_model() {
+        let db = create_test_rw_db();
+        let mut runner = TestRunner::new(ProptestConfig { cases: 128, ..Default::default() });
+        runner
+            .run(&proptest::collection::vec(dup_op(), 1..64), |ops| {
+                let tx = db.tx_mut().unwrap();
+                tx.clear::<PlainStorageState>().unwrap();
+                let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+                let mut model = BTreeSet::new();
+
+                for op in ops {
+                    match op {
+                        DupOp::Upsert(a, s, v) => {
+                            let (address, entry) = dup_entry((a, s, v));
+                            cursor.upsert(address, entry).unwrap();
+                            model.insert((a, s, v));
+                        }
+                        DupOp::AppendDup(a, s, v) => {
+                            let last = model.last().copied();
+                            // Appending the last entry again, or appending to any key but the
+                            // last one is backend-specific.
+                            if last.is_some_and(|last| last == (a, s, v) || a < last.0) {
+                                continue
+                            }
+                            let (address, entry) = dup_entry((a, s, v));
+                            let result = cursor.append_dup(address, entry);
+                            prop_assert_eq!(
+                                result.is_ok(),
+                                last.map_or(true, |last| (a, s, v) > last)
+                            );
+                            if result.is_ok() {
+                                model.insert((a, s, v));
+                            }
+                        }
+                        DupOp::DeleteKey(a) => {
+                            let deleted = tx
+                                .delete::<PlainStorageState>(Address::with_last_byte(a), None)
+                                .unwrap();
+                            let len = model.len();
+                            model.retain(|(address, _, _)| *address != a);
+                            prop_assert_eq!(deleted, model.len() != len);
+                        }
+                        DupOp::DeleteEntry(a, s, v) => {
+                            let (address, entry) = dup_entry((a, s, v));
+                            let deleted =
+                                tx.delete::<PlainStorageState>(address, Some(entry)).unwrap();
+                            prop_assert_eq!(deleted, model.remove(&(a, s, v)));
+                        }
+                        DupOp::SeekBySubkey { address, subkey, steps } => {
+                            let mut values = Vec::new();
+                            let mut value = cursor
+                                .seek_by_key_subkey(
+                                    Address::with_last_byte(address),
+                                    B256::with_last_byte(subkey),
+                                )
+                                .unwrap();
+                            while let Some(current) = value {
+                                values.push(current);
+                                if values.len() > steps {
+                                    break
+                                }
+                                value = cursor.next_dup_val().unwrap();
+                            }
+                            let expected = model
+                                .range((address, subkey, 0)..)
+                                .take_while(|(a, _, _)| *a == address)
+                                .take(steps + 1)
+                                .map(|entry| dup_entry(*entry).1)
+                                .collect::<Vec<_>>();
+                            prop_assert_eq!(values, expected);
+                        }
+                        DupOp::WalkDup(a) => {
+                            let rows = cursor
+                                .walk_dup(Some(Address::with_last_byte(a)), None)
+                                .unwrap()
+                                .collect::<Result<Vec<_>, _>>()
+                                .unwrap();
+                            let expected = model
+                                .range((a, 0, 0)..)
+                                .take_while(|(address, _, _)| *address == a)
+                                .map(|entry| dup_entry(*entry))
+                                .collect::<Vec<_>>();
+                            prop_assert_eq!(rows, expected);
+                        }
+                        DupOp::NextNoDup { steps } => {
+                            let mut rows = Vec::new();
+                            let mut row = cursor.first().unwrap();
+                            while let Some(current) = row {
+                                rows.push(current);
+                                if rows.len() > steps {
+                                    break
+                                }
+                                row = cursor.next_no_dup().unwrap();
+                            }
+                            let mut expected = model.iter().copied().collect::<Vec<_>>();
+                            expected.dedup_by_key(|(address, _, _)| *address);
+                            let expected = expected
+                                .into_iter()
+                                .take(steps + 1)
+                                .map(dup_entry)
+                                .collect::<Vec<_>>();
+                            prop_assert_eq!(rows, expected);
+                        }
+                    }
+                }
+
+                let rows = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+                prop_assert_eq!(rows, model.into_iter().map(dup_entry).collect::<Vec<_>>());
+                Ok(())
+            })
+            .unwrap();
+    }
 }
@@ -3,17 +3,23 @@
 use crate::{
     cursor::{DbCursorRO, DbCursorRW},
     database::Database,
-    database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
+    database_metrics::{
+        DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics, DatabaseTableStats, TablePages,
+        TableStats,
+    },
     metrics::DatabaseEnvMetrics,
     models::client_version::ClientVersion,
+    operation_log::{LogEntry, OperationLog},
+    slow_query::SlowQueryLogger,
     tables::{self, TableType, Tables},
     transaction::{DbTx, DbTxMut},
     utils::default_page_size,
+    write_profile::WriteProfiler,
     DatabaseError,
 };
 use eyre::Context;
 use metrics::{gauge, Label};
-use reth_interfaces::db::LogLevel;
+use reth_interfaces::db::{DatabaseErrorInfo, LogLevel};
 use reth_libmdbx::{
     DatabaseFlags, Environment, EnvironmentFlags, Geometry, MaxReadTransactionDuration, Mode,
     PageSize, SyncMode, RO, RW,
@@ -21,9 +27,9 @@ use reth_libmdbx::{
 use reth_tracing::tracing::error;
 use std::{
     ops::Deref,
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tx::Tx;
 
@@ -87,6 +93,22 @@ pub struct DatabaseArguments {
     ///
     /// This flag affects only at environment opening but can't be changed after.
     exclusive: Option<bool>,
+    /// Database operations that take longer than this duration are logged. If [None], slow
+    /// operations are not logged.
+    slow_query_threshold: Option<Duration>,
+    /// If `true`, database operations are wrapped in tracing spans tagged with the table and
+    /// operation name.
+    operation_spans: bool,
+    /// Path of the log that the write operations are recorded to. If [None], write operations
+    /// are not recorded.
+    operation_log: Option<PathBuf>,
+    /// Period after which the data of the commits is flushed to disk. If [Some], commits don't
+    /// wait for the data to be flushed, and the first commit after the period elapses flushes all
+    /// preceding commits. A system crash can lose the commits since the last flush, but never
+    /// corrupts the database. If [None], every commit is durable.
+    sync_period: Option<Duration>,
+    /// If `true`, the puts, deletes and commits are counted per table, see [WriteProfiler].
+    write_profile: bool,
 }
 
 impl DatabaseArguments {
@@ -97,6 +119,11 @@ impl DatabaseArguments {
             log_level: None,
             max_read_transaction_duration: None,
             exclusive: None,
+            slow_query_threshold: None,
+            operation_spans: false,
+            operation_log: None,
+            sync_period: None,
+            write_profile: false,
         }
     }
 
@@ -121,6 +148,36 @@ impl DatabaseArguments {
         self
     }
 
+    /// Set the threshold above which database operations are logged.
+    pub fn with_slow_query_threshold(mut self, slow_query_threshold: Option<Duration>) -> Self {
+        self.slow_query_threshold = slow_query_threshold;
+        self
+    }
+
+    /// Enable wrapping of database operations in tracing spans.
+    pub fn with_operation_spans(mut self, operation_spans: bool) -> Self {
+        self.operation_spans = operation_spans;
+        self
+    }
+
+    /// Set the path of the log that the write operations are recorded to.
+    pub fn with_operation_log(mut self, operation_log: Option<PathBuf>) -> Self {
+        self.operation_log = operation_log;
+        self
+    }
+
+    /// Set the period after which the data of the commits is flushed to disk.
+    pub fn with_sync_period(mut self, sync_period: Option<Duration>) -> Self {
+        self.sync_period = sync_period;
+        self
+    }
+
+    /// Enable counting of the puts, deletes and commits per table.
+    pub fn with_write_profile(mut self, write_profile: bool) -> Self {
+        self.write_profile = write_profile;
+        self
+    }
+
     /// Returns the client version if any.
     pub fn client_version(&self) -> &ClientVersion {
         &self.client_version
@@ -134,6 +191,14 @@ pub struct DatabaseEnv {
     inner: Environment,
     /// Cache for metric handles. If `None`, metrics are not recorded.
     metrics: Option<Arc<DatabaseEnvMetrics>>,
+    /// Logger for slow database operations. If `None`, slow operations are not logged.
+    slow_query_logger: Option<Arc<SlowQueryLogger>>,
+    /// If `true`, database operations are wrapped in tracing spans.
+    operation_spans: bool,
+    /// Log of the write operations. If `None`, write operations are not recorded.
+    operation_log: Option<Arc<OperationLog>>,
+    /// Counters of the writes per table. If `None`, writes are not counted.
+    write_profiler: Option<Arc<WriteProfiler>>,
 }
 
 impl Database for DatabaseEnv {
@@ -141,19 +206,30 @@ impl Database for DatabaseEnv {
     type TXMut = tx::Tx<RW>;
 
     fn tx(&self) -> Result<Self::TX, DatabaseError> {
-        Tx::new_with_metrics(
+        Ok(Tx::new_with_metrics(
             self.inner.begin_ro_txn().map_err(|e| DatabaseError::InitTx(e.into()))?,
             self.metrics.as_ref().cloned(),
         )
-        .map_err(|e| DatabaseError::InitTx(e.into()))
+        .map_err(|e| DatabaseError::InitTx(e.into()))?
+        .with_slow_query_logger(self.slow_query_logger.clone())
+        .with_operation_spans(self.operation_spans))
     }
 
     fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
-        Tx::new_with_metrics(
+        let tx = Tx::new_with_metrics(
             self.inner.begin_rw_txn().map_err(|e| DatabaseError::InitTx(e.into()))?,
             self.metrics.as_ref().cloned(),
         )
-        .map_err(|e| DatabaseError::InitTx(e.into()))
+        .map_err(|e| DatabaseError::InitTx(e.into()))?
+        .with_slow_query_logger(self.slow_query_logger.clone())
+        .with_operation_spans(self.operation_spans)
+        .with_write_profiler(self.write_profiler.clone());
+
+        if let Some(operation_log) = &self.operation_log {
+            operation_log.record(LogEntry::Begin);
+        }
+
+        Ok(tx.with_operation_log(self.operation_log.clone()))
     }
 }
 
@@ -235,6 +311,25 @@ impl DatabaseMetrics for DatabaseEnv {
 impl DatabaseMetadata for DatabaseEnv {
     fn metadata(&self) -> DatabaseMetadataValue {
         DatabaseMetadataValue::new(self.freelist().ok())
+            .with_write_profiler(self.write_profiler.clone())
+    }
+}
+
+impl DatabaseTableStats for DatabaseEnv {
+    fn table_stats(&self, table: Tables) -> Result<TableStats, DatabaseError> {
+        let tx = self.tx()?;
+        let table_db =
+            tx.inner.open_db(Some(table.name())).map_err(|e| DatabaseError::Open(e.into()))?;
+        let stats = tx.inner.db_stat(&table_db).map_err(|e| DatabaseError::Stats(e.into()))?;
+
+        let pages = TablePages {
+            page_size: stats.page_size() as usize,
+            branch: stats.branch_pages(),
+            leaf: stats.leaf_pages(),
+            overflow: stats.overflow_pages(),
+        };
+        let size = pages.page_size * (pages.branch + pages.leaf + pages.overflow);
+        Ok(TableStats::new(stats.entries(), size).with_pages(pages))
     }
 }
 
@@ -254,7 +349,12 @@ impl DatabaseEnv {
             DatabaseEnvKind::RW => {
                 // enable writemap mode in RW mode
                 inner_env.write_map();
-                Mode::ReadWrite { sync_mode: SyncMode::Durable }
+                if let Some(sync_period) = args.sync_period {
+                    inner_env.set_sync_period(sync_period);
+                    Mode::ReadWrite { sync_mode: SyncMode::SafeNoSync }
+                } else {
+                    Mode::ReadWrite { sync_mode: SyncMode::Durable }
+                }
             }
         };
 
@@ -375,20 +475,65 @@ impl DatabaseEnv {
             inner_env.set_max_read_transaction_duration(max_read_transaction_duration);
         }
 
+        let operation_log = args
+            .operation_log
+            .map(|log_path| {
+                OperationLog::open(&log_path).map(Arc::new).map_err(|e| {
+                    DatabaseError::Open(DatabaseErrorInfo {
+                        message: format!(
+                            "failed to open operation log {}: {e}",
+                            log_path.display()
+                        ),
+                        code: e.raw_os_error().unwrap_or_default(),
+                    })
+                })
+            })
+            .transpose()?;
+
         let env = DatabaseEnv {
             inner: inner_env.open(path).map_err(|e| DatabaseError::Open(e.into()))?,
             metrics: None,
+            slow_query_logger: args.slow_query_threshold.map(SlowQueryLogger::new).map(Arc::new),
+            operation_spans: args.operation_spans,
+            operation_log,
+            write_profiler: args.write_profile.then(Default::default),
         };
 
         Ok(env)
     }
 
+    /// Returns the counters of the writes per table, if enabled with
+    /// [DatabaseArguments::with_write_profile].
+    pub fn write_profiler(&self) -> Option<Arc<WriteProfiler>> {
+        self.write_profiler.clone()
+    }
+
     /// Enables metrics on the database.
     pub fn with_metrics(mut self) -> Self {
         self.metrics = Some(DatabaseEnvMetrics::new().into());
         self
     }
 
+    /// Flushes the data of all preceding commits to disk.
+    ///
+    /// This is a no-op unless the environment was opened with a sync period, see
+    /// [DatabaseArguments::with_sync_period], in which case the commits since the last flush are
+    /// made durable.
+    pub fn sync(&self) -> Result<(), DatabaseError> {
+        self.inner.sync(true).map_err(|e| DatabaseError::Sync(e.into()))?;
+        Ok(())
+    }
+
+    /// Makes the commits from now on durable, and flushes the data of all preceding commits to
+    /// disk, so that no commit is lost to a system crash, whenever it's made.
+    ///
+    /// This is used before the database is closed, as the writers may still commit after the
+    /// last [DatabaseEnv::sync]. Waits for the current write transaction to finish.
+    pub fn disable_sync_period(&self) -> Result<(), DatabaseError> {
+        self.inner.set_sync_durable().map_err(|e| DatabaseError::Sync(e.into()))?;
+        self.sync()
+    }
+
     /// Creates all the defined tables, if necessary.
     pub fn create_tables(&self) -> Result<(), DatabaseError> {
         let tx = self.inner.begin_rw_txn().map_err(|e| DatabaseError::InitTx(e.into()))?;
@@ -442,7 +587,7 @@ impl Deref for DatabaseEnv {
 mod tests {
     use super::*;
     use crate::{
-        abstraction::table::{Encode, Table},
+        abstraction::table::{Compress, Encode, Table},
         cursor::{DbDupCursorRO, DbDupCursorRW, ReverseWalker, Walker},
         models::{AccountBeforeTx, ShardedKey},
         tables::{
@@ -451,10 +596,14 @@ mod tests {
         test_utils::*,
         AccountChangeSets,
     };
+    use rand::Rng;
     use reth_interfaces::db::{DatabaseWriteError, DatabaseWriteOperation};
     use reth_libmdbx::Error;
     use reth_primitives::{Account, Address, Header, IntegerList, StorageEntry, B256, U256};
-    use std::str::FromStr;
+    use std::{
+        io::{Read, Write},
+        str::FromStr,
+    };
     use tempfile::TempDir;
 
     /// Create database for testing
@@ -507,6 +656,126 @@ mod tests {
         tx.commit().expect(ERROR_COMMIT);
     }
 
+    #[test]
+    fn db_encoded_key_put_get() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+
+        let key = 1u64.encode();
+        let hash = B256::with_last_byte(1);
+
+        // PUT encoded, GET typed
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put_by_encoded_key(Tables::CanonicalHeaders, &key, hash.as_slice()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<CanonicalHeaders>(1).expect(ERROR_GET), Some(hash));
+        assert_eq!(
+            tx.get_by_encoded_key(Tables::CanonicalHeaders, &key).expect(ERROR_GET),
+            Some(hash.to_vec())
+        );
+        assert_eq!(
+            tx.get_by_encoded_key(Tables::CanonicalHeaders, &2u64.encode()).expect(ERROR_GET),
+            None
+        );
+        tx.commit().expect(ERROR_COMMIT);
+
+        // Encoded puts to a dupsort table add duplicates, and deletes remove a single one
+        let address = Address::with_last_byte(1);
+        let value00 = StorageEntry::default();
+        let value11 = StorageEntry { key: B256::with_last_byte(1), value: U256::from(1) };
+        let encode_entry = |entry: StorageEntry| -> Vec<u8> { entry.compress() };
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for entry in [value11, value00] {
+            tx.put_by_encoded_key(
+                Tables::PlainStorageState,
+                address.as_slice(),
+                &encode_entry(entry),
+            )
+            .expect(ERROR_PUT);
+        }
+        assert_eq!(
+            tx.get_by_encoded_key(Tables::PlainStorageState, address.as_slice()).expect(ERROR_GET),
+            Some(encode_entry(value00))
+        );
+        assert!(tx
+            .delete_by_encoded_key(
+                Tables::PlainStorageState,
+                address.as_slice(),
+                Some(&encode_entry(value00))
+            )
+            .unwrap());
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+        assert_eq!(cursor.seek_exact(address).unwrap(), Some((address, value11)));
+        assert_eq!(cursor.next_dup().unwrap(), None);
+    }
+
+    #[test]
+    fn db_walk_by_encoded_key() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for number in 0..5u64 {
+            tx.put::<CanonicalHeaders>(number, B256::with_last_byte(number as u8))
+                .expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let walk = |start: Option<u64>, end: Option<u64>, limit: usize| {
+            let (start, end) = (start.map(Encode::encode), end.map(Encode::encode));
+            let mut entries = Vec::new();
+            let walked = tx
+                .walk_by_encoded_key(
+                    Tables::CanonicalHeaders,
+                    start.as_ref().map(|key| key.as_slice()),
+                    end.as_ref().map(|key| key.as_slice()),
+                    |key, value| {
+                        entries.push((key.to_vec(), value.to_vec()));
+                        entries.len() < limit
+                    },
+                )
+                .unwrap();
+            assert_eq!(walked, entries.len());
+            entries.into_iter().map(|(key, _)| key[7]).collect::<Vec<_>>()
+        };
+
+        assert_eq!(walk(None, None, usize::MAX), vec![0, 1, 2, 3, 4]);
+        assert_eq!(walk(Some(1), Some(4), usize::MAX), vec![1, 2, 3]);
+        assert_eq!(walk(Some(2), None, 2), vec![2, 3]);
+    }
+
+    #[test]
+    fn db_sync_period() {
+        let path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+        let args = DatabaseArguments::new(ClientVersion::default())
+            .with_sync_period(Some(Duration::from_secs(60)));
+        let env = DatabaseEnv::open(&path, DatabaseEnvKind::RW, args).expect(ERROR_DB_CREATION);
+        env.create_tables().expect(ERROR_TABLE_CREATION);
+
+        let value = Header::default();
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(1, value.clone()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        // Non-durable commits are flushed on demand and when the environment is closed.
+        env.sync().expect("failed to sync");
+
+        // Commits after the sync period is disabled are durable on their own.
+        env.disable_sync_period().expect("failed to disable sync period");
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(2, value.clone()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        drop(env);
+        let env = create_test_db_with_path(DatabaseEnvKind::RO, &path);
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<Headers>(1).expect(ERROR_GET), Some(value.clone()));
+        assert_eq!(tx.get::<Headers>(2).expect(ERROR_GET), Some(value));
+    }
+
     #[test]
     fn db_cursor_walk() {
         let env = create_test_db(DatabaseEnvKind::RW);
@@ -913,6 +1182,63 @@ mod tests {
         tx.commit().expect(ERROR_COMMIT);
     }
 
+    #[test]
+    fn db_cursor_read_your_writes() {
+        let db: Arc<DatabaseEnv> = create_test_db(DatabaseEnvKind::RW);
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        vec![0, 2, 4, 6, 8]
+            .into_iter()
+            .try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO))
+            .expect(ERROR_PUT);
+
+        // A cursor opened before a write observes it.
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        tx.put::<CanonicalHeaders>(1, B256::with_last_byte(1)).expect(ERROR_PUT);
+        assert_eq!(cursor.seek_exact(1), Ok(Some((1, B256::with_last_byte(1)))));
+
+        // A positioned cursor yields the entries put after its position and skips the deleted
+        // ones.
+        assert_eq!(cursor.seek_exact(2), Ok(Some((2, B256::ZERO))));
+        tx.put::<CanonicalHeaders>(3, B256::with_last_byte(3)).expect(ERROR_PUT);
+        assert!(tx.delete::<CanonicalHeaders>(4, None).unwrap());
+        assert_eq!(cursor.next(), Ok(Some((3, B256::with_last_byte(3)))));
+        assert_eq!(cursor.next(), Ok(Some((6, B256::ZERO))));
+
+        // Deleting the current entry through another cursor moves the next step to the entry that
+        // followed it.
+        let mut write_cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        assert_eq!(write_cursor.seek_exact(6), Ok(Some((6, B256::ZERO))));
+        write_cursor.delete_current().unwrap();
+        assert_eq!(cursor.next(), Ok(Some((8, B256::ZERO))));
+
+        // Upserts through a write cursor are observed by reads of the transaction.
+        write_cursor.upsert(8, B256::with_last_byte(8)).expect(ERROR_UPSERT);
+        assert_eq!(tx.get::<CanonicalHeaders>(8), Ok(Some(B256::with_last_byte(8))));
+
+        // A walker in the middle of iteration observes the writes in the same way.
+        let mut walker = cursor.walk(Some(0)).unwrap();
+        assert_eq!(walker.next(), Some(Ok((0, B256::ZERO))));
+        tx.put::<CanonicalHeaders>(5, B256::with_last_byte(5)).expect(ERROR_PUT);
+        assert!(tx.delete::<CanonicalHeaders>(2, None).unwrap());
+        let keys = walker.map(|entry| entry.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(keys, vec![1, 3, 5, 8]);
+
+        // Dup cursors observe the writes to the values of a key.
+        let address = Address::with_last_byte(1);
+        let entry =
+            |slot: u8| StorageEntry { key: B256::with_last_byte(slot), value: U256::from(slot) };
+        let mut dup_cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+        tx.put::<PlainStorageState>(address, entry(1)).expect(ERROR_PUT);
+        assert_eq!(dup_cursor.seek_exact(address), Ok(Some((address, entry(1)))));
+        tx.put::<PlainStorageState>(address, entry(2)).expect(ERROR_PUT);
+        assert_eq!(dup_cursor.next_dup_val(), Ok(Some(entry(2))));
+        assert_eq!(
+            dup_cursor.seek_by_key_subkey(address, B256::with_last_byte(2)),
+            Ok(Some(entry(2)))
+        );
+        tx.commit().expect(ERROR_COMMIT);
+    }
+
     #[test]
     fn db_cursor_append() {
         let db: Arc<DatabaseEnv> = create_test_db(DatabaseEnvKind::RW);
@@ -1289,4 +1615,114 @@ mod tests {
             assert_eq!(list400, list);
         }
     }
+
+    /// Environment variable with the database path of the [db_crash_workload].
+    const CRASH_WORKLOAD_PATH: &str = "RETH_DB_CRASH_WORKLOAD_PATH";
+    /// Number of keys overwritten by every transaction of the [db_crash_workload].
+    const CRASH_WORKLOAD_KEYS: u64 = 1_000;
+    /// Prefix of the [db_crash_workload] output lines acknowledging a commit.
+    const CRASH_WORKLOAD_COMMITTED: &str = "committed ";
+
+    fn crash_workload_value(id: u64) -> B256 {
+        let mut value = B256::ZERO;
+        value[24..].copy_from_slice(&id.to_be_bytes());
+        value
+    }
+
+    fn crash_workload_id(value: B256) -> u64 {
+        u64::from_be_bytes(value[24..].try_into().unwrap())
+    }
+
+    /// Write workload of [db_commit_is_atomic_on_crash]. Every transaction overwrites all keys
+    /// with its own id, and the id is printed once the commit returns.
+    ///
+    /// It's executed in a child process that is killed at a random point.
+    #[test]
+    #[ignore = "executed by db_commit_is_atomic_on_crash in a child process"]
+    fn db_crash_workload() {
+        let path = std::env::var_os(CRASH_WORKLOAD_PATH)
+            .unwrap_or_else(|| panic!("{CRASH_WORKLOAD_PATH} is not set"));
+        let db = create_test_db_with_path(DatabaseEnvKind::RW, Path::new(&path));
+
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut id = tx.get::<CanonicalHeaders>(0).expect(ERROR_GET).map_or(0, crash_workload_id);
+        drop(tx);
+
+        let mut stdout = std::io::stdout();
+        loop {
+            id += 1;
+            let tx = db.tx_mut().expect(ERROR_INIT_TX);
+            for key in 0..CRASH_WORKLOAD_KEYS {
+                tx.put::<CanonicalHeaders>(key, crash_workload_value(id)).expect(ERROR_PUT);
+            }
+            tx.commit().expect(ERROR_COMMIT);
+
+            writeln!(stdout, "{CRASH_WORKLOAD_COMMITTED}{id}").unwrap();
+            stdout.flush().unwrap();
+        }
+    }
+
+    /// Kills the [db_crash_workload] at random points, including in the middle of a commit, and
+    /// verifies that only whole transactions are visible after reopening the database.
+    #[test]
+    fn db_commit_is_atomic_on_crash() {
+        let path = TempDir::new().expect(ERROR_TEMPDIR);
+        let mut rng = rand::thread_rng();
+        let mut last_id = 0;
+
+        for _ in 0..5 {
+            let mut child = std::process::Command::new(std::env::current_exe().unwrap())
+                .args([
+                    "implementation::mdbx::tests::db_crash_workload",
+                    "--exact",
+                    "--ignored",
+                    "--nocapture",
+                ])
+                .env(CRASH_WORKLOAD_PATH, path.path())
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(rng.gen_range(100..500)));
+            child.kill().unwrap();
+
+            let mut output = String::new();
+            child.stdout.take().unwrap().read_to_string(&mut output).unwrap();
+            child.wait().unwrap();
+            let acknowledged = output
+                .lines()
+                .filter_map(|line| line.strip_prefix(CRASH_WORKLOAD_COMMITTED))
+                .map(|id| id.parse::<u64>().unwrap())
+                .last();
+
+            let db = create_test_db_with_path(DatabaseEnvKind::RW, path.path());
+            let tx = db.tx().expect(ERROR_INIT_TX);
+            let values = tx
+                .cursor_read::<CanonicalHeaders>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .map(|entry| entry.map(|(_, value)| value))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            // Either no transaction was committed yet, or all keys were written by the same one.
+            if let Some(first) = values.first() {
+                assert_eq!(values.len() as u64, CRASH_WORKLOAD_KEYS);
+                assert!(values.iter().all(|value| value == first), "partial commit is visible");
+
+                let id = crash_workload_id(*first);
+                assert!(id >= last_id, "committed transaction was lost");
+                last_id = id;
+            }
+
+            // Every commit acknowledged to the workload must have survived the kill.
+            if let Some(acknowledged) = acknowledged {
+                assert!(
+                    last_id >= acknowledged,
+                    "acknowledged transaction {acknowledged} was lost"
+                );
+            }
+        }
+    }
 }
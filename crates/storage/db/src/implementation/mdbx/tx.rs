@@ -1,17 +1,20 @@
 //! Transaction wrapper for libmdbx-sys.
 
-use super::cursor::Cursor;
+use super::cursor::{write_flags, Cursor};
 use crate::{
     metrics::{DatabaseEnvMetrics, Operation, TransactionMode, TransactionOutcome},
+    operation_log::{LogEntry, OperationLog},
+    slow_query::SlowQueryLogger,
     table::{Compress, DupSort, Encode, Table, TableImporter},
     tables::{utils::decode_one, Tables},
     transaction::{DbTx, DbTxMut},
+    write_profile::WriteProfiler,
     DatabaseError,
 };
 use once_cell::sync::OnceCell;
 use reth_interfaces::db::{DatabaseWriteError, DatabaseWriteOperation};
 use reth_libmdbx::{ffi::DBI, CommitLatency, Transaction, TransactionKind, WriteFlags, RW};
-use reth_tracing::tracing::{debug, trace, warn};
+use reth_tracing::tracing::{debug, debug_span, trace, warn};
 use std::{
     backtrace::Backtrace,
     marker::PhantomData,
@@ -37,6 +40,18 @@ pub struct Tx<K: TransactionKind> {
     /// If [Some], then metrics are reported.
     metrics_handler: Option<MetricsHandler<K>>,
 
+    /// Logger for slow database operations. If [Some], slow operations are logged.
+    slow_query_logger: Option<Arc<SlowQueryLogger>>,
+
+    /// If `true`, database operations are wrapped in tracing spans.
+    operation_spans: bool,
+
+    /// Log of the write operations. If [Some], successful writes are recorded.
+    operation_log: Option<Arc<OperationLog>>,
+
+    /// Counters of the writes per table. If [Some], writes and commits are counted.
+    write_profiler: Option<Arc<WriteProfiler>>,
+
     /// Database table handle cache.
     // TODO: Use `std::sync::OnceLock` once `get_or_try_init` is stable.
     db_handles: [OnceCell<DBI>; Tables::COUNT],
@@ -76,7 +91,53 @@ impl<K: TransactionKind> Tx<K> {
         const ONCECELL_DBI_NEW: OnceCell<DBI> = OnceCell::new();
         #[allow(clippy::declare_interior_mutable_const)]
         const DB_HANDLES: [OnceCell<DBI>; Tables::COUNT] = [ONCECELL_DBI_NEW; Tables::COUNT];
-        Self { inner, db_handles: DB_HANDLES, metrics_handler }
+        Self {
+            inner,
+            db_handles: DB_HANDLES,
+            metrics_handler,
+            slow_query_logger: None,
+            operation_spans: false,
+            operation_log: None,
+            write_profiler: None,
+        }
+    }
+
+    /// Enables logging of slow database operations for the transaction and its cursors.
+    pub(crate) fn with_slow_query_logger(
+        mut self,
+        slow_query_logger: Option<Arc<SlowQueryLogger>>,
+    ) -> Self {
+        self.slow_query_logger = slow_query_logger;
+        self
+    }
+
+    /// Enables wrapping of database operations of the transaction and its cursors in tracing
+    /// spans.
+    pub(crate) fn with_operation_spans(mut self, operation_spans: bool) -> Self {
+        self.operation_spans = operation_spans;
+        self
+    }
+
+    /// Enables recording of the successful writes of the transaction and its cursors.
+    pub(crate) fn with_operation_log(mut self, operation_log: Option<Arc<OperationLog>>) -> Self {
+        self.operation_log = operation_log;
+        self
+    }
+
+    /// Enables counting of the writes of the transaction and its cursors.
+    pub(crate) fn with_write_profiler(
+        mut self,
+        write_profiler: Option<Arc<WriteProfiler>>,
+    ) -> Self {
+        self.write_profiler = write_profiler;
+        self
+    }
+
+    /// Records the entry to the operation log, if enabled.
+    fn record_operation(&self, entry: impl FnOnce() -> LogEntry) {
+        if let Some(operation_log) = &self.operation_log {
+            operation_log.record(entry());
+        }
     }
 
     /// Gets this transaction ID.
@@ -106,7 +167,11 @@ impl<K: TransactionKind> Tx<K> {
         Ok(Cursor::new_with_metrics(
             inner,
             self.metrics_handler.as_ref().map(|h| h.env_metrics.clone()),
-        ))
+        )
+        .with_slow_query_logger(self.slow_query_logger.clone())
+        .with_operation_spans(self.operation_spans)
+        .with_operation_log(self.operation_log.clone())
+        .with_write_profiler(self.write_profiler.clone()))
     }
 
     /// If `self.metrics_handler == Some(_)`, measure the time it takes to execute the closure and
@@ -118,6 +183,10 @@ impl<K: TransactionKind> Tx<K> {
         outcome: TransactionOutcome,
         f: impl FnOnce(Self) -> (R, Option<CommitLatency>),
     ) -> R {
+        let _span = self.operation_spans.then(|| {
+            debug_span!(target: "storage::db::operation", "db_operation", operation = outcome.as_str())
+                .entered()
+        });
         let run = |tx| {
             let start = Instant::now();
             let (result, commit_latency) = f(tx);
@@ -166,6 +235,15 @@ impl<K: TransactionKind> Tx<K> {
         value_size: Option<usize>,
         f: impl FnOnce(&Transaction<K>) -> R,
     ) -> R {
+        let _span = self.operation_spans.then(|| {
+            debug_span!(
+                target: "storage::db::operation",
+                "db_operation",
+                table = T::NAME,
+                operation = operation.as_str()
+            )
+            .entered()
+        });
         if let Some(metrics_handler) = &self.metrics_handler {
             metrics_handler.log_backtrace_on_long_read_transaction();
             metrics_handler
@@ -279,21 +357,40 @@ impl<K: TransactionKind> DbTx for Tx<K> {
     type DupCursor<T: DupSort> = Cursor<K, T>;
 
     fn get<T: Table>(&self, key: T::Key) -> Result<Option<<T as Table>::Value>, DatabaseError> {
-        self.execute_with_operation_metric::<T, _>(Operation::Get, None, |tx| {
-            tx.get(self.get_dbi::<T>()?, key.encode().as_ref())
+        let key = key.encode();
+        let get = |tx: &Transaction<K>| {
+            tx.get(self.get_dbi::<T>()?, key.as_ref())
                 .map_err(|e| DatabaseError::Read(e.into()))?
                 .map(decode_one::<T>)
                 .transpose()
+        };
+        self.execute_with_operation_metric::<T, _>(Operation::Get, None, |tx| {
+            match &self.slow_query_logger {
+                Some(logger) => {
+                    logger.record(T::NAME, "get", || get(tx), |_| Some(key.as_ref().to_vec()))
+                }
+                None => get(tx),
+            }
         })
     }
 
     fn commit(self) -> Result<bool, DatabaseError> {
-        self.execute_with_close_transaction_metric(TransactionOutcome::Commit, |this| {
-            match this.inner.commit().map_err(|e| DatabaseError::Commit(e.into())) {
+        let operation_log = self.operation_log.clone();
+        let write_profiler = self.write_profiler.clone();
+        let result = self.execute_with_close_transaction_metric(
+            TransactionOutcome::Commit,
+            |this| match this.inner.commit().map_err(|e| DatabaseError::Commit(e.into())) {
                 Ok((v, latency)) => (Ok(v), Some(latency)),
                 Err(e) => (Err(e), None),
-            }
-        })
+            },
+        );
+        if let (Ok(_), Some(operation_log)) = (&result, operation_log) {
+            operation_log.record(LogEntry::Commit);
+        }
+        if let (Ok(_), Some(write_profiler)) = (&result, write_profiler) {
+            write_profiler.record_commit();
+        }
+        result
     }
 
     fn abort(self) {
@@ -339,11 +436,15 @@ impl DbTxMut for Tx<RW> {
     fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
         let key = key.encode();
         let value = value.compress();
-        self.execute_with_operation_metric::<T, _>(
+        let logged =
+            self.operation_log.is_some().then(|| (key.as_ref().to_vec(), value.as_ref().to_vec()));
+        let bytes = key.as_ref().len() + value.as_ref().len();
+        let result = self.execute_with_operation_metric::<T, _>(
             Operation::Put,
             Some(value.as_ref().len()),
             |tx| {
-                tx.put(self.get_dbi::<T>()?, key.as_ref(), value, WriteFlags::UPSERT).map_err(|e| {
+                let flags = write_flags::<T>(WriteFlags::UPSERT);
+                tx.put(self.get_dbi::<T>()?, key.as_ref(), value, flags).map_err(|e| {
                     DatabaseWriteError {
                         info: e.into(),
                         operation: DatabaseWriteOperation::Put,
@@ -353,7 +454,14 @@ impl DbTxMut for Tx<RW> {
                     .into()
                 })
             },
-        )
+        );
+        if let (Ok(()), Some((key, value))) = (&result, logged) {
+            self.record_operation(|| LogEntry::Put { table: T::TABLE, key, value });
+        }
+        if let (Ok(()), Some(write_profiler)) = (&result, &self.write_profiler) {
+            write_profiler.record_put(T::TABLE, bytes);
+        }
+        result
     }
 
     fn delete<T: Table>(
@@ -368,14 +476,28 @@ impl DbTxMut for Tx<RW> {
             data = Some(value.as_ref());
         };
 
-        self.execute_with_operation_metric::<T, _>(Operation::Delete, None, |tx| {
-            tx.del(self.get_dbi::<T>()?, key.encode(), data)
-                .map_err(|e| DatabaseError::Delete(e.into()))
-        })
+        let key = key.encode();
+        let deleted =
+            self.execute_with_operation_metric::<T, _>(Operation::Delete, None, |tx| {
+                tx.del(self.get_dbi::<T>()?, key.as_ref(), data)
+                    .map_err(|e| DatabaseError::Delete(e.into()))
+            })?;
+        if deleted {
+            self.record_operation(|| LogEntry::Delete {
+                table: T::TABLE,
+                key: key.as_ref().to_vec(),
+                value: data.map(<[u8]>::to_vec),
+            });
+            if let Some(write_profiler) = &self.write_profiler {
+                write_profiler.record_delete(T::TABLE);
+            }
+        }
+        Ok(deleted)
     }
 
     fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
         self.inner.clear_db(self.get_dbi::<T>()?).map_err(|e| DatabaseError::Delete(e.into()))?;
+        self.record_operation(|| LogEntry::Clear { table: T::TABLE });
 
         Ok(())
     }
@@ -66,12 +66,26 @@
 /// Traits defining the database abstractions, such as cursors and transactions.
 pub mod abstraction;
 
+/// Cached database for serving the state reads from memory.
+pub mod cache;
+pub mod chunked_tx;
+#[cfg(test)]
+mod conformance;
 mod implementation;
+/// In-memory database for tests and ephemeral nodes.
+pub mod memory;
 mod metrics;
+pub mod operation_log;
+/// Overlay database for buffering writes in memory on top of a read-only database.
+pub mod overlay;
+/// Shadow database for validating a secondary database against the primary one.
+pub mod shadow;
+mod slow_query;
 pub mod static_file;
 pub mod tables;
 mod utils;
 pub mod version;
+pub mod write_profile;
 
 #[cfg(feature = "mdbx")]
 /// Bindings for [MDBX](https://libmdbx.dqdkfa.ru/).
@@ -172,7 +186,10 @@ pub mod test_utils {
     use super::*;
     use crate::{
         database::Database,
-        database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
+        database_metrics::{
+            DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics, DatabaseTableStats,
+            TableStats,
+        },
         models::client_version::ClientVersion,
     };
     use reth_libmdbx::MaxReadTransactionDuration;
@@ -248,6 +265,12 @@ pub mod test_utils {
         }
     }
 
+    impl<DB: DatabaseTableStats> DatabaseTableStats for TempDatabase<DB> {
+        fn table_stats(&self, table: Tables) -> Result<TableStats, DatabaseError> {
+            self.db().table_stats(table)
+        }
+    }
+
     /// Create static_files path for testing
     pub fn create_test_static_files_dir() -> (TempDir, PathBuf) {
         let temp_dir = TempDir::with_prefix("reth-test-static-").expect(ERROR_TEMPDIR);
@@ -0,0 +1,325 @@
+use super::{
+    tx::{first_of_key, remove_key, upsert, TxTables, RW},
+    TableData,
+};
+use crate::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    table::{Compress, Decode, Decompress, DupSort, Encode, Table, TableRow},
+    DatabaseError,
+};
+use reth_interfaces::db::{DatabaseErrorInfo, DatabaseWriteError, DatabaseWriteOperation};
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+
+/// Error code of mdbx `MDBX_KEYEXIST`, so that the errors match the mdbx backend.
+pub(crate) const KEY_EXIST: i32 = -30799;
+/// Error code of mdbx `MDBX_EKEYMISMATCH`.
+pub(crate) const KEY_MISMATCH: i32 = -30418;
+
+/// Encoded `(key, value)` pair.
+pub(crate) type RawEntry = (Vec<u8>, Vec<u8>);
+
+/// Cursor over a table of the [MemoryDatabase](super::MemoryDatabase).
+#[derive(Debug)]
+pub struct MemoryCursor<K, T> {
+    tables: Arc<TxTables>,
+    /// The entry the cursor is positioned at. The entry is kept after it's deleted, so the cursor
+    /// can continue from its position.
+    current: Option<RawEntry>,
+    _phantom: PhantomData<(K, T)>,
+}
+
+impl<K, T: Table> MemoryCursor<K, T> {
+    pub(crate) fn new(tables: Arc<TxTables>) -> Self {
+        Self { tables, current: None, _phantom: PhantomData }
+    }
+
+    fn read<R>(&self, f: impl FnOnce(&TableData) -> R) -> R {
+        self.tables.read(T::TABLE, f)
+    }
+
+    fn write<R>(&self, f: impl FnOnce(&mut TableData) -> R) -> R {
+        self.tables.write(T::TABLE, f)
+    }
+
+    /// Position the cursor at the entry and decode it.
+    fn position(&mut self, entry: Option<RawEntry>) -> PairResult<T> {
+        self.current = entry.clone();
+        entry.map(decode_entry::<T>).transpose()
+    }
+
+    /// Move the cursor to the entry if it exists, otherwise keep the current position.
+    fn advance(&mut self, entry: Option<RawEntry>) -> PairResult<T> {
+        if entry.is_some() {
+            self.current = entry.clone();
+        }
+        entry.map(decode_entry::<T>).transpose()
+    }
+
+    /// Returns the first entry greater than or equal to `(key, value)`. If `exact_key` is `true`,
+    /// only the entries of the key are returned.
+    fn seek_raw(&self, key: &[u8], value: &[u8], exact_key: bool) -> Option<RawEntry> {
+        self.read(|data| {
+            data.range((key.to_vec(), value.to_vec())..)
+                .next()
+                .filter(|(entry_key, _)| !exact_key || entry_key == key)
+                .cloned()
+        })
+    }
+
+    /// Returns the entry following the current one.
+    fn next_raw(&self) -> Option<RawEntry> {
+        self.read(|data| match &self.current {
+            Some(current) => {
+                data.range((Bound::Excluded(current), Bound::Unbounded)).next().cloned()
+            }
+            None => data.first().cloned(),
+        })
+    }
+}
+
+impl<K: Send + Sync, T: Table> DbCursorRO<T> for MemoryCursor<K, T> {
+    fn first(&mut self) -> PairResult<T> {
+        let entry = self.read(|data| data.first().cloned());
+        self.position(entry)
+    }
+
+    fn seek_exact(&mut self, key: T::Key) -> PairResult<T> {
+        let key = key.encode();
+        // Like mdbx, the cursor is positioned at the following key if the key doesn't exist.
+        let entry = self.seek_raw(key.as_ref(), &[], false);
+        self.current = entry.clone();
+        entry.filter(|(found, _)| found == key.as_ref()).map(decode_entry::<T>).transpose()
+    }
+
+    fn seek(&mut self, key: T::Key) -> PairResult<T> {
+        let entry = self.seek_raw(key.encode().as_ref(), &[], false);
+        self.position(entry)
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        let entry = self.next_raw();
+        self.advance(entry)
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        let entry = self.read(|data| match &self.current {
+            Some(current) => {
+                data.range((Bound::Unbounded, Bound::Excluded(current))).next_back().cloned()
+            }
+            None => data.last().cloned(),
+        });
+        self.advance(entry)
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        let entry = self.read(|data| data.last().cloned());
+        self.position(entry)
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        let entry = self.read(|data| self.current.clone().filter(|entry| data.contains(entry)));
+        entry.map(decode_entry::<T>).transpose()
+    }
+
+    fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
+        let start = if let Some(start_key) = start_key {
+            self.seek(start_key).transpose()
+        } else {
+            self.first().transpose()
+        };
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range(
+        &mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start =
+            if let Some(start_key) = start_key { self.seek(start_key) } else { self.last() }
+                .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<K: Send + Sync, T: DupSort> DbDupCursorRO<T> for MemoryCursor<K, T> {
+    fn next_dup(&mut self) -> PairResult<T> {
+        let Some((key, _)) = &self.current else { return Ok(None) };
+        let entry = self.next_raw().filter(|(entry_key, _)| entry_key == key);
+        self.advance(entry)
+    }
+
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        let entry = self.read(|data| match &self.current {
+            Some(current) => data
+                .range((Bound::Excluded(current), Bound::Unbounded))
+                .find(|(key, _)| key != &current.0)
+                .cloned(),
+            None => data.first().cloned(),
+        });
+        self.advance(entry)
+    }
+
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        Ok(self.next_dup()?.map(|(_, value)| value))
+    }
+
+    fn seek_by_key_subkey(&mut self, key: T::Key, subkey: T::SubKey) -> ValueOnlyResult<T> {
+        let entry = self.seek_raw(key.encode().as_ref(), subkey.encode().as_ref(), true);
+        Ok(self.position(entry)?.map(|(_, value)| value))
+    }
+
+    fn walk_dup(
+        &mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        let entry = match (key, subkey) {
+            (Some(key), Some(subkey)) => {
+                self.seek_raw(key.encode().as_ref(), subkey.encode().as_ref(), true)
+            }
+            (Some(key), None) => self.seek_raw(key.encode().as_ref(), &[], true),
+            (None, Some(subkey)) => self
+                .read(|data| data.first().cloned())
+                .and_then(|(key, _)| self.seek_raw(&key, subkey.encode().as_ref(), true)),
+            (None, None) => self.read(|data| data.first().cloned()),
+        };
+        let start = self.position(entry).transpose();
+
+        Ok(DupWalker { cursor: self, start, end_subkey: Bound::Unbounded })
+    }
+}
+
+impl<T: Table> DbCursorRW<T> for MemoryCursor<RW, T> {
+    fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let entry: RawEntry = (key.encode().into(), value.compress().into());
+        if T::TABLE.rejects_duplicate_values() && self.read(|data| data.contains(&entry)) {
+            return Err(write_error::<T>(KEY_EXIST, DatabaseWriteOperation::CursorUpsert, entry.0))
+        }
+        self.write(|data| upsert(data, T::TABLE, entry.0.clone(), entry.1.clone()));
+        self.current = Some(entry);
+        Ok(())
+    }
+
+    fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let entry: RawEntry = (key.encode().into(), value.compress().into());
+        if self.read(|data| first_of_key(data, &entry.0).is_some()) {
+            return Err(write_error::<T>(KEY_EXIST, DatabaseWriteOperation::CursorInsert, entry.0))
+        }
+        self.write(|data| data.insert(entry.clone()));
+        self.current = Some(entry);
+        Ok(())
+    }
+
+    /// Appends the entry to the end of the table. Fails if the entry would not be the last one.
+    /// In dupsort tables, the entry may have the same key as the last one.
+    fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let entry: RawEntry = (key.encode().into(), value.compress().into());
+        let in_order = self.read(|data| match data.last() {
+            Some(last) if T::TABLE.is_dupsort() => &entry > last,
+            Some((last_key, _)) => &entry.0 > last_key,
+            None => true,
+        });
+        if !in_order {
+            return Err(write_error::<T>(
+                KEY_MISMATCH,
+                DatabaseWriteOperation::CursorAppend,
+                entry.0,
+            ))
+        }
+        self.write(|data| data.insert(entry.clone()));
+        self.current = Some(entry);
+        Ok(())
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        if let Some(current) = self.current.clone() {
+            self.write(|data| data.remove(&current));
+        }
+        Ok(())
+    }
+}
+
+impl<T: DupSort> DbDupCursorRW<T> for MemoryCursor<RW, T> {
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        if let Some((key, _)) = self.current.clone() {
+            self.write(|data| remove_key(data, &key));
+        }
+        Ok(())
+    }
+
+    /// Appends the value to the end of the values of the key. Fails if the value is less than the
+    /// last value of the key.
+    fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let entry: RawEntry = (key.encode().into(), value.compress().into());
+        let last = self.read(|data| {
+            data.range((entry.0.clone(), Vec::new())..)
+                .take_while(|(key, _)| key == &entry.0)
+                .last()
+                .cloned()
+        });
+        if last.as_ref().is_some_and(|last| &entry < last) {
+            return Err(write_error::<T>(
+                KEY_MISMATCH,
+                DatabaseWriteOperation::CursorAppendDup,
+                entry.0,
+            ))
+        }
+        if T::TABLE.rejects_duplicate_values() && last.as_ref() == Some(&entry) {
+            return Err(write_error::<T>(
+                KEY_EXIST,
+                DatabaseWriteOperation::CursorAppendDup,
+                entry.0,
+            ))
+        }
+        self.write(|data| data.insert(entry.clone()));
+        self.current = Some(entry);
+        Ok(())
+    }
+}
+
+pub(crate) fn decode_entry<T: Table>((key, value): RawEntry) -> Result<TableRow<T>, DatabaseError> {
+    Ok((T::Key::decode(key)?, T::Value::decompress_owned(value)?))
+}
+
+pub(crate) fn write_error<T: Table>(
+    code: i32,
+    operation: DatabaseWriteOperation,
+    key: Vec<u8>,
+) -> DatabaseError {
+    let message = match code {
+        KEY_EXIST => "key/data pair already exists",
+        _ => "the given key value is mismatched to the current cursor position",
+    };
+    DatabaseWriteError {
+        info: DatabaseErrorInfo { message: message.to_string(), code },
+        operation,
+        table_name: T::NAME,
+        key,
+    }
+    .into()
+}
@@ -0,0 +1,238 @@
+//! In-memory database backend.
+//!
+//! Tables are kept as ordered sets of encoded `(key, value)` pairs, so both regular and dupsort
+//! tables are ordered the same way as in mdbx: by key and then by value, comparing the raw bytes.
+//! Read-only transactions work on a snapshot of the tables. A single read-write transaction is
+//! allowed at a time, and it copies the tables it writes to, publishing them on commit.
+
+use crate::{
+    database::Database,
+    database_metrics::{
+        DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics, DatabaseTableStats, TableStats,
+    },
+    DatabaseError, Tables,
+};
+use metrics::Label;
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Condvar, Mutex, RwLock},
+};
+
+mod cursor;
+pub use cursor::MemoryCursor;
+pub(crate) use cursor::{decode_entry, write_error, RawEntry, KEY_EXIST, KEY_MISMATCH};
+
+mod tx;
+pub(crate) use tx::{remove_key, TxTables};
+pub use tx::{MemoryTx, RO, RW};
+
+/// Encoded `(key, value)` pairs of a table.
+pub(crate) type TableData = BTreeSet<(Vec<u8>, Vec<u8>)>;
+
+/// Contents of all tables, indexed by [Tables] discriminant.
+pub(crate) type Snapshot<D = TableData> = Vec<Arc<D>>;
+
+/// Database that keeps all tables in memory.
+///
+/// Nothing is persisted, so the database is only suitable for tests and ephemeral nodes.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryDatabase {
+    inner: Arc<Inner>,
+}
+
+/// Committed contents of the tables and the writer slot, shared by all transactions.
+#[derive(Debug)]
+pub(crate) struct Inner<D = TableData> {
+    /// The last committed contents of the tables.
+    snapshot: RwLock<Arc<Snapshot<D>>>,
+    /// `true` if a read-write transaction is open.
+    writer: Mutex<bool>,
+    /// Notified when the read-write transaction is closed.
+    writer_closed: Condvar,
+}
+
+impl<D: Default> Default for Inner<D> {
+    fn default() -> Self {
+        Self {
+            snapshot: RwLock::new(Arc::new(vec![Arc::default(); Tables::COUNT])),
+            writer: Mutex::new(false),
+            writer_closed: Condvar::new(),
+        }
+    }
+}
+
+impl<D> Inner<D> {
+    /// Returns the last committed contents of the tables.
+    pub(crate) fn snapshot(&self) -> Arc<Snapshot<D>> {
+        self.snapshot.read().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+
+    /// Replace the contents of the tables with the committed ones.
+    pub(crate) fn publish(&self, snapshot: Snapshot<D>) {
+        *self.snapshot.write().unwrap_or_else(|err| err.into_inner()) = Arc::new(snapshot);
+    }
+
+    /// Wait until no other read-write transaction is open and take the writer slot.
+    pub(crate) fn acquire_writer(&self) {
+        let mut writer = self.writer.lock().unwrap_or_else(|err| err.into_inner());
+        while *writer {
+            writer = self.writer_closed.wait(writer).unwrap_or_else(|err| err.into_inner());
+        }
+        *writer = true;
+    }
+
+    /// Release the writer slot.
+    pub(crate) fn release_writer(&self) {
+        *self.writer.lock().unwrap_or_else(|err| err.into_inner()) = false;
+        self.writer_closed.notify_one();
+    }
+}
+
+impl MemoryDatabase {
+    /// Create new empty in-memory database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Database for MemoryDatabase {
+    type TX = MemoryTx<RO>;
+    type TXMut = MemoryTx<RW>;
+
+    fn tx(&self) -> Result<Self::TX, DatabaseError> {
+        Ok(MemoryTx::new(self.inner.clone(), self.inner.snapshot(), false))
+    }
+
+    /// Blocks until the currently open read-write transaction, if any, is closed.
+    fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
+        self.inner.acquire_writer();
+        Ok(MemoryTx::new(self.inner.clone(), self.inner.snapshot(), true))
+    }
+}
+
+impl DatabaseMetrics for MemoryDatabase {
+    fn gauge_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
+        let snapshot = self.inner.snapshot();
+        Tables::ALL
+            .iter()
+            .map(|table| {
+                (
+                    "db.table_entries",
+                    snapshot[*table as usize].len() as f64,
+                    vec![Label::new("table", table.name())],
+                )
+            })
+            .collect()
+    }
+}
+
+impl DatabaseMetadata for MemoryDatabase {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        DatabaseMetadataValue::new(None)
+    }
+}
+
+impl DatabaseTableStats for MemoryDatabase {
+    fn table_stats(&self, table: Tables) -> Result<TableStats, DatabaseError> {
+        let snapshot = self.inner.snapshot();
+        let data = &snapshot[table as usize];
+        let size = data.iter().map(|(key, value)| key.len() + value.len()).sum();
+        Ok(TableStats::new(data.len(), size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+        table::Table,
+        tables::{CanonicalHeaders, PlainStorageState},
+        test_utils::create_test_rw_db,
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{Address, StorageEntry, B256, U256};
+
+    /// Returns all rows of the table.
+    fn rows<T: Table, DB: Database>(db: &DB) -> Vec<(T::Key, T::Value)> {
+        let tx = db.tx().unwrap();
+        let rows = tx.cursor_read::<T>().unwrap().walk(None).unwrap().collect::<Result<_, _>>();
+        rows.unwrap()
+    }
+
+    /// Apply the same writes to the database.
+    fn write<DB: Database>(db: &DB) {
+        let address = |byte: u8| Address::with_last_byte(byte);
+        let entry = |key: u8, value: u8| StorageEntry {
+            key: B256::with_last_byte(key),
+            value: U256::from(value),
+        };
+
+        let tx = db.tx_mut().unwrap();
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        for number in 0..10 {
+            cursor.append(number, B256::with_last_byte(number as u8)).unwrap();
+        }
+        assert!(cursor.append(5, B256::ZERO).is_err());
+        assert!(cursor.insert(5, B256::ZERO).is_err());
+        cursor.upsert(5, B256::ZERO).unwrap();
+        cursor.seek_exact(7).unwrap();
+        cursor.delete_current().unwrap();
+        assert_eq!(cursor.next().unwrap(), Some((8, B256::with_last_byte(8))));
+
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        for key in [3, 1, 2] {
+            cursor.upsert(address(1), entry(key, key)).unwrap();
+        }
+        cursor.append_dup(address(1), entry(4, 4)).unwrap();
+        assert!(cursor.append_dup(address(1), entry(0, 0)).is_err());
+        cursor.append(address(2), entry(1, 1)).unwrap();
+        cursor.append(address(2), entry(2, 2)).unwrap();
+        cursor.upsert(address(3), entry(1, 1)).unwrap();
+
+        assert_eq!(
+            cursor.seek_by_key_subkey(address(1), B256::with_last_byte(2)).unwrap(),
+            Some(entry(2, 2))
+        );
+        cursor.delete_current().unwrap();
+        assert_eq!(cursor.seek_exact(address(1)).unwrap(), Some((address(1), entry(1, 1))));
+        assert_eq!(cursor.next_dup_val().unwrap(), Some(entry(3, 3)));
+        assert_eq!(cursor.next_no_dup().unwrap(), Some((address(2), entry(1, 1))));
+        cursor.delete_current_duplicates().unwrap();
+        assert_eq!(cursor.seek_exact(address(2)).unwrap(), None);
+        tx.delete::<PlainStorageState>(address(1), Some(entry(4, 4))).unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(100, B256::ZERO).unwrap();
+        drop(tx);
+    }
+
+    #[test]
+    fn matches_mdbx() {
+        let memory = MemoryDatabase::new();
+        let mdbx = create_test_rw_db();
+        write(&memory);
+        write(&mdbx);
+
+        assert_eq!(rows::<CanonicalHeaders, _>(&memory), rows::<CanonicalHeaders, _>(&mdbx));
+        assert_eq!(rows::<PlainStorageState, _>(&memory), rows::<PlainStorageState, _>(&mdbx));
+        assert_eq!(rows::<CanonicalHeaders, _>(&memory).len(), 9);
+    }
+
+    #[test]
+    fn read_transactions_are_isolated() {
+        let db = MemoryDatabase::new();
+        let read = db.tx().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(1, B256::ZERO).unwrap();
+        assert_eq!(tx.get::<CanonicalHeaders>(1).unwrap(), Some(B256::ZERO));
+        assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(1).unwrap(), None);
+        tx.commit().unwrap();
+
+        assert_eq!(read.get::<CanonicalHeaders>(1).unwrap(), None);
+        assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(1).unwrap(), Some(B256::ZERO));
+        assert_eq!(db.tx().unwrap().entries::<CanonicalHeaders>().unwrap(), 1);
+    }
+}
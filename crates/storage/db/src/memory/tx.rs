@@ -0,0 +1,186 @@
+use super::{cursor::MemoryCursor, write_error, Inner, RawEntry, Snapshot, TableData, KEY_EXIST};
+use crate::{
+    table::{Compress, Decompress, DupSort, Encode, Table, TableImporter},
+    transaction::{DbTx, DbTxMut},
+    DatabaseError, Tables,
+};
+use reth_interfaces::db::DatabaseWriteOperation;
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+/// Marker of the read-only [MemoryTx].
+#[derive(Debug)]
+pub struct RO;
+
+/// Marker of the read-write [MemoryTx].
+#[derive(Debug)]
+pub struct RW;
+
+/// Transaction of the [MemoryDatabase](super::MemoryDatabase).
+#[derive(Debug)]
+pub struct MemoryTx<K> {
+    inner: Arc<Inner>,
+    /// Contents of the tables as seen by the transaction, shared with its cursors.
+    tables: Arc<TxTables>,
+    /// `true` if the transaction holds the writer slot of the database.
+    writer: bool,
+    _kind: PhantomData<K>,
+}
+
+impl<K> MemoryTx<K> {
+    /// Create new transaction over the snapshot. If `writer` is `true`, the caller must hold the
+    /// writer slot of the database, which is released when the transaction is closed.
+    pub(crate) fn new(inner: Arc<Inner>, snapshot: Arc<Snapshot>, writer: bool) -> Self {
+        Self { inner, tables: TxTables::new(&snapshot), writer, _kind: PhantomData }
+    }
+}
+
+impl<K> Drop for MemoryTx<K> {
+    fn drop(&mut self) {
+        if self.writer {
+            self.inner.release_writer();
+        }
+    }
+}
+
+impl<K: Send + Sync> DbTx for MemoryTx<K> {
+    type Cursor<T: Table> = MemoryCursor<K, T>;
+    type DupCursor<T: DupSort> = MemoryCursor<K, T>;
+
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        let key = key.encode();
+        let value = self.tables.read(T::TABLE, |data| {
+            first_of_key(data, key.as_ref()).map(|(_, value)| value.clone())
+        });
+        value.map(T::Value::decompress_owned).transpose()
+    }
+
+    fn commit(self) -> Result<bool, DatabaseError> {
+        if self.writer {
+            self.inner.publish(self.tables.snapshot());
+        }
+        Ok(true)
+    }
+
+    fn abort(self) {}
+
+    fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        Ok(MemoryCursor::new(self.tables.clone()))
+    }
+
+    fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        Ok(MemoryCursor::new(self.tables.clone()))
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        Ok(self.tables.read(T::TABLE, |data| data.len()))
+    }
+
+    fn disable_long_read_transaction_safety(&mut self) {}
+}
+
+impl DbTxMut for MemoryTx<RW> {
+    type CursorMut<T: Table> = MemoryCursor<RW, T>;
+    type DupCursorMut<T: DupSort> = MemoryCursor<RW, T>;
+
+    fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let (key, value): RawEntry = (key.encode().into(), value.compress().into());
+        if T::TABLE.rejects_duplicate_values() &&
+            self.tables.read(T::TABLE, |data| data.contains(&(key.clone(), value.clone())))
+        {
+            return Err(write_error::<T>(KEY_EXIST, DatabaseWriteOperation::Put, key))
+        }
+        self.tables.write(T::TABLE, |data| upsert(data, T::TABLE, key, value));
+        Ok(())
+    }
+
+    fn delete<T: Table>(
+        &self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        let key: Vec<u8> = key.encode().into();
+        let value: Option<Vec<u8>> = value.map(|value| value.compress().into());
+        Ok(self.tables.write(T::TABLE, |data| match value {
+            // Values are only matched in dupsort tables, like in mdbx.
+            Some(value) if T::TABLE.is_dupsort() => data.remove(&(key, value)),
+            _ => remove_key(data, &key),
+        }))
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        self.tables.write(T::TABLE, TableData::clear);
+        Ok(())
+    }
+
+    fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
+        Ok(MemoryCursor::new(self.tables.clone()))
+    }
+
+    fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
+        Ok(MemoryCursor::new(self.tables.clone()))
+    }
+}
+
+impl TableImporter for MemoryTx<RW> {}
+
+/// Contents of the tables of a transaction.
+///
+/// Tables are shared with the snapshot the transaction was opened with, and copied on the first
+/// write.
+#[derive(Debug)]
+pub(crate) struct TxTables<D = TableData>(Mutex<Snapshot<D>>);
+
+impl<D> TxTables<D> {
+    pub(crate) fn new(snapshot: &Snapshot<D>) -> Arc<Self> {
+        Arc::new(Self(Mutex::new(snapshot.clone())))
+    }
+
+    /// Returns the current contents of the tables.
+    pub(crate) fn snapshot(&self) -> Snapshot<D> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+
+    /// Execute the closure with the contents of the table.
+    pub(crate) fn read<R>(&self, table: Tables, f: impl FnOnce(&D) -> R) -> R {
+        f(&self.0.lock().unwrap_or_else(|err| err.into_inner())[table as usize])
+    }
+
+    /// Execute the closure with the mutable contents of the table.
+    pub(crate) fn write<R>(&self, table: Tables, f: impl FnOnce(&mut D) -> R) -> R
+    where
+        D: Clone,
+    {
+        let mut tables = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        f(Arc::make_mut(&mut tables[table as usize]))
+    }
+}
+
+/// Returns the first entry of the key.
+pub(crate) fn first_of_key<'a>(data: &'a TableData, key: &[u8]) -> Option<&'a (Vec<u8>, Vec<u8>)> {
+    data.range((key.to_vec(), Vec::new())..).next().filter(|(entry_key, _)| entry_key == key)
+}
+
+/// Insert the entry. In regular tables, it replaces the existing value of the key, while in
+/// dupsort tables, it's added to the existing values of the key.
+pub(crate) fn upsert(data: &mut TableData, table: Tables, key: Vec<u8>, value: Vec<u8>) {
+    if !table.is_dupsort() {
+        remove_key(data, &key);
+    }
+    data.insert((key, value));
+}
+
+/// Remove all entries of the key. Returns `true` if any entry was removed.
+pub(crate) fn remove_key(data: &mut TableData, key: &[u8]) -> bool {
+    let entries = data
+        .range((key.to_vec(), Vec::new())..)
+        .take_while(|(entry_key, _)| entry_key == key)
+        .cloned()
+        .collect::<Vec<_>>();
+    for entry in &entries {
+        data.remove(entry);
+    }
+    !entries.is_empty()
+}
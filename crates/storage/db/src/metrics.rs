@@ -215,6 +215,8 @@ pub(crate) enum Operation {
     CursorDeleteCurrent,
     /// Database cursor delete current duplicates operation.
     CursorDeleteCurrentDuplicates,
+    /// Database cursor batched delete operation.
+    CursorDeleteWhile,
 }
 
 impl Operation {
@@ -230,6 +232,7 @@ impl Operation {
             Operation::CursorAppendDup => "cursor-append-dup",
             Operation::CursorDeleteCurrent => "cursor-delete-current",
             Operation::CursorDeleteCurrentDuplicates => "cursor-delete-current-duplicates",
+            Operation::CursorDeleteWhile => "cursor-delete-while",
         }
     }
 }
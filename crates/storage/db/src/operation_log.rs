@@ -0,0 +1,462 @@
+//! Write-ahead log of the database write operations that can be replayed against another database.
+//!
+//! Every successful write of a read-write transaction is recorded with its raw encoded key and
+//! compressed value. Cursor writes are recorded as the equivalent transaction writes, e.g. a
+//! successful `append` is recorded as a [LogEntry::Put]. Entries of a transaction are framed by
+//! [LogEntry::Begin] and [LogEntry::Commit], so only committed transactions are replayed.
+//!
+//! ```text
+//! entry := kind (u8) | table? | key? | value?
+//! table := length (u8) | name
+//! key, value := length (u32 BE, u32::MAX if absent) | bytes
+//! ```
+
+use crate::{
+    database::Database,
+    table::{DupSort, Key, Table, Value},
+    transaction::{DbTx, DbTxMut},
+    DatabaseError, RawDupSort, RawKey, RawTable, RawValue, TableViewer, Tables,
+};
+use reth_tracing::tracing::warn;
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Read, Write},
+    ops::{Bound, RangeBounds},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+const KIND_BEGIN: u8 = 0;
+const KIND_COMMIT: u8 = 1;
+const KIND_PUT: u8 = 2;
+const KIND_DELETE: u8 = 3;
+const KIND_CLEAR: u8 = 4;
+
+/// Length marker of an absent value.
+const ABSENT: u32 = u32::MAX;
+
+/// Entry of the operation log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogEntry {
+    /// A read-write transaction was opened.
+    Begin,
+    /// The current transaction was committed.
+    Commit,
+    /// The value was written for the key, with the same semantics as [DbTxMut::put].
+    Put {
+        /// Table of the entry.
+        table: Tables,
+        /// Encoded key.
+        key: Vec<u8>,
+        /// Compressed value.
+        value: Vec<u8>,
+    },
+    /// The key was deleted, with the same semantics as [DbTxMut::delete].
+    Delete {
+        /// Table of the entry.
+        table: Tables,
+        /// Encoded key.
+        key: Vec<u8>,
+        /// Compressed value. For dupsort tables, only this value of the key is deleted.
+        value: Option<Vec<u8>>,
+    },
+    /// The table was cleared.
+    Clear {
+        /// Cleared table.
+        table: Tables,
+    },
+}
+
+/// Appends the write operations of the database to a file.
+#[derive(Debug)]
+pub(crate) struct OperationLog {
+    writer: Mutex<BufWriter<File>>,
+    /// Set after the first failed write, after which the log is no longer consistent and nothing
+    /// else is recorded.
+    failed: AtomicBool,
+}
+
+impl OperationLog {
+    /// Open the log at the path, appending to it if it already exists.
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { writer: Mutex::new(BufWriter::new(file)), failed: AtomicBool::new(false) })
+    }
+
+    /// Record the entry. The log is flushed on every commit.
+    pub(crate) fn record(&self, entry: LogEntry) {
+        if self.failed.load(Ordering::Relaxed) {
+            return
+        }
+
+        let mut writer = self.writer.lock().unwrap_or_else(|err| err.into_inner());
+        let result = write_entry(&mut *writer, &entry).and_then(|_| {
+            if entry == LogEntry::Commit {
+                writer.flush()?;
+            }
+            Ok(())
+        });
+        if let Err(err) = result {
+            self.failed.store(true, Ordering::Relaxed);
+            warn!(
+                target: "storage::db::operation_log",
+                %err,
+                "Failed to write the operation log, recording is stopped"
+            );
+        }
+    }
+}
+
+/// Write the entry in the log format.
+pub fn write_entry<W: Write>(writer: &mut W, entry: &LogEntry) -> io::Result<()> {
+    match entry {
+        LogEntry::Begin => writer.write_all(&[KIND_BEGIN]),
+        LogEntry::Commit => writer.write_all(&[KIND_COMMIT]),
+        LogEntry::Put { table, key, value } => {
+            writer.write_all(&[KIND_PUT])?;
+            write_table(writer, *table)?;
+            write_bytes(writer, Some(key))?;
+            write_bytes(writer, Some(value))
+        }
+        LogEntry::Delete { table, key, value } => {
+            writer.write_all(&[KIND_DELETE])?;
+            write_table(writer, *table)?;
+            write_bytes(writer, Some(key))?;
+            write_bytes(writer, value.as_deref())
+        }
+        LogEntry::Clear { table } => {
+            writer.write_all(&[KIND_CLEAR])?;
+            write_table(writer, *table)
+        }
+    }
+}
+
+fn write_table<W: Write>(writer: &mut W, table: Tables) -> io::Result<()> {
+    let name = table.name().as_bytes();
+    writer.write_all(&[name.len() as u8])?;
+    writer.write_all(name)
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: Option<&[u8]>) -> io::Result<()> {
+    match bytes {
+        Some(bytes) => {
+            let length = u32::try_from(bytes.len())
+                .ok()
+                .filter(|length| *length != ABSENT)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "entry too large"))?;
+            writer.write_all(&length.to_be_bytes())?;
+            writer.write_all(bytes)
+        }
+        None => writer.write_all(&ABSENT.to_be_bytes()),
+    }
+}
+
+/// Reads the entries of the operation log.
+#[derive(Debug)]
+pub struct LogReader<R> {
+    reader: R,
+}
+
+impl<R: Read> LogReader<R> {
+    /// Create a new reader of the log.
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Read the next entry. Returns [None] at the end of the log.
+    ///
+    /// An entry truncated by a crash at the end of the log is also treated as the end of the log.
+    pub fn read_entry(&mut self) -> io::Result<Option<LogEntry>> {
+        let mut kind = [0; 1];
+        if self.reader.read(&mut kind)? == 0 {
+            return Ok(None)
+        }
+
+        let entry = match kind[0] {
+            KIND_BEGIN => Ok(LogEntry::Begin),
+            KIND_COMMIT => Ok(LogEntry::Commit),
+            KIND_PUT => self.read_table().and_then(|table| {
+                let key = self.read_bytes()?.ok_or_else(missing_key)?;
+                let value = self.read_bytes()?.ok_or_else(missing_key)?;
+                Ok(LogEntry::Put { table, key, value })
+            }),
+            KIND_DELETE => self.read_table().and_then(|table| {
+                let key = self.read_bytes()?.ok_or_else(missing_key)?;
+                Ok(LogEntry::Delete { table, key, value: self.read_bytes()? })
+            }),
+            KIND_CLEAR => self.read_table().map(|table| LogEntry::Clear { table }),
+            kind => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unknown operation log entry kind: {kind}"),
+                ))
+            }
+        };
+
+        match entry {
+            Ok(entry) => Ok(Some(entry)),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn read_table(&mut self) -> io::Result<Tables> {
+        let mut length = [0; 1];
+        self.reader.read_exact(&mut length)?;
+        let mut name = vec![0; length[0] as usize];
+        self.reader.read_exact(&mut name)?;
+        String::from_utf8_lossy(&name)
+            .parse()
+            .map_err(|err: String| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    fn read_bytes(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut length = [0; 4];
+        self.reader.read_exact(&mut length)?;
+        let length = u32::from_be_bytes(length);
+        if length == ABSENT {
+            return Ok(None)
+        }
+        let mut bytes = vec![0; length as usize];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(Some(bytes))
+    }
+}
+
+impl<R: Read> Iterator for LogReader<R> {
+    type Item = io::Result<LogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_entry().transpose()
+    }
+}
+
+fn missing_key() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "missing key or value in operation log entry")
+}
+
+/// Replay the committed transactions of the log with a sequence number in `sequences` against the
+/// database.
+///
+/// Committed transactions are numbered from 1 in the order of the log, so a database restored from
+/// a copy taken after transaction `n` is brought to its state after transaction `m` by replaying
+/// `n + 1..=m`. Every replayed transaction is applied in a separate transaction. Entries of the
+/// transactions that were not committed, including the last one if the log was truncated, are
+/// skipped. Returns the number of replayed transactions.
+pub fn replay<DB: Database, R: Read>(
+    db: &DB,
+    reader: LogReader<R>,
+    sequences: impl RangeBounds<u64>,
+) -> eyre::Result<u64> {
+    let mut pending = Vec::new();
+    let mut sequence = 0;
+    let mut transactions = 0;
+
+    for entry in reader {
+        match entry? {
+            LogEntry::Begin => pending.clear(),
+            LogEntry::Commit => {
+                sequence += 1;
+                if !sequences.contains(&sequence) {
+                    let past_end = match sequences.end_bound() {
+                        Bound::Included(end) | Bound::Excluded(end) => sequence >= *end,
+                        Bound::Unbounded => false,
+                    };
+                    if past_end {
+                        break
+                    }
+                    pending.clear();
+                    continue
+                }
+
+                let tx = db.tx_mut()?;
+                for entry in pending.drain(..) {
+                    let table = match &entry {
+                        LogEntry::Put { table, .. } |
+                        LogEntry::Delete { table, .. } |
+                        LogEntry::Clear { table } => *table,
+                        LogEntry::Begin | LogEntry::Commit => unreachable!("not pending"),
+                    };
+                    table.view(&ReplayViewer { tx: &tx, entry })?;
+                }
+                tx.commit()?;
+                transactions += 1;
+            }
+            entry => pending.push(entry),
+        }
+    }
+
+    Ok(transactions)
+}
+
+/// Applies a single log entry to the table.
+struct ReplayViewer<'a, TX> {
+    tx: &'a TX,
+    entry: LogEntry,
+}
+
+impl<TX: DbTxMut + DbTx> ReplayViewer<'_, TX> {
+    /// Apply the entry to the raw table. The value of [LogEntry::Delete] is only used for dupsort
+    /// tables, where it selects the deleted duplicate.
+    fn apply<T, K, V>(&self, delete_value: bool) -> Result<(), DatabaseError>
+    where
+        T: Table<Key = RawKey<K>, Value = RawValue<V>>,
+        K: Key,
+        V: Value,
+    {
+        match &self.entry {
+            LogEntry::Put { key, value, .. } => {
+                self.tx.put::<T>(RawKey::from_vec(key.clone()), RawValue::from_vec(value.clone()))
+            }
+            LogEntry::Delete { key, value, .. } => {
+                let value = value.clone().filter(|_| delete_value).map(RawValue::from_vec);
+                self.tx.delete::<T>(RawKey::from_vec(key.clone()), value).map(|_| ())
+            }
+            LogEntry::Clear { .. } => self.tx.clear::<T>(),
+            LogEntry::Begin | LogEntry::Commit => Ok(()),
+        }
+    }
+}
+
+impl<TX: DbTxMut + DbTx> TableViewer<()> for ReplayViewer<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        self.apply::<RawTable<T>, _, _>(false)
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<(), Self::Error> {
+        self.apply::<RawDupSort<T>, _, _>(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+        mdbx::DatabaseArguments,
+        models::client_version::ClientVersion,
+        tables::{CanonicalHeaders, PlainStorageState},
+        test_utils::{create_test_rw_db, tempdir_path},
+        DatabaseEnv, DatabaseEnvKind,
+    };
+    use reth_primitives::{Address, StorageEntry, B256, U256};
+
+    #[test]
+    fn entries_roundtrip() {
+        let entries = vec![
+            LogEntry::Begin,
+            LogEntry::Put { table: Tables::CanonicalHeaders, key: vec![1], value: vec![2; 32] },
+            LogEntry::Delete { table: Tables::PlainStorageState, key: vec![3], value: None },
+            LogEntry::Delete {
+                table: Tables::PlainStorageState,
+                key: vec![3],
+                value: Some(vec![]),
+            },
+            LogEntry::Clear { table: Tables::Headers },
+            LogEntry::Commit,
+        ];
+        let mut log = Vec::new();
+        for entry in &entries {
+            write_entry(&mut log, entry).unwrap();
+        }
+
+        let read = LogReader::new(log.as_slice()).collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(read, entries);
+
+        // A truncated entry is treated as the end of the log.
+        let read = LogReader::new(&log[..log.len() - 3]).collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(read, entries[..entries.len() - 2]);
+    }
+
+    #[test]
+    fn recorded_log_replays_committed_transactions() {
+        let path = tempdir_path();
+        let log_path = path.join("operations.log");
+        let args = DatabaseArguments::new(ClientVersion::default())
+            .with_operation_log(Some(log_path.clone()));
+        let db = DatabaseEnv::open(&path, DatabaseEnvKind::RW, args).unwrap();
+        db.create_tables().unwrap();
+
+        let address = Address::with_last_byte(1);
+        let entry = |key: u8, value: u64| StorageEntry {
+            key: B256::with_last_byte(key),
+            value: U256::from(value),
+        };
+
+        let tx = db.tx_mut().unwrap();
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        for number in 0..10 {
+            cursor.append(number, B256::with_last_byte(number as u8)).unwrap();
+        }
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        for key in 0..5 {
+            cursor.append_dup(address, entry(key, key as u64)).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        tx.delete::<CanonicalHeaders>(3, None).unwrap();
+        tx.delete::<PlainStorageState>(address, Some(entry(1, 1))).unwrap();
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        cursor.seek_by_key_subkey(address, B256::with_last_byte(2)).unwrap();
+        cursor.delete_current().unwrap();
+        tx.put::<CanonicalHeaders>(4, B256::ZERO).unwrap();
+        tx.commit().unwrap();
+
+        // Aborted transactions are not replayed.
+        let tx = db.tx_mut().unwrap();
+        tx.clear::<CanonicalHeaders>().unwrap();
+        drop(tx);
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(10, B256::with_last_byte(10)).unwrap();
+        tx.commit().unwrap();
+        drop(db);
+
+        let original = DatabaseEnv::open(&path, DatabaseEnvKind::RO, Default::default()).unwrap();
+        let replayed = create_test_rw_db();
+        let reader = LogReader::new(File::open(&log_path).unwrap());
+        assert_eq!(replay(&*replayed, reader, ..).unwrap(), 3);
+
+        let dump = |db: &DatabaseEnv| {
+            let tx = db.tx().unwrap();
+            let headers = tx
+                .cursor_read::<CanonicalHeaders>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let storage = tx
+                .cursor_dup_read::<PlainStorageState>()
+                .unwrap()
+                .walk_dup(None, None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            (headers, storage)
+        };
+        let (headers, storage) = dump(&original);
+        assert_eq!(headers.len(), 10);
+        assert_eq!(
+            storage,
+            vec![(address, entry(0, 0)), (address, entry(3, 3)), (address, entry(4, 4))]
+        );
+        assert_eq!(dump(replayed.db()), (headers, storage));
+
+        // Replaying the rest of the log onto a database recovered to the second transaction ends
+        // up in the same state.
+        let recovered = create_test_rw_db();
+        let reader = LogReader::new(File::open(&log_path).unwrap());
+        assert_eq!(replay(&*recovered, reader, ..=2).unwrap(), 2);
+        assert_eq!(dump(recovered.db()).0.len(), 9);
+        let reader = LogReader::new(File::open(&log_path).unwrap());
+        assert_eq!(replay(&*recovered, reader, 3..).unwrap(), 1);
+        assert_eq!(dump(recovered.db()), dump(replayed.db()));
+    }
+}
@@ -0,0 +1,525 @@
+use super::TableChanges;
+use crate::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    memory::{decode_entry, write_error, RawEntry, TxTables, KEY_EXIST, KEY_MISMATCH, RW},
+    table::{Compress, DupSort, Encode, Table},
+    tables::{RawDupSort, RawKey, RawTable, TableRawRow, TableViewer},
+    transaction::DbTx,
+    DatabaseError,
+};
+use reth_interfaces::db::DatabaseWriteOperation;
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+
+/// Result of the operations of the [BaseCursor].
+type RawResult = Result<Option<RawEntry>, DatabaseError>;
+
+/// Cursor over the raw entries of a table of the base database.
+pub(crate) trait BaseCursor: Send + Sync {
+    fn first(&mut self) -> RawResult;
+
+    fn last(&mut self) -> RawResult;
+
+    /// Returns the first entry greater than or equal to `(key, value)`.
+    fn seek(&mut self, key: &[u8], value: &[u8]) -> RawResult;
+
+    fn next(&mut self) -> RawResult;
+
+    fn prev(&mut self) -> RawResult;
+}
+
+/// [BaseCursor] over a regular table, which has a single entry per key.
+struct PlainCursor<T, C>(C, PhantomData<T>);
+
+impl<T: Table, C: DbCursorRO<RawTable<T>> + Send + Sync> BaseCursor for PlainCursor<T, C> {
+    fn first(&mut self) -> RawResult {
+        Ok(self.0.first()?.map(raw_entry::<T>))
+    }
+
+    fn last(&mut self) -> RawResult {
+        Ok(self.0.last()?.map(raw_entry::<T>))
+    }
+
+    fn seek(&mut self, key: &[u8], value: &[u8]) -> RawResult {
+        let entry = self.0.seek(RawKey::from_vec(key.to_vec()))?.map(raw_entry::<T>);
+        match entry {
+            Some((found, found_value)) if found == key && found_value.as_slice() < value => {
+                self.next()
+            }
+            entry => Ok(entry),
+        }
+    }
+
+    fn next(&mut self) -> RawResult {
+        Ok(self.0.next()?.map(raw_entry::<T>))
+    }
+
+    fn prev(&mut self) -> RawResult {
+        Ok(self.0.prev()?.map(raw_entry::<T>))
+    }
+}
+
+/// [BaseCursor] over a dupsort table, which seeks to the values of a key without walking all of
+/// them.
+struct DupCursor<T, C>(C, PhantomData<T>);
+
+impl<T: DupSort, C: DbDupCursorRO<RawDupSort<T>> + DbCursorRO<RawDupSort<T>> + Send + Sync>
+    BaseCursor for DupCursor<T, C>
+{
+    fn first(&mut self) -> RawResult {
+        Ok(self.0.first()?.map(raw_entry::<T>))
+    }
+
+    fn last(&mut self) -> RawResult {
+        Ok(self.0.last()?.map(raw_entry::<T>))
+    }
+
+    fn seek(&mut self, key: &[u8], value: &[u8]) -> RawResult {
+        let raw_key = || RawKey::from_vec(key.to_vec());
+        if !value.is_empty() {
+            let subkey = RawKey::from_vec(value.to_vec());
+            if let Some(value) = self.0.seek_by_key_subkey(raw_key(), subkey)? {
+                return Ok(Some((key.to_vec(), value.into_value())))
+            }
+            // All values of the key are less than the value, so continue from the next key.
+            if self.0.seek_exact(raw_key())?.is_some() {
+                return Ok(self.0.next_no_dup()?.map(raw_entry::<T>))
+            }
+        }
+        Ok(self.0.seek(raw_key())?.map(raw_entry::<T>))
+    }
+
+    fn next(&mut self) -> RawResult {
+        Ok(self.0.next()?.map(raw_entry::<T>))
+    }
+
+    fn prev(&mut self) -> RawResult {
+        Ok(self.0.prev()?.map(raw_entry::<T>))
+    }
+}
+
+fn raw_entry<T: Table>((key, value): TableRawRow<T>) -> RawEntry {
+    (key.into_key(), value.into_value())
+}
+
+/// Opens the [BaseCursor] over the table of the base transaction.
+pub(crate) fn base_cursor<TX: DbTx + 'static, T: Table>(
+    tx: &TX,
+) -> Result<Box<dyn BaseCursor>, DatabaseError> {
+    T::TABLE.view(&BaseCursorViewer(tx))
+}
+
+struct BaseCursorViewer<'a, TX>(&'a TX);
+
+impl<TX: DbTx + 'static> TableViewer<Box<dyn BaseCursor>> for BaseCursorViewer<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<Box<dyn BaseCursor>, Self::Error> {
+        Ok(Box::new(PlainCursor(self.0.cursor_read::<RawTable<T>>()?, PhantomData::<T>)))
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<Box<dyn BaseCursor>, Self::Error> {
+        Ok(Box::new(DupCursor(self.0.cursor_dup_read::<RawDupSort<T>>()?, PhantomData::<T>)))
+    }
+}
+
+/// Cursor of the [OverlayDatabase](super::OverlayDatabase).
+///
+/// Positions of the cursor are tracked by entry rather than by the base cursor, so every move
+/// seeks the base cursor to the current entry first. Entries of the base table that are removed
+/// by the changes are skipped, and the remaining ones are merged with the added entries.
+pub struct OverlayCursor<K, T> {
+    base: Box<dyn BaseCursor>,
+    changes: Arc<TxTables<TableChanges>>,
+    /// The entry the cursor is positioned at. The entry is kept after it's deleted, so the cursor
+    /// can continue from its position.
+    current: Option<RawEntry>,
+    _phantom: PhantomData<(K, T)>,
+}
+
+impl<K, T> fmt::Debug for OverlayCursor<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OverlayCursor")
+            .field("changes", &self.changes)
+            .field("current", &self.current)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, T: Table> OverlayCursor<K, T> {
+    pub(crate) fn new(base: Box<dyn BaseCursor>, changes: Arc<TxTables<TableChanges>>) -> Self {
+        Self { base, changes, current: None, _phantom: PhantomData }
+    }
+
+    /// Position the cursor at the entry and decode it.
+    fn position(&mut self, entry: Option<RawEntry>) -> PairResult<T> {
+        self.current = entry.clone();
+        entry.map(decode_entry::<T>).transpose()
+    }
+
+    /// Move the cursor to the entry if it exists, otherwise keep the current position.
+    fn advance(&mut self, entry: Option<RawEntry>) -> PairResult<T> {
+        if entry.is_some() {
+            self.current = entry.clone();
+        }
+        entry.map(decode_entry::<T>).transpose()
+    }
+
+    fn first_raw(&mut self) -> RawResult {
+        let Self { base, changes, .. } = self;
+        changes.read(T::TABLE, |changes| {
+            let base_entry = base.first()?;
+            let base_entry = skip_deleted_forward(base.as_mut(), changes, base_entry)?;
+            Ok(min_entry(base_entry, changes.added.first().cloned()))
+        })
+    }
+
+    fn last_raw(&mut self) -> RawResult {
+        let Self { base, changes, .. } = self;
+        changes.read(T::TABLE, |changes| {
+            let base_entry = base.last()?;
+            let base_entry = skip_deleted_backward(base.as_mut(), changes, base_entry)?;
+            Ok(max_entry(base_entry, changes.added.last().cloned()))
+        })
+    }
+
+    /// Returns the first entry greater than or equal to `(key, value)`.
+    pub(crate) fn seek_raw(&mut self, key: &[u8], value: &[u8]) -> RawResult {
+        let Self { base, changes, .. } = self;
+        changes.read(T::TABLE, |changes| {
+            let base_entry = base.seek(key, value)?;
+            let base_entry = skip_deleted_forward(base.as_mut(), changes, base_entry)?;
+            let added = changes.added.range((key.to_vec(), value.to_vec())..).next().cloned();
+            Ok(min_entry(base_entry, added))
+        })
+    }
+
+    /// Returns the last entry less than `(key, value)`.
+    fn seek_before_raw(&mut self, key: &[u8], value: &[u8]) -> RawResult {
+        let Self { base, changes, .. } = self;
+        changes.read(T::TABLE, |changes| {
+            let base_entry = seek_before(base.as_mut(), key, value)?;
+            let base_entry = skip_deleted_backward(base.as_mut(), changes, base_entry)?;
+            let added = changes.added.range(..(key.to_vec(), value.to_vec())).next_back().cloned();
+            Ok(max_entry(base_entry, added))
+        })
+    }
+
+    /// Returns the entry following the current one.
+    fn next_raw(&mut self) -> RawResult {
+        let Some(current) = self.current.clone() else { return self.first_raw() };
+        let Self { base, changes, .. } = self;
+        changes.read(T::TABLE, |changes| {
+            let mut base_entry = base.seek(&current.0, &current.1)?;
+            if base_entry.as_ref() == Some(&current) {
+                base_entry = base.next()?;
+            }
+            let base_entry = skip_deleted_forward(base.as_mut(), changes, base_entry)?;
+            let added =
+                changes.added.range((Bound::Excluded(&current), Bound::Unbounded)).next().cloned();
+            Ok(min_entry(base_entry, added))
+        })
+    }
+
+    /// Returns the entry preceding the current one.
+    fn prev_raw(&mut self) -> RawResult {
+        match self.current.clone() {
+            Some((key, value)) => self.seek_before_raw(&key, &value),
+            None => self.last_raw(),
+        }
+    }
+}
+
+/// Returns the last entry of the base cursor less than `(key, value)`.
+fn seek_before(base: &mut dyn BaseCursor, key: &[u8], value: &[u8]) -> RawResult {
+    match base.seek(key, value)? {
+        Some(_) => base.prev(),
+        None => base.last(),
+    }
+}
+
+/// Moves the base cursor forward from the entry until an entry that isn't removed by the changes.
+fn skip_deleted_forward(
+    base: &mut dyn BaseCursor,
+    changes: &TableChanges,
+    mut entry: Option<RawEntry>,
+) -> RawResult {
+    if changes.cleared {
+        return Ok(None)
+    }
+    while let Some(current) = &entry {
+        entry = if changes.deleted_keys.contains(&current.0) {
+            base.seek(&next_key(&current.0), &[])?
+        } else if changes.is_deleted(current) {
+            base.next()?
+        } else {
+            break
+        };
+    }
+    Ok(entry)
+}
+
+/// Moves the base cursor backward from the entry until an entry that isn't removed by the changes.
+fn skip_deleted_backward(
+    base: &mut dyn BaseCursor,
+    changes: &TableChanges,
+    mut entry: Option<RawEntry>,
+) -> RawResult {
+    if changes.cleared {
+        return Ok(None)
+    }
+    while let Some(current) = &entry {
+        entry = if changes.deleted_keys.contains(&current.0) {
+            seek_before(base, &current.0, &[])?
+        } else if changes.is_deleted(current) {
+            base.prev()?
+        } else {
+            break
+        };
+    }
+    Ok(entry)
+}
+
+/// Returns the smallest key greater than the key.
+fn next_key(key: &[u8]) -> Vec<u8> {
+    let mut next = key.to_vec();
+    next.push(0);
+    next
+}
+
+fn min_entry(a: Option<RawEntry>, b: Option<RawEntry>) -> Option<RawEntry> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn max_entry(a: Option<RawEntry>, b: Option<RawEntry>) -> Option<RawEntry> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+impl<K: Send + Sync, T: Table> DbCursorRO<T> for OverlayCursor<K, T> {
+    fn first(&mut self) -> PairResult<T> {
+        let entry = self.first_raw()?;
+        self.position(entry)
+    }
+
+    fn seek_exact(&mut self, key: T::Key) -> PairResult<T> {
+        let key = key.encode();
+        // Like mdbx, the cursor is positioned at the following key if the key doesn't exist.
+        let entry = self.seek_raw(key.as_ref(), &[])?;
+        self.current = entry.clone();
+        entry.filter(|(found, _)| found == key.as_ref()).map(decode_entry::<T>).transpose()
+    }
+
+    fn seek(&mut self, key: T::Key) -> PairResult<T> {
+        let entry = self.seek_raw(key.encode().as_ref(), &[])?;
+        self.position(entry)
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        let entry = self.next_raw()?;
+        self.advance(entry)
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        let entry = self.prev_raw()?;
+        self.advance(entry)
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        let entry = self.last_raw()?;
+        self.position(entry)
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        let Some(current) = self.current.clone() else { return Ok(None) };
+        let entry = self.seek_raw(&current.0, &current.1)?.filter(|entry| entry == &current);
+        entry.map(decode_entry::<T>).transpose()
+    }
+
+    fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
+        let start = if let Some(start_key) = start_key {
+            self.seek(start_key).transpose()
+        } else {
+            self.first().transpose()
+        };
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range(
+        &mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start =
+            if let Some(start_key) = start_key { self.seek(start_key) } else { self.last() }
+                .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<K: Send + Sync, T: DupSort> DbDupCursorRO<T> for OverlayCursor<K, T> {
+    fn next_dup(&mut self) -> PairResult<T> {
+        let Some((key, _)) = self.current.clone() else { return Ok(None) };
+        let entry = self.next_raw()?.filter(|(found, _)| found == &key);
+        self.advance(entry)
+    }
+
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        let entry = match self.current.clone() {
+            Some((key, _)) => self.seek_raw(&next_key(&key), &[])?,
+            None => self.first_raw()?,
+        };
+        self.advance(entry)
+    }
+
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        Ok(self.next_dup()?.map(|(_, value)| value))
+    }
+
+    fn seek_by_key_subkey(&mut self, key: T::Key, subkey: T::SubKey) -> ValueOnlyResult<T> {
+        let key = key.encode();
+        let entry = self
+            .seek_raw(key.as_ref(), subkey.encode().as_ref())?
+            .filter(|(found, _)| found == key.as_ref());
+        Ok(self.position(entry)?.map(|(_, value)| value))
+    }
+
+    fn walk_dup(
+        &mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        let key = match key {
+            Some(key) => Some(key.encode().into()),
+            None => self.first_raw()?.map(|(key, _)| key),
+        };
+        let subkey: Vec<u8> = subkey.map(|subkey| subkey.encode().into()).unwrap_or_default();
+        let entry = match key {
+            Some(key) => self.seek_raw(&key, &subkey)?.filter(|(found, _)| found == &key),
+            None => None,
+        };
+        let start = self.position(entry).transpose();
+
+        Ok(DupWalker { cursor: self, start, end_subkey: Bound::Unbounded })
+    }
+}
+
+impl<T: Table> DbCursorRW<T> for OverlayCursor<RW, T> {
+    fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let entry: RawEntry = (key.encode().into(), value.compress().into());
+        if T::TABLE.rejects_duplicate_values() &&
+            self.seek_raw(&entry.0, &entry.1)?.as_ref() == Some(&entry)
+        {
+            return Err(write_error::<T>(KEY_EXIST, DatabaseWriteOperation::CursorUpsert, entry.0))
+        }
+        self.changes
+            .write(T::TABLE, |changes| changes.put(T::TABLE, entry.0.clone(), entry.1.clone()));
+        self.current = Some(entry);
+        Ok(())
+    }
+
+    fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let entry: RawEntry = (key.encode().into(), value.compress().into());
+        if self.seek_raw(&entry.0, &[])?.is_some_and(|(found, _)| found == entry.0) {
+            return Err(write_error::<T>(KEY_EXIST, DatabaseWriteOperation::CursorInsert, entry.0))
+        }
+        self.changes
+            .write(T::TABLE, |changes| changes.put(T::TABLE, entry.0.clone(), entry.1.clone()));
+        self.current = Some(entry);
+        Ok(())
+    }
+
+    /// Appends the entry to the end of the table. Fails if the entry would not be the last one.
+    /// In dupsort tables, the entry may have the same key as the last one.
+    fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let entry: RawEntry = (key.encode().into(), value.compress().into());
+        let in_order = match self.last_raw()? {
+            Some(last) if T::TABLE.is_dupsort() => entry > last,
+            Some((last_key, _)) => entry.0 > last_key,
+            None => true,
+        };
+        if !in_order {
+            return Err(write_error::<T>(
+                KEY_MISMATCH,
+                DatabaseWriteOperation::CursorAppend,
+                entry.0,
+            ))
+        }
+        self.changes
+            .write(T::TABLE, |changes| changes.put(T::TABLE, entry.0.clone(), entry.1.clone()));
+        self.current = Some(entry);
+        Ok(())
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        if let Some(current) = self.current.clone() {
+            self.changes.write(T::TABLE, |changes| changes.delete_entry(T::TABLE, current));
+        }
+        Ok(())
+    }
+}
+
+impl<T: DupSort> DbDupCursorRW<T> for OverlayCursor<RW, T> {
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        if let Some((key, _)) = self.current.clone() {
+            self.changes.write(T::TABLE, |changes| changes.delete_key(&key));
+        }
+        Ok(())
+    }
+
+    /// Appends the value to the end of the values of the key. Fails if the value is less than the
+    /// last value of the key.
+    fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let entry: RawEntry = (key.encode().into(), value.compress().into());
+        let last = self
+            .seek_before_raw(&next_key(&entry.0), &[])?
+            .filter(|(last_key, _)| last_key == &entry.0);
+        if last.as_ref().is_some_and(|last| &entry < last) {
+            return Err(write_error::<T>(
+                KEY_MISMATCH,
+                DatabaseWriteOperation::CursorAppendDup,
+                entry.0,
+            ))
+        }
+        if T::TABLE.rejects_duplicate_values() && last.as_ref() == Some(&entry) {
+            return Err(write_error::<T>(
+                KEY_EXIST,
+                DatabaseWriteOperation::CursorAppendDup,
+                entry.0,
+            ))
+        }
+        self.changes
+            .write(T::TABLE, |changes| changes.put(T::TABLE, entry.0.clone(), entry.1.clone()));
+        self.current = Some(entry);
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+//! Overlay database that buffers all writes in memory on top of a base database.
+//!
+//! The base database is only read from, so the overlay can be used for dry-run execution,
+//! simulations and testing unwinds without mutating the real data directory. Cursors merge the
+//! entries of the base database with the buffered changes, in the same order as mdbx.
+
+use crate::{
+    cursor::DbCursorRO,
+    database::Database,
+    database_metrics::{
+        DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics, DatabaseTableStats, TableStats,
+    },
+    memory::{remove_key, Inner, RawEntry, TableData, RO, RW},
+    table::Table,
+    transaction::DbTx,
+    DatabaseError, RawTable, TableViewer, Tables,
+};
+use std::{collections::BTreeSet, sync::Arc};
+
+mod cursor;
+pub use cursor::OverlayCursor;
+
+mod tx;
+pub use tx::OverlayTx;
+
+/// Database that layers an in-memory change set over a read-only base database.
+///
+/// Committed changes are visible to all later transactions of the overlay, but are never written
+/// to the base database. As in [MemoryDatabase](crate::memory::MemoryDatabase), a single
+/// read-write transaction is allowed at a time.
+#[derive(Debug)]
+pub struct OverlayDatabase<DB> {
+    /// The base database.
+    base: DB,
+    /// Changes committed on top of the base database.
+    changes: Arc<Inner<TableChanges>>,
+}
+
+impl<DB> OverlayDatabase<DB> {
+    /// Create new overlay without any changes over the base database.
+    pub fn new(base: DB) -> Self {
+        Self { base, changes: Arc::default() }
+    }
+
+    /// Returns the base database.
+    pub fn base(&self) -> &DB {
+        &self.base
+    }
+}
+
+impl<DB: Database> Database for OverlayDatabase<DB> {
+    type TX = OverlayTx<DB::TX, RO>;
+    type TXMut = OverlayTx<DB::TX, RW>;
+
+    fn tx(&self) -> Result<Self::TX, DatabaseError> {
+        Ok(OverlayTx::new(self.base.tx()?, self.changes.clone(), false))
+    }
+
+    /// Blocks until the currently open read-write transaction, if any, is closed.
+    fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
+        let base = self.base.tx()?;
+        self.changes.acquire_writer();
+        Ok(OverlayTx::new(base, self.changes.clone(), true))
+    }
+}
+
+impl<DB: DatabaseMetrics> DatabaseMetrics for OverlayDatabase<DB> {
+    fn report_metrics(&self) {
+        self.base.report_metrics()
+    }
+}
+
+impl<DB: DatabaseMetadata> DatabaseMetadata for OverlayDatabase<DB> {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        self.base.metadata()
+    }
+}
+
+/// The size of the merged table is only known by walking it, so the stats of the overlay are
+/// computed from its raw entries and don't include page usage.
+impl<DB: Database> DatabaseTableStats for OverlayDatabase<DB> {
+    fn table_stats(&self, table: Tables) -> Result<TableStats, DatabaseError> {
+        struct StatsViewer<'a, TX>(&'a TX);
+
+        impl<TX: DbTx> TableViewer<TableStats> for StatsViewer<'_, TX> {
+            type Error = DatabaseError;
+
+            fn view<T: Table>(&self) -> Result<TableStats, Self::Error> {
+                let mut cursor = self.0.cursor_read::<RawTable<T>>()?;
+                let (mut entries, mut size) = (0, 0);
+                for row in cursor.walk(None)? {
+                    let (key, value) = row?;
+                    entries += 1;
+                    size += key.raw_key().len() + value.raw_value().len();
+                }
+                Ok(TableStats::new(entries, size))
+            }
+        }
+
+        let tx = self.tx()?;
+        table.view(&StatsViewer(&tx))
+    }
+}
+
+/// Changes of a table on top of the base database.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TableChanges {
+    /// `true` if all entries of the base table are removed.
+    cleared: bool,
+    /// Keys whose entries in the base table are removed.
+    deleted_keys: BTreeSet<Vec<u8>>,
+    /// Entries of the base table that are removed.
+    deleted_entries: BTreeSet<RawEntry>,
+    /// Entries added on top of the base table.
+    added: TableData,
+}
+
+impl TableChanges {
+    /// Returns `true` if the table is the same as in the base database.
+    fn is_empty(&self) -> bool {
+        !self.cleared &&
+            self.deleted_keys.is_empty() &&
+            self.deleted_entries.is_empty() &&
+            self.added.is_empty()
+    }
+
+    /// Returns `true` if the entry of the base table is removed.
+    fn is_deleted(&self, entry: &RawEntry) -> bool {
+        self.cleared || self.deleted_keys.contains(&entry.0) || self.deleted_entries.contains(entry)
+    }
+
+    /// Insert the entry. In regular tables, it replaces the existing value of the key, while in
+    /// dupsort tables, it's added to the existing values of the key.
+    fn put(&mut self, table: Tables, key: Vec<u8>, value: Vec<u8>) {
+        if !table.is_dupsort() {
+            self.delete_key(&key);
+        }
+        self.added.insert((key, value));
+    }
+
+    /// Remove all entries of the key.
+    fn delete_key(&mut self, key: &[u8]) {
+        remove_key(&mut self.added, key);
+        if !self.cleared {
+            self.deleted_keys.insert(key.to_vec());
+        }
+    }
+
+    /// Remove the entry. In regular tables, the key has a single entry, so the key is removed.
+    fn delete_entry(&mut self, table: Tables, entry: RawEntry) {
+        if !table.is_dupsort() {
+            self.delete_key(&entry.0);
+        } else {
+            self.added.remove(&entry);
+            if !self.cleared {
+                self.deleted_entries.insert(entry);
+            }
+        }
+    }
+
+    /// Remove all entries of the table.
+    fn clear(&mut self) {
+        *self = Self { cleared: true, ..Default::default() };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+        memory::MemoryDatabase,
+        table::Table,
+        tables::{CanonicalHeaders, PlainAccountState, PlainStorageState},
+        test_utils::create_test_rw_db,
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{Account, Address, StorageEntry, B256, U256};
+
+    fn entry(key: u8, value: u8) -> StorageEntry {
+        StorageEntry { key: B256::with_last_byte(key), value: U256::from(value) }
+    }
+
+    /// Returns all rows of the table, walking forwards and backwards.
+    fn rows<T: Table, DB: Database>(db: &DB) -> (Vec<(T::Key, T::Value)>, Vec<(T::Key, T::Value)>) {
+        let tx = db.tx().unwrap();
+        let mut cursor = tx.cursor_read::<T>().unwrap();
+        let forward = cursor.walk(None).unwrap().collect::<Result<_, _>>().unwrap();
+        let backward = cursor.walk_back(None).unwrap().collect::<Result<_, _>>().unwrap();
+        (forward, backward)
+    }
+
+    /// Assert that all tables used by the tests have the same rows in both databases.
+    fn assert_same_rows<A: Database, B: Database>(a: &A, b: &B) {
+        assert_eq!(rows::<CanonicalHeaders, _>(a), rows::<CanonicalHeaders, _>(b));
+        assert_eq!(rows::<PlainStorageState, _>(a), rows::<PlainStorageState, _>(b));
+        assert_eq!(rows::<PlainAccountState, _>(a), rows::<PlainAccountState, _>(b));
+    }
+
+    /// Fill the database with the initial data.
+    fn fill<DB: Database>(db: &DB) {
+        let tx = db.tx_mut().unwrap();
+        for number in 0..10 {
+            tx.put::<CanonicalHeaders>(number, B256::with_last_byte(number as u8)).unwrap();
+        }
+        for byte in 1..4 {
+            for key in [1, 3, 5] {
+                tx.put::<PlainStorageState>(Address::with_last_byte(byte), entry(key, key))
+                    .unwrap();
+            }
+            tx.put::<PlainAccountState>(Address::with_last_byte(byte), Account::default()).unwrap();
+        }
+        tx.commit().unwrap();
+    }
+
+    /// Apply the same changes to the database.
+    fn modify<DB: Database>(db: &DB) {
+        let address = |byte: u8| Address::with_last_byte(byte);
+
+        let tx = db.tx_mut().unwrap();
+        assert!(tx.delete::<CanonicalHeaders>(3, None).unwrap());
+        assert!(!tx.delete::<CanonicalHeaders>(3, None).unwrap());
+        tx.put::<CanonicalHeaders>(5, B256::ZERO).unwrap();
+        tx.put::<CanonicalHeaders>(20, B256::ZERO).unwrap();
+
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        assert!(cursor.insert(4, B256::ZERO).is_err());
+        assert!(cursor.append(19, B256::ZERO).is_err());
+        cursor.append(21, B256::ZERO).unwrap();
+        assert_eq!(cursor.seek(3).unwrap(), Some((4, B256::with_last_byte(4))));
+        assert_eq!(cursor.prev().unwrap(), Some((2, B256::with_last_byte(2))));
+        cursor.delete_current().unwrap();
+        assert_eq!(cursor.next().unwrap(), Some((4, B256::with_last_byte(4))));
+        assert_eq!(cursor.next().unwrap(), Some((5, B256::ZERO)));
+
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        cursor.upsert(address(1), entry(2, 2)).unwrap();
+        cursor.upsert(address(1), entry(6, 6)).unwrap();
+        assert_eq!(
+            cursor.seek_by_key_subkey(address(1), B256::with_last_byte(3)).unwrap(),
+            Some(entry(3, 3))
+        );
+        cursor.delete_current().unwrap();
+        assert_eq!(cursor.next_dup_val().unwrap(), Some(entry(5, 5)));
+        assert_eq!(cursor.next_dup_val().unwrap(), Some(entry(6, 6)));
+        assert_eq!(cursor.next_dup_val().unwrap(), None);
+        assert_eq!(cursor.next_no_dup().unwrap(), Some((address(2), entry(1, 1))));
+        cursor.delete_current_duplicates().unwrap();
+        assert_eq!(cursor.seek_exact(address(2)).unwrap(), None);
+        assert!(cursor.append_dup(address(3), entry(4, 4)).is_err());
+        cursor.append_dup(address(3), entry(7, 7)).unwrap();
+        assert!(tx.delete::<PlainStorageState>(address(3), Some(entry(5, 5))).unwrap());
+
+        tx.clear::<PlainAccountState>().unwrap();
+        tx.put::<PlainAccountState>(address(2), Account { nonce: 1, ..Default::default() })
+            .unwrap();
+        assert_eq!(tx.entries::<PlainAccountState>().unwrap(), 1);
+        tx.commit().unwrap();
+    }
+
+    #[test]
+    fn matches_memory_database() {
+        let base = create_test_rw_db();
+        fill(&base);
+        let overlay = OverlayDatabase::new(base);
+        modify(&overlay);
+
+        let expected = MemoryDatabase::new();
+        fill(&expected);
+        modify(&expected);
+        assert_same_rows(&overlay, &expected);
+
+        // The base database is left untouched.
+        let unmodified = MemoryDatabase::new();
+        fill(&unmodified);
+        assert_same_rows(overlay.base(), &unmodified);
+    }
+
+    #[test]
+    fn table_stats_match_memory_database() {
+        let base = create_test_rw_db();
+        fill(&base);
+        let overlay = OverlayDatabase::new(base);
+        modify(&overlay);
+
+        let expected = MemoryDatabase::new();
+        fill(&expected);
+        modify(&expected);
+        for table in
+            [Tables::CanonicalHeaders, Tables::PlainStorageState, Tables::PlainAccountState]
+        {
+            assert_eq!(overlay.table_stats(table).unwrap(), expected.table_stats(table).unwrap());
+        }
+    }
+
+    #[test]
+    fn uncommitted_changes_are_discarded() {
+        let base = create_test_rw_db();
+        fill(&base);
+        let overlay = OverlayDatabase::new(base);
+
+        let tx = overlay.tx_mut().unwrap();
+        tx.clear::<CanonicalHeaders>().unwrap();
+        assert_eq!(tx.get::<CanonicalHeaders>(1).unwrap(), None);
+        assert_eq!(overlay.tx().unwrap().entries::<CanonicalHeaders>().unwrap(), 10);
+        drop(tx);
+
+        let tx = overlay.tx().unwrap();
+        assert_eq!(tx.get::<CanonicalHeaders>(1).unwrap(), Some(B256::with_last_byte(1)));
+    }
+}
@@ -0,0 +1,150 @@
+use super::{cursor::base_cursor, OverlayCursor, TableChanges};
+use crate::{
+    cursor::DbCursorRO,
+    memory::{write_error, Inner, RawEntry, TxTables, KEY_EXIST, RW},
+    table::{Compress, DupSort, Encode, Table, TableImporter},
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_interfaces::db::DatabaseWriteOperation;
+use std::{marker::PhantomData, sync::Arc};
+
+/// Transaction of the [OverlayDatabase](super::OverlayDatabase).
+///
+/// Reads are served from the changes of the overlay, falling back to the read-only transaction of
+/// the base database.
+#[derive(Debug)]
+pub struct OverlayTx<TX, K> {
+    /// The read-only transaction of the base database.
+    base: TX,
+    inner: Arc<Inner<TableChanges>>,
+    /// Changes as seen by the transaction, shared with its cursors.
+    changes: Arc<TxTables<TableChanges>>,
+    /// `true` if the transaction holds the writer slot of the overlay.
+    writer: bool,
+    _kind: PhantomData<K>,
+}
+
+impl<TX, K> OverlayTx<TX, K> {
+    /// Create new transaction over the committed changes. If `writer` is `true`, the caller must
+    /// hold the writer slot of the overlay, which is released when the transaction is closed.
+    pub(crate) fn new(base: TX, inner: Arc<Inner<TableChanges>>, writer: bool) -> Self {
+        let changes = TxTables::new(&inner.snapshot());
+        Self { base, inner, changes, writer, _kind: PhantomData }
+    }
+
+    /// Returns the read-only transaction of the base database.
+    pub fn base(&self) -> &TX {
+        &self.base
+    }
+}
+
+impl<TX: DbTx + 'static, K> OverlayTx<TX, K> {
+    fn cursor<T: Table>(&self) -> Result<OverlayCursor<K, T>, DatabaseError> {
+        Ok(OverlayCursor::new(base_cursor::<TX, T>(&self.base)?, self.changes.clone()))
+    }
+}
+
+impl<TX, K> Drop for OverlayTx<TX, K> {
+    fn drop(&mut self) {
+        if self.writer {
+            self.inner.release_writer();
+        }
+    }
+}
+
+impl<TX: DbTx + 'static, K: Send + Sync> DbTx for OverlayTx<TX, K> {
+    type Cursor<T: Table> = OverlayCursor<K, T>;
+    type DupCursor<T: DupSort> = OverlayCursor<K, T>;
+
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        Ok(self.cursor::<T>()?.seek_exact(key)?.map(|(_, value)| value))
+    }
+
+    fn commit(self) -> Result<bool, DatabaseError> {
+        if self.writer {
+            self.inner.publish(self.changes.snapshot());
+        }
+        Ok(true)
+    }
+
+    fn abort(self) {}
+
+    fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        self.cursor()
+    }
+
+    fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        self.cursor()
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        if self.changes.read(T::TABLE, TableChanges::is_empty) {
+            return self.base.entries::<T>()
+        }
+        self.cursor::<T>()?.walk(None)?.try_fold(0, |entries, row| row.map(|_| entries + 1))
+    }
+
+    fn disable_long_read_transaction_safety(&mut self) {
+        self.base.disable_long_read_transaction_safety()
+    }
+}
+
+impl<TX: DbTx + 'static> DbTxMut for OverlayTx<TX, RW> {
+    type CursorMut<T: Table> = OverlayCursor<RW, T>;
+    type DupCursorMut<T: DupSort> = OverlayCursor<RW, T>;
+
+    fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let entry: RawEntry = (key.encode().into(), value.compress().into());
+        if T::TABLE.rejects_duplicate_values() &&
+            self.cursor::<T>()?.seek_raw(&entry.0, &entry.1)?.as_ref() == Some(&entry)
+        {
+            return Err(write_error::<T>(KEY_EXIST, DatabaseWriteOperation::Put, entry.0))
+        }
+        let (key, value) = entry;
+        self.changes.write(T::TABLE, |changes| changes.put(T::TABLE, key, value));
+        Ok(())
+    }
+
+    fn delete<T: Table>(
+        &self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        let key: Vec<u8> = key.encode().into();
+        let value: Option<Vec<u8>> = value.map(|value| value.compress().into());
+        let mut cursor = self.cursor::<T>()?;
+        match value {
+            // Values are only matched in dupsort tables, like in mdbx.
+            Some(value) if T::TABLE.is_dupsort() => {
+                let entry = (key, value);
+                if cursor.seek_raw(&entry.0, &entry.1)?.as_ref() != Some(&entry) {
+                    return Ok(false)
+                }
+                self.changes.write(T::TABLE, |changes| changes.delete_entry(T::TABLE, entry));
+            }
+            _ => {
+                if !cursor.seek_raw(&key, &[])?.is_some_and(|(found, _)| found == key) {
+                    return Ok(false)
+                }
+                self.changes.write(T::TABLE, |changes| changes.delete_key(&key));
+            }
+        }
+        Ok(true)
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        self.changes.write(T::TABLE, TableChanges::clear);
+        Ok(())
+    }
+
+    fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
+        self.cursor()
+    }
+
+    fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
+        self.cursor()
+    }
+}
+
+impl<TX: DbTx + 'static> TableImporter for OverlayTx<TX, RW> {}
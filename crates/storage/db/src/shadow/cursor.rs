@@ -0,0 +1,294 @@
+use super::{
+    compare_rows, compare_values, duplicate_value, record_divergence, record_secondary_error,
+};
+use crate::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    table::{DupSort, Table},
+    DatabaseError,
+};
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    sync::{atomic::AtomicU64, Arc},
+};
+
+/// Cursor of the [ShadowDatabase](super::ShadowDatabase).
+///
+/// All operations are executed against both cursors to keep their positions in sync.
+/// If the secondary cursor is [None], the operations are executed only against the primary
+/// cursor.
+#[derive(Debug)]
+pub struct ShadowCursor<T, P, S> {
+    /// The primary database cursor.
+    primary: P,
+    /// The secondary database cursor.
+    secondary: Option<S>,
+    /// The number of detected divergences.
+    divergences: Arc<AtomicU64>,
+    _table: PhantomData<T>,
+}
+
+impl<T: Table, P, S> ShadowCursor<T, P, S> {
+    pub(crate) fn new(primary: P, secondary: Option<S>, divergences: Arc<AtomicU64>) -> Self {
+        Self { primary, secondary, divergences, _table: PhantomData }
+    }
+
+    /// Execute the operation against the secondary cursor if any.
+    /// Errors are recorded as divergences.
+    fn on_secondary<R>(
+        &mut self,
+        operation: &'static str,
+        f: impl FnOnce(&mut S) -> Result<R, DatabaseError>,
+    ) -> Option<R> {
+        let secondary = self.secondary.as_mut()?;
+        f(secondary)
+            .map_err(|error| {
+                record_secondary_error(&self.divergences, Some(T::NAME), operation, error)
+            })
+            .ok()
+    }
+
+    /// Execute the read operation against both cursors and compare the resulting rows.
+    fn read_row(
+        &mut self,
+        operation: &'static str,
+        primary: impl FnOnce(&mut P) -> PairResult<T>,
+        secondary: impl FnOnce(&mut S) -> PairResult<T>,
+    ) -> PairResult<T> {
+        let primary = primary(&mut self.primary)?;
+        let Some(secondary) = self.on_secondary(operation, secondary) else { return Ok(primary) };
+
+        let (row, diverged) = compare_rows::<T>(primary, secondary)?;
+        if diverged {
+            record_divergence(&self.divergences, T::NAME, operation);
+        }
+        Ok(row)
+    }
+
+    /// Execute the read operation against both cursors and compare the resulting values.
+    fn read_value(
+        &mut self,
+        operation: &'static str,
+        primary: impl FnOnce(&mut P) -> ValueOnlyResult<T>,
+        secondary: impl FnOnce(&mut S) -> ValueOnlyResult<T>,
+    ) -> ValueOnlyResult<T> {
+        let primary = primary(&mut self.primary)?;
+        let Some(secondary) = self.on_secondary(operation, secondary) else { return Ok(primary) };
+
+        let (value, diverged) = compare_values(primary, secondary)?;
+        if diverged {
+            record_divergence(&self.divergences, T::NAME, operation);
+        }
+        Ok(value)
+    }
+
+    /// Execute the write operation against both cursors.
+    fn write(
+        &mut self,
+        operation: &'static str,
+        primary: impl FnOnce(&mut P) -> Result<(), DatabaseError>,
+        secondary: impl FnOnce(&mut S) -> Result<(), DatabaseError>,
+    ) -> Result<(), DatabaseError> {
+        primary(&mut self.primary)?;
+        self.on_secondary(operation, secondary);
+        Ok(())
+    }
+}
+
+impl<T, P, S> DbCursorRO<T> for ShadowCursor<T, P, S>
+where
+    T: Table,
+    P: DbCursorRO<T>,
+    S: DbCursorRO<T>,
+{
+    fn first(&mut self) -> PairResult<T> {
+        self.read_row("first", |c| c.first(), |c| c.first())
+    }
+
+    fn seek_exact(&mut self, key: T::Key) -> PairResult<T> {
+        let secondary_key = key.clone();
+        self.read_row("seek_exact", |c| c.seek_exact(key), |c| c.seek_exact(secondary_key))
+    }
+
+    fn seek(&mut self, key: T::Key) -> PairResult<T> {
+        let secondary_key = key.clone();
+        self.read_row("seek", |c| c.seek(key), |c| c.seek(secondary_key))
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        self.read_row("next", |c| c.next(), |c| c.next())
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        self.read_row("prev", |c| c.prev(), |c| c.prev())
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        self.read_row("last", |c| c.last(), |c| c.last())
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        self.read_row("current", |c| c.current(), |c| c.current())
+    }
+
+    fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
+        let start = match start_key {
+            Some(start_key) => self.seek(start_key),
+            None => self.first(),
+        }
+        .transpose();
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range(
+        &mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start = match start_key {
+            Some(start_key) => self.seek(start_key),
+            None => self.last(),
+        }
+        .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<T, P, S> DbDupCursorRO<T> for ShadowCursor<T, P, S>
+where
+    T: DupSort,
+    P: DbDupCursorRO<T> + DbCursorRO<T>,
+    S: DbDupCursorRO<T> + DbCursorRO<T>,
+{
+    fn next_dup(&mut self) -> PairResult<T> {
+        self.read_row("next_dup", |c| c.next_dup(), |c| c.next_dup())
+    }
+
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        self.read_row("next_no_dup", |c| c.next_no_dup(), |c| c.next_no_dup())
+    }
+
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        self.read_value("next_dup_val", |c| c.next_dup_val(), |c| c.next_dup_val())
+    }
+
+    fn seek_by_key_subkey(&mut self, key: T::Key, subkey: T::SubKey) -> ValueOnlyResult<T> {
+        let (secondary_key, secondary_subkey) = (key.clone(), subkey.clone());
+        self.read_value(
+            "seek_by_key_subkey",
+            |c| c.seek_by_key_subkey(key, subkey),
+            |c| c.seek_by_key_subkey(secondary_key, secondary_subkey),
+        )
+    }
+
+    fn walk_dup(
+        &mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        let start = match (key, subkey) {
+            (Some(key), Some(subkey)) => self
+                .seek_by_key_subkey(key.clone(), subkey)
+                .map(|value| value.map(|value| (key, value)))
+                .transpose(),
+            (Some(key), None) => self.seek_exact(key).transpose(),
+            (None, Some(subkey)) => match self.first()? {
+                Some((key, _)) => self
+                    .seek_by_key_subkey(key.clone(), subkey)
+                    .map(|value| value.map(|value| (key, value)))
+                    .transpose(),
+                None => None,
+            },
+            (None, None) => self.first().transpose(),
+        };
+
+        Ok(DupWalker { cursor: self, start, end_subkey: Bound::Unbounded })
+    }
+}
+
+impl<T, P, S> DbCursorRW<T> for ShadowCursor<T, P, S>
+where
+    T: Table,
+    P: DbCursorRW<T>,
+    S: DbCursorRW<T>,
+{
+    fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let (primary_value, secondary_value) = duplicate_value(value)?;
+        let secondary_key = key.clone();
+        self.write(
+            "upsert",
+            |c| c.upsert(key, primary_value),
+            |c| c.upsert(secondary_key, secondary_value),
+        )
+    }
+
+    fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let (primary_value, secondary_value) = duplicate_value(value)?;
+        let secondary_key = key.clone();
+        self.write(
+            "insert",
+            |c| c.insert(key, primary_value),
+            |c| c.insert(secondary_key, secondary_value),
+        )
+    }
+
+    fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let (primary_value, secondary_value) = duplicate_value(value)?;
+        let secondary_key = key.clone();
+        self.write(
+            "append",
+            |c| c.append(key, primary_value),
+            |c| c.append(secondary_key, secondary_value),
+        )
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        self.write("delete_current", |c| c.delete_current(), |c| c.delete_current())
+    }
+}
+
+impl<T, P, S> DbDupCursorRW<T> for ShadowCursor<T, P, S>
+where
+    T: DupSort,
+    P: DbDupCursorRW<T>,
+    S: DbDupCursorRW<T>,
+{
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        self.write(
+            "delete_current_duplicates",
+            |c| c.delete_current_duplicates(),
+            |c| c.delete_current_duplicates(),
+        )
+    }
+
+    fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let (primary_value, secondary_value) = duplicate_value(value)?;
+        let secondary_key = key.clone();
+        self.write(
+            "append_dup",
+            |c| c.append_dup(key, primary_value),
+            |c| c.append_dup(secondary_key, secondary_value),
+        )
+    }
+}
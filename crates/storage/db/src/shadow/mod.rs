@@ -0,0 +1,264 @@
+//! Shadow database that mirrors all writes of the primary database into the secondary one.
+//!
+//! The shadow database can be used to validate a new database backend against the production
+//! traffic before switching over. All writes are applied to both databases, while reads are served
+//! from the primary database. A sample of read transactions is also executed against the secondary
+//! database and the results are compared to detect divergences.
+
+use crate::{
+    database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
+    table::{Table, Value},
+    DatabaseError,
+};
+use metrics::Label;
+use reth_tracing::tracing::warn;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+mod cursor;
+pub use cursor::ShadowCursor;
+
+mod tx;
+pub use tx::ShadowTx;
+
+/// Database that applies every write transaction to both primary and secondary databases.
+///
+/// Reads are always served from the primary database. Every
+/// [read sample interval](ShadowDatabase::with_read_sample_interval)-th read transaction is
+/// executed against both databases and the results are compared. Read-write transactions are
+/// always executed against both databases.
+///
+/// Errors of the secondary database never fail the operation and are counted as divergences.
+#[derive(Debug)]
+pub struct ShadowDatabase<P, S> {
+    /// The primary database.
+    primary: P,
+    /// The secondary database.
+    secondary: S,
+    /// Every n-th read transaction is compared against the secondary database.
+    /// If zero, read transactions are never compared.
+    read_sample_interval: u64,
+    /// The number of read transactions opened so far.
+    read_transactions: AtomicU64,
+    /// The number of detected divergences.
+    divergences: Arc<AtomicU64>,
+}
+
+impl<P, S> ShadowDatabase<P, S> {
+    /// The default read sample interval.
+    pub const DEFAULT_READ_SAMPLE_INTERVAL: u64 = 100;
+
+    /// Create new shadow database.
+    pub fn new(primary: P, secondary: S) -> Self {
+        Self {
+            primary,
+            secondary,
+            read_sample_interval: Self::DEFAULT_READ_SAMPLE_INTERVAL,
+            read_transactions: AtomicU64::new(0),
+            divergences: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Set the interval of read transactions compared against the secondary database.
+    /// If zero, read transactions are never compared.
+    pub fn with_read_sample_interval(mut self, read_sample_interval: u64) -> Self {
+        self.read_sample_interval = read_sample_interval;
+        self
+    }
+
+    /// Returns the primary database.
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    /// Returns the secondary database.
+    pub fn secondary(&self) -> &S {
+        &self.secondary
+    }
+
+    /// Returns the number of divergences detected so far.
+    pub fn divergences(&self) -> u64 {
+        self.divergences.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the next read transaction should be compared against the secondary
+    /// database.
+    fn sample_read_transaction(&self) -> bool {
+        self.read_sample_interval != 0 &&
+            self.read_transactions.fetch_add(1, Ordering::Relaxed) % self.read_sample_interval ==
+                0
+    }
+}
+
+impl<P: Database, S: Database> Database for ShadowDatabase<P, S> {
+    type TX = ShadowTx<P::TX, S::TX>;
+    type TXMut = ShadowTx<P::TXMut, S::TXMut>;
+
+    fn tx(&self) -> Result<Self::TX, DatabaseError> {
+        let primary = self.primary.tx()?;
+        let secondary = if self.sample_read_transaction() {
+            self.secondary
+                .tx()
+                .map_err(|error| record_secondary_error(&self.divergences, None, "tx", error))
+                .ok()
+        } else {
+            None
+        };
+        Ok(ShadowTx::new(primary, secondary, self.divergences.clone()))
+    }
+
+    fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
+        let primary = self.primary.tx_mut()?;
+        let secondary = self
+            .secondary
+            .tx_mut()
+            .map_err(|error| record_secondary_error(&self.divergences, None, "tx_mut", error))
+            .ok();
+        Ok(ShadowTx::new(primary, secondary, self.divergences.clone()))
+    }
+}
+
+impl<P: DatabaseMetrics, S> DatabaseMetrics for ShadowDatabase<P, S> {
+    fn report_metrics(&self) {
+        self.primary.report_metrics();
+        metrics::gauge!("db.shadow.divergences", self.divergences() as f64);
+    }
+
+    fn gauge_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
+        let mut metrics = self.primary.gauge_metrics();
+        metrics.push(("db.shadow.divergences", self.divergences() as f64, vec![]));
+        metrics
+    }
+}
+
+impl<P: DatabaseMetadata, S> DatabaseMetadata for ShadowDatabase<P, S> {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        self.primary.metadata()
+    }
+}
+
+/// Record the divergence between primary and secondary databases.
+fn record_divergence(divergences: &AtomicU64, table: &'static str, operation: &'static str) {
+    divergences.fetch_add(1, Ordering::Relaxed);
+    warn!(target: "storage::db::shadow", table, operation, "Secondary database diverged from the primary");
+}
+
+/// Record the error of the secondary database as a divergence.
+fn record_secondary_error(
+    divergences: &AtomicU64,
+    table: Option<&'static str>,
+    operation: &'static str,
+    error: DatabaseError,
+) {
+    divergences.fetch_add(1, Ordering::Relaxed);
+    warn!(target: "storage::db::shadow", ?table, operation, %error, "Secondary database operation failed");
+}
+
+/// Duplicate the value by compressing it and decompressing it twice.
+fn duplicate_value<V: Value>(value: V) -> Result<(V, V), DatabaseError> {
+    let compressed = value.compress();
+    Ok((V::decompress(compressed.as_ref())?, V::decompress(compressed)?))
+}
+
+/// Compare the values returned from primary and secondary databases.
+///
+/// Returns the primary value and `true` if the values diverged.
+fn compare_values<V: Value>(
+    primary: Option<V>,
+    secondary: Option<V>,
+) -> Result<(Option<V>, bool), DatabaseError> {
+    match (primary, secondary) {
+        (None, None) => Ok((None, false)),
+        (Some(primary), Some(secondary)) => {
+            let primary = primary.compress();
+            let diverged = primary.as_ref() != secondary.compress().as_ref();
+            Ok((Some(V::decompress(primary)?), diverged))
+        }
+        (primary, _) => Ok((primary, true)),
+    }
+}
+
+/// Compare the rows returned from primary and secondary databases.
+///
+/// Returns the primary row and `true` if the rows diverged.
+fn compare_rows<T: Table>(
+    primary: Option<(T::Key, T::Value)>,
+    secondary: Option<(T::Key, T::Value)>,
+) -> Result<(Option<(T::Key, T::Value)>, bool), DatabaseError> {
+    match (primary, secondary) {
+        (None, None) => Ok((None, false)),
+        (Some((primary_key, primary_value)), Some((secondary_key, secondary_value))) => {
+            let keys_diverged = primary_key != secondary_key;
+            let (value, values_diverged) =
+                compare_values(Some(primary_value), Some(secondary_value))?;
+            Ok((value.map(|value| (primary_key, value)), keys_diverged || values_diverged))
+        }
+        (primary, _) => Ok((primary, true)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+        tables,
+        test_utils::create_test_rw_db,
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{Account, Address, StorageEntry, B256, U256};
+
+    #[test]
+    fn writes_are_mirrored() {
+        let db = ShadowDatabase::new(create_test_rw_db(), create_test_rw_db())
+            .with_read_sample_interval(1);
+
+        let address = Address::with_last_byte(1);
+        let account = Account { nonce: 1, ..Default::default() };
+        let entry = StorageEntry { key: B256::with_last_byte(2), value: U256::from(3) };
+        {
+            let tx = db.tx_mut().unwrap();
+            tx.put::<tables::PlainAccountState>(address, account).unwrap();
+            let mut cursor = tx.cursor_dup_write::<tables::PlainStorageState>().unwrap();
+            cursor.upsert(address, entry).unwrap();
+            let next_entry = StorageEntry { key: B256::with_last_byte(3), ..entry };
+            cursor.append_dup(address, next_entry).unwrap();
+            assert_eq!(cursor.seek_exact(address).unwrap(), Some((address, entry)));
+            cursor.delete_current().unwrap();
+            tx.commit().unwrap();
+        }
+
+        for tx in [db.primary().tx().unwrap(), db.secondary().tx().unwrap()] {
+            assert_eq!(tx.get::<tables::PlainAccountState>(address).unwrap(), Some(account));
+            let mut cursor = tx.cursor_dup_read::<tables::PlainStorageState>().unwrap();
+            assert_eq!(
+                cursor.seek_by_key_subkey(address, B256::ZERO).unwrap(),
+                Some(StorageEntry { key: B256::with_last_byte(3), ..entry })
+            );
+        }
+        assert_eq!(db.divergences(), 0);
+    }
+
+    #[test]
+    fn divergence_is_detected_on_read() {
+        let db = ShadowDatabase::new(create_test_rw_db(), create_test_rw_db())
+            .with_read_sample_interval(1);
+
+        let address = Address::with_last_byte(1);
+        let account = Account { nonce: 1, ..Default::default() };
+        let tx = db.primary().tx_mut().unwrap();
+        tx.put::<tables::PlainAccountState>(address, account).unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.get::<tables::PlainAccountState>(address).unwrap(), Some(account));
+        assert_eq!(db.divergences(), 1);
+
+        let mut cursor = tx.cursor_read::<tables::PlainAccountState>().unwrap();
+        assert_eq!(cursor.first().unwrap(), Some((address, account)));
+        assert_eq!(db.divergences(), 2);
+    }
+}
@@ -0,0 +1,177 @@
+use super::{
+    compare_values, duplicate_value, record_divergence, record_secondary_error, ShadowCursor,
+};
+use crate::{
+    table::{DupSort, Table, TableImporter},
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use std::sync::{atomic::AtomicU64, Arc};
+
+/// Transaction of the [ShadowDatabase](super::ShadowDatabase).
+///
+/// If the secondary transaction is [None], the operations are executed only against the
+/// primary transaction.
+#[derive(Debug)]
+pub struct ShadowTx<P, S> {
+    /// The primary database transaction.
+    primary: P,
+    /// The secondary database transaction.
+    secondary: Option<S>,
+    /// The number of detected divergences.
+    divergences: Arc<AtomicU64>,
+}
+
+impl<P, S> ShadowTx<P, S> {
+    pub(crate) fn new(primary: P, secondary: Option<S>, divergences: Arc<AtomicU64>) -> Self {
+        Self { primary, secondary, divergences }
+    }
+
+    /// Returns the primary database transaction.
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    /// Returns the secondary database transaction if any.
+    pub fn secondary(&self) -> Option<&S> {
+        self.secondary.as_ref()
+    }
+
+    /// Execute the operation against the secondary transaction if any.
+    /// Errors are recorded as divergences.
+    fn on_secondary<R>(
+        &self,
+        table: Option<&'static str>,
+        operation: &'static str,
+        f: impl FnOnce(&S) -> Result<R, DatabaseError>,
+    ) -> Option<R> {
+        let secondary = self.secondary.as_ref()?;
+        f(secondary)
+            .map_err(|error| record_secondary_error(&self.divergences, table, operation, error))
+            .ok()
+    }
+}
+
+impl<P: DbTx, S: DbTx> DbTx for ShadowTx<P, S> {
+    type Cursor<T: Table> = ShadowCursor<T, P::Cursor<T>, S::Cursor<T>>;
+    type DupCursor<T: DupSort> = ShadowCursor<T, P::DupCursor<T>, S::DupCursor<T>>;
+
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        let primary = self.primary.get::<T>(key.clone())?;
+        let Some(secondary) = self.on_secondary(Some(T::NAME), "get", |tx| tx.get::<T>(key)) else {
+            return Ok(primary)
+        };
+
+        let (value, diverged) = compare_values(primary, secondary)?;
+        if diverged {
+            record_divergence(&self.divergences, T::NAME, "get");
+        }
+        Ok(value)
+    }
+
+    fn commit(self) -> Result<bool, DatabaseError> {
+        let result = self.primary.commit()?;
+        if let Some(secondary) = self.secondary {
+            if let Err(error) = secondary.commit() {
+                record_secondary_error(&self.divergences, None, "commit", error);
+            }
+        }
+        Ok(result)
+    }
+
+    fn abort(self) {
+        self.primary.abort();
+        if let Some(secondary) = self.secondary {
+            secondary.abort();
+        }
+    }
+
+    fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        Ok(ShadowCursor::new(
+            self.primary.cursor_read()?,
+            self.on_secondary(Some(T::NAME), "cursor_read", |tx| tx.cursor_read()),
+            self.divergences.clone(),
+        ))
+    }
+
+    fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        Ok(ShadowCursor::new(
+            self.primary.cursor_dup_read()?,
+            self.on_secondary(Some(T::NAME), "cursor_dup_read", |tx| tx.cursor_dup_read()),
+            self.divergences.clone(),
+        ))
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        let entries = self.primary.entries::<T>()?;
+        if self
+            .on_secondary(Some(T::NAME), "entries", |tx| tx.entries::<T>())
+            .is_some_and(|secondary| secondary != entries)
+        {
+            record_divergence(&self.divergences, T::NAME, "entries");
+        }
+        Ok(entries)
+    }
+
+    fn disable_long_read_transaction_safety(&mut self) {
+        self.primary.disable_long_read_transaction_safety();
+        if let Some(secondary) = self.secondary.as_mut() {
+            secondary.disable_long_read_transaction_safety();
+        }
+    }
+}
+
+impl<P: DbTxMut + DbTx, S: DbTxMut + DbTx> DbTxMut for ShadowTx<P, S> {
+    type CursorMut<T: Table> = ShadowCursor<T, P::CursorMut<T>, S::CursorMut<T>>;
+    type DupCursorMut<T: DupSort> = ShadowCursor<T, P::DupCursorMut<T>, S::DupCursorMut<T>>;
+
+    fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let (primary_value, secondary_value) = duplicate_value(value)?;
+        self.primary.put::<T>(key.clone(), primary_value)?;
+        self.on_secondary(Some(T::NAME), "put", |tx| tx.put::<T>(key, secondary_value));
+        Ok(())
+    }
+
+    fn delete<T: Table>(
+        &self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        let (primary_value, secondary_value) = match value.map(duplicate_value).transpose()? {
+            Some((primary, secondary)) => (Some(primary), Some(secondary)),
+            None => (None, None),
+        };
+        let deleted = self.primary.delete::<T>(key.clone(), primary_value)?;
+        if self
+            .on_secondary(Some(T::NAME), "delete", |tx| tx.delete::<T>(key, secondary_value))
+            .is_some_and(|secondary| secondary != deleted)
+        {
+            record_divergence(&self.divergences, T::NAME, "delete");
+        }
+        Ok(deleted)
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        self.primary.clear::<T>()?;
+        self.on_secondary(Some(T::NAME), "clear", |tx| tx.clear::<T>());
+        Ok(())
+    }
+
+    fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
+        Ok(ShadowCursor::new(
+            self.primary.cursor_write()?,
+            self.on_secondary(Some(T::NAME), "cursor_write", |tx| tx.cursor_write()),
+            self.divergences.clone(),
+        ))
+    }
+
+    fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
+        Ok(ShadowCursor::new(
+            self.primary.cursor_dup_write()?,
+            self.on_secondary(Some(T::NAME), "cursor_dup_write", |tx| tx.cursor_dup_write()),
+            self.divergences.clone(),
+        ))
+    }
+}
+
+impl<P: DbTxMut + DbTx, S: DbTxMut + DbTx> TableImporter for ShadowTx<P, S> {}
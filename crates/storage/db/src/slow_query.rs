@@ -0,0 +1,107 @@
+use reth_primitives::hex;
+use reth_tracing::tracing::warn;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Minimum interval between two consecutive slow query logs.
+const SLOW_QUERY_LOG_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Logs database operations that took longer than the configured threshold.
+///
+/// Logs are sampled: at most one slow operation is logged per [SLOW_QUERY_LOG_INTERVAL]. The number
+/// of slow operations that were skipped since the last log is reported with the next one.
+#[derive(Debug)]
+pub(crate) struct SlowQueryLogger {
+    /// Operations that took longer than this duration are logged.
+    threshold: Duration,
+    /// The time the logger was created at. Used as a reference point for `next_log_at`.
+    created_at: Instant,
+    /// The number of milliseconds since `created_at` after which the next log can be emitted.
+    next_log_at: AtomicU64,
+    /// The number of slow operations that were not logged since the last log.
+    skipped: AtomicU64,
+}
+
+impl SlowQueryLogger {
+    /// Create a new slow query logger with the given threshold.
+    pub(crate) fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            created_at: Instant::now(),
+            next_log_at: AtomicU64::new(0),
+            skipped: AtomicU64::new(0),
+        }
+    }
+
+    /// Execute the closure and log the operation if it took longer than the threshold.
+    ///
+    /// The encoded key is only retrieved with `key` if the operation needs to be logged.
+    pub(crate) fn record<R>(
+        &self,
+        table: &'static str,
+        operation: &'static str,
+        f: impl FnOnce() -> R,
+        key: impl FnOnce(&R) -> Option<Vec<u8>>,
+    ) -> R {
+        let start = Instant::now();
+        let result = f();
+        let elapsed = start.elapsed();
+
+        if elapsed >= self.threshold && self.should_log() {
+            let skipped = self.skipped.swap(0, Ordering::Relaxed);
+            let key = key(&result).map(hex::encode).unwrap_or_default();
+            warn!(
+                target: "storage::db::slow_query",
+                table,
+                operation,
+                %key,
+                ?elapsed,
+                skipped,
+                "Slow database operation"
+            );
+        }
+
+        result
+    }
+
+    /// Returns `true` if enough time has passed since the last log. Otherwise, increments the
+    /// number of skipped logs.
+    fn should_log(&self) -> bool {
+        let now = self.created_at.elapsed().as_millis() as u64;
+        let next_log_at = self.next_log_at.load(Ordering::Relaxed);
+        if now >= next_log_at &&
+            self.next_log_at
+                .compare_exchange(
+                    next_log_at,
+                    now + SLOW_QUERY_LOG_INTERVAL.as_millis() as u64,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+        {
+            return true
+        }
+
+        self.skipped.fetch_add(1, Ordering::Relaxed);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_query_logs_are_sampled() {
+        let logger = SlowQueryLogger::new(Duration::ZERO);
+        assert!(logger.should_log());
+        assert!(!logger.should_log());
+        assert!(!logger.should_log());
+        assert_eq!(logger.skipped.load(Ordering::Relaxed), 2);
+
+        assert_eq!(logger.record("table", "get", || 1, |_| Some(vec![0xab])), 1);
+        assert_eq!(logger.skipped.load(Ordering::Relaxed), 3);
+    }
+}
@@ -25,13 +25,14 @@ pub(crate) mod utils;
 
 use crate::{
     abstraction::table::Table,
-    table::DupSort,
+    table::{DupSort, DupSortValue, DuplicateValues},
     tables::{
         codecs::CompactU256,
         models::{
             accounts::{AccountBeforeTx, BlockNumberAddress},
             blocks::{HeaderHash, StoredBlockOmmers},
             client_version::ClientVersion,
+            log_index_key::LogIndexKey,
             storage_sharded_key::StorageShardedKey,
             ShardedKey, StoredBlockBodyIndices, StoredBlockWithdrawals,
         },
@@ -109,7 +110,10 @@ macro_rules! tables {
     (@view $name:ident $v:ident) => { $v.view::<$name>() };
     (@view $name:ident $v:ident $_subkey:ty) => { $v.view_dupsort::<$name>() };
 
-    ($( $(#[$attr:meta])* table $name:ident<Key = $key:ty, Value = $value:ty $(, SubKey = $subkey:ty)? $(,)?>; )*) => {
+    (@duplicate_values $name:ident) => { None };
+    (@duplicate_values $name:ident $_subkey:ty) => { Some(<$name as DupSort>::DUPLICATE_VALUES) };
+
+    ($( $(#[$attr:meta])* table $name:ident<Key = $key:ty, Value = $value:ty $(, SubKey = $subkey:ty $(, DuplicateValues = $duplicate_values:ident)?)? $(,)?>; )*) => {
         // Table marker types.
         $(
             $(#[$attr])*
@@ -117,6 +121,9 @@ macro_rules! tables {
             #[doc = concat!("Marker type representing a database table mapping [`", stringify!($key), "`] to [`", stringify!($value), "`].")]
             $(
                 #[doc = concat!("\n\nThis table's `DUPSORT` subkey is [`", stringify!($subkey), "`].")]
+                $(
+                    #[doc = concat!("\n\nEqual values of a key are handled with [`DuplicateValues::", stringify!($duplicate_values), "`].")]
+                )?
             )?
             pub struct $name {
                 _private: (),
@@ -140,6 +147,14 @@ macro_rules! tables {
             $(
                 impl DupSort for $name {
                     type SubKey = $subkey;
+                    $(
+                        const DUPLICATE_VALUES: DuplicateValues =
+                            DuplicateValues::$duplicate_values;
+                    )?
+
+                    fn subkey(value: &Self::Value) -> Self::SubKey {
+                        DupSortValue::subkey(value)
+                    }
                 }
             )?
         )*
@@ -183,6 +198,22 @@ macro_rules! tables {
                 }
             }
 
+            /// Returns how the table treats a value equal to one already stored for the key, or
+            /// [None] if the table is not a `DUPSORT` table.
+            pub const fn duplicate_values(&self) -> Option<DuplicateValues> {
+                match self {
+                    $(
+                        Self::$name => tables!(@duplicate_values $name $($subkey)?),
+                    )*
+                }
+            }
+
+            /// Returns `true` if writing a value equal to one already stored for the key must
+            /// fail.
+            pub const fn rejects_duplicate_values(&self) -> bool {
+                matches!(self.duplicate_values(), Some(DuplicateValues::Reject))
+            }
+
             /// The type of the given table in database.
             pub const fn table_type(&self) -> TableType {
                 if self.is_dupsort() {
@@ -280,6 +311,12 @@ tables! {
     /// Canonical only Stores transaction receipts.
     table Receipts<Key = TxNumber, Value = Receipt>;
 
+    /// Stores the numbers of the transactions that emitted a log with the address and the first
+    /// topic in the block.
+    ///
+    /// Only maintained for the blocks executed while the log index is enabled.
+    table LogIndex<Key = LogIndexKey, Value = IntegerList>;
+
     /// Stores all smart contract bytecodes.
     /// There will be multiple accounts that have same bytecode
     /// So we would need to introduce reference counter.
@@ -342,6 +379,26 @@ tables! {
     /// and needs to be removed.
     table StorageChangeSets<Key = BlockNumberAddress, Value = StorageEntry, SubKey = B256>;
 
+    /// Stores the state of an account before the first change of it in a changeset epoch, keyed
+    /// by the first block of the epoch. Filled by folding the [`AccountChangeSets`] of the blocks
+    /// of the epoch, see `reth_primitives::CHANGESET_EPOCH_LENGTH`.
+    table AccountChangeSetEpochs<
+        Key = BlockNumber,
+        Value = AccountBeforeTx,
+        SubKey = Address,
+        DuplicateValues = Reject,
+    >;
+
+    /// Stores the state of a storage key before the first change of it in a changeset epoch,
+    /// keyed by the first block of the epoch. Filled by folding the [`StorageChangeSets`] of the
+    /// blocks of the epoch, see `reth_primitives::CHANGESET_EPOCH_LENGTH`.
+    table StorageChangeSetEpochs<
+        Key = BlockNumberAddress,
+        Value = StorageEntry,
+        SubKey = B256,
+        DuplicateValues = Reject,
+    >;
+
     /// Stores the current state of an [`Account`] indexed with `keccak256Address`
     /// This table is in preparation for merkelization and calculation of state root.
     /// We are saving whole account data as it is needed for partial update when
@@ -4,7 +4,7 @@ use std::ops::{Range, RangeInclusive};
 
 use crate::{
     impl_fixed_arbitrary,
-    table::{Decode, Encode},
+    table::{Decode, DupSortValue, Encode},
     DatabaseError,
 };
 use reth_codecs::{derive_arbitrary, Compact};
@@ -57,6 +57,12 @@ impl Compact for AccountBeforeTx {
     }
 }
 
+impl DupSortValue<Address> for AccountBeforeTx {
+    fn subkey(&self) -> Address {
+        self.address
+    }
+}
+
 /// [`BlockNumber`] concatenated with [`Address`]. Used as the key for
 /// [`StorageChangeSets`](crate::tables::StorageChangeSets)
 ///
@@ -0,0 +1,58 @@
+//! Log index key
+
+use crate::{
+    table::{Decode, Encode},
+    DatabaseError,
+};
+use reth_primitives::{Address, BlockNumber, B256};
+use serde::{Deserialize, Serialize};
+
+/// Key of the log index, made of the address and the first topic of a log, and the block it was
+/// emitted in. Example:
+///
+/// `Address | Topic0 | 200` -> transactions of block 200 emitting the log.
+#[derive(
+    Debug, Default, Clone, Copy, Eq, Ord, PartialOrd, PartialEq, Serialize, Deserialize, Hash,
+)]
+pub struct LogIndexKey {
+    /// Address of the log.
+    pub address: Address,
+    /// First topic of the log.
+    pub topic0: B256,
+    /// Block the log was emitted in.
+    pub block_number: BlockNumber,
+}
+
+impl LogIndexKey {
+    /// Creates a new `LogIndexKey`.
+    pub fn new(address: Address, topic0: B256, block_number: BlockNumber) -> Self {
+        Self { address, topic0, block_number }
+    }
+}
+
+impl Encode for LogIndexKey {
+    type Encoded = Vec<u8>;
+
+    fn encode(self) -> Self::Encoded {
+        let mut buf: Vec<u8> = Encode::encode(self.address).into();
+        buf.extend_from_slice(&Encode::encode(self.topic0));
+        buf.extend_from_slice(&self.block_number.to_be_bytes());
+        buf
+    }
+}
+
+impl Decode for LogIndexKey {
+    fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, DatabaseError> {
+        let value = value.as_ref();
+        if value.len() != 60 {
+            return Err(DatabaseError::Decode)
+        }
+
+        let address = Address::decode(&value[..20])?;
+        let topic0 = B256::decode(&value[20..52])?;
+        let block_number =
+            u64::from_be_bytes(value[52..].try_into().map_err(|_| DatabaseError::Decode)?);
+
+        Ok(Self { address, topic0, block_number })
+    }
+}
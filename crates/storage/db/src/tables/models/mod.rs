@@ -1,19 +1,20 @@
 //! Implements data structures specific to the database
 
 use crate::{
-    table::{Decode, Encode},
+    table::{Decode, DupSortValue, Encode},
     DatabaseError,
 };
 use reth_codecs::Compact;
 use reth_primitives::{
-    trie::{StoredNibbles, StoredNibblesSubKey},
-    Address, PruneSegment, B256,
+    trie::{StorageTrieEntry, StoredNibbles, StoredNibblesSubKey},
+    Address, PruneSegment, StorageEntry, B256,
 };
 
 pub mod accounts;
 pub mod blocks;
 pub mod client_version;
 pub mod integer_list;
+pub mod log_index_key;
 pub mod sharded_key;
 pub mod storage_sharded_key;
 
@@ -142,6 +143,18 @@ impl Decode for StoredNibblesSubKey {
     }
 }
 
+impl DupSortValue<B256> for StorageEntry {
+    fn subkey(&self) -> B256 {
+        self.key
+    }
+}
+
+impl DupSortValue<StoredNibblesSubKey> for StorageTrieEntry {
+    fn subkey(&self) -> StoredNibblesSubKey {
+        self.nibbles.clone()
+    }
+}
+
 impl Encode for PruneSegment {
     type Encoded = [u8; 1];
 
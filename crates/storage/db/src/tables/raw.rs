@@ -1,5 +1,5 @@
 use crate::{
-    table::{Compress, Decode, Decompress, DupSort, Encode, Key, Table, Value},
+    table::{Compress, Decode, Decompress, DupSort, DuplicateValues, Encode, Key, Table, Value},
     DatabaseError,
 };
 use serde::{Deserialize, Serialize};
@@ -37,6 +37,16 @@ impl<T: DupSort> Table for RawDupSort<T> {
 
 impl<T: DupSort> DupSort for RawDupSort<T> {
     type SubKey = RawKey<T::SubKey>;
+
+    const DUPLICATE_VALUES: DuplicateValues = T::DUPLICATE_VALUES;
+
+    /// A value that can't be decoded is its own subkey, since its raw bytes start with the subkey.
+    fn subkey(value: &Self::Value) -> Self::SubKey {
+        match value.value() {
+            Ok(value) => RawKey::new(T::subkey(&value)),
+            Err(_) => RawKey::from_vec(value.raw_value().to_vec()),
+        }
+    }
 }
 
 /// Raw table key.
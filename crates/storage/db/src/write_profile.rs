@@ -0,0 +1,227 @@
+//! Profile of the database writes, grouped by table.
+//!
+//! The [WriteProfiler] counts the puts, deletes and commits of the read-write transactions and
+//! their cursors. A [WriteProfile] is taken with [WriteProfiler::take], which resets the counters,
+//! so consecutive profiles cover consecutive periods of time. This is used by the pipeline to
+//! attribute the writes to the stage that made them.
+
+use crate::Tables;
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Counters of the writes to a single table.
+#[derive(Debug, Default)]
+struct TableWriteCounters {
+    puts: AtomicU64,
+    put_bytes: AtomicU64,
+    deletes: AtomicU64,
+}
+
+/// Counts the writes of the database, grouped by table.
+#[derive(Debug)]
+pub struct WriteProfiler {
+    /// Counters of each table, indexed by the [Tables] discriminant.
+    tables: [TableWriteCounters; Tables::COUNT],
+    /// The number of committed read-write transactions.
+    commits: AtomicU64,
+}
+
+impl Default for WriteProfiler {
+    fn default() -> Self {
+        Self {
+            tables: std::array::from_fn(|_| TableWriteCounters::default()),
+            commits: AtomicU64::new(0),
+        }
+    }
+}
+
+impl WriteProfiler {
+    /// Records a put of a key and a value of `bytes` total encoded size to the table.
+    pub(crate) fn record_put(&self, table: Tables, bytes: usize) {
+        let counters = &self.tables[table as usize];
+        counters.puts.fetch_add(1, Ordering::Relaxed);
+        counters.put_bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Records a delete from the table.
+    pub(crate) fn record_delete(&self, table: Tables) {
+        self.tables[table as usize].deletes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a commit of a read-write transaction.
+    pub(crate) fn record_commit(&self) {
+        self.commits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the writes recorded since the previous call and resets the counters.
+    pub fn take(&self) -> WriteProfile {
+        let tables = Tables::ALL
+            .iter()
+            .filter_map(|table| {
+                let counters = &self.tables[*table as usize];
+                let profile = TableWriteProfile {
+                    puts: counters.puts.swap(0, Ordering::Relaxed),
+                    put_bytes: counters.put_bytes.swap(0, Ordering::Relaxed),
+                    deletes: counters.deletes.swap(0, Ordering::Relaxed),
+                };
+                (!profile.is_empty()).then_some((*table, profile))
+            })
+            .collect();
+        WriteProfile { commits: self.commits.swap(0, Ordering::Relaxed), tables }
+    }
+}
+
+/// Writes to a single table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableWriteProfile {
+    /// The number of puts, including cursor upserts, inserts and appends.
+    pub puts: u64,
+    /// The total encoded size of the keys and values that were put.
+    pub put_bytes: u64,
+    /// The number of deletes, including cursor deletes.
+    pub deletes: u64,
+}
+
+impl TableWriteProfile {
+    /// Returns `true` if nothing was written to the table.
+    pub fn is_empty(&self) -> bool {
+        self.puts == 0 && self.deletes == 0
+    }
+
+    /// Adds the writes of `other` to this profile.
+    pub fn merge(&mut self, other: &Self) {
+        self.puts += other.puts;
+        self.put_bytes += other.put_bytes;
+        self.deletes += other.deletes;
+    }
+}
+
+/// Writes to the database over a period of time, see [WriteProfiler::take].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteProfile {
+    /// The number of committed read-write transactions.
+    pub commits: u64,
+    /// The writes to each table that was written to, in the order of [Tables::ALL].
+    pub tables: Vec<(Tables, TableWriteProfile)>,
+}
+
+impl WriteProfile {
+    /// Returns `true` if nothing was written or committed.
+    pub fn is_empty(&self) -> bool {
+        self.commits == 0 && self.tables.is_empty()
+    }
+
+    /// Returns the writes to all tables combined.
+    pub fn total(&self) -> TableWriteProfile {
+        self.tables.iter().fold(TableWriteProfile::default(), |mut total, (_, profile)| {
+            total.merge(profile);
+            total
+        })
+    }
+
+    /// Adds the writes of `other` to this profile.
+    pub fn merge(&mut self, other: &Self) {
+        self.commits += other.commits;
+        for (table, profile) in &other.tables {
+            match self.tables.iter_mut().find(|(existing, _)| existing == table) {
+                Some((_, existing)) => existing.merge(profile),
+                None => self.tables.push((*table, *profile)),
+            }
+        }
+        self.tables.sort_by_key(|(table, _)| *table as usize);
+    }
+}
+
+impl fmt::Display for WriteProfile {
+    /// Formats the profile as a table with a row per table, ordered by the bytes put.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tables = self.tables.clone();
+        tables.sort_by(|(_, a), (_, b)| b.put_bytes.cmp(&a.put_bytes));
+
+        writeln!(f, "{:<32} {:>14} {:>16} {:>14}", "Table", "Puts", "Put bytes", "Deletes")?;
+        for (table, profile) in tables {
+            writeln!(
+                f,
+                "{:<32} {:>14} {:>16} {:>14}",
+                table.name(),
+                profile.puts,
+                profile.put_bytes,
+                profile.deletes
+            )?;
+        }
+        write!(f, "Commits: {}", self.commits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRW},
+        database::Database,
+        mdbx::DatabaseArguments,
+        models::client_version::ClientVersion,
+        tables::{CanonicalHeaders, PlainStorageState},
+        test_utils::tempdir_path,
+        transaction::{DbTx, DbTxMut},
+        DatabaseEnv, DatabaseEnvKind,
+    };
+    use reth_primitives::{Address, StorageEntry, B256, U256};
+
+    #[test]
+    fn records_writes_per_table() {
+        let path = tempdir_path();
+        let args = DatabaseArguments::new(ClientVersion::default()).with_write_profile(true);
+        let db = DatabaseEnv::open(&path, DatabaseEnvKind::RW, args).unwrap();
+        db.create_tables().unwrap();
+        let profiler = db.write_profiler().unwrap();
+        profiler.take();
+
+        let address = Address::with_last_byte(1);
+        let entry = StorageEntry { key: B256::with_last_byte(1), value: U256::from(1) };
+
+        let tx = db.tx_mut().unwrap();
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        for number in 0..10 {
+            cursor.append(number, B256::with_last_byte(number as u8)).unwrap();
+        }
+        cursor.seek_exact(3).unwrap();
+        cursor.delete_current().unwrap();
+        tx.delete::<CanonicalHeaders>(4, None).unwrap();
+        tx.cursor_dup_write::<PlainStorageState>().unwrap().append_dup(address, entry).unwrap();
+        tx.commit().unwrap();
+
+        // Writes of aborted transactions are recorded, but not committed.
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(10, B256::ZERO).unwrap();
+        drop(tx);
+
+        let profile = profiler.take();
+        assert_eq!(profile.commits, 1);
+        assert_eq!(
+            profile.tables,
+            vec![
+                (
+                    Tables::CanonicalHeaders,
+                    TableWriteProfile { puts: 11, put_bytes: 11 * (8 + 32), deletes: 2 }
+                ),
+                (
+                    Tables::PlainStorageState,
+                    TableWriteProfile { puts: 1, put_bytes: 20 + 33, deletes: 0 }
+                ),
+            ]
+        );
+        assert_eq!(profile.total().puts, 12);
+
+        // The counters are reset.
+        assert!(profiler.take().is_empty());
+
+        let mut merged = WriteProfile::default();
+        merged.merge(&profile);
+        merged.merge(&profile);
+        assert_eq!(merged.commits, 2);
+        assert_eq!(merged.tables[0].1.deletes, 4);
+    }
+}
@@ -462,6 +462,49 @@ impl Cursor<RW> {
 
         Ok(())
     }
+
+    /// Deletes the current key/data pair and the ones following it while `predicate` returns
+    /// `true` for them, and returns the number of deleted pairs.
+    ///
+    /// All pairs are visited and deleted under a single transaction lock, and the borrowed slices
+    /// passed to `predicate` are never copied. The cursor is left at the first pair that was not
+    /// deleted.
+    pub fn del_while(&mut self, mut predicate: impl FnMut(&[u8], &[u8]) -> bool) -> Result<usize> {
+        unsafe {
+            self.txn.txn_execute(|_| {
+                let mut deleted = 0;
+                // After a deletion the cursor already points at the following pair.
+                loop {
+                    let mut key_val = slice_to_val(None);
+                    let mut data_val = slice_to_val(None);
+                    match mdbx_result(ffi::mdbx_cursor_get(
+                        self.cursor,
+                        &mut key_val,
+                        &mut data_val,
+                        MDBX_GET_CURRENT,
+                    )) {
+                        Ok(_) => {}
+                        Err(Error::NotFound | Error::NoData) => break,
+                        Err(err) => return Err(err),
+                    }
+
+                    let key =
+                        std::slice::from_raw_parts(key_val.iov_base as *const u8, key_val.iov_len);
+                    let data = std::slice::from_raw_parts(
+                        data_val.iov_base as *const u8,
+                        data_val.iov_len,
+                    );
+                    if !predicate(key, data) {
+                        break
+                    }
+
+                    mdbx_result(ffi::mdbx_cursor_del(self.cursor, WriteFlags::CURRENT.bits()))?;
+                    deleted += 1;
+                }
+                Ok(deleted)
+            })?
+        }
+    }
 }
 
 impl<K> Clone for Cursor<K>
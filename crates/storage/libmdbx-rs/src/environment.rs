@@ -48,6 +48,7 @@ impl Environment {
             txn_dp_limit: None,
             spill_max_denominator: None,
             spill_min_denominator: None,
+            sync_period: None,
             geometry: None,
             log_level: None,
             kind: Default::default(),
@@ -154,6 +155,23 @@ impl Environment {
         mdbx_result(unsafe { ffi::mdbx_env_sync_ex(self.env_ptr(), force, false) })
     }
 
+    /// Makes the commits of the following write transactions durable, as with
+    /// [SyncMode::Durable](crate::SyncMode::Durable), whatever the sync mode the environment was
+    /// opened with.
+    ///
+    /// Waits for the current write transaction to finish, so it must not be called from the
+    /// thread of a write transaction.
+    pub fn set_sync_durable(&self) -> Result<()> {
+        mdbx_result(unsafe {
+            ffi::mdbx_env_set_flags(
+                self.env_ptr(),
+                ffi::MDBX_NOMETASYNC | ffi::MDBX_UTTERLY_NOSYNC,
+                false,
+            )
+        })?;
+        Ok(())
+    }
+
     /// Retrieves statistics about this environment.
     pub fn stat(&self) -> Result<Stat> {
         unsafe {
@@ -582,6 +600,7 @@ pub struct EnvironmentBuilder {
     txn_dp_limit: Option<u64>,
     spill_max_denominator: Option<u64>,
     spill_min_denominator: Option<u64>,
+    sync_period: Option<u64>,
     geometry: Option<Geometry<(Option<usize>, Option<usize>)>>,
     log_level: Option<ffi::MDBX_log_level_t>,
     kind: EnvironmentKind,
@@ -656,6 +675,7 @@ impl EnvironmentBuilder {
                     (ffi::MDBX_opt_txn_dp_limit, self.txn_dp_limit),
                     (ffi::MDBX_opt_spill_max_denominator, self.spill_max_denominator),
                     (ffi::MDBX_opt_spill_min_denominator, self.spill_min_denominator),
+                    (ffi::MDBX_opt_sync_period, self.sync_period),
                 ] {
                     if let Some(v) = v {
                         mdbx_result(ffi::mdbx_env_set_option(env, opt, v))?;
@@ -809,6 +829,17 @@ impl EnvironmentBuilder {
         self
     }
 
+    /// Sets the period after which the data of non-durable commits is flushed to disk.
+    ///
+    /// The period is checked at the end of every commit, so with [crate::SyncMode::SafeNoSync] or
+    /// [crate::SyncMode::UtterlyNoSync] the first commit after the period elapses flushes all
+    /// preceding commits.
+    pub fn set_sync_period(&mut self, period: Duration) -> &mut Self {
+        // The period is set in 1/65536 of a second.
+        self.sync_period = Some((period.as_secs_f64() * 65536.0) as u64);
+        self
+    }
+
     /// Set all size-related parameters of environment, including page size and the min/max size of
     /// the memory map.
     pub fn set_geometry<R: RangeBounds<usize>>(&mut self, geometry: Geometry<R>) -> &mut Self {
@@ -334,3 +334,32 @@ fn test_put_del() {
         (Cow::Borrowed(b"key2" as &[u8]), Cow::Borrowed(b"val2" as &[u8]))
     );
 }
+
+#[test]
+fn test_del_while() {
+    let dir = tempdir().unwrap();
+    let env = Environment::builder().open(dir.path()).unwrap();
+
+    let txn = env.begin_rw_txn().unwrap();
+    let db = txn.open_db(None).unwrap();
+    let mut cursor = txn.cursor(&db).unwrap();
+
+    cursor.put(b"key1", b"val1", WriteFlags::empty()).unwrap();
+    cursor.put(b"key2", b"val2", WriteFlags::empty()).unwrap();
+    cursor.put(b"key3", b"val3", WriteFlags::empty()).unwrap();
+    cursor.put(b"key4", b"val4", WriteFlags::empty()).unwrap();
+
+    cursor.set::<()>(b"key2").unwrap();
+    assert_eq!(cursor.del_while(|key, _| key != b"key4").unwrap(), 2);
+    assert_eq!(
+        cursor.get_current().unwrap().unwrap(),
+        (Cow::Borrowed(b"key4" as &[u8]), Cow::Borrowed(b"val4" as &[u8]))
+    );
+
+    assert_eq!(cursor.del_while(|_, _| true).unwrap(), 1);
+    assert_eq!(cursor.get_current::<Vec<u8>, Vec<u8>>().unwrap(), None);
+    assert_eq!(
+        cursor.last().unwrap().unwrap(),
+        (Cow::Borrowed(b"key1" as &[u8]), Cow::Borrowed(b"val1" as &[u8]))
+    );
+}
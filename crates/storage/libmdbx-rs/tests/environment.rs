@@ -81,6 +81,30 @@ fn test_sync() {
     }
 }
 
+#[test]
+fn test_set_sync_durable() {
+    let dir = tempdir().unwrap();
+    {
+        let env = Environment::builder()
+            .set_flags(EnvironmentFlags {
+                mode: Mode::ReadWrite { sync_mode: SyncMode::SafeNoSync },
+                ..Default::default()
+            })
+            .open(dir.path())
+            .unwrap();
+        env.set_sync_durable().unwrap();
+
+        let txn = env.begin_rw_txn().unwrap();
+        let db = txn.open_db(None).unwrap();
+        txn.put(db.dbi(), b"key", b"val", WriteFlags::empty()).unwrap();
+        txn.commit().unwrap();
+    }
+    {
+        let env = Environment::builder().set_flags(Mode::ReadOnly.into()).open(dir.path()).unwrap();
+        env.set_sync_durable().unwrap_err();
+    }
+}
+
 #[test]
 fn test_stat() {
     let dir = tempdir().unwrap();
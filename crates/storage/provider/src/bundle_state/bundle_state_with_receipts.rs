@@ -1,4 +1,4 @@
-use crate::{providers::StaticFileProviderRWRefMut, StateChanges, StateReverts};
+use crate::{providers::StaticFileProviderRWRefMut, LogIndexChanges, StateChanges, StateReverts};
 use reth_db::{
     cursor::{DbCursorRO, DbCursorRW},
     tables,
@@ -314,6 +314,10 @@ impl BundleStateWithReceipts {
         let mut bodies_cursor = tx.cursor_read::<tables::BlockBodyIndices>()?;
         let mut receipts_cursor = tx.cursor_write::<tables::Receipts>()?;
 
+        // Blocks before the start of the log index are not indexed.
+        let log_index_start = LogIndexChanges::start_block(tx)?;
+        let mut log_index = LogIndexChanges::default();
+
         for (idx, receipts) in self.receipts.into_iter().enumerate() {
             let block_number = self.first_block + idx as u64;
             let first_tx_index = bodies_cursor
@@ -321,6 +325,18 @@ impl BundleStateWithReceipts {
                 .map(|(_, indices)| indices.first_tx_num())
                 .ok_or_else(|| ProviderError::BlockBodyIndicesNotFound(block_number))?;
 
+            if log_index_start.is_some_and(|start| block_number >= start) {
+                for (tx_idx, receipt) in receipts.iter().enumerate() {
+                    if let Some(receipt) = receipt {
+                        log_index.insert_receipt(
+                            block_number,
+                            first_tx_index + tx_idx as u64,
+                            receipt,
+                        );
+                    }
+                }
+            }
+
             if let Some(static_file_producer) = &mut static_file_producer {
                 // Increment block on static file header.
                 static_file_producer.increment_block(StaticFileSegment::Receipts, block_number)?;
@@ -339,6 +355,8 @@ impl BundleStateWithReceipts {
             }
         }
 
+        log_index.write_to_db(tx)?;
+
         StateChanges(plain_state).write_to_db(tx)?;
 
         Ok(())
@@ -348,11 +366,11 @@ impl BundleStateWithReceipts {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{test_utils::create_test_provider_factory, AccountReader};
+    use crate::{test_utils::create_test_provider_factory, AccountReader, ReceiptProvider};
     use reth_db::{
         cursor::DbDupCursorRO,
         database::Database,
-        models::{AccountBeforeTx, BlockNumberAddress},
+        models::{AccountBeforeTx, BlockNumberAddress, StoredBlockBodyIndices},
         test_utils::create_test_rw_db,
     };
     use reth_primitives::keccak256;
@@ -1109,6 +1127,50 @@ mod tests {
         assert_eq!(storage_changes.next(), None);
     }
 
+    #[test]
+    fn write_to_storage_log_index() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        assert_eq!(provider.set_log_index_enabled(true).unwrap(), Some(1));
+
+        let address = Address::repeat_byte(0x11);
+        let topic = B256::repeat_byte(0x22);
+        let log = Log { address, topics: vec![topic], data: Default::default() };
+        let receipt = Receipt { logs: vec![log.clone(), log], ..Default::default() };
+
+        for block_number in 0..3 {
+            let indices = StoredBlockBodyIndices { first_tx_num: block_number * 2, tx_count: 2 };
+            provider.tx_ref().put::<tables::BlockBodyIndices>(block_number, indices).unwrap();
+        }
+
+        // block 0 was executed before the log index was enabled
+        BundleStateWithReceipts::new(
+            BundleState::default(),
+            Receipts::from_vec(vec![
+                vec![Some(receipt.clone()), None],
+                vec![Some(Receipt::default()), Some(receipt.clone())],
+                vec![Some(receipt.clone()), Some(receipt)],
+            ]),
+            0,
+        )
+        .write_to_storage(provider.tx_ref(), None, OriginalValuesKnown::Yes)
+        .unwrap();
+
+        assert_eq!(
+            provider.log_index_blocks(&[address], &[topic], 1..=2).unwrap(),
+            Some(BTreeMap::from([(1, vec![3]), (2, vec![4, 5])]))
+        );
+        assert_eq!(
+            provider.log_index_blocks(&[address], &[B256::ZERO], 1..=2).unwrap(),
+            Some(BTreeMap::new())
+        );
+        assert_eq!(provider.log_index_blocks(&[address], &[topic], 0..=2).unwrap(), None);
+
+        assert_eq!(provider.set_log_index_enabled(false).unwrap(), None);
+        assert_eq!(provider.log_index_blocks(&[address], &[topic], 1..=2).unwrap(), None);
+        assert_eq!(provider.tx_ref().entries::<tables::LogIndex>().unwrap(), 0);
+    }
+
     #[test]
     fn revert_to_indices() {
         let base = BundleStateWithReceipts {
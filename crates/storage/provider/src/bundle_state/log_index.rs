@@ -0,0 +1,61 @@
+use reth_db::{
+    cursor::DbCursorRW,
+    models::log_index_key::LogIndexKey,
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_interfaces::db::DatabaseError;
+use reth_primitives::{stage::StageId, BlockNumber, IntegerList, Receipt, TxNumber};
+use std::collections::BTreeMap;
+
+/// Stage checkpoint key under which the first block covered by the log index is stored.
+///
+/// The entry only exists while the log index is enabled.
+pub const LOG_INDEX_CHECKPOINT: StageId = StageId::Other("LogIndex");
+
+/// Log index entries of executed blocks, mapping the address and the first topic of the logs to
+/// the transactions emitting them.
+#[derive(Debug, Default)]
+pub struct LogIndexChanges(pub BTreeMap<LogIndexKey, Vec<TxNumber>>);
+
+impl LogIndexChanges {
+    /// Returns the first block covered by the log index, or `None` if the index is disabled.
+    pub fn start_block<TX: DbTx>(tx: &TX) -> Result<Option<BlockNumber>, DatabaseError> {
+        Ok(tx
+            .get::<tables::StageCheckpoints>(LOG_INDEX_CHECKPOINT.to_string())?
+            .map(|checkpoint| checkpoint.block_number))
+    }
+
+    /// Records the logs of the receipt of the transaction. Logs without topics are not indexed.
+    pub fn insert_receipt(
+        &mut self,
+        block_number: BlockNumber,
+        tx_number: TxNumber,
+        receipt: &Receipt,
+    ) {
+        for log in &receipt.logs {
+            let Some(topic0) = log.topics.first() else { continue };
+            let txs =
+                self.0.entry(LogIndexKey::new(log.address, *topic0, block_number)).or_default();
+            // Transactions are inserted in ascending order, so only the last one can be a
+            // duplicate.
+            if txs.last() != Some(&tx_number) {
+                txs.push(tx_number);
+            }
+        }
+    }
+
+    /// Write the log index entries to the database.
+    ///
+    /// Existing entries of the same blocks are overwritten. Entries of unwound blocks are left in
+    /// place: they only add candidate blocks, which are discarded once their receipts are matched
+    /// against the filter.
+    pub fn write_to_db<TX: DbTxMut + DbTx>(self, tx: &TX) -> Result<(), DatabaseError> {
+        tracing::trace!(target: "provider::log_index", len = self.0.len(), "Writing log index");
+        let mut cursor = tx.cursor_write::<tables::LogIndex>()?;
+        for (key, txs) in self.0 {
+            cursor.upsert(key, IntegerList::new_pre_sorted(txs))?;
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@
 //! This module contains all the logic related to bundle state.
 mod bundle_state_with_receipts;
 mod hashed_state_changes;
+mod log_index;
 mod state_changes;
 mod state_reverts;
 
@@ -9,5 +10,6 @@ pub use bundle_state_with_receipts::{
     AccountRevertInit, BundleStateInit, BundleStateWithReceipts, OriginalValuesKnown, RevertsInit,
 };
 pub use hashed_state_changes::HashedStateChanges;
+pub use log_index::{LogIndexChanges, LOG_INDEX_CHECKPOINT};
 pub use state_changes::StateChanges;
 pub use state_reverts::StateReverts;
@@ -3,22 +3,33 @@ use crate::{
     to_range,
     traits::{BlockSource, ReceiptProvider},
     BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode,
-    ProviderError, PruneCheckpointReader, StageCheckpointReader, StateProviderBox,
-    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    EvmEnvProvider, HashedAccountRange, HashedStorageRange, HeaderProvider, HeaderSyncGap,
+    HeaderSyncGapProvider, HeaderSyncMode, ProviderError, PruneCheckpointReader,
+    StageCheckpointReader, StateProviderBox, StateRangeProvider, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
+};
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO, DbDupCursorRW},
+    database::Database,
+    init_db,
+    models::StoredBlockBodyIndices,
+    table::Table,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    DatabaseEnv, DatabaseError, TableViewer, Tables,
 };
-use reth_db::{database::Database, init_db, models::StoredBlockBodyIndices, DatabaseEnv};
 use reth_evm::ConfigureEvmEnv;
 use reth_interfaces::{provider::ProviderResult, RethError, RethResult};
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
     Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, ChainInfo,
     ChainSpec, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders,
-    SealedHeader, StaticFileSegment, TransactionMeta, TransactionSigned, TransactionSignedNoHash,
-    TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
+    SealedHeader, StaticFileSegment, StorageEntry, TransactionMeta, TransactionSigned,
+    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
 };
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
+    collections::BTreeMap,
     ops::{RangeBounds, RangeInclusive},
     path::{Path, PathBuf},
     sync::Arc,
@@ -153,6 +164,79 @@ impl<DB: Database> ProviderFactory<DB> {
         trace!(target: "providers::db", ?block_number, %block_hash, "Returning historical state provider for block hash");
         Ok(state_provider)
     }
+
+    /// Checks that the database is usable before syncing on top of it.
+    ///
+    /// Verifies that all tables exist, and that written rows are read back and deleted, ordered
+    /// by key and by subkey within a dupsort key as the providers expect. The rows are written
+    /// with keys that real data never reaches, in a transaction that is aborted afterwards.
+    pub fn health_check(&self) -> ProviderResult<()> {
+        let tx = self.db.tx()?;
+        for table in Tables::ALL {
+            table.view(&TableEntries(&tx)).map_err(|err| {
+                ProviderError::HealthCheck(format!("table {table} is not accessible: {err}"))
+            })?;
+        }
+        drop(tx);
+
+        let tx = self.db.tx_mut()?;
+
+        let numbers = [u64::MAX, u64::MAX - 256, u64::MAX - 1];
+        let hash = |number: u64| B256::left_padding_from(&number.to_be_bytes());
+        for number in numbers {
+            tx.put::<tables::CanonicalHeaders>(number, hash(number))?;
+        }
+        let walked = tx
+            .cursor_read::<tables::CanonicalHeaders>()?
+            .walk(Some(u64::MAX - 256))?
+            .map(|row| row.map(|(number, _)| number))
+            .collect::<Result<Vec<_>, _>>()?;
+        if walked != [u64::MAX - 256, u64::MAX - 1, u64::MAX] {
+            let message = format!("keys are walked out of order: {walked:?}");
+            return Err(ProviderError::HealthCheck(message))
+        }
+        for number in numbers {
+            if tx.get::<tables::CanonicalHeaders>(number)? != Some(hash(number)) {
+                return Err(ProviderError::HealthCheck(format!("row #{number} is not read back")))
+            }
+            tx.delete::<tables::CanonicalHeaders>(number, None)?;
+            if tx.get::<tables::CanonicalHeaders>(number)?.is_some() {
+                return Err(ProviderError::HealthCheck(format!("row #{number} is not deleted")))
+            }
+        }
+
+        let address = Address::repeat_byte(0xff);
+        tx.delete::<tables::PlainStorageState>(address, None)?;
+        let mut cursor = tx.cursor_dup_write::<tables::PlainStorageState>()?;
+        for byte in [3, 1, 2] {
+            let entry = StorageEntry { key: B256::with_last_byte(byte), value: U256::from(byte) };
+            cursor.upsert(address, entry)?;
+        }
+        let walked = cursor
+            .walk_dup(Some(address), None)?
+            .map(|row| row.map(|(_, entry)| entry.key[31]))
+            .collect::<Result<Vec<_>, _>>()?;
+        if walked != [1, 2, 3] {
+            return Err(ProviderError::HealthCheck(format!(
+                "dupsort values are walked out of order: {walked:?}"
+            )))
+        }
+        drop(cursor);
+        tx.abort();
+
+        Ok(())
+    }
+}
+
+/// Counts the entries of a table, which fails if the table doesn't exist.
+struct TableEntries<'a, TX>(&'a TX);
+
+impl<TX: DbTx> TableViewer<usize> for TableEntries<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<usize, Self::Error> {
+        self.0.entries::<T>()
+    }
 }
 
 impl<DB: Database> DatabaseProviderFactory<DB> for ProviderFactory<DB> {
@@ -437,6 +521,15 @@ impl<DB: Database> ReceiptProvider for ProviderFactory<DB> {
             |_| true,
         )
     }
+
+    fn log_index_blocks(
+        &self,
+        addresses: &[Address],
+        topics0: &[B256],
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Option<BTreeMap<BlockNumber, Vec<TxNumber>>>> {
+        self.provider()?.log_index_blocks(addresses, topics0, range)
+    }
 }
 
 impl<DB: Database> WithdrawalsProvider for ProviderFactory<DB> {
@@ -549,12 +642,35 @@ impl<DB: Database> PruneCheckpointReader for ProviderFactory<DB> {
     }
 }
 
+impl<DB: Database> StateRangeProvider for ProviderFactory<DB> {
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: B256,
+        max_accounts: usize,
+    ) -> ProviderResult<HashedAccountRange> {
+        self.provider()?.hashed_account_range(start, limit, max_accounts)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: B256,
+        max_slots: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        self.provider()?.hashed_storage_range(hashed_address, start, limit, max_slots)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::ProviderFactory;
     use crate::{
-        providers::StaticFileWriter, test_utils::create_test_provider_factory, BlockHashReader,
-        BlockNumReader, BlockWriter, HeaderSyncGapProvider, HeaderSyncMode, TransactionsProvider,
+        providers::{StaticFileWriter, TransactionHashFilter},
+        test_utils::create_test_provider_factory,
+        BlockHashReader, BlockNumReader, BlockWriter, HeaderSyncGapProvider, HeaderSyncMode,
+        PruneCheckpointWriter, StateRangeProvider, TransactionsProvider,
     };
     use alloy_rlp::Decodable;
     use assert_matches::assert_matches;
@@ -563,6 +679,7 @@ mod tests {
         mdbx::DatabaseArguments,
         tables,
         test_utils::{create_test_static_files_dir, ERROR_TEMPDIR},
+        transaction::{DbTx, DbTxMut},
     };
     use reth_interfaces::{
         provider::ProviderError,
@@ -573,12 +690,23 @@ mod tests {
         RethError,
     };
     use reth_primitives::{
-        hex_literal::hex, ChainSpecBuilder, PruneMode, PruneModes, SealedBlock, StaticFileSegment,
-        TxNumber, B256, U256,
+        hex_literal::hex, static_file::SegmentRangeInclusive, Account, ChainSpecBuilder,
+        PruneCheckpoint, PruneMode, PruneModes, PruneSegment, SealedBlock, StaticFileSegment,
+        StorageEntry, TxNumber, B256, U256,
     };
     use std::{ops::RangeInclusive, sync::Arc};
     use tokio::sync::watch;
 
+    #[test]
+    fn health_check() {
+        let factory = create_test_provider_factory();
+        factory.health_check().unwrap();
+
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.tx_ref().entries::<tables::CanonicalHeaders>().unwrap(), 0);
+        assert_eq!(provider.tx_ref().entries::<tables::PlainStorageState>().unwrap(), 0);
+    }
+
     #[test]
     fn common_history_provider() {
         let factory = create_test_provider_factory();
@@ -662,6 +790,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pruned_transaction_id() {
+        let factory = create_test_provider_factory();
+
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, 0, None, Some(3), None);
+
+        // The transactions are in the static files, and their lookup entries are pruned.
+        let static_file_provider = factory.static_file_provider();
+        {
+            let mut writer =
+                static_file_provider.latest_writer(StaticFileSegment::Transactions).unwrap();
+            writer.increment_block(StaticFileSegment::Transactions, 0).unwrap();
+            for (tx_number, transaction) in block.body.iter().enumerate() {
+                writer
+                    .append_transaction(tx_number as TxNumber, transaction.clone().into())
+                    .unwrap();
+            }
+            writer.commit().unwrap();
+        }
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .save_prune_checkpoint(
+                PruneSegment::TransactionLookup,
+                PruneCheckpoint {
+                    block_number: Some(0),
+                    tx_number: Some(block.body.len() as TxNumber - 1),
+                    prune_mode: PruneMode::Full,
+                },
+            )
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        // The filter also holds a hash of no transaction, which is a false positive.
+        let false_positive = B256::random();
+        let hashes = block
+            .body
+            .iter()
+            .map(|transaction| transaction.hash)
+            .chain([false_positive])
+            .collect::<Vec<_>>();
+        static_file_provider
+            .write_transaction_hash_filters(vec![TransactionHashFilter::new(
+                SegmentRangeInclusive::new(0, 0),
+                0..block.body.len() as TxNumber,
+                &hashes,
+            )])
+            .unwrap();
+
+        let provider = factory.provider().unwrap();
+        for (tx_number, transaction) in block.body.iter().enumerate() {
+            assert_matches!(
+                provider.transaction_id(transaction.hash),
+                Ok(Some(id)) if id == tx_number as TxNumber
+            );
+        }
+        assert_matches!(provider.transaction_id(B256::random()), Ok(None));
+        assert_matches!(provider.transaction_id(false_positive), Ok(None));
+    }
+
     #[test]
     fn get_take_block_transaction_range_recover_senders() {
         let factory = create_test_provider_factory();
@@ -736,4 +924,38 @@ mod tests {
         assert_eq!(gap.local_head, head);
         assert_eq!(gap.target.tip(), consensus_tip.into());
     }
+
+    #[test]
+    fn hashed_state_ranges() {
+        let factory = create_test_provider_factory();
+
+        let hashed_address = B256::with_last_byte(2);
+        let slots = (1..=3)
+            .map(|byte| StorageEntry { key: B256::with_last_byte(byte), value: U256::from(byte) })
+            .collect::<Vec<_>>();
+        let provider = factory.provider_rw().unwrap();
+        for (nonce, slot) in slots.iter().enumerate() {
+            let account = Account { nonce: nonce as u64, ..Default::default() };
+            provider.tx_ref().put::<tables::HashedAccounts>(slot.key, account).unwrap();
+            provider.tx_ref().put::<tables::HashedStorages>(hashed_address, *slot).unwrap();
+        }
+        provider.commit().unwrap();
+
+        let range =
+            factory.hashed_account_range(slots[1].key, B256::repeat_byte(0xff), 10).unwrap();
+        assert_eq!(
+            range.accounts.iter().map(|(hashed_address, _)| *hashed_address).collect::<Vec<_>>(),
+            vec![slots[1].key, slots[2].key]
+        );
+        assert!(!range.proof.is_empty());
+
+        let range =
+            factory.hashed_storage_range(hashed_address, B256::ZERO, slots[1].key, 1).unwrap();
+        assert_eq!(range.slots, slots[..1]);
+        assert!(!range.proof.is_empty());
+
+        let range = factory.hashed_storage_range(B256::ZERO, B256::ZERO, B256::ZERO, 1).unwrap();
+        assert_eq!(range.slots, vec![]);
+        assert_eq!(range.proof, vec![]);
+    }
 }
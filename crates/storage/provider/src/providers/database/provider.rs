@@ -1,16 +1,20 @@
 use crate::{
-    bundle_state::{BundleStateInit, BundleStateWithReceipts, HashedStateChanges, RevertsInit},
+    bundle_state::{
+        BundleStateInit, BundleStateWithReceipts, HashedStateChanges, LogIndexChanges, RevertsInit,
+        LOG_INDEX_CHECKPOINT,
+    },
     providers::{database::metrics, static_file::StaticFileWriter, StaticFileProvider},
     to_range,
     traits::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
     },
     AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
-    Chain, EvmEnvProvider, HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider,
-    HeaderSyncMode, HistoricalStateProvider, HistoryWriter, LatestStateProvider,
-    OriginalValuesKnown, ProviderError, PruneCheckpointReader, PruneCheckpointWriter,
-    StageCheckpointReader, StateProviderBox, StatsReader, StorageReader, TransactionVariant,
-    TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
+    Chain, EvmEnvProvider, HashedAccountRange, HashedStorageRange, HashingWriter, HeaderProvider,
+    HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode, HistoricalStateProvider, HistoryWriter,
+    LatestStateProvider, OriginalValuesKnown, ProviderError, PruneCheckpointReader,
+    PruneCheckpointWriter, StageCheckpointReader, StateProviderBox, StateRangeProvider,
+    StatsReader, StorageReader, TransactionVariant, TransactionsProvider, TransactionsProviderExt,
+    WithdrawalsProvider,
 };
 use itertools::{izip, Itertools};
 use reth_db::{
@@ -18,8 +22,9 @@ use reth_db::{
     cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, RangeWalker},
     database::Database,
     models::{
-        sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
-        ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
+        log_index_key::LogIndexKey, sharded_key, storage_sharded_key::StorageShardedKey,
+        AccountBeforeTx, BlockNumberAddress, ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers,
+        StoredBlockWithdrawals,
     },
     table::{Table, TableRow},
     tables,
@@ -30,6 +35,7 @@ use reth_evm::ConfigureEvmEnv;
 use reth_interfaces::{
     p2p::headers::downloader::SyncTarget,
     provider::{ProviderResult, RootMismatch},
+    trie::StorageRootError,
     RethResult,
 };
 use reth_primitives::{
@@ -45,6 +51,7 @@ use reth_primitives::{
 };
 use reth_trie::{
     prefix_set::{PrefixSet, PrefixSetMut, TriePrefixSets},
+    proof::Proof,
     updates::TrieUpdates,
     HashedPostState, StateRoot,
 };
@@ -163,6 +170,22 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         }
         Ok(items)
     }
+
+    /// Looks up the number of a transaction whose [`tables::TransactionHashNumbers`] entry was
+    /// pruned, using the transaction hash filters of the static files.
+    ///
+    /// Returns `None` if the entries weren't pruned, or no filters were built. The entries are not
+    /// pruned past the highest filter, so every pruned transaction is covered by a filter.
+    fn pruned_transaction_id(&self, tx_hash: TxHash) -> ProviderResult<Option<TxNumber>> {
+        let Some(pruned_tx) = self
+            .get_prune_checkpoint(PruneSegment::TransactionLookup)?
+            .and_then(|checkpoint| checkpoint.tx_number)
+        else {
+            return Ok(None)
+        };
+
+        self.static_file_provider.transaction_id_by_hash_filters(tx_hash, pruned_tx)
+    }
 }
 
 impl<TX: DbTx + 'static> DatabaseProvider<TX> {
@@ -184,6 +207,8 @@ impl<TX: DbTx + 'static> DatabaseProvider<TX> {
             self.get_prune_checkpoint(PruneSegment::AccountHistory)?;
         let storage_history_prune_checkpoint =
             self.get_prune_checkpoint(PruneSegment::StorageHistory)?;
+        let changeset_epochs_prune_checkpoint =
+            self.get_prune_checkpoint(PruneSegment::ChangeSetEpochs)?;
 
         let mut state_provider =
             HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider);
@@ -204,6 +229,14 @@ impl<TX: DbTx + 'static> DatabaseProvider<TX> {
                 prune_checkpoint_block_number + 1,
             );
         }
+        // If we folded changesets into epochs, the state within the folded range is only available
+        // at the first blocks of the epochs.
+        if let Some(prune_checkpoint_block_number) =
+            changeset_epochs_prune_checkpoint.and_then(|checkpoint| checkpoint.block_number)
+        {
+            state_provider =
+                state_provider.with_changeset_epochs_block_number(prune_checkpoint_block_number);
+        }
 
         Ok(Box::new(state_provider))
     }
@@ -362,6 +395,31 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         Ok(self.tx.commit()?)
     }
 
+    /// Enables or disables the log index, returning the first block covered by the index if it
+    /// is enabled.
+    ///
+    /// Enabling the index makes it cover the blocks executed from now on, starting after the
+    /// [Execution][StageId::Execution] checkpoint. Disabling it removes all of its entries.
+    pub fn set_log_index_enabled(&self, enabled: bool) -> ProviderResult<Option<BlockNumber>> {
+        let start = LogIndexChanges::start_block(&self.tx)?;
+        match (enabled, start) {
+            (true, None) => {
+                let executed = self.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default();
+                let start = executed.block_number + 1;
+                self.save_stage_checkpoint(LOG_INDEX_CHECKPOINT, StageCheckpoint::new(start))?;
+                Ok(Some(start))
+            }
+            (false, Some(_)) => {
+                let key = LOG_INDEX_CHECKPOINT.to_string();
+                self.tx.delete::<tables::StageCheckpoints>(key, None)?;
+                self.tx.clear::<tables::LogIndex>()?;
+                Ok(None)
+            }
+            (true, start) => Ok(start),
+            (false, None) => Ok(None),
+        }
+    }
+
     // TODO(joshie) TEMPORARY should be moved to trait providers
 
     /// Unwind or peek at last N blocks of state recreating the [`BundleStateWithReceipts`].
@@ -799,7 +857,12 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
     where
         T: Table<Key = u64>,
     {
-        self.unwind_table::<T, _>(num, |key| key)
+        let Some(first) = num.checked_add(1) else { return Ok(0) };
+        let mut cursor = self.tx.cursor_write::<T>()?;
+        if cursor.seek(first)?.is_none() {
+            return Ok(0)
+        }
+        cursor.delete_while(|_, _| true)
     }
 
     /// Unwind the table to a provided number key.
@@ -1540,7 +1603,10 @@ impl<TX: DbTx> TransactionsProviderExt for DatabaseProvider<TX> {
 
 impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
     fn transaction_id(&self, tx_hash: TxHash) -> ProviderResult<Option<TxNumber>> {
-        Ok(self.tx.get::<tables::TransactionHashNumbers>(tx_hash)?)
+        if let Some(id) = self.tx.get::<tables::TransactionHashNumbers>(tx_hash)? {
+            return Ok(Some(id))
+        }
+        self.pruned_transaction_id(tx_hash)
     }
 
     fn transaction_by_id(&self, id: TxNumber) -> ProviderResult<Option<TransactionSigned>> {
@@ -1739,6 +1805,37 @@ impl<TX: DbTx> ReceiptProvider for DatabaseProvider<TX> {
             |_| true,
         )
     }
+
+    fn log_index_blocks(
+        &self,
+        addresses: &[Address],
+        topics0: &[B256],
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Option<BTreeMap<BlockNumber, Vec<TxNumber>>>> {
+        match LogIndexChanges::start_block(&self.tx)? {
+            Some(start) if start <= *range.start() => {}
+            _ => return Ok(None),
+        }
+
+        let mut cursor = self.tx.cursor_read::<tables::LogIndex>()?;
+        let mut blocks = BTreeMap::<BlockNumber, Vec<TxNumber>>::new();
+        for address in addresses {
+            for topic0 in topics0 {
+                let start = LogIndexKey::new(*address, *topic0, *range.start());
+                let end = LogIndexKey::new(*address, *topic0, *range.end());
+                for entry in cursor.walk_range(start..=end)? {
+                    let (key, txs) = entry?;
+                    blocks.entry(key.block_number).or_default().extend(txs.iter());
+                }
+            }
+        }
+        for txs in blocks.values_mut() {
+            txs.sort_unstable();
+            txs.dedup();
+        }
+
+        Ok(Some(blocks))
+    }
 }
 
 impl<TX: DbTx> WithdrawalsProvider for DatabaseProvider<TX> {
@@ -1926,6 +2023,52 @@ impl<TX: DbTxMut> StageCheckpointWriter for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTx> StateRangeProvider for DatabaseProvider<TX> {
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: B256,
+        max_accounts: usize,
+    ) -> ProviderResult<HashedAccountRange> {
+        let accounts = self
+            .tx
+            .cursor_read::<tables::HashedAccounts>()?
+            .walk_range(start..=limit)?
+            .take(max_accounts)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let last = accounts.last().map_or(limit, |(hashed_address, _)| *hashed_address);
+        let proof = Proof::new(&self.tx)
+            .account_range_proof(&[start, last])
+            .map_err(Into::<DatabaseError>::into)?;
+
+        Ok(HashedAccountRange { accounts, proof })
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: B256,
+        max_slots: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        let slots = self
+            .tx
+            .cursor_dup_read::<tables::HashedStorages>()?
+            .walk_dup_range(hashed_address, start..=limit)?
+            .take(max_slots)
+            .map(|entry| entry.map(|(_, slot)| slot))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let last = slots.last().map_or(limit, |slot| slot.key);
+        let proof = Proof::new(&self.tx)
+            .storage_range_proof(hashed_address, &[start, last])
+            .map_err(|StorageRootError::DB(err)| err)?;
+
+        Ok(HashedStorageRange { slots, proof })
+    }
+}
+
 impl<TX: DbTx> StorageReader for DatabaseProvider<TX> {
     fn plain_state_storages(
         &self,
@@ -1950,6 +2093,19 @@ impl<TX: DbTx> StorageReader for DatabaseProvider<TX> {
             .collect::<ProviderResult<Vec<(_, _)>>>()
     }
 
+    fn plain_state_storage_range(
+        &self,
+        address: Address,
+        range: impl RangeBounds<B256>,
+    ) -> ProviderResult<Vec<StorageEntry>> {
+        Ok(self
+            .tx
+            .cursor_dup_read::<tables::PlainStorageState>()?
+            .walk_dup_range(address, range)?
+            .map(|entry| entry.map(|(_, storage)| storage))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
     fn changed_storages_with_range(
         &self,
         range: RangeInclusive<BlockNumber>,
@@ -2,9 +2,10 @@ use crate::{
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
     BlockSource, BlockchainTreePendingStateProvider, BundleStateDataProvider, CanonChainTracker,
     CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    DatabaseProviderFactory, EvmEnvProvider, HeaderProvider, ProviderError, PruneCheckpointReader,
-    ReceiptProvider, ReceiptProviderIdExt, StageCheckpointReader, StateProviderBox,
-    StateProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    DatabaseProviderFactory, EvmEnvProvider, HashedAccountRange, HashedStorageRange,
+    HeaderProvider, ProviderError, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
+    StageCheckpointReader, StateProviderBox, StateProviderFactory, StateRangeProvider,
+    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::{
     database::Database,
@@ -44,7 +45,8 @@ pub use database::*;
 mod static_file;
 pub use static_file::{
     StaticFileJarProvider, StaticFileProvider, StaticFileProviderRW, StaticFileProviderRWRefMut,
-    StaticFileWriter,
+    StaticFileWriter, TransactionHashFilter, TransactionHashFilterEntry, TransactionHashFilters,
+    BLOCKS_PER_TRANSACTION_HASH_FILTER,
 };
 
 mod state;
@@ -404,6 +406,15 @@ where
     ) -> ProviderResult<Vec<Receipt>> {
         self.database.receipts_by_tx_range(range)
     }
+
+    fn log_index_blocks(
+        &self,
+        addresses: &[Address],
+        topics0: &[B256],
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Option<BTreeMap<BlockNumber, Vec<TxNumber>>>> {
+        self.database.log_index_blocks(addresses, topics0, range)
+    }
 }
 impl<DB, Tree> ReceiptProviderIdExt for BlockchainProvider<DB, Tree>
 where
@@ -550,6 +561,31 @@ where
     }
 }
 
+impl<DB, Tree> StateRangeProvider for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: B256,
+        max_accounts: usize,
+    ) -> ProviderResult<HashedAccountRange> {
+        self.database.hashed_account_range(start, limit, max_accounts)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: B256,
+        max_slots: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        self.database.hashed_storage_range(hashed_address, start, limit, max_slots)
+    }
+}
+
 impl<DB, Tree> ChainSpecProvider for BlockchainProvider<DB, Tree>
 where
     DB: Send + Sync,
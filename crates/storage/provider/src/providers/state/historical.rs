@@ -12,12 +12,12 @@ use reth_db::{
 };
 use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{
-    constants::EPOCH_SLOTS, trie::AccountProof, Account, Address, BlockNumber, Bytecode,
-    StaticFileSegment, StorageKey, StorageValue, B256,
+    changeset_epoch_start, constants::EPOCH_SLOTS, trie::AccountProof, Account, Address,
+    BlockNumber, Bytecode, StaticFileSegment, StorageKey, StorageValue, B256,
 };
 use reth_trie::{updates::TrieUpdates, HashedPostState};
 use revm::db::BundleState;
-use std::fmt::Debug;
+use std::{collections::BTreeMap, fmt::Debug};
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -30,6 +30,8 @@ use std::fmt::Debug;
 /// - [tables::StoragesHistory]
 /// - [tables::AccountChangeSets]
 /// - [tables::StorageChangeSets]
+/// - [tables::AccountChangeSetEpochs]
+/// - [tables::StorageChangeSetEpochs]
 #[derive(Debug)]
 pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     /// Transaction
@@ -79,7 +81,8 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
 
         // history key to search IntegerList of block number changesets.
         let history_key = ShardedKey::new(address, self.block_number);
-        self.history_info::<tables::AccountsHistory, _>(
+        self.history_info::<tables::AccountsHistory, _, _>(
+            &mut self.tx.cursor_read::<tables::AccountsHistory>()?,
             history_key,
             |key| key.key == address,
             self.lowest_available_blocks.account_history_block_number,
@@ -98,7 +101,8 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
 
         // history key to search IntegerList of block number changesets.
         let history_key = StorageShardedKey::new(address, storage_key, self.block_number);
-        self.history_info::<tables::StoragesHistory, _>(
+        self.history_info::<tables::StoragesHistory, _, _>(
+            &mut self.tx.cursor_read::<tables::StoragesHistory>()?,
             history_key,
             |key| key.address == address && key.sharded_key.key == storage_key,
             self.lowest_available_blocks.storage_history_block_number,
@@ -134,21 +138,60 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         Ok(HashedPostState::from_revert_range(self.tx, self.block_number..=tip)?)
     }
 
-    fn history_info<T, K>(
+    /// Returns the state of the account before it was changed in the block, if the changeset of
+    /// the block was folded into its epoch.
+    ///
+    /// The epoch holds the state before the first change in the epoch, which equals the state
+    /// before the change in the block at every block the history is available at.
+    fn folded_account_changeset(
         &self,
+        block_number: BlockNumber,
+        address: Address,
+    ) -> ProviderResult<Option<Account>> {
+        Ok(self
+            .tx
+            .cursor_dup_read::<tables::AccountChangeSetEpochs>()?
+            .seek_by_key_subkey(changeset_epoch_start(block_number), address)?
+            .filter(|acc| acc.address == address)
+            .ok_or(ProviderError::AccountChangesetNotFound { block_number, address })?
+            .info)
+    }
+
+    /// Returns the value of the storage slot before it was changed in the block, if the changeset
+    /// of the block was folded into its epoch. See [Self::folded_account_changeset].
+    fn folded_storage_changeset(
+        &self,
+        block_number: BlockNumber,
+        address: Address,
+        storage_key: StorageKey,
+    ) -> ProviderResult<StorageValue> {
+        Ok(self
+            .tx
+            .cursor_dup_read::<tables::StorageChangeSetEpochs>()?
+            .seek_by_key_subkey((changeset_epoch_start(block_number), address).into(), storage_key)?
+            .filter(|entry| entry.key == storage_key)
+            .ok_or_else(|| ProviderError::StorageChangesetNotFound {
+                block_number,
+                address,
+                storage_key: Box::new(storage_key),
+            })?
+            .value)
+    }
+
+    fn history_info<T, K, C>(
+        &self,
+        cursor: &mut C,
         key: K,
         key_filter: impl Fn(&K) -> bool,
         lowest_available_block_number: Option<BlockNumber>,
     ) -> ProviderResult<HistoryInfo>
     where
         T: Table<Key = K, Value = BlockNumberList>,
+        C: DbCursorRO<T>,
     {
-        let mut cursor = self.tx.cursor_read::<T>()?;
-
-        // Lookup the history chunk in the history index. If they key does not appear in the
-        // index, the first chunk for the next key will be returned so we filter out chunks that
-        // have a different key.
-        if let Some(chunk) = cursor.seek(key)?.filter(|(key, _)| key_filter(key)).map(|x| x.1 .0) {
+        // Lookup the history chunk in the history index.
+        let shard = seek_history_shard::<T, _>(cursor, key, &key_filter)?;
+        if let Some(chunk) = shard.map(|shard| shard.0) {
             // Get the rank of the first entry before or equal to our block.
             let mut rank = chunk.rank(self.block_number);
 
@@ -168,7 +211,7 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
             // table.
             if rank == 0 &&
                 block_number != Some(self.block_number) &&
-                !cursor.prev()?.is_some_and(|(key, _)| key_filter(&key))
+                !has_previous_shard::<T, _>(cursor, &key_filter)?
             {
                 if let (Some(_), Some(block_number)) = (lowest_available_block_number, block_number)
                 {
@@ -198,21 +241,53 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
     }
 }
 
+/// Seek the shard of the history table `T` that contains the block of `key`, which is the first
+/// shard of the key with a highest block number greater than or equal to the block.
+///
+/// If the key doesn't appear in the index, the cursor lands on the first shard of the next key,
+/// so shards for which `key_filter` returns `false` are discarded. The cursor is left at the shard,
+/// so [has_previous_shard] can check the shard before it.
+pub(crate) fn seek_history_shard<T, C>(
+    cursor: &mut C,
+    key: T::Key,
+    key_filter: impl Fn(&T::Key) -> bool,
+) -> ProviderResult<Option<BlockNumberList>>
+where
+    T: Table<Value = BlockNumberList>,
+    C: DbCursorRO<T>,
+{
+    Ok(cursor.seek(key)?.filter(|(key, _)| key_filter(key)).map(|(_, shard)| shard))
+}
+
+/// Returns `true` if the shard before the one at the cursor position belongs to the key matched
+/// by `key_filter`.
+pub(crate) fn has_previous_shard<T, C>(
+    cursor: &mut C,
+    key_filter: impl Fn(&T::Key) -> bool,
+) -> ProviderResult<bool>
+where
+    T: Table<Value = BlockNumberList>,
+    C: DbCursorRO<T>,
+{
+    Ok(cursor.prev()?.is_some_and(|(key, _)| key_filter(&key)))
+}
+
 impl<'b, TX: DbTx> AccountReader for HistoricalStateProviderRef<'b, TX> {
     /// Get basic account information.
     fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
         match self.account_history_lookup(address)? {
             HistoryInfo::NotYetWritten => Ok(None),
-            HistoryInfo::InChangeset(changeset_block_number) => Ok(self
-                .tx
-                .cursor_dup_read::<tables::AccountChangeSets>()?
-                .seek_by_key_subkey(changeset_block_number, address)?
-                .filter(|acc| acc.address == address)
-                .ok_or(ProviderError::AccountChangesetNotFound {
-                    block_number: changeset_block_number,
-                    address,
-                })?
-                .info),
+            HistoryInfo::InChangeset(changeset_block_number) => {
+                match self
+                    .tx
+                    .cursor_dup_read::<tables::AccountChangeSets>()?
+                    .seek_by_key_subkey(changeset_block_number, address)?
+                    .filter(|acc| acc.address == address)
+                {
+                    Some(acc) => Ok(acc.info),
+                    None => self.folded_account_changeset(changeset_block_number, address),
+                }
+            }
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
                 Ok(self.tx.get::<tables::PlainAccountState>(address)?)
             }
@@ -281,18 +356,19 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
     ) -> ProviderResult<Option<StorageValue>> {
         match self.storage_history_lookup(address, storage_key)? {
             HistoryInfo::NotYetWritten => Ok(None),
-            HistoryInfo::InChangeset(changeset_block_number) => Ok(Some(
-                self.tx
+            HistoryInfo::InChangeset(changeset_block_number) => {
+                match self
+                    .tx
                     .cursor_dup_read::<tables::StorageChangeSets>()?
                     .seek_by_key_subkey((changeset_block_number, address).into(), storage_key)?
                     .filter(|entry| entry.key == storage_key)
-                    .ok_or_else(|| ProviderError::StorageChangesetNotFound {
-                        block_number: changeset_block_number,
-                        address,
-                        storage_key: Box::new(storage_key),
-                    })?
-                    .value,
-            )),
+                {
+                    Some(entry) => Ok(Some(entry.value)),
+                    None => self
+                        .folded_storage_changeset(changeset_block_number, address, storage_key)
+                        .map(Some),
+                }
+            }
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => Ok(self
                 .tx
                 .cursor_dup_read::<tables::PlainStorageState>()?
@@ -303,6 +379,84 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
         }
     }
 
+    /// Get storage of many slots at once.
+    ///
+    /// The slots are resolved in sorted order, so that the history index, the changesets and the
+    /// plain state are each read in a single forward pass instead of a fresh seek per slot.
+    fn storages(
+        &self,
+        slots: &[(Address, StorageKey)],
+    ) -> ProviderResult<Vec<Option<StorageValue>>> {
+        if !self.lowest_available_blocks.is_storage_history_available(self.block_number) {
+            return Err(ProviderError::StateAtBlockPruned(self.block_number))
+        }
+
+        let mut order = (0..slots.len()).collect::<Vec<_>>();
+        order.sort_unstable_by_key(|index| slots[*index]);
+
+        // Resolve the location of every slot with one pass over the history index.
+        let mut history_cursor = self.tx.cursor_read::<tables::StoragesHistory>()?;
+        let mut in_changesets = BTreeMap::<(BlockNumber, Address), Vec<(StorageKey, usize)>>::new();
+        let mut in_plain_state = Vec::new();
+        for index in order {
+            let (address, storage_key) = slots[index];
+            let history_key = StorageShardedKey::new(address, storage_key, self.block_number);
+            match self.history_info::<tables::StoragesHistory, _, _>(
+                &mut history_cursor,
+                history_key,
+                |key| key.address == address && key.sharded_key.key == storage_key,
+                self.lowest_available_blocks.storage_history_block_number,
+            )? {
+                HistoryInfo::NotYetWritten => {}
+                HistoryInfo::InChangeset(changeset_block_number) => in_changesets
+                    .entry((changeset_block_number, address))
+                    .or_default()
+                    .push((storage_key, index)),
+                HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
+                    in_plain_state.push((address, storage_key, index))
+                }
+            }
+        }
+
+        let mut values = vec![None; slots.len()];
+
+        // The changeset of an account at a block only holds the slots written in that block, so
+        // it's merged with the requested slots by walking the range between them.
+        let mut changeset_cursor = self.tx.cursor_dup_read::<tables::StorageChangeSets>()?;
+        for ((changeset_block_number, address), storage_keys) in in_changesets {
+            let range = storage_keys[0].0..=storage_keys[storage_keys.len() - 1].0;
+            let mut walker =
+                changeset_cursor.walk_dup_range((changeset_block_number, address).into(), range)?;
+            let mut entry = walker.next().transpose()?;
+            for (storage_key, index) in storage_keys {
+                while entry.as_ref().is_some_and(|(_, entry)| entry.key < storage_key) {
+                    entry = walker.next().transpose()?;
+                }
+                let value = match entry.as_ref().filter(|(_, entry)| entry.key == storage_key) {
+                    Some((_, entry)) => entry.value,
+                    None => {
+                        self.folded_storage_changeset(changeset_block_number, address, storage_key)?
+                    }
+                };
+                values[index] = Some(value);
+            }
+        }
+
+        // The plain state of an account may hold any number of slots, so the requested slots are
+        // sought one by one with the same cursor instead.
+        let mut plain_state_cursor = self.tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        for (address, storage_key, index) in in_plain_state {
+            let value = plain_state_cursor
+                .seek_by_key_subkey(address, storage_key)?
+                .filter(|entry| entry.key == storage_key)
+                .map(|entry| entry.value)
+                .unwrap_or(StorageValue::ZERO);
+            values[index] = Some(value);
+        }
+
+        Ok(values)
+    }
+
     /// Get account code by its hash
     fn bytecode_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
         self.tx.get::<tables::Bytecodes>(code_hash).map_err(Into::into)
@@ -356,6 +510,12 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         self
     }
 
+    /// Set the highest block number whose changesets were folded into epochs.
+    pub fn with_changeset_epochs_block_number(mut self, block_number: BlockNumber) -> Self {
+        self.lowest_available_blocks.changeset_epochs_block_number = Some(block_number);
+        self
+    }
+
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref(&self) -> HistoricalStateProviderRef<'_, TX> {
@@ -383,37 +543,57 @@ pub struct LowestAvailableBlocks {
     /// [reth_primitives::PruneSegment::StorageHistory] was pruned.
     /// [Option::None] means all history is available.
     pub storage_history_block_number: Option<BlockNumber>,
+    /// Highest block number whose changesets were folded into epochs by
+    /// [reth_primitives::PruneSegment::ChangeSetEpochs]. Up to this block, the history is only
+    /// available at the first blocks of the epochs.
+    /// [Option::None] means no changesets were folded.
+    pub changeset_epochs_block_number: Option<BlockNumber>,
 }
 
 impl LowestAvailableBlocks {
     /// Check if account history is available at the provided block number, i.e. lowest available
     /// block number for account history is less than or equal to the provided block number.
     pub fn is_account_history_available(&self, at: BlockNumber) -> bool {
-        self.account_history_block_number.map(|block_number| block_number <= at).unwrap_or(true)
+        self.account_history_block_number.map(|block_number| block_number <= at).unwrap_or(true) &&
+            self.is_changeset_history_available(at)
     }
 
     /// Check if storage history is available at the provided block number, i.e. lowest available
     /// block number for storage history is less than or equal to the provided block number.
     pub fn is_storage_history_available(&self, at: BlockNumber) -> bool {
-        self.storage_history_block_number.map(|block_number| block_number <= at).unwrap_or(true)
+        self.storage_history_block_number.map(|block_number| block_number <= at).unwrap_or(true) &&
+            self.is_changeset_history_available(at)
+    }
+
+    /// Check if the history is available at the provided block number with regard to the
+    /// changesets folded into epochs, i.e. the block number is after the highest folded block
+    /// number or it's the first block of an epoch.
+    pub fn is_changeset_history_available(&self, at: BlockNumber) -> bool {
+        self.changeset_epochs_block_number
+            .map(|block_number| block_number < at || changeset_epoch_start(at) == at)
+            .unwrap_or(true)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        providers::state::historical::{HistoryInfo, LowestAvailableBlocks},
+        providers::state::historical::{
+            has_previous_shard, seek_history_shard, HistoryInfo, LowestAvailableBlocks,
+        },
         test_utils::create_test_provider_factory,
         AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
     };
     use reth_db::{
         models::{storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey},
-        tables,
+        tables::{self, AccountsHistory},
         transaction::{DbTx, DbTxMut},
         BlockNumberList,
     };
     use reth_interfaces::provider::ProviderError;
-    use reth_primitives::{address, b256, Account, Address, StorageEntry, B256, U256};
+    use reth_primitives::{
+        address, b256, Account, Address, StorageEntry, B256, CHANGESET_EPOCH_LENGTH, U256,
+    };
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
     const HIGHER_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
@@ -656,6 +836,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn history_provider_get_storages() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+
+        let other_storage = B256::with_last_byte(2);
+        for (address, storage_key, blocks) in [
+            (ADDRESS, STORAGE, vec![3, 7]),
+            (ADDRESS, other_storage, vec![7, 10]),
+            (HIGHER_ADDRESS, STORAGE, vec![4]),
+        ] {
+            for &block_number in &blocks {
+                let entry = StorageEntry { key: storage_key, value: U256::from(block_number) };
+                tx.put::<tables::StorageChangeSets>((block_number, address).into(), entry).unwrap();
+            }
+            tx.put::<tables::StoragesHistory>(
+                StorageShardedKey::new(address, storage_key, u64::MAX),
+                BlockNumberList::new(blocks).unwrap(),
+            )
+            .unwrap();
+            let entry = StorageEntry { key: storage_key, value: U256::from(100) };
+            tx.put::<tables::PlainStorageState>(address, entry).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = factory.provider().unwrap().into_tx();
+        let slots = [
+            (HIGHER_ADDRESS, STORAGE),
+            (ADDRESS, other_storage),
+            (ADDRESS, STORAGE),
+            (ADDRESS, B256::with_last_byte(3)),
+            (ADDRESS, other_storage),
+        ];
+        for block_number in [0, 3, 4, 5, 7, 8, 10, 11] {
+            let provider =
+                HistoricalStateProviderRef::new(&tx, block_number, static_file_provider.clone());
+            let expected = slots
+                .iter()
+                .map(|(address, storage_key)| provider.storage(*address, *storage_key).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(provider.storages(&slots), Ok(expected), "block {block_number}");
+        }
+    }
+
     #[test]
     fn history_provider_unavailable() {
         let factory = create_test_provider_factory();
@@ -670,6 +895,7 @@ mod tests {
             LowestAvailableBlocks {
                 account_history_block_number: Some(3),
                 storage_history_block_number: Some(3),
+                changeset_epochs_block_number: None,
             },
             static_file_provider.clone(),
         );
@@ -690,6 +916,7 @@ mod tests {
             LowestAvailableBlocks {
                 account_history_block_number: Some(2),
                 storage_history_block_number: Some(2),
+                changeset_epochs_block_number: None,
             },
             static_file_provider.clone(),
         );
@@ -707,6 +934,7 @@ mod tests {
             LowestAvailableBlocks {
                 account_history_block_number: Some(1),
                 storage_history_block_number: Some(1),
+                changeset_epochs_block_number: None,
             },
             static_file_provider,
         );
@@ -716,4 +944,90 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    #[test]
+    fn history_provider_changeset_epochs() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+
+        let epoch_block = CHANGESET_EPOCH_LENGTH;
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey { key: ADDRESS, highest_block_number: u64::MAX },
+            BlockNumberList::new([3, 7, epoch_block + 5]).unwrap(),
+        )
+        .unwrap();
+
+        let acc_plain = Account { nonce: 100, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at_epoch = Account { nonce: 7, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at3 = Account { nonce: 3, balance: U256::ZERO, bytecode_hash: None };
+
+        // Changesets of all blocks are folded into epochs, keeping the first change of each epoch.
+        tx.put::<tables::AccountChangeSetEpochs>(
+            0,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at3) },
+        )
+        .unwrap();
+        tx.put::<tables::AccountChangeSetEpochs>(
+            epoch_block,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at_epoch) },
+        )
+        .unwrap();
+        tx.put::<tables::PlainAccountState>(ADDRESS, acc_plain).unwrap();
+        tx.commit().unwrap();
+
+        let tx = factory.provider().unwrap().into_tx();
+        let provider = |block_number| {
+            HistoricalStateProviderRef::new_with_lowest_available_blocks(
+                &tx,
+                block_number,
+                LowestAvailableBlocks {
+                    changeset_epochs_block_number: Some(epoch_block + 5),
+                    ..Default::default()
+                },
+                static_file_provider.clone(),
+            )
+        };
+
+        assert_eq!(provider(0).basic_account(ADDRESS), Ok(Some(acc_at3)));
+        assert_eq!(provider(4).basic_account(ADDRESS), Err(ProviderError::StateAtBlockPruned(4)));
+        assert_eq!(provider(epoch_block).basic_account(ADDRESS), Ok(Some(acc_at_epoch)));
+        assert_eq!(provider(epoch_block + 6).basic_account(ADDRESS), Ok(Some(acc_plain)));
+    }
+
+    #[test]
+    fn history_shard_lookup() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+
+        let shard = |block_number: u64| BlockNumberList::new([block_number]).unwrap();
+        for highest_block_number in [100, 200, 300] {
+            let key = ShardedKey::new(ADDRESS, highest_block_number);
+            tx.put::<AccountsHistory>(key, shard(highest_block_number)).unwrap();
+        }
+        tx.put::<AccountsHistory>(ShardedKey::last(ADDRESS), shard(400)).unwrap();
+        tx.put::<AccountsHistory>(ShardedKey::last(HIGHER_ADDRESS), shard(400)).unwrap();
+
+        let mut cursor = tx.cursor_read::<AccountsHistory>().unwrap();
+        let is_address = |key: &ShardedKey<Address>| key.key == ADDRESS;
+        let mut seek = |block_number| {
+            let key = ShardedKey::new(ADDRESS, block_number);
+            let shard = seek_history_shard::<AccountsHistory, _>(&mut cursor, key, is_address);
+            let has_previous = has_previous_shard::<AccountsHistory, _>(&mut cursor, is_address);
+            (shard.unwrap(), has_previous.unwrap())
+        };
+
+        // The first shard has no shard before it.
+        assert_eq!(seek(50), (Some(shard(100)), false));
+        assert_eq!(seek(100), (Some(shard(100)), false));
+        // `Address | 150` falls in the `Address | 200` shard.
+        assert_eq!(seek(150), (Some(shard(200)), true));
+        // Blocks after the last sharded block fall in the last shard.
+        assert_eq!(seek(1000), (Some(shard(400)), true));
+
+        // A key that isn't indexed lands on the shard of the next key, which is discarded.
+        let key = ShardedKey::new(Address::with_last_byte(3), 0);
+        let shard = seek_history_shard::<AccountsHistory, _>(&mut cursor, key, is_address);
+        assert_eq!(shard.unwrap(), None);
+    }
 }
@@ -0,0 +1,279 @@
+use memmap2::Mmap;
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{fs::FsPathError, static_file::SegmentRangeInclusive, TxHash, TxNumber};
+use std::{
+    fs::File,
+    io::Write,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// Number of blocks whose transaction hashes are held by one [`TransactionHashFilter`].
+pub const BLOCKS_PER_TRANSACTION_HASH_FILTER: u64 = 1_000;
+
+/// Number of bits of a [`TransactionHashFilter`] per transaction.
+const BITS_PER_TRANSACTION: u64 = 24;
+
+/// Number of bits set in a [`TransactionHashFilter`] per transaction hash. Along with
+/// [`BITS_PER_TRANSACTION`], gives a false positive rate of about 0.001%, so that a lookup of an
+/// unknown hash rarely hashes the transactions of a filter again, even with many filters.
+const BITS_PER_HASH: u64 = 17;
+
+/// Size of the header of an encoded [`TransactionHashFilter`]: the block range, the transaction
+/// range and the number of words of the filter.
+const HEADER_SIZE: usize = 5 * 8;
+
+/// Bloom filter of the hashes of the transactions of a range of blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionHashFilter {
+    /// Blocks of the transactions.
+    block_range: SegmentRangeInclusive,
+    /// Numbers of the transactions.
+    tx_range: Range<TxNumber>,
+    /// Bits of the filter.
+    words: Vec<u64>,
+}
+
+impl TransactionHashFilter {
+    /// Creates the filter of the hashes of the transactions of the block range.
+    pub fn new<'a>(
+        block_range: SegmentRangeInclusive,
+        tx_range: Range<TxNumber>,
+        hashes: impl IntoIterator<Item = &'a TxHash>,
+    ) -> Self {
+        let bits = (tx_range.end - tx_range.start) * BITS_PER_TRANSACTION;
+        let mut words = vec![0u64; bits.div_ceil(64).max(1) as usize];
+        let bits = words.len() as u64 * 64;
+        for hash in hashes {
+            for bit in bit_positions(hash, bits) {
+                words[(bit / 64) as usize] |= 1u64 << (bit % 64);
+            }
+        }
+        Self { block_range, tx_range, words }
+    }
+
+    /// Returns the blocks of the transactions of the filter.
+    pub fn block_range(&self) -> SegmentRangeInclusive {
+        self.block_range
+    }
+
+    /// Encodes the filter into the buffer.
+    fn encode(&self, buf: &mut Vec<u8>) {
+        for value in [
+            self.block_range.start(),
+            self.block_range.end(),
+            self.tx_range.start,
+            self.tx_range.end,
+            self.words.len() as u64,
+        ] {
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+        for word in &self.words {
+            buf.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+}
+
+/// Location of a [`TransactionHashFilter`] in a [`TransactionHashFilters`] file.
+#[derive(Debug, Clone)]
+pub struct TransactionHashFilterEntry {
+    /// Blocks of the transactions.
+    pub block_range: SegmentRangeInclusive,
+    /// Numbers of the transactions.
+    pub tx_range: Range<TxNumber>,
+    /// Offset of the words of the filter in the file.
+    offset: usize,
+    /// Number of words of the filter.
+    words: usize,
+}
+
+/// Transaction hash filters of a transactions static file, ordered by block range. The filters
+/// are stored next to the static file, and read through a memory map.
+#[derive(Debug, Default)]
+pub struct TransactionHashFilters {
+    /// Memory map of the file, `None` if the file doesn't exist.
+    mmap: Option<Mmap>,
+    /// Filters of the file.
+    entries: Vec<TransactionHashFilterEntry>,
+}
+
+impl TransactionHashFilters {
+    /// Loads the filters of the file at the path. A missing file has no filters.
+    ///
+    /// A filter truncated at the end of the file is ignored, so it's built again. So is a filter
+    /// whose header is corrupted, along with the filters after it.
+    pub fn load(path: &Path) -> ProviderResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default())
+        }
+
+        let file = File::open(path).map_err(|err| FsPathError::open(err, path))?;
+        // SAFETY: The file is replaced by renaming a new file over it, and never modified in place.
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|err| FsPathError::read(err, path))?;
+
+        let read_u64 = |offset: usize| {
+            u64::from_le_bytes(mmap[offset..offset + 8].try_into().expect("slice of 8 bytes"))
+        };
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        while offset + HEADER_SIZE <= mmap.len() {
+            // A filter has at least one word, and its size can't exceed the file.
+            let Some((words, end)) = usize::try_from(read_u64(offset + 32))
+                .ok()
+                .filter(|words| *words > 0)
+                .and_then(|words| {
+                    let end = words.checked_mul(8)?.checked_add(offset + HEADER_SIZE)?;
+                    Some((words, end))
+                })
+                .filter(|(_, end)| *end <= mmap.len())
+            else {
+                break
+            };
+            entries.push(TransactionHashFilterEntry {
+                block_range: SegmentRangeInclusive::new(read_u64(offset), read_u64(offset + 8)),
+                tx_range: read_u64(offset + 16)..read_u64(offset + 24),
+                offset: offset + HEADER_SIZE,
+                words,
+            });
+            offset = end;
+        }
+
+        Ok(Self { mmap: Some(mmap), entries })
+    }
+
+    /// Writes the filters to the file at the path, replacing the filters of the file starting at
+    /// or after the first new filter.
+    pub fn write(&self, path: &Path, filters: &[TransactionHashFilter]) -> ProviderResult<()> {
+        let Some(first) = filters.first() else { return Ok(()) };
+
+        let mut buf = Vec::new();
+        if let Some(mmap) = &self.mmap {
+            let kept = self
+                .entries
+                .iter()
+                .take_while(|entry| entry.block_range.start() < first.block_range.start())
+                .last()
+                .map_or(0, |entry| entry.offset + entry.words * 8);
+            buf.extend_from_slice(&mmap[..kept]);
+        }
+        for filter in filters {
+            filter.encode(&mut buf);
+        }
+
+        // Write the filters to a temporary file first, so the file is replaced atomically.
+        let tmp_path = tmp_path(path);
+        let mut file =
+            File::create(&tmp_path).map_err(|err| FsPathError::create_file(err, &tmp_path))?;
+        file.write_all(&buf)
+            .and_then(|_| file.sync_all())
+            .map_err(|err| FsPathError::write(err, &tmp_path))?;
+        reth_primitives::fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    }
+
+    /// Returns the filters, ordered by block range.
+    pub fn entries(&self) -> &[TransactionHashFilterEntry] {
+        &self.entries
+    }
+
+    /// Returns `true` if the transaction hash may be held by the filter. There might be false
+    /// positives.
+    pub fn may_contain(&self, entry: &TransactionHashFilterEntry, hash: &TxHash) -> bool {
+        let Some(mmap) = &self.mmap else { return false };
+        let word = |index: u64| {
+            let offset = entry.offset + index as usize * 8;
+            u64::from_le_bytes(mmap[offset..offset + 8].try_into().expect("slice of 8 bytes"))
+        };
+        bit_positions(hash, entry.words as u64 * 64)
+            .all(|bit| word(bit / 64) & (1u64 << (bit % 64)) != 0)
+    }
+}
+
+/// Returns the path of the temporary file used to replace the file at the path.
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".tmp");
+    path.into()
+}
+
+/// Returns the positions of the bits of the hash in a filter of the given number of bits.
+///
+/// Transaction hashes are uniformly distributed, so the positions are derived from the hash
+/// itself with double hashing.
+fn bit_positions(hash: &TxHash, bits: u64) -> impl Iterator<Item = u64> {
+    let h1 = u64::from_le_bytes(hash[..8].try_into().expect("slice of 8 bytes"));
+    let h2 = u64::from_le_bytes(hash[8..16].try_into().expect("slice of 8 bytes")) | 1;
+    (0..BITS_PER_HASH).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+
+    #[test]
+    fn transaction_hash_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("filters");
+
+        let hashes = (0..100).map(|_| B256::random()).collect::<Vec<_>>();
+        let first =
+            TransactionHashFilter::new(SegmentRangeInclusive::new(0, 999), 0..50, &hashes[..50]);
+        let second = TransactionHashFilter::new(
+            SegmentRangeInclusive::new(1000, 1499),
+            50..60,
+            &hashes[50..60],
+        );
+        TransactionHashFilters::default().write(&path, &[first.clone(), second]).unwrap();
+
+        // The filter of the partial block range is replaced.
+        let filters = TransactionHashFilters::load(&path).unwrap();
+        let second = TransactionHashFilter::new(
+            SegmentRangeInclusive::new(1000, 1999),
+            50..100,
+            &hashes[50..],
+        );
+        filters.write(&path, &[second]).unwrap();
+
+        let filters = TransactionHashFilters::load(&path).unwrap();
+        let entries = filters.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].block_range, first.block_range());
+        assert_eq!(entries[0].tx_range, 0..50);
+        assert_eq!(entries[1].block_range, SegmentRangeInclusive::new(1000, 1999));
+        assert_eq!(entries[1].tx_range, 50..100);
+
+        for hash in &hashes[..50] {
+            assert!(filters.may_contain(&entries[0], hash));
+        }
+        for hash in &hashes[50..] {
+            assert!(filters.may_contain(&entries[1], hash));
+        }
+    }
+
+    #[test]
+    fn corrupted_transaction_hash_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("filters");
+
+        let hashes = (0..10).map(|_| B256::random()).collect::<Vec<_>>();
+        let filter = TransactionHashFilter::new(SegmentRangeInclusive::new(0, 999), 0..10, &hashes);
+        let mut valid = Vec::new();
+        filter.encode(&mut valid);
+
+        // The filters from the one with a corrupted number of words are ignored.
+        for words in [0, u64::MAX, u64::MAX / 8, (u64::MAX - HEADER_SIZE as u64) / 8 + 1] {
+            let mut buf = valid.clone();
+            let mut corrupted = valid.clone();
+            corrupted[32..40].copy_from_slice(&words.to_le_bytes());
+            buf.extend_from_slice(&corrupted);
+            buf.extend_from_slice(&valid);
+            std::fs::write(&path, buf).unwrap();
+
+            let filters = TransactionHashFilters::load(&path).unwrap();
+            assert_eq!(filters.entries().len(), 1, "words: {words}");
+            assert!(filters.may_contain(&filters.entries()[0], &hashes[0]));
+        }
+    }
+}
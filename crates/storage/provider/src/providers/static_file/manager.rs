@@ -1,6 +1,7 @@
 use super::{
     metrics::StaticFileProviderMetrics, LoadedJar, StaticFileJarProvider, StaticFileProviderRW,
-    StaticFileProviderRWRefMut, BLOCKS_PER_STATIC_FILE,
+    StaticFileProviderRWRefMut, TransactionHashFilter, TransactionHashFilterEntry,
+    TransactionHashFilters, BLOCKS_PER_STATIC_FILE,
 };
 use crate::{
     to_range, BlockHashReader, BlockNumReader, BlockReader, BlockSource, HeaderProvider,
@@ -77,6 +78,9 @@ pub struct StaticFileProviderInner {
     load_filters: bool,
     /// Maintains a map of StaticFile writers for each [`StaticFileSegment`]
     writers: DashMap<StaticFileSegment, StaticFileProviderRW>,
+    /// Loaded transaction hash filters of the transactions static files, indexed by the end of
+    /// their fixed block range.
+    transaction_hash_filters: DashMap<BlockNumber, Arc<TransactionHashFilters>>,
     metrics: Option<Arc<StaticFileProviderMetrics>>,
 }
 
@@ -88,6 +92,7 @@ impl StaticFileProviderInner {
             writers: Default::default(),
             static_files_max_block: Default::default(),
             static_files_tx_index: Default::default(),
+            transaction_hash_filters: Default::default(),
             path: path.as_ref().to_path_buf(),
             load_filters: false,
             metrics: None,
@@ -254,6 +259,14 @@ impl StaticFileProvider {
 
         jar.delete().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
 
+        if segment == StaticFileSegment::Transactions {
+            self.transaction_hash_filters.remove(&fixed_block_range.end());
+            let path = self.transaction_hash_filters_path(&fixed_block_range);
+            if path.exists() {
+                reth_primitives::fs::remove_file(path)?;
+            }
+        }
+
         let mut segment_max_block = None;
         if fixed_block_range.start() > 0 {
             segment_max_block = Some(fixed_block_range.start() - 1)
@@ -461,6 +474,125 @@ impl StaticFileProvider {
             .and_then(|index| index.last_key_value().map(|(last_tx, _)| *last_tx))
     }
 
+    /// Returns the path of the transaction hash filters of the transactions static file of the
+    /// fixed block range.
+    fn transaction_hash_filters_path(&self, fixed_block_range: &SegmentRangeInclusive) -> PathBuf {
+        self.path.join(format!(
+            "{}.txfilter",
+            StaticFileSegment::Transactions.filename(fixed_block_range)
+        ))
+    }
+
+    /// Returns the transaction hash filters of the transactions static file of the fixed block
+    /// range, loading them if they aren't cached.
+    pub fn transaction_hash_filters(
+        &self,
+        fixed_block_range: &SegmentRangeInclusive,
+    ) -> ProviderResult<Arc<TransactionHashFilters>> {
+        if let Some(filters) = self.transaction_hash_filters.get(&fixed_block_range.end()) {
+            return Ok(filters.clone())
+        }
+
+        let filters = Arc::new(TransactionHashFilters::load(
+            &self.transaction_hash_filters_path(fixed_block_range),
+        )?);
+        self.transaction_hash_filters.insert(fixed_block_range.end(), filters.clone());
+        Ok(filters)
+    }
+
+    /// Writes the transaction hash filters, replacing the existing filters starting at or after
+    /// the first new filter of each transactions static file.
+    ///
+    /// Filters must be ordered by block range.
+    pub fn write_transaction_hash_filters(
+        &self,
+        filters: Vec<TransactionHashFilter>,
+    ) -> ProviderResult<()> {
+        let mut filters_by_file = BTreeMap::<BlockNumber, Vec<TransactionHashFilter>>::new();
+        for filter in filters {
+            let fixed_block_range = find_fixed_range(filter.block_range().start());
+            filters_by_file.entry(fixed_block_range.end()).or_default().push(filter);
+        }
+
+        for (end, filters) in filters_by_file {
+            let fixed_block_range = find_fixed_range(end);
+            self.transaction_hash_filters(&fixed_block_range)?
+                .write(&self.transaction_hash_filters_path(&fixed_block_range), &filters)?;
+            // Loaded again on the next access.
+            self.transaction_hash_filters.remove(&end);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the highest transaction hash filter, if any.
+    pub fn highest_transaction_hash_filter(
+        &self,
+    ) -> ProviderResult<Option<TransactionHashFilterEntry>> {
+        let Some(highest_block) =
+            self.get_highest_static_file_block(StaticFileSegment::Transactions)
+        else {
+            return Ok(None)
+        };
+
+        let mut fixed_block_range = find_fixed_range(highest_block);
+        loop {
+            if let Some(entry) = self.transaction_hash_filters(&fixed_block_range)?.entries().last()
+            {
+                return Ok(Some(entry.clone()))
+            }
+            if fixed_block_range.start() == 0 {
+                return Ok(None)
+            }
+            fixed_block_range = find_fixed_range(fixed_block_range.start() - 1);
+        }
+    }
+
+    /// Looks up the number of the transaction with the hash among the transactions up to
+    /// `highest_tx`, using the transaction hash filters.
+    ///
+    /// The transactions of each filter that may hold the hash are hashed again, newest first.
+    ///
+    /// A hash that isn't found, e.g. of a pending transaction, probes every filter down to the
+    /// genesis block. Probing the filter of 1,000 blocks reads 17 words of its memory map, and
+    /// the transactions of a filter are hashed again for each false positive, about one per
+    /// 100,000 filters.
+    pub fn transaction_id_by_hash_filters(
+        &self,
+        hash: TxHash,
+        highest_tx: TxNumber,
+    ) -> ProviderResult<Option<TxNumber>> {
+        let (Some(highest_block), Some(highest_static_file_tx)) = (
+            self.get_highest_static_file_block(StaticFileSegment::Transactions),
+            self.get_highest_static_file_tx(StaticFileSegment::Transactions),
+        ) else {
+            return Ok(None)
+        };
+        let tx_end = highest_tx.min(highest_static_file_tx) + 1;
+
+        let mut fixed_block_range = find_fixed_range(highest_block);
+        loop {
+            let filters = self.transaction_hash_filters(&fixed_block_range)?;
+            for entry in filters.entries().iter().rev() {
+                if entry.tx_range.start >= tx_end || !filters.may_contain(entry, &hash) {
+                    continue
+                }
+                let tx_range = entry.tx_range.start..entry.tx_range.end.min(tx_end);
+                if let Some((_, tx_number)) = self
+                    .transaction_hashes_by_range(tx_range)?
+                    .into_iter()
+                    .find(|(tx_hash, _)| *tx_hash == hash)
+                {
+                    return Ok(Some(tx_number))
+                }
+            }
+            if fixed_block_range.start() == 0 {
+                return Ok(None)
+            }
+            fixed_block_range = find_fixed_range(fixed_block_range.start() - 1);
+        }
+    }
+
     /// Gets the highest static file block for all segments.
     pub fn get_highest_static_files(&self) -> HighestStaticFiles {
         HighestStaticFiles {
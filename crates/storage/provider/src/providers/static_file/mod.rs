@@ -7,6 +7,12 @@ pub use jar::StaticFileJarProvider;
 mod writer;
 pub use writer::{StaticFileProviderRW, StaticFileProviderRWRefMut};
 
+mod hash_filter;
+pub use hash_filter::{
+    TransactionHashFilter, TransactionHashFilterEntry, TransactionHashFilters,
+    BLOCKS_PER_TRANSACTION_HASH_FILTER,
+};
+
 mod metrics;
 
 use reth_interfaces::provider::{ProviderError, ProviderResult};
@@ -40,7 +40,7 @@ pub use state::{
 };
 
 mod trie;
-pub use trie::StateRootProvider;
+pub use trie::{HashedAccountRange, HashedStorageRange, StateRangeProvider, StateRootProvider};
 
 mod transactions;
 pub use transactions::{TransactionsProvider, TransactionsProviderExt};
@@ -1,7 +1,13 @@
-use std::ops::RangeBounds;
+use std::{
+    collections::BTreeMap,
+    ops::{RangeBounds, RangeInclusive},
+};
 
 use reth_interfaces::provider::ProviderResult;
-use reth_primitives::{BlockHashOrNumber, BlockId, BlockNumberOrTag, Receipt, TxHash, TxNumber};
+use reth_primitives::{
+    Address, BlockHashOrNumber, BlockId, BlockNumber, BlockNumberOrTag, Receipt, TxHash, TxNumber,
+    B256,
+};
 
 use crate::BlockIdReader;
 
@@ -28,6 +34,23 @@ pub trait ReceiptProvider: Send + Sync {
         &self,
         range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<Receipt>>;
+
+    /// Get the blocks of the range with logs emitted by any of the addresses with any of the
+    /// topics as first topic, using the log index. The blocks are mapped to the numbers of the
+    /// transactions emitting the logs.
+    ///
+    /// The result may contain blocks without matching logs, so the receipts of the blocks still
+    /// need to be matched against the filter.
+    ///
+    /// Returns `None` if the log index is disabled or does not cover the start of the range.
+    fn log_index_blocks(
+        &self,
+        _addresses: &[Address],
+        _topics0: &[B256],
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Option<BTreeMap<BlockNumber, Vec<TxNumber>>>> {
+        Ok(None)
+    }
 }
 
 /// Trait extension for `ReceiptProvider`, for types that implement `BlockId` conversion.
@@ -20,6 +20,17 @@ pub trait StateProvider: BlockHashReader + AccountReader + StateRootProvider + S
         storage_key: StorageKey,
     ) -> ProviderResult<Option<StorageValue>>;
 
+    /// Get storage of the given account and storage key pairs, in the order of the pairs.
+    ///
+    /// Implementations may override it to resolve all pairs in a single pass over the storage
+    /// tables.
+    fn storages(
+        &self,
+        slots: &[(Address, StorageKey)],
+    ) -> ProviderResult<Vec<Option<StorageValue>>> {
+        slots.iter().map(|(account, storage_key)| self.storage(*account, *storage_key)).collect()
+    }
+
     /// Get account code by its hash
     fn bytecode_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>>;
 
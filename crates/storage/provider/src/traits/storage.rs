@@ -1,6 +1,6 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
-    ops::RangeInclusive,
+    ops::{RangeBounds, RangeInclusive},
 };
 
 use auto_impl::auto_impl;
@@ -16,6 +16,13 @@ pub trait StorageReader: Send + Sync {
         addresses_with_keys: impl IntoIterator<Item = (Address, impl IntoIterator<Item = B256>)>,
     ) -> ProviderResult<Vec<(Address, Vec<StorageEntry>)>>;
 
+    /// Get the plainstate storage entries of the address whose storage keys are in the range.
+    fn plain_state_storage_range(
+        &self,
+        address: Address,
+        range: impl RangeBounds<B256>,
+    ) -> ProviderResult<Vec<StorageEntry>>;
+
     /// Iterate over storage changesets and return all storage slots that were changed.
     fn changed_storages_with_range(
         &self,
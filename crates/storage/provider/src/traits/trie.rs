@@ -1,6 +1,6 @@
 use auto_impl::auto_impl;
 use reth_interfaces::provider::ProviderResult;
-use reth_primitives::B256;
+use reth_primitives::{Account, Bytes, StorageEntry, B256};
 use reth_trie::updates::TrieUpdates;
 use revm::db::BundleState;
 
@@ -23,3 +23,51 @@ pub trait StateRootProvider: Send + Sync {
         bundle_state: &BundleState,
     ) -> ProviderResult<(B256, TrieUpdates)>;
 }
+
+/// Contiguous range of accounts of the latest hashed state, along with the proof of its
+/// boundaries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HashedAccountRange {
+    /// Accounts ordered by hashed address.
+    pub accounts: Vec<(B256, Account)>,
+    /// Account trie nodes proving the start of the range and the last returned account.
+    pub proof: Vec<Bytes>,
+}
+
+/// Contiguous range of storage slots of an account of the latest hashed state, along with the
+/// proof of its boundaries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HashedStorageRange {
+    /// Storage entries ordered by hashed slot.
+    pub slots: Vec<StorageEntry>,
+    /// Storage trie nodes proving the start of the range and the last returned slot.
+    pub proof: Vec<Bytes>,
+}
+
+/// A type that can serve contiguous ranges of the latest hashed state with proofs against the
+/// state root, as needed to serve the state to syncing peers.
+#[auto_impl(&, Box, Arc)]
+pub trait StateRangeProvider: Send + Sync {
+    /// Returns at most `max_accounts` accounts whose hashed addresses are in `start..=limit`.
+    ///
+    /// The proof covers `start` and the last returned account, or `limit` if no account is in the
+    /// range.
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: B256,
+        max_accounts: usize,
+    ) -> ProviderResult<HashedAccountRange>;
+
+    /// Returns at most `max_slots` storage slots of the account whose hashed slots are in
+    /// `start..=limit`.
+    ///
+    /// The proof covers `start` and the last returned slot, or `limit` if no slot is in the range.
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: B256,
+        max_slots: usize,
+    ) -> ProviderResult<HashedStorageRange>;
+}
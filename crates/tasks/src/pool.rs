@@ -74,6 +74,20 @@ impl BlockingTaskPool {
         Self::builder().build().map(Self::new)
     }
 
+    /// Convenience function to build a new threadpool with the given number of threads, instead
+    /// of one thread per CPU.
+    ///
+    /// If `num_threads` is `0`, the number of threads is chosen the same way as in
+    /// [`BlockingTaskPool::build`].
+    pub fn build_with_num_threads(num_threads: usize) -> Result<Self, rayon::ThreadPoolBuildError> {
+        Self::builder().num_threads(num_threads).build().map(Self::new)
+    }
+
+    /// Returns the number of threads of the threadpool.
+    pub fn num_threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
     /// Asynchronous wrapper around Rayon's
     /// [`ThreadPool::spawn`](rayon::ThreadPool::spawn).
     ///
@@ -130,6 +144,21 @@ pub struct BlockingTaskHandle<T> {
     pub(crate) rx: oneshot::Receiver<thread::Result<T>>,
 }
 
+impl<T> BlockingTaskHandle<T> {
+    /// Attempts to take the result of the task without waiting for it.
+    ///
+    /// Returns `None` if the task has not completed yet.
+    pub fn try_take(&mut self) -> Option<thread::Result<T>> {
+        match self.rx.try_recv() {
+            Ok(res) => Some(res),
+            Err(oneshot::error::TryRecvError::Empty) => None,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                Some(Err(Box::<TokioBlockingTaskError>::default()))
+            }
+        }
+    }
+}
+
 impl<T> Future for BlockingTaskHandle<T> {
     type Output = thread::Result<T>;
 
@@ -161,6 +190,26 @@ mod tests {
         assert_eq!(res, 5);
     }
 
+    #[tokio::test]
+    async fn blocking_pool_num_threads() {
+        let pool = BlockingTaskPool::build_with_num_threads(3).unwrap();
+        assert_eq!(pool.num_threads(), 3);
+        assert_eq!(pool.spawn(rayon::current_num_threads).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn blocking_pool_try_take() {
+        let pool = BlockingTaskPool::build().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        let mut res = pool.spawn(move || {
+            rx.recv().unwrap();
+            5
+        });
+        assert!(res.try_take().is_none());
+        tx.send(()).unwrap();
+        assert_eq!(res.await.unwrap(), 5);
+    }
+
     #[tokio::test]
     async fn blocking_pool_panic() {
         let pool = BlockingTaskPool::build().unwrap();
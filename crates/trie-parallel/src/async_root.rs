@@ -5,37 +5,51 @@ use reth_db::database::Database;
 use reth_interfaces::trie::StorageRootError;
 use reth_primitives::{
     trie::{HashBuilder, Nibbles, TrieAccount},
-    B256,
+    Account, B256,
 };
 use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory, ProviderError};
-use reth_tasks::pool::BlockingTaskPool;
+use reth_tasks::pool::{BlockingTaskGuard, BlockingTaskHandle, BlockingTaskPool};
 use reth_trie::{
     hashed_cursor::HashedPostStateCursorFactory,
-    node_iter::{AccountNode, AccountNodeIter},
-    trie_cursor::TrieCursorFactory,
+    node_iter::{AccountNode, AccountNodeIter, TrieBranchNode},
+    trie_cursor::{CachedTrieCursorFactory, TrieCursorFactory, TrieNodeCache},
     updates::TrieUpdates,
     walker::TrieWalker,
     HashedPostState, StorageRoot,
 };
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 use thiserror::Error;
+use tokio::sync::OwnedSemaphorePermit;
 use tracing::*;
 
 #[cfg(feature = "metrics")]
-use crate::metrics::ParallelStateRootMetrics;
+use crate::metrics::{BlockingPoolMetrics, ParallelStateRootMetrics};
+#[cfg(feature = "metrics")]
+use std::time::Instant;
 
 /// Async state root calculator.
 ///
 /// The calculator starts off by launching tasks to compute storage roots.
 /// Then, it immediately starts walking the state trie updating the necessary trie
 /// nodes in the process. Upon encountering a leaf node, it will poll the storage root
-/// task for the corresponding hashed address.
+/// task for the corresponding hashed address. If the storage root is not ready yet,
+/// the leaf and all subsequent nodes are buffered and the walk continues. Buffered nodes
+/// are added to the hash builder in key order as soon as the storage roots become available.
 ///
 /// Internally, the calculator uses [ConsistentDbView] since
 /// it needs to rely on database state saying the same until
 /// the last transaction is open.
 /// See docs of using [ConsistentDbView] for caveats.
 ///
+/// The storage root tasks of a computation that is dropped before completion, e.g. because it
+/// failed or was superseded by another computation, are cancelled and not run on the pool.
+///
 /// For sync usage, take a look at `ParallelStateRoot`.
 #[derive(Debug)]
 pub struct AsyncStateRoot<DB, Provider> {
@@ -45,9 +59,16 @@ pub struct AsyncStateRoot<DB, Provider> {
     blocking_pool: BlockingTaskPool,
     /// Changed hashed state.
     hashed_state: HashedPostState,
+    /// Cache of the trie nodes shared between computations.
+    trie_cache: Option<TrieNodeCache>,
+    /// Guard limiting the number of storage root tasks queued or running on the pool.
+    task_guard: Option<BlockingTaskGuard>,
     /// Parallel state root metrics.
     #[cfg(feature = "metrics")]
     metrics: ParallelStateRootMetrics,
+    /// Blocking pool metrics.
+    #[cfg(feature = "metrics")]
+    pool_metrics: BlockingPoolMetrics,
 }
 
 impl<DB, Provider> AsyncStateRoot<DB, Provider> {
@@ -61,10 +82,64 @@ impl<DB, Provider> AsyncStateRoot<DB, Provider> {
             view,
             blocking_pool,
             hashed_state,
+            trie_cache: None,
+            task_guard: None,
             #[cfg(feature = "metrics")]
             metrics: ParallelStateRootMetrics::default(),
+            #[cfg(feature = "metrics")]
+            pool_metrics: BlockingPoolMetrics::default(),
         }
     }
+
+    /// Set the cache of the trie nodes to read the tries through. The cache is bypassed if it holds
+    /// the nodes of a tip other than the one of the view.
+    pub fn with_trie_cache(mut self, cache: TrieNodeCache) -> Self {
+        self.trie_cache = Some(cache);
+        self
+    }
+
+    /// Limit the number of storage root tasks queued or running on the pool at once by the
+    /// permits of the given guard. The guard can be shared between computations to limit their
+    /// combined number of tasks.
+    pub fn with_task_guard(mut self, guard: BlockingTaskGuard) -> Self {
+        self.task_guard = Some(guard);
+        self
+    }
+
+    /// Acquire the permit to spawn a storage root task, if the number of tasks is limited.
+    async fn acquire_task_permit(
+        &self,
+    ) -> Result<Option<OwnedSemaphorePermit>, AsyncStateRootError> {
+        match &self.task_guard {
+            Some(guard) => Ok(Some(
+                guard.clone().acquire_owned().await.map_err(|_| AsyncStateRootError::Cancelled)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Spawn the task on the blocking pool, recording how long it waits for a thread and runs.
+    fn spawn_blocking<F, R>(&self, task: F) -> BlockingTaskHandle<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        #[cfg(feature = "metrics")]
+        let task = {
+            let metrics = self.pool_metrics.clone();
+            metrics.queued_tasks.increment(1.0);
+            let queued_at = Instant::now();
+            move || {
+                let started_at = Instant::now();
+                metrics.queued_tasks.decrement(1.0);
+                metrics.task_wait_duration_seconds.record((started_at - queued_at).as_secs_f64());
+                let result = task();
+                metrics.task_execution_duration_seconds.record(started_at.elapsed().as_secs_f64());
+                result
+            }
+        };
+        self.blocking_pool.spawn_fifo(task)
+    }
 }
 
 impl<DB, Provider> AsyncStateRoot<DB, Provider>
@@ -84,6 +159,41 @@ where
         self.calculate(true).await
     }
 
+    /// Calculate the storage root of a single account asynchronously.
+    ///
+    /// The storage root is computed on the blocking pool over the database state of the view
+    /// overlaid with the changed hashed state.
+    pub async fn storage_root(mut self, hashed_address: B256) -> Result<B256, AsyncStateRootError> {
+        let prefix_set = self
+            .hashed_state
+            .construct_prefix_sets()
+            .storage_prefix_sets
+            .remove(&hashed_address)
+            .unwrap_or_default();
+        let hashed_state_sorted = std::mem::take(&mut self.hashed_state).into_sorted();
+
+        let view = self.view.clone();
+        let trie_cache = self.trie_cache.as_ref().map(|cache| cache.at_tip(view.tip()));
+        let permit = self.acquire_task_permit().await?;
+        #[cfg(feature = "metrics")]
+        let metrics = self.metrics.storage_trie.clone();
+        self.spawn_blocking(move || -> Result<_, AsyncStateRootError> {
+            let _permit = permit;
+            let provider = view.provider_ro()?;
+            Ok(StorageRoot::new_hashed(
+                CachedTrieCursorFactory::new(provider.tx_ref(), trie_cache),
+                HashedPostStateCursorFactory::new(provider.tx_ref(), &hashed_state_sorted),
+                hashed_address,
+                #[cfg(feature = "metrics")]
+                metrics,
+            )
+            .with_prefix_set(prefix_set)
+            .root()?)
+        })
+        .await
+        .map_err(|_| AsyncStateRootError::StorageRootChannelClosed { hashed_address })?
+    }
+
     async fn calculate(
         self,
         retain_updates: bool,
@@ -95,6 +205,12 @@ where
             prefix_sets.storage_prefix_sets,
         );
         let hashed_state_sorted = Arc::new(self.hashed_state.into_sorted());
+        let trie_cache = self.trie_cache.as_ref().map(|cache| cache.at_tip(self.view.tip()));
+        #[cfg(feature = "metrics")]
+        self.pool_metrics.threads.set(self.blocking_pool.num_threads() as f64);
+
+        // Skip the storage root tasks that haven't started yet if the computation is dropped.
+        let cancel_on_drop = CancelOnDrop::default();
 
         // Pre-calculate storage roots async for accounts which were changed.
         tracker.set_precomputed_storage_roots(storage_root_targets.len() as u64);
@@ -105,21 +221,27 @@ where
         {
             let view = self.view.clone();
             let hashed_state_sorted = hashed_state_sorted.clone();
+            let trie_cache = trie_cache.clone();
+            let cancelled = cancel_on_drop.cancelled.clone();
+            let permit = self.acquire_task_permit().await?;
             #[cfg(feature = "metrics")]
             let metrics = self.metrics.storage_trie.clone();
-            let handle =
-                self.blocking_pool.spawn_fifo(move || -> Result<_, AsyncStateRootError> {
-                    let provider = view.provider_ro()?;
-                    Ok(StorageRoot::new_hashed(
-                        provider.tx_ref(),
-                        HashedPostStateCursorFactory::new(provider.tx_ref(), &hashed_state_sorted),
-                        hashed_address,
-                        #[cfg(feature = "metrics")]
-                        metrics,
-                    )
-                    .with_prefix_set(prefix_set)
-                    .calculate(retain_updates)?)
-                });
+            let handle = self.spawn_blocking(move || -> Result<_, AsyncStateRootError> {
+                let _permit = permit;
+                if cancelled.load(Ordering::Relaxed) {
+                    return Err(AsyncStateRootError::Cancelled)
+                }
+                let provider = view.provider_ro()?;
+                Ok(StorageRoot::new_hashed(
+                    CachedTrieCursorFactory::new(provider.tx_ref(), trie_cache),
+                    HashedPostStateCursorFactory::new(provider.tx_ref(), &hashed_state_sorted),
+                    hashed_address,
+                    #[cfg(feature = "metrics")]
+                    metrics,
+                )
+                .with_prefix_set(prefix_set)
+                .calculate(retain_updates)?)
+            });
             storage_roots.insert(hashed_address, handle);
         }
 
@@ -129,7 +251,7 @@ where
         let provider_ro = self.view.provider_ro()?;
         let tx = provider_ro.tx_ref();
         let hashed_cursor_factory = HashedPostStateCursorFactory::new(tx, &hashed_state_sorted);
-        let trie_cursor_factory = tx;
+        let trie_cursor_factory = CachedTrieCursorFactory::new(tx, trie_cache);
 
         let trie_cursor =
             trie_cursor_factory.account_trie_cursor().map_err(ProviderError::Database)?;
@@ -142,43 +264,65 @@ where
                 .map_err(ProviderError::Database)?;
 
         let mut account_rlp = Vec::with_capacity(128);
+        let mut pending_nodes = VecDeque::new();
         while let Some(node) = account_node_iter.try_next().map_err(ProviderError::Database)? {
             match node {
                 AccountNode::Branch(node) => {
-                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                    pending_nodes.push_back(PendingAccountNode::Branch(node));
                 }
                 AccountNode::Leaf(hashed_address, account) => {
-                    let (storage_root, _, updates) = match storage_roots.remove(&hashed_address) {
-                        Some(rx) => rx.await.map_err(|_| {
-                            AsyncStateRootError::StorageRootChannelClosed { hashed_address }
-                        })??,
+                    let storage_root = match storage_roots.remove(&hashed_address) {
+                        Some(handle) => PendingStorageRoot::Pending(handle),
                         // Since we do not store all intermediate nodes in the database, there might
                         // be a possibility of re-adding a non-modified leaf to the hash builder.
                         None => {
                             tracker.inc_missed_leaves();
-                            StorageRoot::new_hashed(
-                                trie_cursor_factory,
-                                hashed_cursor_factory.clone(),
-                                hashed_address,
-                                #[cfg(feature = "metrics")]
-                                self.metrics.storage_trie.clone(),
+                            PendingStorageRoot::Ready(
+                                StorageRoot::new_hashed(
+                                    trie_cursor_factory.clone(),
+                                    hashed_cursor_factory.clone(),
+                                    hashed_address,
+                                    #[cfg(feature = "metrics")]
+                                    self.metrics.storage_trie.clone(),
+                                )
+                                .calculate(retain_updates)?,
                             )
-                            .calculate(retain_updates)?
                         }
                     };
+                    pending_nodes.push_back(PendingAccountNode::Leaf(
+                        hashed_address,
+                        account,
+                        storage_root,
+                    ));
+                }
+            }
 
-                    if retain_updates {
-                        trie_updates.extend(updates.into_iter());
-                    }
-
-                    account_rlp.clear();
-                    let account = TrieAccount::from((account, storage_root));
-                    account.encode(&mut account_rlp as &mut dyn BufMut);
-                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+            // Add all nodes at the front of the queue whose storage roots are already available
+            // and keep walking the trie instead of waiting for the rest.
+            while let Some(node) = pending_nodes.front_mut() {
+                if !node.try_ready()? {
+                    break
                 }
+                let node = pending_nodes.pop_front().expect("front node exists");
+                node.resolve().await?.add_to(
+                    &mut hash_builder,
+                    &mut trie_updates,
+                    &mut account_rlp,
+                    retain_updates,
+                );
             }
         }
 
+        // Wait for the remaining storage roots and add the buffered nodes in key order.
+        for node in pending_nodes {
+            node.resolve().await?.add_to(
+                &mut hash_builder,
+                &mut trie_updates,
+                &mut account_rlp,
+                retain_updates,
+            );
+        }
+
         let root = hash_builder.root();
 
         trie_updates.finalize_state_updates(
@@ -207,6 +351,107 @@ where
     }
 }
 
+/// The result of storage root calculation.
+type StorageRootResult = (B256, usize, TrieUpdates);
+
+/// Flag set once the computation that spawned the storage root tasks is dropped.
+#[derive(Debug, Default)]
+struct CancelOnDrop {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Storage root of the account leaf that is either computed or is still being computed.
+#[derive(Debug)]
+enum PendingStorageRoot {
+    /// Storage root is available.
+    Ready(StorageRootResult),
+    /// Storage root is being computed on the blocking pool.
+    Pending(BlockingTaskHandle<Result<StorageRootResult, AsyncStateRootError>>),
+}
+
+/// Account node that was walked, but not yet added to the hash builder.
+#[derive(Debug)]
+enum PendingAccountNode {
+    /// Branch node.
+    Branch(TrieBranchNode),
+    /// Leaf node with its storage root.
+    Leaf(B256, Account, PendingStorageRoot),
+}
+
+impl PendingAccountNode {
+    /// Returns `true` if the node can be added to the hash builder without waiting.
+    fn try_ready(&mut self) -> Result<bool, AsyncStateRootError> {
+        let Self::Leaf(hashed_address, _, storage_root) = self else { return Ok(true) };
+        if let PendingStorageRoot::Pending(handle) = storage_root {
+            let Some(result) = handle.try_take() else { return Ok(false) };
+            let hashed_address = *hashed_address;
+            *storage_root =
+                PendingStorageRoot::Ready(result.map_err(|_| {
+                    AsyncStateRootError::StorageRootChannelClosed { hashed_address }
+                })??);
+        }
+        Ok(true)
+    }
+
+    /// Wait for the storage root of the node if necessary.
+    async fn resolve(self) -> Result<ResolvedAccountNode, AsyncStateRootError> {
+        Ok(match self {
+            Self::Branch(node) => ResolvedAccountNode::Branch(node),
+            Self::Leaf(hashed_address, account, storage_root) => {
+                let result = match storage_root {
+                    PendingStorageRoot::Ready(result) => result,
+                    PendingStorageRoot::Pending(handle) => handle.await.map_err(|_| {
+                        AsyncStateRootError::StorageRootChannelClosed { hashed_address }
+                    })??,
+                };
+                ResolvedAccountNode::Leaf(hashed_address, account, result)
+            }
+        })
+    }
+}
+
+/// Account node ready to be added to the hash builder.
+#[derive(Debug)]
+enum ResolvedAccountNode {
+    /// Branch node.
+    Branch(TrieBranchNode),
+    /// Leaf node with its storage root.
+    Leaf(B256, Account, StorageRootResult),
+}
+
+impl ResolvedAccountNode {
+    /// Add the node to the hash builder and collect storage trie updates if necessary.
+    fn add_to(
+        self,
+        hash_builder: &mut HashBuilder,
+        trie_updates: &mut TrieUpdates,
+        account_rlp: &mut Vec<u8>,
+        retain_updates: bool,
+    ) {
+        match self {
+            Self::Branch(node) => {
+                hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+            }
+            Self::Leaf(hashed_address, account, (storage_root, _, updates)) => {
+                if retain_updates {
+                    trie_updates.extend(updates.into_iter());
+                }
+
+                account_rlp.clear();
+                let account = TrieAccount::from((account, storage_root));
+                account.encode(account_rlp as &mut dyn BufMut);
+                hash_builder.add_leaf(Nibbles::unpack(hashed_address), account_rlp);
+            }
+        }
+    }
+}
+
 /// Error during async state root calculation.
 #[derive(Error, Debug)]
 pub enum AsyncStateRootError {
@@ -222,6 +467,9 @@ pub enum AsyncStateRootError {
     /// Provider error.
     #[error(transparent)]
     Provider(#[from] ProviderError),
+    /// The computation was dropped before the storage root task started.
+    #[error("state root computation was cancelled")]
+    Cancelled,
 }
 
 #[cfg(test)]
@@ -318,11 +566,79 @@ mod tests {
         }
 
         assert_eq!(
-            AsyncStateRoot::new(consistent_view.clone(), blocking_pool.clone(), hashed_state)
+            AsyncStateRoot::new(
+                consistent_view.clone(),
+                blocking_pool.clone(),
+                hashed_state.clone()
+            )
+            .incremental_root()
+            .await
+            .unwrap(),
+            test_utils::state_root(state.clone())
+        );
+
+        // A single permit runs the storage root tasks one at a time.
+        assert_eq!(
+            AsyncStateRoot::new(consistent_view, blocking_pool, hashed_state)
+                .with_task_guard(BlockingTaskGuard::new(1))
                 .incremental_root()
                 .await
                 .unwrap(),
             test_utils::state_root(state)
         );
     }
+
+    #[tokio::test]
+    async fn async_storage_root() {
+        let blocking_pool = BlockingTaskPool::new(ThreadPoolBuilder::default().build().unwrap());
+
+        let factory = create_test_provider_factory();
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+
+        let mut rng = rand::thread_rng();
+        let address = Address::random();
+        let hashed_address = keccak256(address);
+        let mut storage = (0..100)
+            .map(|_| (B256::from(U256::from(rng.gen::<u64>())), U256::from(rng.gen::<u64>())))
+            .collect::<HashMap<_, _>>();
+
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            provider_rw
+                .insert_storage_for_hashing([(
+                    address,
+                    storage.iter().map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                )])
+                .unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        assert_eq!(
+            AsyncStateRoot::new(
+                consistent_view.clone(),
+                blocking_pool.clone(),
+                HashedPostState::default()
+            )
+            .storage_root(hashed_address)
+            .await
+            .unwrap(),
+            test_utils::storage_root(storage.clone())
+        );
+
+        let mut hashed_storage = HashedStorage::new(false);
+        for (slot, value) in storage.iter_mut().take(10) {
+            *value = U256::from(rng.gen::<u64>());
+            hashed_storage.storage.insert(keccak256(slot), *value);
+        }
+        let mut hashed_state = HashedPostState::default();
+        hashed_state.storages.insert(hashed_address, hashed_storage);
+
+        assert_eq!(
+            AsyncStateRoot::new(consistent_view, blocking_pool, hashed_state)
+                .storage_root(hashed_address)
+                .await
+                .unwrap(),
+            test_utils::storage_root(storage)
+        );
+    }
 }
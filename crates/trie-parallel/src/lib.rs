@@ -17,6 +17,10 @@ pub mod stats;
 #[cfg(feature = "async")]
 pub mod async_root;
 
+/// Invalidation of the trie node cache on canonical chain updates.
+#[cfg(feature = "async")]
+pub mod trie_cache;
+
 /// Implementation of parallel state root computation.
 #[cfg(feature = "parallel")]
 pub mod parallel_root;
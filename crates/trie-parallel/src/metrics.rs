@@ -1,5 +1,5 @@
 use crate::stats::ParallelTrieStats;
-use metrics::Histogram;
+use metrics::{Gauge, Histogram};
 use reth_metrics::Metrics;
 use reth_trie::metrics::{TrieRootMetrics, TrieType};
 
@@ -42,3 +42,20 @@ pub struct ParallelTrieMetrics {
     /// The number of leaves for which we did not pre-compute the storage roots.
     pub missed_leaves: Histogram,
 }
+
+/// Metrics of the blocking pool tasks spawned by the async state root computation.
+///
+/// Long waits for a thread of the pool with short execution times mean that the pool is too small
+/// for the load, while long execution times point to slow database reads.
+#[derive(Clone, Metrics)]
+#[metrics(scope = "trie_parallel.blocking_pool")]
+pub struct BlockingPoolMetrics {
+    /// The number of threads of the pool.
+    pub threads: Gauge,
+    /// The number of spawned tasks waiting for a thread of the pool.
+    pub queued_tasks: Gauge,
+    /// The number of seconds tasks waited for a thread of the pool.
+    pub task_wait_duration_seconds: Histogram,
+    /// The number of seconds tasks ran for.
+    pub task_execution_duration_seconds: Histogram,
+}
@@ -1,20 +1,24 @@
 use crate::{stats::ParallelTrieTracker, storage_root_targets::StorageRootTargets};
 use alloy_rlp::{BufMut, Encodable};
 use rayon::prelude::*;
-use reth_db::database::Database;
+use reth_db::{
+    database::Database,
+    transaction::{DbTx, DbTxMut},
+};
 use reth_interfaces::trie::StorageRootError;
 use reth_primitives::{
     trie::{HashBuilder, Nibbles, TrieAccount},
-    B256,
+    B256, U256,
 };
 use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory, ProviderError};
 use reth_trie::{
-    hashed_cursor::{HashedCursorFactory, HashedPostStateCursorFactory},
+    hashed_cursor::{HashedAccountCursor, HashedCursorFactory, HashedPostStateCursorFactory},
     node_iter::{AccountNode, AccountNodeIter},
-    trie_cursor::TrieCursorFactory,
-    updates::TrieUpdates,
+    prefix_set::PrefixSet,
+    trie_cursor::{noop::NoopTrieCursorFactory, TrieCursorFactory},
+    updates::{TrieUpdates, TrieUpdatesWriter},
     walker::TrieWalker,
-    HashedPostState, StorageRoot,
+    HashedPostState, HashedPostStateSorted, StorageRoot, StorageSubtrie,
 };
 use std::collections::HashMap;
 use thiserror::Error;
@@ -23,6 +27,14 @@ use tracing::*;
 #[cfg(feature = "metrics")]
 use crate::metrics::ParallelStateRootMetrics;
 
+/// The default number of ranges the hashed accounts are split into
+/// for full state root calculation.
+pub const DEFAULT_FULL_ROOT_RANGES: usize = 256;
+
+/// The default number of changed storage slots of an account
+/// from which its storage root is calculated in parallel subtries.
+pub const DEFAULT_STORAGE_SUBTRIES_THRESHOLD: usize = 10_000;
+
 /// Parallel incremental state root calculator.
 ///
 /// The calculator starts off by pre-computing storage roots of changed
@@ -41,6 +53,11 @@ pub struct ParallelStateRoot<DB, Provider> {
     view: ConsistentDbView<DB, Provider>,
     /// Changed hashed state.
     hashed_state: HashedPostState,
+    /// The number of ranges the hashed accounts are split into for full root calculation.
+    full_root_ranges: usize,
+    /// The number of changed storage slots of an account from which its storage root
+    /// is calculated in parallel subtries.
+    storage_subtries_threshold: usize,
     /// Parallel state root metrics.
     #[cfg(feature = "metrics")]
     metrics: ParallelStateRootMetrics,
@@ -52,10 +69,25 @@ impl<DB, Provider> ParallelStateRoot<DB, Provider> {
         Self {
             view,
             hashed_state,
+            full_root_ranges: DEFAULT_FULL_ROOT_RANGES,
+            storage_subtries_threshold: DEFAULT_STORAGE_SUBTRIES_THRESHOLD,
             #[cfg(feature = "metrics")]
             metrics: ParallelStateRootMetrics::default(),
         }
     }
+
+    /// Set the number of ranges the hashed accounts are split into for full root calculation.
+    pub fn with_full_root_ranges(mut self, ranges: usize) -> Self {
+        self.full_root_ranges = ranges;
+        self
+    }
+
+    /// Set the number of changed storage slots of an account from which its storage root
+    /// is calculated in parallel subtries.
+    pub fn with_storage_subtries_threshold(mut self, threshold: usize) -> Self {
+        self.storage_subtries_threshold = threshold;
+        self
+    }
 }
 
 impl<DB, Provider> ParallelStateRoot<DB, Provider>
@@ -65,19 +97,109 @@ where
 {
     /// Calculate incremental state root in parallel.
     pub fn incremental_root(self) -> Result<B256, ParallelStateRootError> {
-        self.calculate(false).map(|(root, _)| root)
+        self.calculate(false, |_| Ok(())).map(|(root, _)| root)
     }
 
     /// Calculate incremental state root with updates in parallel.
     pub fn incremental_root_with_updates(
         self,
     ) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
-        self.calculate(true)
+        let mut storage_updates = TrieUpdates::default();
+        let (root, mut trie_updates) = self.calculate(true, |updates| {
+            storage_updates.extend(updates);
+            Ok(())
+        })?;
+        trie_updates.extend(storage_updates);
+        Ok((root, trie_updates))
+    }
+
+    /// Calculate incremental state root in parallel and stream the trie updates
+    /// to the database through the provided writer.
+    ///
+    /// Storage trie updates are flushed in batches as soon as they are finalized.
+    /// The account trie updates are written once the root is computed. Before that,
+    /// the consistency of the view is checked again to make sure that the written
+    /// updates correspond to the state the root was computed against.
+    pub fn incremental_root_with_writer<TX>(
+        self,
+        mut writer: TrieUpdatesWriter<'_, TX>,
+    ) -> Result<B256, ParallelStateRootError>
+    where
+        DB: Clone,
+        Provider: Clone,
+        TX: DbTx + DbTxMut,
+    {
+        let view = self.view.clone();
+        let (root, trie_updates) = self.calculate(true, |updates| {
+            writer.write(updates).map_err(|error| ProviderError::Database(error).into())
+        })?;
+
+        // Final fix-up: make sure the state did not change during the computation.
+        view.provider_ro()?;
+        let stats = writer.finish(trie_updates).map_err(ProviderError::Database)?;
+        debug!(target: "trie::parallel_state_root", ?stats, "wrote trie updates");
+        Ok(root)
     }
 
+    /// Calculate the state root from scratch in parallel.
+    ///
+    /// See [ParallelStateRoot::full_root_with_updates] for more info.
+    pub fn full_root(self) -> Result<B256, ParallelStateRootError> {
+        self.calculate_full(false).map(|(root, _)| root)
+    }
+
+    /// Calculate the state root from scratch in parallel with updates.
+    ///
+    /// Unlike incremental calculation, the existing intermediate trie nodes are ignored
+    /// and both account and storage tries are rebuilt from the hashed state. The hashed
+    /// accounts keyspace is split into contiguous ranges. Account leaves and storage
+    /// roots of each range are computed in parallel, and the results are added
+    /// to the hash builder in key order.
+    ///
+    /// NOTE: The updates do not contain deletions of existing trie nodes. The trie tables
+    /// are expected to be cleared before the updates are written.
+    pub fn full_root_with_updates(self) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
+        self.calculate_full(true)
+    }
+
+    fn calculate_full(
+        self,
+        retain_updates: bool,
+    ) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
+        let hashed_state_sorted = self.hashed_state.into_sorted();
+        let mut storage_updates = TrieUpdates::default();
+        let (root, mut trie_updates) = calculate_full_root(
+            self.full_root_ranges,
+            retain_updates,
+            |(start, end)| {
+                let provider_ro = self.view.provider_ro()?;
+                account_range_result(
+                    HashedPostStateCursorFactory::new(provider_ro.tx_ref(), &hashed_state_sorted),
+                    *start,
+                    *end,
+                    retain_updates,
+                    #[cfg(feature = "metrics")]
+                    &self.metrics,
+                )
+            },
+            |updates| {
+                storage_updates.extend(updates);
+                Ok(())
+            },
+            #[cfg(feature = "metrics")]
+            &self.metrics,
+        )?;
+        trie_updates.extend(storage_updates);
+        Ok((root, trie_updates))
+    }
+
+    /// Calculate the state root. Storage trie updates are passed to `on_storage_updates`
+    /// as soon as they are finalized, and the returned updates only contain the account trie
+    /// updates.
     fn calculate(
         self,
         retain_updates: bool,
+        mut on_storage_updates: impl FnMut(TrieUpdates) -> Result<(), ParallelStateRootError>,
     ) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
         let mut tracker = ParallelTrieTracker::default();
         let prefix_sets = self.hashed_state.construct_prefix_sets();
@@ -93,17 +215,13 @@ where
         let mut storage_roots = storage_root_targets
             .into_par_iter()
             .map(|(hashed_address, prefix_set)| {
-                let provider_ro = self.view.provider_ro()?;
-                let storage_root_result = StorageRoot::new_hashed(
-                    provider_ro.tx_ref(),
-                    HashedPostStateCursorFactory::new(provider_ro.tx_ref(), &hashed_state_sorted),
+                let storage_root_result = self.storage_root(
                     hashed_address,
-                    #[cfg(feature = "metrics")]
-                    self.metrics.storage_trie.clone(),
-                )
-                .with_prefix_set(prefix_set)
-                .calculate(retain_updates);
-                Ok((hashed_address, storage_root_result?))
+                    prefix_set,
+                    &hashed_state_sorted,
+                    retain_updates,
+                )?;
+                Ok((hashed_address, storage_root_result))
             })
             .collect::<Result<HashMap<_, _>, ParallelStateRootError>>()?;
 
@@ -150,7 +268,7 @@ where
                     };
 
                     if retain_updates {
-                        trie_updates.extend(updates.into_iter());
+                        on_storage_updates(updates)?;
                     }
 
                     account_rlp.clear();
@@ -189,6 +307,256 @@ where
     }
 }
 
+/// The result of full state root calculation for a range of hashed accounts.
+#[derive(Debug)]
+struct AccountRangeResult {
+    /// Hashed addresses with RLP encoded trie accounts, sorted by hashed address.
+    leaves: Vec<(B256, Vec<u8>)>,
+    /// Storage trie updates of accounts in the range.
+    updates: TrieUpdates,
+}
+
+/// Parallel from-scratch state root calculator over a single database transaction.
+///
+/// The hashed accounts are split into ranges and calculated in parallel like in
+/// [ParallelStateRoot::full_root_with_updates], but all ranges are read through the cursors of the
+/// same transaction instead of the transactions of a [ConsistentDbView]. This makes it usable
+/// during staged sync, where the view refuses to create providers, and the hashed state may only
+/// be visible to the transaction of the stage.
+#[derive(Debug)]
+pub struct ParallelFullStateRoot<'a, TX> {
+    /// The transaction the hashed state is read from.
+    tx: &'a TX,
+    /// The number of ranges the hashed accounts are split into.
+    ranges: usize,
+    /// Parallel state root metrics.
+    #[cfg(feature = "metrics")]
+    metrics: ParallelStateRootMetrics,
+}
+
+impl<'a, TX: DbTx> ParallelFullStateRoot<'a, TX> {
+    /// Create new from-scratch state root calculator over the transaction.
+    pub fn new(tx: &'a TX) -> Self {
+        Self {
+            tx,
+            ranges: DEFAULT_FULL_ROOT_RANGES,
+            #[cfg(feature = "metrics")]
+            metrics: ParallelStateRootMetrics::default(),
+        }
+    }
+
+    /// Set the number of ranges the hashed accounts are split into.
+    pub fn with_ranges(mut self, ranges: usize) -> Self {
+        self.ranges = ranges;
+        self
+    }
+
+    /// Calculate the state root with updates.
+    ///
+    /// The storage trie updates are passed to `on_storage_updates` after every batch of ranges
+    /// calculated in parallel, so they can be written to the database without holding the
+    /// storage tries of the whole state in memory. The returned updates only contain the account
+    /// trie updates.
+    ///
+    /// NOTE: The updates do not contain deletions of existing trie nodes. The trie tables
+    /// are expected to be cleared before the updates are written.
+    pub fn root_with_updates(
+        self,
+        on_storage_updates: impl FnMut(TrieUpdates) -> Result<(), ParallelStateRootError>,
+    ) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
+        calculate_full_root(
+            self.ranges,
+            true,
+            |(start, end)| {
+                account_range_result(
+                    self.tx,
+                    *start,
+                    *end,
+                    true,
+                    #[cfg(feature = "metrics")]
+                    &self.metrics,
+                )
+            },
+            on_storage_updates,
+            #[cfg(feature = "metrics")]
+            &self.metrics,
+        )
+    }
+}
+
+/// Calculate the state root from scratch by splitting the hashed accounts into `ranges` contiguous
+/// ranges, calculated in parallel by `account_range`.
+///
+/// The account leaves of the ranges are added to the hash builder in key order. The storage trie
+/// updates are passed to `on_storage_updates` after every batch of ranges, and the account trie
+/// updates are returned.
+fn calculate_full_root(
+    ranges: usize,
+    retain_updates: bool,
+    account_range: impl Fn(&(B256, Option<B256>)) -> Result<AccountRangeResult, ParallelStateRootError>
+        + Sync,
+    mut on_storage_updates: impl FnMut(TrieUpdates) -> Result<(), ParallelStateRootError>,
+    #[cfg(feature = "metrics")] metrics: &ParallelStateRootMetrics,
+) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
+    let mut tracker = ParallelTrieTracker::default();
+    let ranges = hashed_key_ranges(ranges);
+    let window = rayon::current_num_threads().max(1);
+
+    debug!(target: "trie::parallel_state_root", ranges = ranges.len(), "calculating full state root");
+    let mut hash_builder = HashBuilder::default().with_updates(retain_updates);
+    // Process at most as many ranges at once as there are threads in the pool
+    // to bound the number of leaves held in memory.
+    for ranges in ranges.chunks(window) {
+        let results = ranges
+            .par_iter()
+            .map(&account_range)
+            .collect::<Result<Vec<_>, ParallelStateRootError>>()?;
+
+        let mut storage_updates = TrieUpdates::default();
+        for result in results {
+            for (hashed_address, account_rlp) in result.leaves {
+                tracker.inc_leaf();
+                hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+            }
+            storage_updates.extend(result.updates);
+        }
+        on_storage_updates(storage_updates)?;
+    }
+
+    let root = hash_builder.root();
+
+    let mut trie_updates = TrieUpdates::default();
+    let (_, hash_builder_updates) = hash_builder.split();
+    trie_updates.extend_with_account_updates(hash_builder_updates);
+
+    let stats = tracker.finish();
+
+    #[cfg(feature = "metrics")]
+    metrics.record_state_trie(stats);
+
+    trace!(
+        target: "trie::parallel_state_root",
+        %root,
+        duration = ?stats.duration(),
+        leaves_added = stats.leaves_added(),
+        "calculated full state root"
+    );
+
+    Ok((root, trie_updates))
+}
+
+/// Calculate the account leaves and storage roots of the hashed accounts from `start` inclusive
+/// to `end` exclusive.
+fn account_range_result<H>(
+    hashed_cursor_factory: H,
+    start: B256,
+    end: Option<B256>,
+    retain_updates: bool,
+    #[cfg(feature = "metrics")] metrics: &ParallelStateRootMetrics,
+) -> Result<AccountRangeResult, ParallelStateRootError>
+where
+    H: HashedCursorFactory + Clone,
+{
+    let mut hashed_account_cursor =
+        hashed_cursor_factory.hashed_account_cursor().map_err(ProviderError::Database)?;
+
+    let mut result = AccountRangeResult { leaves: Vec::new(), updates: TrieUpdates::default() };
+    let mut entry = hashed_account_cursor.seek(start).map_err(ProviderError::Database)?;
+    while let Some((hashed_address, account)) = entry {
+        if end.is_some_and(|end| hashed_address >= end) {
+            break
+        }
+
+        let (storage_root, _, updates) = StorageRoot::new_hashed(
+            NoopTrieCursorFactory,
+            hashed_cursor_factory.clone(),
+            hashed_address,
+            #[cfg(feature = "metrics")]
+            metrics.storage_trie.clone(),
+        )
+        .calculate(retain_updates)?;
+        if retain_updates {
+            result.updates.extend(updates);
+        }
+
+        let mut account_rlp = Vec::with_capacity(128);
+        TrieAccount::from((account, storage_root)).encode(&mut account_rlp as &mut dyn BufMut);
+        result.leaves.push((hashed_address, account_rlp));
+
+        entry = hashed_account_cursor.next().map_err(ProviderError::Database)?;
+    }
+    Ok(result)
+}
+
+/// Split the hashed keyspace into the given number of contiguous ranges.
+///
+/// Each range is represented by its inclusive start and exclusive end.
+/// The last range is unbounded.
+fn hashed_key_ranges(ranges: usize) -> Vec<(B256, Option<B256>)> {
+    let ranges = ranges.max(1);
+    let step = U256::MAX / U256::from(ranges);
+    (0..ranges)
+        .map(|idx| {
+            let start = B256::from(step * U256::from(idx));
+            let end = (idx + 1 < ranges).then(|| B256::from(step * U256::from(idx + 1)));
+            (start, end)
+        })
+        .collect()
+}
+
+impl<DB, Provider> ParallelStateRoot<DB, Provider>
+where
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB> + Send + Sync,
+{
+    /// Calculate the storage root of the account. If the number of changed slots reaches
+    /// the threshold, the subtries of the storage trie are calculated in parallel.
+    fn storage_root(
+        &self,
+        hashed_address: B256,
+        prefix_set: PrefixSet,
+        hashed_state_sorted: &HashedPostStateSorted,
+        retain_updates: bool,
+    ) -> Result<(B256, usize, TrieUpdates), ParallelStateRootError> {
+        if prefix_set.len() >= self.storage_subtries_threshold {
+            let subtries = (0..16u8)
+                .into_par_iter()
+                .map(|nibble| {
+                    let provider_ro = self.view.provider_ro()?;
+                    let subtrie = StorageRoot::new_hashed(
+                        provider_ro.tx_ref(),
+                        HashedPostStateCursorFactory::new(
+                            provider_ro.tx_ref(),
+                            hashed_state_sorted,
+                        ),
+                        hashed_address,
+                        #[cfg(feature = "metrics")]
+                        self.metrics.storage_trie.clone(),
+                    )
+                    .with_prefix_set(prefix_set.clone())
+                    .calculate_subtrie(nibble, retain_updates)?;
+                    Ok(subtrie)
+                })
+                .collect::<Result<Vec<_>, ParallelStateRootError>>()?;
+            if let Some(result) = StorageSubtrie::merge(hashed_address, subtries, retain_updates) {
+                return Ok(result)
+            }
+            trace!(target: "trie::parallel_state_root", ?hashed_address, "storage trie has less than two subtries");
+        }
+
+        let provider_ro = self.view.provider_ro()?;
+        Ok(StorageRoot::new_hashed(
+            provider_ro.tx_ref(),
+            HashedPostStateCursorFactory::new(provider_ro.tx_ref(), hashed_state_sorted),
+            hashed_address,
+            #[cfg(feature = "metrics")]
+            self.metrics.storage_trie.clone(),
+        )
+        .with_prefix_set(prefix_set)
+        .calculate(retain_updates)?)
+    }
+}
+
 /// Error during parallel state root calculation.
 #[derive(Error, Debug)]
 pub enum ParallelStateRootError {
@@ -215,10 +583,46 @@ impl From<ParallelStateRootError> for ProviderError {
 mod tests {
     use super::*;
     use rand::Rng;
-    use reth_primitives::{keccak256, Account, Address, StorageEntry, U256};
-    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter};
+    use reth_db::{cursor::DbCursorRO, tables};
+    use reth_primitives::{keccak256, Account, Address, StorageEntry};
+    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter, ProviderFactory};
     use reth_trie::{test_utils, HashedStorage};
 
+    fn insert_random_state(factory: &ProviderFactory<impl Database>) -> B256 {
+        let mut rng = rand::thread_rng();
+        let state = (0..100)
+            .map(|_| {
+                let address = Address::random();
+                let account =
+                    Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+                let storage = (0..rng.gen_range(0..100))
+                    .map(|_| {
+                        (B256::from(U256::from(rng.gen::<u64>())), U256::from(rng.gen::<u64>()))
+                    })
+                    .collect::<HashMap<_, _>>();
+                (address, (account, storage))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .insert_account_for_hashing(
+                state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+            )
+            .unwrap();
+        provider_rw
+            .insert_storage_for_hashing(state.iter().map(|(address, (_, storage))| {
+                (
+                    *address,
+                    storage.iter().map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                )
+            }))
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        test_utils::state_root(state)
+    }
+
     #[tokio::test]
     async fn random_parallel_root() {
         let factory = create_test_provider_factory();
@@ -301,4 +705,240 @@ mod tests {
             test_utils::state_root(state)
         );
     }
+
+    #[test]
+    fn streamed_updates_match_flushed_updates() {
+        let factory = create_test_provider_factory();
+        let expected_root = insert_random_state(&factory);
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+
+        // Compute the root and collect all updates in memory.
+        let (root, updates) =
+            ParallelStateRoot::new(consistent_view.clone(), HashedPostState::default())
+                .incremental_root_with_updates()
+                .unwrap();
+        assert_eq!(root, expected_root);
+
+        let provider_rw = factory.provider_rw().unwrap();
+        updates.flush(provider_rw.tx_ref()).unwrap();
+        let account_nodes = provider_rw
+            .tx_ref()
+            .cursor_read::<tables::AccountsTrie>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let storage_nodes = provider_rw
+            .tx_ref()
+            .cursor_read::<tables::StoragesTrie>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        // Discard the flushed updates.
+        drop(provider_rw);
+
+        // Compute the root again streaming the updates in small batches.
+        let provider_rw = factory.provider_rw().unwrap();
+        let writer = TrieUpdatesWriter::new(provider_rw.tx_ref()).with_batch_size(10);
+        let root = ParallelStateRoot::new(consistent_view, HashedPostState::default())
+            .incremental_root_with_writer(writer)
+            .unwrap();
+        assert_eq!(root, expected_root);
+
+        assert_eq!(
+            provider_rw
+                .tx_ref()
+                .cursor_read::<tables::AccountsTrie>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            account_nodes
+        );
+        assert_eq!(
+            provider_rw
+                .tx_ref()
+                .cursor_read::<tables::StoragesTrie>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            storage_nodes
+        );
+    }
+
+    #[test]
+    fn full_root_matches_sequential_root() {
+        let factory = create_test_provider_factory();
+        let expected_root = insert_random_state(&factory);
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+
+        for ranges in [1, 3, 16, 300] {
+            let (root, updates) =
+                ParallelStateRoot::new(consistent_view.clone(), HashedPostState::default())
+                    .with_full_root_ranges(ranges)
+                    .full_root_with_updates()
+                    .unwrap();
+            assert_eq!(root, expected_root);
+
+            let provider = factory.provider().unwrap();
+            let (_, expected_updates) =
+                reth_trie::StateRoot::from_tx(provider.tx_ref()).root_with_updates().unwrap();
+            assert_eq!(
+                updates.into_iter().filter(|(_, op)| op.is_update()).collect::<HashMap<_, _>>(),
+                expected_updates
+                    .into_iter()
+                    .filter(|(_, op)| op.is_update())
+                    .collect::<HashMap<_, _>>()
+            );
+        }
+    }
+
+    #[test]
+    fn full_root_over_transaction_matches_sequential_root() {
+        let factory = create_test_provider_factory();
+        let expected_root = insert_random_state(&factory);
+
+        let provider = factory.provider().unwrap();
+        let (_, expected_updates) =
+            reth_trie::StateRoot::from_tx(provider.tx_ref()).root_with_updates().unwrap();
+
+        let mut storage_updates = TrieUpdates::default();
+        let mut batches = 0;
+        let (root, updates) = ParallelFullStateRoot::new(provider.tx_ref())
+            .with_ranges(300)
+            .root_with_updates(|updates| {
+                storage_updates.extend(updates);
+                batches += 1;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(root, expected_root);
+        assert_eq!(batches, 300_usize.div_ceil(rayon::current_num_threads()));
+
+        assert_eq!(
+            updates
+                .into_iter()
+                .chain(storage_updates)
+                .filter(|(_, op)| op.is_update())
+                .collect::<HashMap<_, _>>(),
+            expected_updates
+                .into_iter()
+                .filter(|(_, op)| op.is_update())
+                .collect::<HashMap<_, _>>()
+        );
+    }
+
+    #[test]
+    fn storage_subtries_match_sequential_root() {
+        let factory = create_test_provider_factory();
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+
+        // An account with a large storage and an account whose storage is a single subtrie.
+        let mut rng = rand::thread_rng();
+        let mut state = [(Address::random(), 1000), (Address::random(), 1)]
+            .into_iter()
+            .map(|(address, slots)| {
+                let storage = (0..slots)
+                    .map(|_| (B256::random(), U256::from(rng.gen::<u64>())))
+                    .collect::<HashMap<_, _>>();
+                (address, (Account::default(), storage))
+            })
+            .collect::<HashMap<_, _>>();
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            provider_rw
+                .insert_account_for_hashing(
+                    state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+                )
+                .unwrap();
+            provider_rw
+                .insert_storage_for_hashing(state.iter().map(|(address, (_, storage))| {
+                    (
+                        *address,
+                        storage
+                            .iter()
+                            .map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                    )
+                }))
+                .unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        let hashed_state = |state: &HashMap<Address, (Account, HashMap<B256, U256>)>| {
+            let mut hashed_state = HashedPostState::default();
+            for (address, (account, storage)) in state {
+                let hashed_address = keccak256(address);
+                hashed_state.accounts.insert(hashed_address, Some(*account));
+                let hashed_storage = hashed_state
+                    .storages
+                    .entry(hashed_address)
+                    .or_insert_with(|| HashedStorage::new(false));
+                for (slot, value) in storage {
+                    hashed_storage.storage.insert(keccak256(slot), *value);
+                }
+            }
+            hashed_state
+        };
+
+        // Computes the root and flushes the updates, returning the storage trie nodes along with
+        // the provider the updates are flushed with.
+        let calculate = |threshold: usize, hashed_state: HashedPostState| {
+            let (root, updates) = ParallelStateRoot::new(consistent_view.clone(), hashed_state)
+                .with_storage_subtries_threshold(threshold)
+                .incremental_root_with_updates()
+                .unwrap();
+            let provider_rw = factory.provider_rw().unwrap();
+            updates.flush(provider_rw.tx_ref()).unwrap();
+            let storage_nodes = provider_rw
+                .tx_ref()
+                .cursor_read::<tables::StoragesTrie>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            (root, storage_nodes, provider_rw)
+        };
+
+        // Without the storage tries in the database.
+        let (root, storage_nodes, provider_rw) = calculate(1, hashed_state(&state));
+        drop(provider_rw);
+        let (expected_root, expected_storage_nodes, provider_rw) =
+            calculate(usize::MAX, hashed_state(&state));
+        assert_eq!(root, test_utils::state_root(state.clone()));
+        assert_eq!(root, expected_root);
+        assert_eq!(storage_nodes, expected_storage_nodes);
+        provider_rw.commit().unwrap();
+
+        // With the storage tries in the database.
+        for (_, storage) in state.values_mut() {
+            for value in storage.values_mut().take(100) {
+                *value = U256::from(rng.gen::<u64>());
+            }
+        }
+        let (root, storage_nodes, provider_rw) = calculate(1, hashed_state(&state));
+        drop(provider_rw);
+        let (expected_root, expected_storage_nodes, _) =
+            calculate(usize::MAX, hashed_state(&state));
+        assert_eq!(root, test_utils::state_root(state));
+        assert_eq!(root, expected_root);
+        assert_eq!(storage_nodes, expected_storage_nodes);
+    }
+
+    #[test]
+    fn hashed_key_ranges_cover_keyspace() {
+        let ranges = hashed_key_ranges(7);
+        assert_eq!(ranges.len(), 7);
+        assert_eq!(ranges[0].0, B256::ZERO);
+        assert_eq!(ranges.last().unwrap().1, None);
+        for (current, next) in ranges.iter().zip(ranges.iter().skip(1)) {
+            assert_eq!(current.1, Some(next.0));
+        }
+    }
 }
@@ -0,0 +1,37 @@
+use reth_provider::{CanonStateNotification, CanonStateNotifications};
+use reth_trie::trie_cursor::TrieNodeCache;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::*;
+
+/// Keeps the [TrieNodeCache] consistent with the database by dropping the nodes changed by the
+/// canonical chain updates. The task runs until the notification channel is closed.
+///
+/// This allows sharing the cache between all state root computations of the node, regardless of
+/// which component writes the blocks. Reorgs and missed notifications clear the cache.
+pub async fn invalidate_trie_cache_task(
+    cache: TrieNodeCache,
+    mut notifications: CanonStateNotifications,
+) {
+    loop {
+        match notifications.recv().await {
+            Ok(notification) => on_canonical_state(&cache, notification),
+            Err(RecvError::Lagged(skipped)) => {
+                debug!(target: "trie::cache", skipped, "canonical state notifications lagging behind, clearing trie cache");
+                cache.clear();
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+fn on_canonical_state(cache: &TrieNodeCache, notification: CanonStateNotification) {
+    if notification.reverted().is_some() {
+        cache.clear();
+    }
+    if let Some(committed) = notification.committed() {
+        let parent = committed.first().parent_hash;
+        let tip = committed.tip().hash();
+        trace!(target: "trie::cache", ?parent, ?tip, "invalidating trie cache");
+        cache.invalidate(Some(parent), Some(tip), &committed.state().hash_state_slow());
+    }
+}
@@ -38,7 +38,7 @@ pub mod proof;
 
 /// The implementation of the Merkle Patricia Trie.
 mod trie;
-pub use trie::{StateRoot, StorageRoot};
+pub use trie::{StateRoot, StorageRoot, StorageSubtrie};
 
 /// Buffer for trie updates.
 pub mod updates;
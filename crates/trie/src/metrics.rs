@@ -1,5 +1,5 @@
-use crate::stats::TrieStats;
-use metrics::Histogram;
+use crate::{stats::TrieStats, updates::TrieUpdatesStats};
+use metrics::{Counter, Histogram};
 use reth_metrics::Metrics;
 
 /// Wrapper for state root metrics.
@@ -46,6 +46,33 @@ impl TrieRootMetrics {
     }
 }
 
+/// Metrics for writing trie updates to the database.
+#[derive(Clone, Metrics)]
+#[metrics(scope = "trie.updates")]
+pub struct TrieUpdatesMetrics {
+    /// The number of written account trie nodes.
+    account_nodes_written: Counter,
+    /// The number of removed account trie nodes.
+    account_nodes_removed: Counter,
+    /// The number of written storage trie nodes.
+    storage_nodes_written: Counter,
+    /// The number of removed storage trie nodes.
+    storage_nodes_removed: Counter,
+    /// The number of removed storage tries.
+    storage_tries_removed: Counter,
+}
+
+impl TrieUpdatesMetrics {
+    /// Record trie updates stats as metrics.
+    pub fn record(&self, stats: TrieUpdatesStats) {
+        self.account_nodes_written.increment(stats.account_nodes_written as u64);
+        self.account_nodes_removed.increment(stats.account_nodes_removed as u64);
+        self.storage_nodes_written.increment(stats.storage_nodes_written as u64);
+        self.storage_nodes_removed.increment(stats.storage_nodes_removed as u64);
+        self.storage_tries_removed.increment(stats.storage_tries_removed as u64);
+    }
+}
+
 /// Trie type for differentiating between various trie calculations.
 #[derive(Clone, Copy, Debug)]
 pub enum TrieType {
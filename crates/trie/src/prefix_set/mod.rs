@@ -170,6 +170,12 @@ impl PrefixSet {
     pub fn is_empty(&self) -> bool {
         self.keys.is_empty()
     }
+
+    /// Returns the set of the keys that have the given prefix.
+    pub fn with_prefix(&self, prefix: &Nibbles) -> Self {
+        let keys = self.keys.iter().filter(|key| key.has_prefix(prefix)).cloned().collect();
+        Self { keys: Arc::new(keys), index: 0 }
+    }
 }
 
 #[cfg(test)]
@@ -12,8 +12,9 @@ use reth_primitives::{
     constants::EMPTY_ROOT_HASH,
     keccak256,
     trie::{AccountProof, HashBuilder, Nibbles, StorageProof, TrieAccount},
-    Address, B256,
+    Address, Bytes, B256, U256,
 };
+use std::collections::BTreeMap;
 
 /// A struct for generating merkle proofs.
 ///
@@ -97,6 +98,60 @@ where
         Ok(account_proof)
     }
 
+    /// Generate the account trie nodes on the paths to the hashed addresses, ordered by path.
+    ///
+    /// The nodes prove the presence or the absence of the accounts, so the proof of the first and
+    /// the last hashed address of a contiguous range of accounts proves the range.
+    pub fn account_range_proof(
+        &self,
+        hashed_addresses: &[B256],
+    ) -> Result<Vec<Bytes>, StateRootError> {
+        let target_nibbles = hashed_addresses.iter().map(Nibbles::unpack).collect::<Vec<_>>();
+
+        let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let trie_cursor =
+            DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
+        let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
+        let walker = TrieWalker::new(trie_cursor, prefix_set);
+
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(target_nibbles);
+        let mut account_rlp = Vec::with_capacity(128);
+        let mut account_node_iter = AccountNodeIter::new(walker, hashed_account_cursor);
+        while let Some(account_node) = account_node_iter.try_next()? {
+            match account_node {
+                AccountNode::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                AccountNode::Leaf(hashed_address, account) => {
+                    let storage_root = self.storage_root(hashed_address)?;
+
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                }
+            }
+        }
+
+        let _ = hash_builder.root();
+
+        Ok(hash_builder.take_proofs().into_values().collect())
+    }
+
+    /// Generate the storage trie nodes of the account on the paths to the hashed slots, ordered by
+    /// path. See [Proof::account_range_proof].
+    pub fn storage_range_proof(
+        &self,
+        hashed_address: B256,
+        hashed_slots: &[B256],
+    ) -> Result<Vec<Bytes>, StorageRootError> {
+        let target_nibbles = hashed_slots.iter().map(Nibbles::unpack).collect();
+        let (_, proof_nodes) =
+            self.storage_root_with_proof_nodes(hashed_address, target_nibbles, |_, _| {})?;
+        Ok(proof_nodes.into_values().collect())
+    }
+
     /// Compute storage root.
     pub fn storage_root(&self, hashed_address: B256) -> Result<B256, StorageRootError> {
         let (storage_root, _) = self.storage_root_with_proofs(hashed_address, &[])?;
@@ -109,16 +164,47 @@ where
         hashed_address: B256,
         slots: &[B256],
     ) -> Result<(B256, Vec<StorageProof>), StorageRootError> {
-        let mut hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
-
         let mut proofs = slots.iter().copied().map(StorageProof::new).collect::<Vec<_>>();
+        let target_nibbles = proofs.iter().map(|p| p.nibbles.clone()).collect::<Vec<_>>();
+
+        let (root, all_proof_nodes) = self.storage_root_with_proof_nodes(
+            hashed_address,
+            target_nibbles,
+            |nibbles, value| {
+                if let Some(proof) = proofs.iter_mut().find(|proof| proof.nibbles == *nibbles) {
+                    proof.set_value(value);
+                }
+            },
+        )?;
+
+        for proof in proofs.iter_mut() {
+            // Iterate over all proof nodes and find the matching ones.
+            // The filtered results are guaranteed to be in order.
+            let matching_proof_nodes = all_proof_nodes
+                .iter()
+                .filter(|(path, _)| proof.nibbles.starts_with(path))
+                .map(|(_, node)| node.clone());
+            proof.set_proof(matching_proof_nodes.collect());
+        }
+
+        Ok((root, proofs))
+    }
+
+    /// Compute the storage root and retain the trie nodes on the paths to the target nibbles.
+    /// `on_leaf` is called with every storage leaf visited.
+    fn storage_root_with_proof_nodes(
+        &self,
+        hashed_address: B256,
+        target_nibbles: Vec<Nibbles>,
+        mut on_leaf: impl FnMut(&Nibbles, U256),
+    ) -> Result<(B256, BTreeMap<Nibbles, Bytes>), StorageRootError> {
+        let mut hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
 
         // short circuit on empty storage
         if hashed_storage_cursor.is_storage_empty(hashed_address)? {
-            return Ok((EMPTY_ROOT_HASH, proofs))
+            return Ok((EMPTY_ROOT_HASH, BTreeMap::new()))
         }
 
-        let target_nibbles = proofs.iter().map(|p| p.nibbles.clone()).collect::<Vec<_>>();
         let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
         let trie_cursor = DatabaseStorageTrieCursor::new(
             self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
@@ -136,28 +222,14 @@ where
                 }
                 StorageNode::Leaf(hashed_slot, value) => {
                     let nibbles = Nibbles::unpack(hashed_slot);
-                    if let Some(proof) = proofs.iter_mut().find(|proof| proof.nibbles == nibbles) {
-                        proof.set_value(value);
-                    }
+                    on_leaf(&nibbles, value);
                     hash_builder.add_leaf(nibbles, alloy_rlp::encode_fixed_size(&value).as_ref());
                 }
             }
         }
 
         let root = hash_builder.root();
-
-        let all_proof_nodes = hash_builder.take_proofs();
-        for proof in proofs.iter_mut() {
-            // Iterate over all proof nodes and find the matching ones.
-            // The filtered results are guaranteed to be in order.
-            let matching_proof_nodes = all_proof_nodes
-                .iter()
-                .filter(|(path, _)| proof.nibbles.starts_with(path))
-                .map(|(_, node)| node.clone());
-            proof.set_proof(matching_proof_nodes.collect());
-        }
-
-        Ok((root, proofs))
+        Ok((root, hash_builder.take_proofs()))
     }
 }
 
@@ -171,7 +243,7 @@ mod tests {
     use reth_interfaces::RethResult;
     use reth_primitives::{Account, Bytes, ChainSpec, StorageEntry, HOLESKY, MAINNET, U256};
     use reth_provider::{test_utils::create_test_provider_factory, HashingWriter, ProviderFactory};
-    use std::{str::FromStr, sync::Arc};
+    use std::{collections::HashSet, str::FromStr, sync::Arc};
 
     /*
         World State (sampled from <https://ethereum.stackexchange.com/questions/268/ethereum-block-architecture/6413#6413>)
@@ -312,6 +384,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn testspec_range_proofs() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let provider = factory.provider().unwrap();
+        let proof = Proof::new(provider.tx_ref());
+
+        // The range proof holds the nodes of the proofs of both boundaries.
+        let first = Address::from_str("0x2031f89b3ea8014eb51a78c316e42af3e0d7695f").unwrap();
+        let last = Address::from_str("0x1ed9b1dd266b607ee278726d324b855a093394a6").unwrap();
+        let range_proof = proof.account_range_proof(&[keccak256(first), keccak256(last)]).unwrap();
+        let expected = [first, last]
+            .into_iter()
+            .flat_map(|address| proof.account_proof(address, &[]).unwrap().proof)
+            .collect::<HashSet<_>>();
+        assert_eq!(range_proof.len(), expected.len());
+        assert_eq!(range_proof.into_iter().collect::<HashSet<_>>(), expected);
+
+        // Accounts without storage have no storage trie nodes.
+        assert_eq!(proof.storage_range_proof(keccak256(first), &[B256::ZERO]).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn holesky_deposit_contract_storage_range_proof() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, HOLESKY.clone()).unwrap();
+
+        let provider = factory.provider().unwrap();
+        let proof = Proof::new(provider.tx_ref());
+
+        let target = Address::from_str("0x4242424242424242424242424242424242424242").unwrap();
+        // existent and non-existent
+        let slots = [B256::with_last_byte(0x22), B256::with_last_byte(0x99)];
+
+        let range_proof =
+            proof.storage_range_proof(keccak256(target), &slots.map(keccak256)).unwrap();
+        let (_, storage_proofs) =
+            proof.storage_root_with_proofs(keccak256(target), &slots).unwrap();
+        let expected = storage_proofs
+            .into_iter()
+            .flat_map(|storage_proof| storage_proof.proof)
+            .collect::<HashSet<_>>();
+        assert_eq!(range_proof.len(), expected.len());
+        assert_eq!(range_proof.into_iter().collect::<HashSet<_>>(), expected);
+    }
+
     #[test]
     fn mainnet_genesis_account_proof() {
         // Create test database and insert genesis accounts.
@@ -59,15 +59,22 @@ impl HashedPostState {
     /// apply them to hashed state in reverse.
     ///
     /// NOTE: In order to have the resulting [HashedPostState] be a correct
-    /// overlay of the plain state, the end of the range must be the current tip.
+    /// overlay of the plain state, the end of the range must be the current tip. If the
+    /// changesets of the range were folded into epochs, the start of the range must be the first
+    /// block of an epoch.
     pub fn from_revert_range<TX: DbTx>(
         tx: &TX,
         range: RangeInclusive<BlockNumber>,
     ) -> Result<Self, DatabaseError> {
         // Iterate over account changesets and record value before first occurring account change.
+        // The changesets folded into epochs precede the ones that weren't, so epochs go first.
         let mut accounts = HashMap::<Address, Option<Account>>::default();
+        let mut account_epochs_cursor = tx.cursor_read::<tables::AccountChangeSetEpochs>()?;
         let mut account_changesets_cursor = tx.cursor_read::<tables::AccountChangeSets>()?;
-        for entry in account_changesets_cursor.walk_range(range.clone())? {
+        for entry in account_epochs_cursor
+            .walk_range(range.clone())?
+            .chain(account_changesets_cursor.walk_range(range.clone())?)
+        {
             let (_, AccountBeforeTx { address, info }) = entry?;
             if let hash_map::Entry::Vacant(entry) = accounts.entry(address) {
                 entry.insert(info);
@@ -76,8 +83,12 @@ impl HashedPostState {
 
         // Iterate over storage changesets and record value before first occurring storage change.
         let mut storages = HashMap::<Address, HashMap<B256, U256>>::default();
+        let mut storage_epochs_cursor = tx.cursor_read::<tables::StorageChangeSetEpochs>()?;
         let mut storage_changesets_cursor = tx.cursor_read::<tables::StorageChangeSets>()?;
-        for entry in storage_changesets_cursor.walk_range(BlockNumberAddress::range(range))? {
+        for entry in storage_epochs_cursor
+            .walk_range(BlockNumberAddress::range(range.clone()))?
+            .chain(storage_changesets_cursor.walk_range(BlockNumberAddress::range(range))?)
+        {
             let (BlockNumberAddress((_, address)), storage) = entry?;
             let account_storage = storages.entry(address).or_default();
             if let hash_map::Entry::Vacant(entry) = account_storage.entry(storage.key) {
@@ -17,7 +17,7 @@ use reth_primitives::{
     trie::{HashBuilder, Nibbles, TrieAccount},
     Address, BlockNumber, B256,
 };
-use std::ops::RangeInclusive;
+use std::{cmp::Ordering, ops::RangeInclusive};
 use tracing::{debug, trace};
 
 #[cfg(feature = "metrics")]
@@ -545,6 +545,135 @@ where
         let storage_slots_walked = stats.leaves_added() as usize;
         Ok((root, storage_slots_walked, trie_updates))
     }
+
+    /// Walks the hashed storage entries of the slots whose hashed keys start with the nibble and
+    /// calculates the node of the storage trie at the path of the nibble.
+    ///
+    /// The subtries of different nibbles are independent of each other, so they can be calculated
+    /// in parallel and merged with [StorageSubtrie::merge].
+    pub fn calculate_subtrie(
+        self,
+        nibble: u8,
+        retain_updates: bool,
+    ) -> Result<StorageSubtrie, StorageRootError> {
+        let hashed_address = self.hashed_address;
+        let prefix_set = self.prefix_set.with_prefix(&Nibbles::from_nibbles_unchecked([nibble]));
+        let hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
+        let trie_cursor = self.trie_cursor_factory.storage_tries_cursor(hashed_address)?;
+        let walker = TrieWalker::new(trie_cursor, prefix_set).with_updates(retain_updates);
+
+        // The keys are added without the nibble, so that the root of the hash builder is the node
+        // at the path of the nibble.
+        let mut hash_builder = HashBuilder::default().with_updates(retain_updates);
+        let mut is_empty = true;
+        let mut leaves = 0;
+
+        let mut storage_node_iter =
+            StorageNodeIter::new(walker, hashed_storage_cursor, hashed_address);
+        while let Some(node) = storage_node_iter.try_next()? {
+            let key = match &node {
+                StorageNode::Branch(node) => node.key.clone(),
+                StorageNode::Leaf(hashed_slot, _) => Nibbles::unpack(hashed_slot),
+            };
+            match key.first().copied().cmp(&Some(nibble)) {
+                Ordering::Less => continue,
+                Ordering::Greater => break,
+                Ordering::Equal => {}
+            }
+
+            let key = Nibbles::from_nibbles_unchecked(&key[1..]);
+            match node {
+                StorageNode::Branch(node) => {
+                    hash_builder.add_branch(key, node.value, node.children_are_in_trie);
+                }
+                StorageNode::Leaf(_, value) => {
+                    leaves += 1;
+                    hash_builder.add_leaf(key, alloy_rlp::encode_fixed_size(&value).as_ref());
+                }
+            }
+            is_empty = false;
+        }
+
+        let hash = (!is_empty).then(|| hash_builder.root());
+
+        // Nodes of other subtries are visited by the walker, but they are updated by their own
+        // calculations.
+        let mut updates = TrieUpdates::default();
+        let (_, walker_updates) = storage_node_iter.walker.split();
+        updates.extend(walker_updates.into_iter().filter(|(key, _)| {
+            matches!(key, TrieKey::StorageNode(_, nibbles) if nibbles.0.first() == Some(&nibble))
+        }));
+
+        let mut in_database_trie = false;
+        let (_, branch_nodes) = hash_builder.split();
+        updates.extend(branch_nodes.into_iter().map(|(key, mut node)| {
+            // The root of the hash builder is not the root of the storage trie.
+            if key.is_empty() {
+                in_database_trie = true;
+                node.root_hash = None;
+            }
+            let key = Nibbles::from_nibbles_unchecked([&[nibble][..], &key[..]].concat());
+            (TrieKey::StorageNode(hashed_address, key.into()), TrieOp::Update(node))
+        }));
+
+        Ok(StorageSubtrie { nibble, hash, in_database_trie, leaves, updates })
+    }
+}
+
+/// The node of a storage trie at the path of a single nibble, calculated with
+/// [StorageRoot::calculate_subtrie].
+#[derive(Debug)]
+pub struct StorageSubtrie {
+    /// The first nibble of the hashed keys of the slots in the subtrie.
+    pub nibble: u8,
+    /// The hash of the node, or `None` if the subtrie is empty.
+    pub hash: Option<B256>,
+    /// `true` if the node is stored in the database trie.
+    pub in_database_trie: bool,
+    /// The number of walked storage entries.
+    pub leaves: usize,
+    /// The trie updates of the subtrie.
+    pub updates: TrieUpdates,
+}
+
+impl StorageSubtrie {
+    /// Merges the subtries of the storage trie into the storage root, the number of walked
+    /// entries and the trie updates.
+    ///
+    /// Returns `None` if less than two subtries are non-empty, since the root node is not a branch
+    /// node then and the storage root must be calculated with [StorageRoot::calculate].
+    pub fn merge(
+        hashed_address: B256,
+        mut subtries: Vec<Self>,
+        retain_updates: bool,
+    ) -> Option<(B256, usize, TrieUpdates)> {
+        if subtries.iter().filter(|subtrie| subtrie.hash.is_some()).count() < 2 {
+            return None
+        }
+
+        subtries.sort_unstable_by_key(|subtrie| subtrie.nibble);
+        let mut hash_builder = HashBuilder::default().with_updates(retain_updates);
+        let mut storage_slots_walked = 0;
+        let mut trie_updates = TrieUpdates::default();
+        for subtrie in subtries {
+            if let Some(hash) = subtrie.hash {
+                hash_builder.add_branch(
+                    Nibbles::from_nibbles_unchecked([subtrie.nibble]),
+                    hash,
+                    subtrie.in_database_trie,
+                );
+            }
+            storage_slots_walked += subtrie.leaves;
+            trie_updates.extend(subtrie.updates);
+        }
+
+        let root = hash_builder.root();
+        let (_, branch_nodes) = hash_builder.split();
+        trie_updates.extend(branch_nodes.into_iter().map(|(key, node)| {
+            (TrieKey::StorageNode(hashed_address, key.into()), TrieOp::Update(node))
+        }));
+        Some((root, storage_slots_walked, trie_updates))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,574 @@
+use super::{TrieCursor, TrieCursorFactory};
+use crate::{
+    updates::{TrieKey, TrieOp, TrieUpdates},
+    HashedPostState,
+};
+use parking_lot::Mutex;
+use reth_db::DatabaseError;
+use reth_primitives::{
+    trie::{BranchNodeCompact, Nibbles, StoredNibbles, StoredNibblesSubKey},
+    B256,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// The default maximum number of entries held by the [TrieNodeCache].
+pub const DEFAULT_MAX_CACHED_TRIE_ENTRIES: usize = 1_000_000;
+
+/// In-memory cache of the account and storage trie nodes, shared between state root
+/// computations.
+///
+/// The cache remembers the branch nodes and the ranges of keys without nodes that were read
+/// through the [CachedTrieCursorFactory], so that the upper levels of the tries, which are
+/// visited by every computation, are only read from the database once.
+///
+/// The cache is only valid for the tip it was populated at. Computations read it through a handle
+/// returned by [TrieNodeCache::at_tip], which is bound to the generation of the cache at that tip.
+/// Once the trie updates of a new block are written to the database, they must be applied with
+/// [TrieNodeCache::apply_updates], or the nodes changed by the block must be dropped with
+/// [TrieNodeCache::invalidate]. Both move the cache to the new tip and start a new generation, so
+/// computations still reading the previous tip neither read nor populate the cache anymore.
+#[derive(Clone, Debug)]
+pub struct TrieNodeCache {
+    inner: Arc<Mutex<CacheInner>>,
+    /// The generation the handle reads and populates, or `None` if it bypasses the cache.
+    generation: Option<u64>,
+}
+
+impl Default for TrieNodeCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CACHED_TRIE_ENTRIES)
+    }
+}
+
+impl TrieNodeCache {
+    /// Create new empty cache. The cache is cleared once it holds more than `max_entries`.
+    pub fn new(max_entries: usize) -> Self {
+        let inner = CacheInner {
+            tip: None,
+            generation: 0,
+            account: CachedTrie::default(),
+            storages: HashMap::default(),
+            entries: 0,
+            max_entries,
+        };
+        Self { inner: Arc::new(Mutex::new(inner)), generation: None }
+    }
+
+    /// Returns the number of cached entries.
+    pub fn len(&self) -> usize {
+        self.inner.lock().entries
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Clear the cache. Handles returned by [TrieNodeCache::at_tip] before are no longer used.
+    pub fn clear(&self) {
+        self.inner.lock().reset(None);
+    }
+
+    /// Returns a handle to read the tries at the given tip through.
+    ///
+    /// An empty cache is moved to the tip. If the cache holds the nodes of another tip, it's left
+    /// intact and the returned handle bypasses it, so computations at different tips don't keep
+    /// clearing each other's nodes. The cache only moves forward with the canonical chain, see
+    /// [TrieNodeCache::apply_updates] and [TrieNodeCache::invalidate].
+    pub fn at_tip(&self, tip: Option<B256>) -> Self {
+        let mut inner = self.inner.lock();
+        if inner.tip != tip && inner.entries == 0 {
+            inner.reset(tip);
+        }
+        let generation = (inner.tip == tip).then_some(inner.generation);
+        Self { inner: self.inner.clone(), generation }
+    }
+
+    /// Apply the trie updates that were written to the database on top of the `parent` tip,
+    /// moving the cache to the new `tip`. The cache is cleared if it's not at the `parent` tip.
+    ///
+    /// Only the tries that were already cached are updated.
+    pub fn apply_updates(&self, parent: Option<B256>, tip: Option<B256>, updates: &TrieUpdates) {
+        let mut inner = self.inner.lock();
+        if inner.tip != parent {
+            inner.reset(tip);
+            return
+        }
+
+        let mut destroyed = Vec::new();
+        for (key, operation) in updates.iter() {
+            let node = match operation {
+                TrieOp::Delete => None,
+                TrieOp::Update(node) => Some(node.clone()),
+            };
+            // Same as when the updates are flushed, the root nodes of the tries are never written.
+            match key {
+                TrieKey::AccountNode(nibbles) => {
+                    if node.is_none() || !nibbles.0.is_empty() {
+                        inner.account.apply(&nibbles.0, node);
+                    }
+                }
+                TrieKey::StorageNode(hashed_address, nibbles) => {
+                    if let Some(trie) =
+                        inner.storages.get_mut(hashed_address).filter(|_| !nibbles.0.is_empty())
+                    {
+                        trie.apply(&nibbles.0, node);
+                    }
+                }
+                // Storage tries are deleted after all node updates are written.
+                TrieKey::StorageTrie(hashed_address) => destroyed.push(*hashed_address),
+            }
+        }
+        for hashed_address in destroyed {
+            inner.storages.remove(&hashed_address);
+        }
+        inner.move_to(tip);
+    }
+
+    /// Drop the cached nodes that may be changed by the state changes of the blocks written on top
+    /// of the `parent` tip, moving the cache to the new `tip`. The cache is cleared if it's not at
+    /// the `parent` tip.
+    ///
+    /// Unlike [TrieNodeCache::apply_updates], the trie updates are not required: the nodes on the
+    /// paths to the changed accounts and storage slots are dropped, together with the storage
+    /// tries of destroyed accounts and wiped storages.
+    pub fn invalidate(&self, parent: Option<B256>, tip: Option<B256>, state: &HashedPostState) {
+        let mut inner = self.inner.lock();
+        if inner.tip != parent {
+            inner.reset(tip);
+            return
+        }
+
+        for hashed_address in state.accounts.keys().chain(state.storages.keys()) {
+            inner.account.invalidate(&Nibbles::unpack(hashed_address));
+        }
+        for (hashed_address, account) in &state.accounts {
+            if account.is_none() {
+                inner.storages.remove(hashed_address);
+            }
+        }
+        for (hashed_address, storage) in &state.storages {
+            if storage.wiped {
+                inner.storages.remove(hashed_address);
+            } else if let Some(trie) = inner.storages.get_mut(hashed_address) {
+                for hashed_slot in storage.storage.keys() {
+                    trie.invalidate(&Nibbles::unpack(hashed_slot));
+                }
+            }
+        }
+        inner.move_to(tip);
+    }
+
+    /// Run the function on the cached trie of the account, or of the storage of the given account.
+    ///
+    /// Returns `None` without running the function if the handle bypasses the cache, or if the
+    /// cache moved past the generation of the handle.
+    fn with_trie<R>(
+        &self,
+        hashed_address: Option<B256>,
+        f: impl FnOnce(&mut CachedTrie) -> R,
+    ) -> Option<R> {
+        let generation = self.generation?;
+        let mut inner = self.inner.lock();
+        if inner.generation != generation {
+            return None
+        }
+        let trie = match hashed_address {
+            Some(hashed_address) => inner.storages.entry(hashed_address).or_default(),
+            None => &mut inner.account,
+        };
+        let before = trie.len();
+        let result = f(trie);
+        let after = trie.len();
+        inner.entries = inner.entries - before + after;
+        if inner.entries > inner.max_entries {
+            inner.clear();
+        }
+        Some(result)
+    }
+}
+
+#[derive(Debug)]
+struct CacheInner {
+    /// The tip the cached tries are at.
+    tip: Option<B256>,
+    /// Incremented whenever the cache moves to another tip.
+    generation: u64,
+    /// The account trie.
+    account: CachedTrie,
+    /// The storage tries by hashed address.
+    storages: HashMap<B256, CachedTrie>,
+    /// The number of cached entries.
+    entries: usize,
+    /// The maximum number of cached entries.
+    max_entries: usize,
+}
+
+impl CacheInner {
+    /// Drop the cached nodes. The tip and the generation are kept, as the cache is still valid
+    /// for it.
+    fn clear(&mut self) {
+        self.account = CachedTrie::default();
+        self.storages.clear();
+        self.entries = 0;
+    }
+
+    /// Clear the cache and move it to the tip.
+    fn reset(&mut self, tip: Option<B256>) {
+        self.clear();
+        self.move_to(tip);
+    }
+
+    /// Move the updated cache to the tip, starting a new generation.
+    fn move_to(&mut self, tip: Option<B256>) {
+        self.entries =
+            self.account.len() + self.storages.values().map(CachedTrie::len).sum::<usize>();
+        self.tip = tip;
+        self.generation += 1;
+    }
+}
+
+/// The known part of a trie.
+#[derive(Debug, Default)]
+struct CachedTrie {
+    /// Nodes by their keys.
+    nodes: BTreeMap<Nibbles, BranchNodeCompact>,
+    /// Disjoint ranges of keys without nodes, from the start key inclusive to the end key
+    /// exclusive. The end key is always a cached node, or the end of the trie if `None`.
+    gaps: BTreeMap<Nibbles, Option<Nibbles>>,
+}
+
+impl CachedTrie {
+    fn len(&self) -> usize {
+        self.nodes.len() + self.gaps.len()
+    }
+
+    /// Returns the gap that contains the key.
+    fn gap_of(&self, key: &Nibbles) -> Option<(&Nibbles, &Option<Nibbles>)> {
+        self.gaps
+            .range(..=key)
+            .next_back()
+            .filter(|(_, end)| end.as_ref().map_or(true, |end| key < end))
+    }
+
+    fn entry(&self, key: &Nibbles) -> (Nibbles, BranchNodeCompact) {
+        (key.clone(), self.nodes[key].clone())
+    }
+
+    /// Returns the node of the key, or `None` if the trie has no such node. Returns `Err` if it's
+    /// unknown.
+    fn seek_exact(&self, key: &Nibbles) -> Result<Option<(Nibbles, BranchNodeCompact)>, ()> {
+        if self.nodes.contains_key(key) {
+            return Ok(Some(self.entry(key)))
+        }
+        self.gap_of(key).map(|_| None).ok_or(())
+    }
+
+    /// Returns the first node with key greater than or equal to the key, or `None` if the trie
+    /// has no such node. Returns `Err` if it's unknown.
+    fn seek(&self, key: &Nibbles) -> Result<Option<(Nibbles, BranchNodeCompact)>, ()> {
+        if self.nodes.contains_key(key) {
+            return Ok(Some(self.entry(key)))
+        }
+        let (_, end) = self.gap_of(key).ok_or(())?;
+        Ok(end.as_ref().map(|end| self.entry(end)))
+    }
+
+    /// Record the result of the exact lookup of the key in the database.
+    fn insert_exact(&mut self, entry: Option<&(Nibbles, BranchNodeCompact)>) {
+        if let Some((key, node)) = entry {
+            self.nodes.insert(key.clone(), node.clone());
+        }
+    }
+
+    /// Record the result of the lookup of the first node greater than or equal to the key in the
+    /// database.
+    fn insert(&mut self, key: &Nibbles, entry: Option<&(Nibbles, BranchNodeCompact)>) {
+        if let Some((found, node)) = entry {
+            self.nodes.insert(found.clone(), node.clone());
+            if found == key {
+                return
+            }
+        }
+        if self.gap_of(key).is_some() {
+            return
+        }
+        let end = entry.map(|(found, _)| found.clone());
+        // The gaps starting within the new one end at the same node, so they are replaced.
+        let covered = self
+            .gaps
+            .range(key.clone()..)
+            .take_while(|(start, _)| end.as_ref().map_or(true, |end| *start < end))
+            .map(|(start, _)| start.clone())
+            .collect::<Vec<_>>();
+        for start in covered {
+            self.gaps.remove(&start);
+        }
+        self.gaps.insert(key.clone(), end);
+    }
+
+    /// Apply the update of the node, or its removal if `node` is `None`.
+    fn apply(&mut self, key: &Nibbles, node: Option<BranchNodeCompact>) {
+        match node {
+            Some(node) => {
+                if !self.nodes.contains_key(key) {
+                    // Split the gap that contained the key, keeping the part before the key.
+                    if let Some((start, _)) = self.gap_of(key) {
+                        let start = start.clone();
+                        if &start == key {
+                            self.gaps.remove(&start);
+                        } else {
+                            self.gaps.insert(start, Some(key.clone()));
+                        }
+                    }
+                }
+                self.nodes.insert(key.clone(), node);
+            }
+            None => self.remove_node(key),
+        }
+    }
+
+    /// Remove the node of the key.
+    fn remove_node(&mut self, key: &Nibbles) {
+        if self.nodes.remove(key).is_some() {
+            // The gap that ended at the node is no longer bounded by a known node.
+            let ending_at_key = self
+                .gaps
+                .range(..key.clone())
+                .next_back()
+                .filter(|(_, end)| end.as_ref() == Some(key))
+                .map(|(start, _)| start.clone());
+            if let Some(start) = ending_at_key {
+                self.gaps.remove(&start);
+            }
+        }
+    }
+
+    /// Forget the nodes and the gaps on the path to the leaf with the given key. These are the
+    /// only parts of the trie that can change when the leaf is updated.
+    fn invalidate(&mut self, key: &Nibbles) {
+        for len in 0..=key.len() {
+            let prefix = Nibbles::from_nibbles_unchecked(&key[..len]);
+            self.remove_node(&prefix);
+            if let Some((start, _)) = self.gap_of(&prefix) {
+                let start = start.clone();
+                self.gaps.remove(&start);
+            }
+        }
+    }
+}
+
+/// Trie cursor factory that consults the [TrieNodeCache] before reading the tries with the cursors
+/// of the inner factory.
+#[derive(Clone, Debug)]
+pub struct CachedTrieCursorFactory<F> {
+    factory: F,
+    cache: Option<TrieNodeCache>,
+}
+
+impl<F> CachedTrieCursorFactory<F> {
+    /// Create new cached trie cursor factory reading the cache through the handle returned by
+    /// [TrieNodeCache::at_tip]. If the cache is `None`, the cursors of the inner factory are used
+    /// as is.
+    pub fn new(factory: F, cache: Option<TrieNodeCache>) -> Self {
+        Self { factory, cache }
+    }
+}
+
+impl<F: TrieCursorFactory> TrieCursorFactory for CachedTrieCursorFactory<F> {
+    fn account_trie_cursor(&self) -> Result<Box<dyn TrieCursor + '_>, DatabaseError> {
+        let cursor = self.factory.account_trie_cursor()?;
+        Ok(match &self.cache {
+            Some(cache) => Box::new(CachedTrieCursor::new(cursor, cache.clone(), None)),
+            None => cursor,
+        })
+    }
+
+    fn storage_tries_cursor(
+        &self,
+        hashed_address: B256,
+    ) -> Result<Box<dyn TrieCursor + '_>, DatabaseError> {
+        let cursor = self.factory.storage_tries_cursor(hashed_address)?;
+        Ok(match &self.cache {
+            Some(cache) => {
+                Box::new(CachedTrieCursor::new(cursor, cache.clone(), Some(hashed_address)))
+            }
+            None => cursor,
+        })
+    }
+}
+
+/// Trie cursor that serves the lookups from the [TrieNodeCache] if possible and caches the results
+/// of the inner cursor otherwise.
+#[derive(Debug)]
+pub struct CachedTrieCursor<C> {
+    cursor: C,
+    cache: TrieNodeCache,
+    /// Hashed address of the storage trie, or `None` for the account trie.
+    hashed_address: Option<B256>,
+    /// The key of the last returned node.
+    current: Option<Nibbles>,
+}
+
+impl<C> CachedTrieCursor<C> {
+    /// Create new cached trie cursor.
+    pub fn new(cursor: C, cache: TrieNodeCache, hashed_address: Option<B256>) -> Self {
+        Self { cursor, cache, hashed_address, current: None }
+    }
+
+    fn position(
+        &mut self,
+        entry: Option<(Nibbles, BranchNodeCompact)>,
+    ) -> Option<(Nibbles, BranchNodeCompact)> {
+        self.current = entry.as_ref().map(|(key, _)| key.clone());
+        entry
+    }
+}
+
+impl<C: TrieCursor> TrieCursor for CachedTrieCursor<C> {
+    fn seek_exact(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        let cached = self
+            .cache
+            .with_trie(self.hashed_address, |trie| trie.seek_exact(&key))
+            .unwrap_or(Err(()));
+        let entry = match cached {
+            Ok(entry) => entry,
+            Err(()) => {
+                let entry = self.cursor.seek_exact(key)?;
+                self.cache.with_trie(self.hashed_address, |trie| trie.insert_exact(entry.as_ref()));
+                entry
+            }
+        };
+        Ok(self.position(entry))
+    }
+
+    fn seek(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        let cached =
+            self.cache.with_trie(self.hashed_address, |trie| trie.seek(&key)).unwrap_or(Err(()));
+        let entry = match cached {
+            Ok(entry) => entry,
+            Err(()) => {
+                let entry = self.cursor.seek(key.clone())?;
+                self.cache.with_trie(self.hashed_address, |trie| trie.insert(&key, entry.as_ref()));
+                entry
+            }
+        };
+        Ok(self.position(entry))
+    }
+
+    fn current(&mut self) -> Result<Option<TrieKey>, DatabaseError> {
+        Ok(self.current.clone().map(|key| match self.hashed_address {
+            Some(hashed_address) => TrieKey::StorageNode(hashed_address, StoredNibblesSubKey(key)),
+            None => TrieKey::AccountNode(StoredNibbles(key)),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{tables, transaction::DbTxMut};
+    use reth_primitives::trie::StoredBranchNode;
+    use reth_provider::test_utils::create_test_provider_factory;
+
+    fn node(hash_mask: u16) -> BranchNodeCompact {
+        let hashes = vec![B256::ZERO; hash_mask.count_ones() as usize];
+        BranchNodeCompact::new(0b11, 0, hash_mask, hashes, None)
+    }
+
+    fn nibbles(nibbles: &[u8]) -> Nibbles {
+        Nibbles::from_nibbles(nibbles)
+    }
+
+    #[test]
+    fn cached_account_trie() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        for key in [vec![0x1], vec![0x3, 0x1], vec![0x5]] {
+            tx.put::<tables::AccountsTrie>(key.into(), StoredBranchNode(node(0))).unwrap();
+        }
+
+        let cache = TrieNodeCache::default();
+        let cached = |tip| CachedTrieCursorFactory::new(tx, Some(cache.at_tip(tip)));
+        let queries = [vec![0x0], vec![0x1], vec![0x2], vec![0x3, 0x1], vec![0x4], vec![0x6]];
+
+        // Populate the cache and check that the cached lookups match the database.
+        let assert_matches = |cached: &CachedTrieCursorFactory<_>| {
+            let mut db_cursor = tx.account_trie_cursor().unwrap();
+            let mut cursor = cached.account_trie_cursor().unwrap();
+            for _ in 0..2 {
+                for query in &queries {
+                    assert_eq!(
+                        cursor.seek(nibbles(query)).unwrap(),
+                        db_cursor.seek(nibbles(query)).unwrap()
+                    );
+                    assert_eq!(
+                        cursor.seek_exact(nibbles(query)).unwrap(),
+                        db_cursor.seek_exact(nibbles(query)).unwrap()
+                    );
+                }
+            }
+        };
+        let initial = cached(None);
+        assert_matches(&initial);
+        assert!(!cache.is_empty());
+
+        // Remove the cached trie from the database, so that the lookups are served by the cache.
+        tx.clear::<tables::AccountsTrie>().unwrap();
+        let mut cursor = initial.account_trie_cursor().unwrap();
+        assert_eq!(cursor.seek(nibbles(&[0x2])).unwrap(), Some((nibbles(&[0x3, 0x1]), node(0))));
+        assert_eq!(
+            cursor.current().unwrap(),
+            Some(TrieKey::AccountNode(StoredNibbles(nibbles(&[0x3, 0x1]))))
+        );
+        for key in [vec![0x1], vec![0x3, 0x1], vec![0x5]] {
+            tx.put::<tables::AccountsTrie>(key.into(), StoredBranchNode(node(0))).unwrap();
+        }
+
+        // Write the updates to the database and apply them to the cache.
+        let updates = TrieUpdates::from([
+            (TrieKey::AccountNode(nibbles(&[0x1]).into()), TrieOp::Delete),
+            (TrieKey::AccountNode(nibbles(&[0x3, 0x1]).into()), TrieOp::Update(node(0b1))),
+            (TrieKey::AccountNode(nibbles(&[0x4]).into()), TrieOp::Update(node(0b10))),
+            (TrieKey::AccountNode(nibbles(&[0x7]).into()), TrieOp::Update(node(0b11))),
+        ]);
+        updates.clone().flush(tx).unwrap();
+        let tip = Some(B256::with_last_byte(1));
+        cache.apply_updates(None, tip, &updates);
+        assert_matches(&cached(tip));
+
+        // Reads at another tip bypass the cache, which is left intact.
+        let entries = cache.len();
+        assert_matches(&cached(Some(B256::with_last_byte(2))));
+        assert_eq!(cache.len(), entries);
+
+        // Change the nodes on the path to the account and drop them from the cache.
+        tx.put::<tables::AccountsTrie>(vec![0x3].into(), StoredBranchNode(node(0b1))).unwrap();
+        tx.put::<tables::AccountsTrie>(vec![0x3, 0x1].into(), StoredBranchNode(node(0b10)))
+            .unwrap();
+        let state = HashedPostState {
+            accounts: HashMap::from([(B256::repeat_byte(0x31), None)]),
+            storages: HashMap::default(),
+        };
+        let stale = cached(tip);
+        let (parent, tip) = (tip, Some(B256::with_last_byte(3)));
+        cache.invalidate(parent, tip, &state);
+        assert!(!cache.is_empty());
+        assert_matches(&cached(tip));
+
+        // Reads that started at the previous tip don't populate the cache anymore.
+        cache.clear();
+        assert_matches(&stale);
+        assert!(cache.is_empty());
+    }
+}
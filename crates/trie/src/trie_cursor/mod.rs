@@ -5,6 +5,7 @@ use reth_primitives::{
     B256,
 };
 
+mod cache;
 mod database_cursors;
 mod subnode;
 
@@ -12,6 +13,9 @@ mod subnode;
 pub mod noop;
 
 pub use self::{
+    cache::{
+        CachedTrieCursor, CachedTrieCursorFactory, TrieNodeCache, DEFAULT_MAX_CACHED_TRIE_ENTRIES,
+    },
     database_cursors::{DatabaseAccountTrieCursor, DatabaseStorageTrieCursor},
     subnode::CursorSubNode,
 };
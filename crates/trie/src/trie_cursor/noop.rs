@@ -4,7 +4,7 @@ use reth_db::DatabaseError;
 use reth_primitives::trie::{BranchNodeCompact, Nibbles};
 
 /// Noop trie cursor factory.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, Copy)]
 #[non_exhaustive]
 pub struct NoopTrieCursorFactory;
 
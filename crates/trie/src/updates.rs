@@ -11,7 +11,10 @@ use reth_primitives::{
     },
     B256,
 };
-use std::collections::{hash_map::IntoIter, HashMap, HashSet};
+use std::{
+    collections::{hash_map::IntoIter, BTreeMap, HashMap, HashSet},
+    ops::AddAssign,
+};
 
 use crate::walker::TrieWalker;
 
@@ -76,6 +79,20 @@ impl TrieUpdates {
         }
     }
 
+    /// Returns the first key, in the order the updates are flushed, whose operations differ
+    /// between the updates, along with the operations of both.
+    pub fn first_difference<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> Option<(&'a TrieKey, Option<&'a TrieOp>, Option<&'a TrieOp>)> {
+        self.trie_operations
+            .keys()
+            .chain(other.trie_operations.keys())
+            .filter(|key| self.trie_operations.get(key) != other.trie_operations.get(key))
+            .min()
+            .map(|key| (key, self.trie_operations.get(key), other.trie_operations.get(key)))
+    }
+
     /// Extend the updates with trie updates.
     pub fn extend(&mut self, updates: impl IntoIterator<Item = (TrieKey, TrieOp)>) {
         self.trie_operations.extend(updates);
@@ -130,59 +147,302 @@ impl TrieUpdates {
     }
 
     /// Flush updates all aggregated updates to the database.
-    pub fn flush(self, tx: &(impl DbTx + DbTxMut)) -> Result<(), reth_db::DatabaseError> {
+    ///
+    /// The updates are grouped by table and written in key order. Nodes of storage tries that are
+    /// not in the database yet are appended without looking up the previous entries.
+    ///
+    /// Returns the number of written and removed nodes.
+    pub fn flush(
+        self,
+        tx: &(impl DbTx + DbTxMut),
+    ) -> Result<TrieUpdatesStats, reth_db::DatabaseError> {
+        let mut stats = TrieUpdatesStats::default();
         if self.trie_operations.is_empty() {
-            return Ok(())
+            return Ok(stats)
         }
 
-        let mut account_trie_cursor = tx.cursor_write::<tables::AccountsTrie>()?;
-        let mut storage_trie_cursor = tx.cursor_dup_write::<tables::StoragesTrie>()?;
-
-        let mut trie_operations = Vec::from_iter(self.trie_operations);
-        trie_operations.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-        for (key, operation) in trie_operations {
+        let mut account_nodes = BTreeMap::new();
+        let mut storage_tries = BTreeMap::<B256, StorageTrieOps>::new();
+        for (key, operation) in self.trie_operations {
             match key {
-                TrieKey::AccountNode(nibbles) => match operation {
-                    TrieOp::Delete => {
-                        if account_trie_cursor.seek_exact(nibbles)?.is_some() {
-                            account_trie_cursor.delete_current()?;
-                        }
-                    }
-                    TrieOp::Update(node) => {
-                        if !nibbles.0.is_empty() {
-                            account_trie_cursor.upsert(nibbles, StoredBranchNode(node))?;
-                        }
-                    }
-                },
+                TrieKey::AccountNode(nibbles) => {
+                    account_nodes.insert(nibbles, operation);
+                }
+                TrieKey::StorageNode(hashed_address, nibbles) => {
+                    storage_tries
+                        .entry(hashed_address)
+                        .or_default()
+                        .nodes
+                        .insert(nibbles, operation);
+                }
                 TrieKey::StorageTrie(hashed_address) => match operation {
                     TrieOp::Delete => {
-                        if storage_trie_cursor.seek_exact(hashed_address)?.is_some() {
-                            storage_trie_cursor.delete_current_duplicates()?;
-                        }
+                        storage_tries.entry(hashed_address).or_default().removed = true
                     }
                     TrieOp::Update(..) => unreachable!("Cannot update full storage trie."),
                 },
-                TrieKey::StorageNode(hashed_address, nibbles) => {
-                    if !nibbles.is_empty() {
-                        // Delete the old entry if it exists.
-                        if storage_trie_cursor
-                            .seek_by_key_subkey(hashed_address, nibbles.clone())?
-                            .filter(|e| e.nibbles == nibbles)
-                            .is_some()
-                        {
-                            storage_trie_cursor.delete_current()?;
-                        }
-
-                        // The operation is an update, insert new entry.
-                        if let TrieOp::Update(node) = operation {
-                            storage_trie_cursor
-                                .upsert(hashed_address, StorageTrieEntry { nibbles, node })?;
-                        }
+            }
+        }
+
+        let mut account_trie_cursor = tx.cursor_write::<tables::AccountsTrie>()?;
+        for (nibbles, operation) in account_nodes {
+            match operation {
+                TrieOp::Delete => {
+                    if account_trie_cursor.seek_exact(nibbles)?.is_some() {
+                        account_trie_cursor.delete_current()?;
+                        stats.account_nodes_removed += 1;
+                    }
+                }
+                TrieOp::Update(node) => {
+                    if !nibbles.0.is_empty() {
+                        account_trie_cursor.upsert(nibbles, StoredBranchNode(node))?;
+                        stats.account_nodes_written += 1;
                     }
                 }
-            };
+            }
+        }
+
+        let mut storage_trie_cursor = tx.cursor_dup_write::<tables::StoragesTrie>()?;
+        for (hashed_address, storage_trie) in storage_tries {
+            let exists = storage_trie_cursor.seek_exact(hashed_address)?.is_some();
+
+            // Removal of the storage trie supersedes the updates of its nodes.
+            if storage_trie.removed {
+                if exists {
+                    storage_trie_cursor.delete_current_duplicates()?;
+                    stats.storage_tries_removed += 1;
+                }
+                continue
+            }
+
+            for (nibbles, operation) in storage_trie.nodes {
+                if nibbles.is_empty() {
+                    continue
+                }
+
+                // The storage trie is new, so the sorted nodes can be appended.
+                if !exists {
+                    if let TrieOp::Update(node) = operation {
+                        storage_trie_cursor
+                            .append_dup(hashed_address, StorageTrieEntry { nibbles, node })?;
+                        stats.storage_nodes_written += 1;
+                    }
+                    continue
+                }
+
+                // Delete the old entry if it exists.
+                if storage_trie_cursor
+                    .seek_by_key_subkey(hashed_address, nibbles.clone())?
+                    .filter(|e| e.nibbles == nibbles)
+                    .is_some()
+                {
+                    storage_trie_cursor.delete_current()?;
+                    if !operation.is_update() {
+                        stats.storage_nodes_removed += 1;
+                    }
+                }
+
+                // The operation is an update, insert new entry.
+                if let TrieOp::Update(node) = operation {
+                    storage_trie_cursor
+                        .upsert(hashed_address, StorageTrieEntry { nibbles, node })?;
+                    stats.storage_nodes_written += 1;
+                }
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::TrieUpdatesMetrics::default().record(stats);
+
+        Ok(stats)
+    }
+}
+
+/// Operations on a single storage trie, grouped for the flush.
+#[derive(Debug, Default)]
+struct StorageTrieOps {
+    /// `true` if the whole storage trie is removed.
+    removed: bool,
+    /// Operations on the nodes of the storage trie.
+    nodes: BTreeMap<StoredNibblesSubKey, TrieOp>,
+}
+
+/// The number of trie nodes written and removed by flushing [TrieUpdates].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TrieUpdatesStats {
+    /// The number of written account trie nodes.
+    pub account_nodes_written: usize,
+    /// The number of removed account trie nodes.
+    pub account_nodes_removed: usize,
+    /// The number of written storage trie nodes.
+    pub storage_nodes_written: usize,
+    /// The number of removed storage trie nodes, excluding the nodes of removed storage tries.
+    pub storage_nodes_removed: usize,
+    /// The number of removed storage tries.
+    pub storage_tries_removed: usize,
+}
+
+impl AddAssign for TrieUpdatesStats {
+    fn add_assign(&mut self, other: Self) {
+        self.account_nodes_written += other.account_nodes_written;
+        self.account_nodes_removed += other.account_nodes_removed;
+        self.storage_nodes_written += other.storage_nodes_written;
+        self.storage_nodes_removed += other.storage_nodes_removed;
+        self.storage_tries_removed += other.storage_tries_removed;
+    }
+}
+
+/// Trie updates writer that flushes finalized trie updates to the database in batches
+/// instead of accumulating all of them in memory.
+///
+/// Updates are buffered until the batch size is reached. The remaining updates must be
+/// written with [TrieUpdatesWriter::finish] once the root computation is complete.
+#[derive(Debug)]
+pub struct TrieUpdatesWriter<'a, TX> {
+    /// The database transaction to write updates to.
+    tx: &'a TX,
+    /// Updates that were not flushed yet.
+    buffer: TrieUpdates,
+    /// The number of buffered updates that triggers the flush.
+    batch_size: usize,
+    /// The total number of flushed updates.
+    flushed: usize,
+    /// The total number of written and removed nodes.
+    stats: TrieUpdatesStats,
+}
+
+impl<'a, TX> TrieUpdatesWriter<'a, TX> {
+    /// The default number of buffered updates that triggers the flush.
+    pub const DEFAULT_BATCH_SIZE: usize = 100_000;
+
+    /// Create new trie updates writer for the transaction.
+    pub fn new(tx: &'a TX) -> Self {
+        Self {
+            tx,
+            buffer: TrieUpdates::default(),
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+            flushed: 0,
+            stats: TrieUpdatesStats::default(),
         }
+    }
+
+    /// Set the number of buffered updates that triggers the flush.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Returns the total number of updates flushed so far.
+    pub fn flushed(&self) -> usize {
+        self.flushed
+    }
 
+    /// Returns the total number of nodes written and removed so far.
+    pub fn stats(&self) -> TrieUpdatesStats {
+        self.stats
+    }
+}
+
+impl<'a, TX> TrieUpdatesWriter<'a, TX>
+where
+    TX: DbTx + DbTxMut,
+{
+    /// Write finalized trie updates.
+    ///
+    /// Flushes buffered updates to the database if the batch size is reached.
+    pub fn write(
+        &mut self,
+        updates: impl IntoIterator<Item = (TrieKey, TrieOp)>,
+    ) -> Result<(), reth_db::DatabaseError> {
+        self.buffer.extend(updates);
+        if self.buffer.len() >= self.batch_size {
+            self.flush_buffer()?;
+        }
+        Ok(())
+    }
+
+    /// Write the final updates and flush all remaining buffered updates to the database.
+    ///
+    /// Returns the total number of written and removed nodes.
+    pub fn finish(
+        mut self,
+        updates: impl IntoIterator<Item = (TrieKey, TrieOp)>,
+    ) -> Result<TrieUpdatesStats, reth_db::DatabaseError> {
+        self.buffer.extend(updates);
+        self.flush_buffer()?;
+        Ok(self.stats)
+    }
+
+    fn flush_buffer(&mut self) -> Result<(), reth_db::DatabaseError> {
+        let updates = std::mem::take(&mut self.buffer);
+        self.flushed += updates.len();
+        self.stats += updates.flush(self.tx)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_provider::test_utils::create_test_provider_factory;
+
+    fn node() -> BranchNodeCompact {
+        BranchNodeCompact::new(0b11, 0, 0, vec![], None)
+    }
+
+    fn storage_node(hashed_address: B256, nibbles: Vec<u8>) -> (TrieKey, TrieOp) {
+        (TrieKey::StorageNode(hashed_address, nibbles.into()), TrieOp::Update(node()))
+    }
+
+    #[test]
+    fn flush_stats() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let [existing, new, removed, missing] = [1, 2, 3, 4].map(B256::with_last_byte);
+        tx.put::<tables::AccountsTrie>(vec![0x1].into(), StoredBranchNode(node())).unwrap();
+        for (hashed_address, nibbles) in
+            [(existing, vec![0x1]), (existing, vec![0x2]), (removed, vec![0x1])]
+        {
+            let entry = StorageTrieEntry { nibbles: nibbles.into(), node: node() };
+            tx.put::<tables::StoragesTrie>(hashed_address, entry).unwrap();
+        }
+
+        let updates = TrieUpdates::from([
+            (TrieKey::AccountNode(vec![0x1].into()), TrieOp::Delete),
+            (TrieKey::AccountNode(vec![0x2].into()), TrieOp::Update(node())),
+            (TrieKey::AccountNode(StoredNibbles(Nibbles::default())), TrieOp::Update(node())),
+            (TrieKey::StorageNode(existing, vec![0x1].into()), TrieOp::Delete),
+            storage_node(existing, vec![0x3]),
+            storage_node(new, vec![0x2]),
+            storage_node(new, vec![0x1]),
+            storage_node(removed, vec![0x2]),
+            (TrieKey::StorageTrie(removed), TrieOp::Delete),
+            (TrieKey::StorageTrie(missing), TrieOp::Delete),
+        ]);
+        assert_eq!(
+            updates.flush(tx).unwrap(),
+            TrieUpdatesStats {
+                account_nodes_written: 1,
+                account_nodes_removed: 1,
+                storage_nodes_written: 3,
+                storage_nodes_removed: 1,
+                storage_tries_removed: 1,
+            }
+        );
+
+        let mut cursor = tx.cursor_read::<tables::StoragesTrie>().unwrap();
+        let storage_nodes = cursor
+            .walk(None)
+            .unwrap()
+            .map(|entry| entry.map(|(hashed_address, entry)| (hashed_address, entry.nibbles)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let expected =
+            [(existing, vec![0x2]), (existing, vec![0x3]), (new, vec![0x1]), (new, vec![0x2])].map(
+                |(hashed_address, nibbles)| (hashed_address, StoredNibblesSubKey::from(nibbles)),
+            );
+        assert_eq!(storage_nodes, expected);
+    }
+}